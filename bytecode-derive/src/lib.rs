@@ -0,0 +1,449 @@
+//! Derive macro for the `FromByteCode` trait of the [`bytecode`](https://docs.rs/bytecode) crate.
+//!
+//! Use it through the `derive` feature of `bytecode` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericParam, Ident, Lifetime,
+    LifetimeParam, LitInt, LitStr, Type,
+};
+
+/// Derives `FromByteCode` for a struct, reading its fields in declaration order.
+///
+/// The byte order set on the `ByteCode` is used unless overridden, and is restored afterwards.
+/// On error the pointer and byte order are left where they were before the struct.
+///
+/// # Attributes
+///
+/// On the struct:
+///
+/// - `#[bytecode(endian = "little")]` or `"big"`: byte order of all fields.
+/// - `#[bytecode(check_layout)]`: records the span each field was read from and, in debug builds,
+///   checks that each span starts where the previous one ended, after any `pad`, and does not end
+///   before it started, overlapping the fields before it. Returns [`Error::Layout`](https://docs.rs/bytecode/latest/bytecode/enum.Error.html#variant.Layout) if one does not.
+/// - `#[bytecode(check_layout, size = N)]`: also checks that the struct, padding included, is `N` bytes long,
+///   catching fields of the wrong type and undeclared gaps.
+///
+/// On a field:
+///
+/// - `#[bytecode(endian = "little")]` or `"big"`: byte order of this field only.
+/// - `#[bytecode(pad = N)]`: `N` bytes of padding skipped before the field.
+/// - `#[bytecode(string = N)]`: a `String` or `&str` of `N` bytes, with trailing NULs trimmed.
+/// - `#[bytecode(prefix = T)]`: a `Vec` whose element count is read as a `T` just before it.
+/// - `#[bytecode(count = field)]`: a `Vec` whose element count is an earlier field.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, FromByteCode};
+///
+/// #[derive(FromByteCode)]
+/// #[bytecode(endian = "little")]
+/// struct Header<'a> {
+///     #[bytecode(endian = "big")]
+///     magic: u32,
+///     #[bytecode(string = 8)]
+///     name: &'a str,
+///     #[bytecode(prefix = u8)]
+///     sizes: Vec<u16>,
+///     flag_count: u16,
+///     #[bytecode(count = flag_count)]
+///     flags: Vec<u8>,
+/// }
+///
+/// let mut bytes = ByteCode::new(&[
+///     0xca, 0xfe, 0xba, 0xbe, // magic
+///     b'm', b'a', b'i', b'n', 0x00, 0x00, 0x00, 0x00, // name
+///     0x02, 0x10, 0x00, 0x20, 0x00, // sizes
+///     0x01, 0x00, 0xff, // flags
+/// ]);
+/// let header: Header = bytes.take_into();
+/// assert_eq!(header.magic, 0xcafe_babe);
+/// assert_eq!(header.name, "main");
+/// assert_eq!(header.sizes, [0x10, 0x20]);
+/// assert_eq!(header.flags, [0xff]);
+/// assert!(bytes.is_end());
+/// ```
+#[proc_macro_derive(FromByteCode, attributes(bytecode))]
+pub fn derive_from_bytecode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_bytecode(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `FixedSize` for a struct whose fields all have a fixed encoded size,
+/// summing the sizes of its fields.
+///
+/// `#[bytecode(string = N)]` fields count as `N` bytes. Fields using `prefix` or `count`
+/// have no fixed size and are rejected.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, FixedSize, FromByteCode};
+///
+/// #[derive(FromByteCode, FixedSize)]
+/// struct Entry {
+///     id: u16,
+///     #[bytecode(string = 6)]
+///     name: String,
+///     offset: u32,
+/// }
+///
+/// assert_eq!(Entry::SIZE, 12);
+///
+/// let mut bytes = ByteCode::new(&[0x00, 0x01, b'a', b'b', 0, 0, 0, 0, 0x00, 0x00, 0x00, 0x10]);
+/// let entry: Entry = bytes.take_exact_struct_size();
+/// assert_eq!(entry.name, "ab");
+/// ```
+#[proc_macro_derive(FixedSize, attributes(bytecode))]
+pub fn derive_fixed_size(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_fixed_size(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How a field is read.
+enum Kind {
+    Plain,
+    String(LitInt),
+    Prefixed(Type),
+    Counted(Ident),
+}
+
+struct StructAttrs {
+    endian: Option<TokenStream2>,
+    check_layout: bool,
+    size: Option<LitInt>,
+}
+
+struct FieldAttrs {
+    endian: Option<TokenStream2>,
+    pad: Option<LitInt>,
+    kind: Kind,
+}
+
+fn expand_from_bytecode(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "FromByteCode can only be derived for structs",
+            ))
+        }
+    };
+    let struct_attrs = parse_struct_attrs(&input.attrs)?;
+    let check = struct_attrs.check_layout;
+
+    // The lifetime of the underlying data: the struct's first lifetime, or a fresh one.
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__bytecode", Span::call_site());
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::bytecode::FromByteCode<#lifetime>));
+    }
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (bindings, reads) = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let ident = field.ident.clone().unwrap();
+                let read = read_field(i, &ident, &ident.to_string(), field, &lifetime, check)?;
+                Ok((ident, read))
+            })
+            .collect::<syn::Result<Vec<_>>>()?
+            .into_iter()
+            .unzip(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let ident = format_ident!("__field{}", i);
+                let read = read_field(i, &ident, &i.to_string(), field, &lifetime, check)?;
+                Ok((ident, read))
+            })
+            .collect::<syn::Result<Vec<_>>>()?
+            .into_iter()
+            .unzip(),
+        Fields::Unit => (Vec::new(), Vec::new()),
+    };
+    let bindings: Vec<Ident> = bindings;
+    let reads: Vec<TokenStream2> = reads;
+    let construct = match &data.fields {
+        Fields::Named(_) => quote!(#name { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#name ( #(#bindings),* )),
+        Fields::Unit => quote!(#name),
+    };
+    let set_endian = struct_attrs
+        .endian
+        .map(|endian| quote!(bytes.set_endian(#endian);));
+    let field_count = data.fields.len();
+    let mutability = (field_count > 0).then(|| quote!(mut));
+    let begin = check.then(|| {
+        quote! {
+            let __begin = bytes.pos();
+            let #mutability __spans: [::core::ops::Range<usize>; #field_count] =
+                ::core::array::from_fn(|_| 0..0);
+        }
+    });
+    let check_size = struct_attrs.size.map(|size| {
+        let last = match data.fields.iter().enumerate().last() {
+            Some((i, field)) => field
+                .ident
+                .as_ref()
+                .map_or_else(|| i.to_string(), ToString::to_string),
+            None => name.to_string(),
+        };
+        quote! {
+            if ::core::cfg!(debug_assertions) && bytes.pos() != __begin + #size {
+                return ::core::result::Result::Err(::bytecode::Error::Layout {
+                    pos: bytes.pos(),
+                    field: ::core::convert::From::from(#last),
+                    expected: __begin + #size,
+                    start: false,
+                });
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::bytecode::FromByteCode<#lifetime> for #name #ty_generics #where_clause {
+            fn from_bytecode(
+                bytes: &mut ::bytecode::ByteCode<#lifetime>,
+            ) -> ::core::result::Result<Self, ::bytecode::Error> {
+                bytes.transaction(|bytes| {
+                    #begin
+                    let __endian = bytes.endian();
+                    #set_endian
+                    #(#reads)*
+                    #check_size
+                    bytes.set_endian(__endian);
+                    ::core::result::Result::Ok(#construct)
+                })
+            }
+        }
+    })
+}
+
+fn expand_fixed_size(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "FixedSize can only be derived for structs",
+            ))
+        }
+    };
+    parse_struct_attrs(&input.attrs)?;
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::bytecode::FixedSize));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let sizes = data
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            let attrs = parse_field_attrs(&field.attrs)?;
+            let pad = attrs.pad.map(|pad| quote!(#pad +));
+            match attrs.kind {
+                Kind::Plain => Ok(quote!(#pad <#ty as ::bytecode::FixedSize>::SIZE)),
+                Kind::String(len) => Ok(quote!(#pad #len)),
+                Kind::Prefixed(_) | Kind::Counted(_) => Err(syn::Error::new_spanned(
+                    field,
+                    "a field with `prefix` or `count` has no fixed size",
+                )),
+            }
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::bytecode::FixedSize for #name #ty_generics #where_clause {
+            const SIZE: usize = 0 #(+ #sizes)*;
+        }
+    })
+}
+
+fn read_field(
+    index: usize,
+    binding: &Ident,
+    name: &str,
+    field: &syn::Field,
+    lifetime: &Lifetime,
+    check: bool,
+) -> syn::Result<TokenStream2> {
+    let attrs = parse_field_attrs(&field.attrs)?;
+    let ty = &field.ty;
+    let read = match attrs.kind {
+        Kind::Plain => quote!(<#ty as ::bytecode::FromByteCode<#lifetime>>::from_bytecode(bytes)?),
+        Kind::String(len) => quote! {{
+            let __str = bytes.try_take_str(#len)?;
+            ::core::convert::From::from(__str.trim_end_matches('\0'))
+        }},
+        Kind::Prefixed(count_ty) => quote! {{
+            let __pos = bytes.pos();
+            let __count = <#count_ty as ::bytecode::FromByteCode<#lifetime>>::from_bytecode(bytes)?;
+            let __count = usize::try_from(__count)
+                .map_err(|_| ::bytecode::Error::Overflow { pos: __pos })?;
+            ::bytecode::__private::take_vec(bytes, __count)?
+        }},
+        Kind::Counted(count) => quote! {{
+            let __count = usize::try_from(#count)
+                .map_err(|_| ::bytecode::Error::Overflow { pos: bytes.pos() })?;
+            ::bytecode::__private::take_vec(bytes, __count)?
+        }},
+    };
+    let read = match attrs.endian {
+        Some(endian) => quote! {
+            let __outer = bytes.endian();
+            bytes.set_endian(#endian);
+            let #binding: #ty = #read;
+            bytes.set_endian(__outer);
+        },
+        None => quote!(let #binding: #ty = #read;),
+    };
+    let start = check.then(|| quote!(let __start = bytes.pos();));
+    let check = check.then(|| {
+        let pad = attrs.pad.as_ref().map(|pad| quote!(+ #pad));
+        let previous_end = match index.checked_sub(1) {
+            Some(previous) => quote!(__spans[#previous].end),
+            None => quote!(__begin),
+        };
+        quote! {
+            // The field must start where the previous one ended, after its declared padding,
+            // and must not end before it started, or later fields would overlap it.
+            __spans[#index] = __start..bytes.pos();
+            let __span = &__spans[#index];
+            if ::core::cfg!(debug_assertions) {
+                let __expected = #previous_end #pad;
+                if __span.start != __expected {
+                    return ::core::result::Result::Err(::bytecode::Error::Layout {
+                        pos: __span.start,
+                        field: ::core::convert::From::from(#name),
+                        expected: __expected,
+                        start: true,
+                    });
+                }
+                if __span.end < __span.start {
+                    return ::core::result::Result::Err(::bytecode::Error::Layout {
+                        pos: __span.end,
+                        field: ::core::convert::From::from(#name),
+                        expected: __span.start,
+                        start: false,
+                    });
+                }
+            }
+        }
+    });
+    let pad = attrs.pad.map(|pad| quote!(bytes.try_skip(#pad)?;));
+    Ok(quote! {
+        #pad
+        #start
+        #read
+        #check
+    })
+}
+
+fn parse_struct_attrs(attrs: &[Attribute]) -> syn::Result<StructAttrs> {
+    let mut result = StructAttrs {
+        endian: None,
+        check_layout: false,
+        size: None,
+    };
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("bytecode")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("endian") {
+                result.endian = Some(parse_endian(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("check_layout") {
+                result.check_layout = true;
+            } else if meta.path.is_ident("size") {
+                result.size = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error(
+                    "unknown struct attribute; expected `endian`, `check_layout` or `size`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    if let (Some(size), false) = (&result.size, result.check_layout) {
+        return Err(syn::Error::new(
+            size.span(),
+            "`size` requires `check_layout`",
+        ));
+    }
+    Ok(result)
+}
+
+fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut result = FieldAttrs {
+        endian: None,
+        pad: None,
+        kind: Kind::Plain,
+    };
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("bytecode")) {
+        attr.parse_nested_meta(|meta| {
+            let kind = if meta.path.is_ident("endian") {
+                result.endian = Some(parse_endian(&meta.value()?.parse()?)?);
+                return Ok(());
+            } else if meta.path.is_ident("pad") {
+                result.pad = Some(meta.value()?.parse()?);
+                return Ok(());
+            } else if meta.path.is_ident("string") {
+                Kind::String(meta.value()?.parse()?)
+            } else if meta.path.is_ident("prefix") {
+                Kind::Prefixed(meta.value()?.parse()?)
+            } else if meta.path.is_ident("count") {
+                Kind::Counted(meta.value()?.parse()?)
+            } else {
+                return Err(meta.error(
+                    "unknown field attribute; expected `endian`, `pad`, `string`, `prefix` or `count`",
+                ));
+            };
+            if !matches!(result.kind, Kind::Plain) {
+                return Err(meta.error("only one of `string`, `prefix` and `count` may be used"));
+            }
+            result.kind = kind;
+            Ok(())
+        })?;
+    }
+    Ok(result)
+}
+
+fn parse_endian(lit: &LitStr) -> syn::Result<TokenStream2> {
+    match lit.value().as_str() {
+        "big" => Ok(quote!(::bytecode::Endian::Big)),
+        "little" => Ok(quote!(::bytecode::Endian::Little)),
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "expected endian = \"big\" or \"little\"",
+        )),
+    }
+}
@@ -0,0 +1,176 @@
+use bytecode::{ByteCode, Endian, Error, FixedSize, FourCc, FromByteCode};
+
+#[derive(Debug, PartialEq, FromByteCode)]
+struct Version(u8, u8);
+
+#[derive(Debug, PartialEq, FromByteCode)]
+struct Header {
+    magic: FourCc,
+    version: Version,
+    #[bytecode(endian = "little")]
+    flags: u16,
+    #[bytecode(string = 4)]
+    name: String,
+    #[bytecode(prefix = u16)]
+    entries: Vec<(u8, i8)>,
+}
+
+#[derive(Debug, PartialEq, FromByteCode)]
+struct Empty;
+
+#[derive(Debug, PartialEq, FromByteCode)]
+struct Wrapper<T> {
+    count: u8,
+    #[bytecode(count = count)]
+    items: Vec<T>,
+}
+
+#[test]
+fn derive_named() {
+    let mut bytes = ByteCode::new(b"RITE\x03\0\x01\x80mrb\0\0\x02\x01\xff\x02\xfe");
+    let header: Header = bytes.take_into();
+    assert_eq!(
+        header,
+        Header {
+            magic: FourCc(*b"RITE"),
+            version: Version(3, 0),
+            flags: 0x8001,
+            name: "mrb".to_owned(),
+            entries: vec![(1, -1), (2, -2)],
+        }
+    );
+    assert!(bytes.is_end());
+    assert_eq!(bytes.endian(), Endian::Big);
+}
+
+#[test]
+fn derive_rollback() {
+    let mut bytes = ByteCode::new(b"RITE\x03\0\x01\x80mrb\0\0\x02\x01\xff\x02");
+    bytes.set_endian(Endian::Little);
+    assert!(matches!(
+        bytes.try_take_into::<Header>(),
+        Err(Error::OutOfBounds { .. })
+    ));
+    assert_eq!(bytes.pos(), 0);
+    assert_eq!(bytes.endian(), Endian::Little);
+}
+
+#[test]
+fn derive_generic_and_unit() {
+    let mut bytes = ByteCode::new(&[0x02, 0x00, 0x01, 0x00, 0x02]);
+    let wrapper: Wrapper<u16> = bytes.take_into();
+    assert_eq!(wrapper.items, [1, 2]);
+    assert_eq!(bytes.take_into::<Empty>(), Empty);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn derive_invalid_utf8() {
+    let mut bytes = ByteCode::new(b"RITE\x03\0\x01\x80\xffrb\0\0\x02\x01\xff\x02\xfe");
+    assert_eq!(
+        bytes.try_take_into::<Header>(),
+        Err(Error::InvalidUtf8 { pos: 8, len: 4 })
+    );
+}
+
+#[derive(Debug, PartialEq, FromByteCode, FixedSize)]
+#[bytecode(endian = "little")]
+struct Record {
+    tag: FourCc,
+    #[bytecode(string = 3)]
+    name: String,
+    pair: Version,
+    value: u32,
+}
+
+impl FixedSize for Version {
+    const SIZE: usize = 2;
+}
+
+#[test]
+fn derive_fixed_size() {
+    assert_eq!(Record::SIZE, 13);
+
+    let mut bytes = ByteCode::new(b"TAG0ab\0\x01\x02\x10\x00\x00\x00");
+    let record: Record = bytes.take_exact_struct_size();
+    assert_eq!(record.name, "ab");
+    assert_eq!(record.value, 0x10);
+    assert!(bytes.is_end());
+}
+
+/// Reads a `u8` offset and the byte it points at, leaving the pointer there.
+#[derive(Debug, PartialEq)]
+struct Jump(u8);
+
+impl<'a> FromByteCode<'a> for Jump {
+    fn from_bytecode(bytes: &mut ByteCode<'a>) -> Result<Self, Error> {
+        let offset = bytes.try_take_into_u8()?;
+        bytes.try_seek(offset as usize)?;
+        Ok(Jump(bytes.try_take_into_u8()?))
+    }
+}
+
+#[derive(Debug, PartialEq, FromByteCode, FixedSize)]
+#[bytecode(check_layout, size = 8)]
+struct Padded {
+    tag: u8,
+    #[bytecode(pad = 3)]
+    value: u32,
+}
+
+#[derive(Debug, PartialEq, FromByteCode)]
+#[bytecode(check_layout)]
+struct Overlapping {
+    tag: u16,
+    jump: Jump,
+}
+
+#[derive(Debug, PartialEq, FromByteCode)]
+#[bytecode(check_layout)]
+struct Rewound {
+    tag: u16,
+    #[bytecode(pad = 1)]
+    jump: Jump,
+    tail: u8,
+}
+
+#[derive(Debug, PartialEq, FromByteCode)]
+#[bytecode(check_layout, size = 4)]
+struct Short(u8, u16);
+
+#[test]
+fn derive_check_layout() {
+    let mut bytes = ByteCode::new(&[0x01, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x02]);
+    assert_eq!(Padded::SIZE, 8);
+    assert_eq!(bytes.take_into::<Padded>(), Padded { tag: 1, value: 2 });
+
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00]);
+    assert_eq!(
+        bytes.try_take_into::<Overlapping>(),
+        Err(Error::Layout {
+            pos: 1,
+            field: "jump".to_owned(),
+            expected: 2,
+            start: false,
+        })
+    );
+    assert_eq!(bytes.pos(), 0);
+
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0xff, 0x00, 0x07]);
+    assert_eq!(
+        bytes.try_take_into::<Rewound>(),
+        Err(Error::Layout {
+            pos: 1,
+            field: "jump".to_owned(),
+            expected: 3,
+            start: false,
+        })
+    );
+
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00, 0x00]);
+    let error = bytes.try_take_into::<Short>().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "field `1` ended at 0x3, but its layout ends at 0x4"
+    );
+}
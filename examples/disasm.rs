@@ -0,0 +1,67 @@
+use bytecode::{ByteCode, Flow, InstructionSet, Opcode, OperandKind};
+
+/// A small stack machine with 16-bit big-endian branch displacements.
+struct StackVm;
+
+impl InstructionSet for StackVm {
+    fn opcode(&self, byte: u8) -> Option<Opcode> {
+        let (mnemonic, operands): (_, &'static [_]) = match byte {
+            0x00 => ("halt", &[]),
+            0x01 => ("push", &[(OperandKind::Signed, 1)]),
+            0x02 => ("push16", &[(OperandKind::Signed, 2)]),
+            0x03 => ("load", &[(OperandKind::Register, 1)]),
+            0x04 => ("store", &[(OperandKind::Register, 1)]),
+            0x05 => ("add", &[]),
+            0x06 => ("sub", &[]),
+            0x07 => ("jz", &[(OperandKind::Relative, 2)]),
+            0x08 => ("jmp", &[(OperandKind::Relative, 2)]),
+            0x09 => ("call", &[(OperandKind::Index, 2)]),
+            0x0a => ("ret", &[]),
+            _ => return None,
+        };
+        Some(Opcode { mnemonic, operands })
+    }
+}
+
+fn main() {
+    // Counts r0 down from 10, calling function 3 on each iteration.
+    let code = [
+        0x01, 0x0a, // push 10
+        0x04, 0x00, // store r0
+        0x03, 0x00, // load r0
+        0x07, 0x00, 0x0d, // jz +13
+        0x09, 0x00, 0x03, // call [3]
+        0x03, 0x00, // load r0
+        0x01, 0x01, // push 1
+        0x06, // sub
+        0x04, 0x00, // store r0
+        0x08, 0xff, 0xee, // jmp -18
+        0x00, // halt
+    ];
+    let mut bytes = ByteCode::new(&code);
+    let map = bytes.decode_instructions(|bytes| StackVm.decode(bytes).ok());
+
+    let cfg = map.basic_blocks(|_, instruction| match instruction.mnemonic {
+        "jz" | "jmp" => Flow::Branch {
+            targets: instruction
+                .branch_target()
+                .and_then(|target| map.index_at(target))
+                .into_iter()
+                .collect(),
+            falls_through: instruction.mnemonic == "jz",
+        },
+        "halt" | "ret" => Flow::Terminate,
+        _ => Flow::Next,
+    });
+
+    let listing = bytes
+        .listing()
+        .format_operand(OperandKind::Relative, |_, instruction| {
+            format!("{:#06x}", instruction.branch_target().unwrap())
+        });
+    for (i, block) in cfg.blocks().iter().enumerate() {
+        println!("block {} -> {:?}", i, block.successors);
+        let instructions = block.instructions.clone().map(|index| &map[index]);
+        print!("{}", listing.render(instructions));
+    }
+}
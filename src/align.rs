@@ -0,0 +1,200 @@
+use alloc::{borrow::ToOwned, format};
+
+use crate::{ByteCode, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Returns `true` if the pointer position is a multiple of `n`.
+    ///
+    /// Positions are relative to the start of this `ByteCode`, so use a child reader from
+    /// [`take_bytecode`](ByteCode::take_bytecode) for alignment relative to a section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// assert!(bytes.is_aligned(4));
+    /// bytes.skip(2);
+    /// assert!(bytes.is_aligned(2));
+    /// assert!(!bytes.is_aligned(4));
+    /// ```
+    pub fn is_aligned(&self, n: usize) -> bool {
+        if let Err(error) = self.check_alignment(n) {
+            self.fail(error);
+        }
+        self.pos % n == 0
+    }
+
+    /// Moves the pointer forward to the next multiple of `n` and returns the number of skipped elements.
+    /// The pointer does not move if it is already aligned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0, or the next multiple of `n` is beyond the end of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// bytes.skip(1);
+    /// assert_eq!(bytes.align_to(4), 3);
+    /// assert_eq!(bytes.pos(), 4);
+    /// assert_eq!(bytes.align_to(4), 0);
+    /// ```
+    pub fn align_to(&mut self, n: usize) -> usize {
+        self.try_align_to(n)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Moves the pointer forward to the next multiple of `n` and returns the number of skipped elements.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`align_to`](ByteCode::align_to),
+    /// which also reports an `n` of 0 as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5]);
+    /// bytes.skip(1);
+    /// assert!(bytes.try_align_to(0).is_err());
+    /// assert_eq!(bytes.try_align_to(4).unwrap(), 3);
+    /// assert!(bytes.try_align_to(8).is_err());
+    /// assert_eq!(bytes.pos(), 4);
+    /// ```
+    pub fn try_align_to(&mut self, n: usize) -> Result<usize, Error> {
+        self.check_alignment(n)?;
+        let num = (n - self.pos % n) % n;
+        self.try_skip(num)?;
+        Ok(num)
+    }
+
+    fn check_alignment(&self, n: usize) -> Result<(), Error> {
+        if n == 0 {
+            return Err(Error::InvalidArgument {
+                pos: self.pos,
+                message: "alignment must not be 0".to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to the elements from the pointer up to the absolute position `offset`, without copying.
+    /// Moves the pointer forward to `offset`.
+    ///
+    /// This suits fields placed at offsets given in a header, with padding or unknown data in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is before the pointer or beyond the end of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00, 0x00, 0x02]);
+    /// bytes.next();
+    /// assert_eq!(bytes.padding_until(4), [0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_into_u8(), 0x02);
+    /// ```
+    pub fn padding_until(&mut self, offset: usize) -> &'a [u8] {
+        self.try_padding_until(offset)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns a reference to the elements from the pointer up to the absolute position `offset`, without copying.
+    /// Moves the pointer forward to `offset`. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`padding_until`](ByteCode::padding_until),
+    /// which also reports an `offset` before the pointer as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00]);
+    /// bytes.next();
+    /// assert!(bytes.try_padding_until(0).is_err());
+    /// assert!(bytes.try_padding_until(4).is_err());
+    /// assert_eq!(bytes.try_padding_until(3).unwrap(), [0x00, 0x00]);
+    /// ```
+    pub fn try_padding_until(&mut self, offset: usize) -> Result<&'a [u8], Error> {
+        if offset < self.pos {
+            return Err(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!(
+                    "offset {:#x} is before the current position {:#x}",
+                    offset, self.pos
+                ),
+            });
+        }
+        self.try_take_ref(offset - self.pos)
+    }
+}
+
+#[test]
+fn align_to() {
+    let mut bytes = ByteCode::new(&[0; 16]);
+    assert_eq!(bytes.align_to(8), 0);
+    bytes.skip(3);
+    assert_eq!(bytes.align_to(1), 0);
+    assert_eq!(bytes.align_to(2), 1);
+    assert_eq!(bytes.align_to(8), 4);
+    assert!(bytes.is_aligned(8));
+    bytes.skip(1);
+    assert_eq!(bytes.align_to(16), 7);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn align_to_child_reader() {
+    let mut bytes = ByteCode::new(&[0; 16]);
+    bytes.skip(3);
+    let mut section = bytes.take_bytecode(8);
+    section.skip(1);
+    assert_eq!(section.align_to(4), 3);
+    assert_eq!(section.pos(), 4);
+}
+
+#[test]
+#[should_panic]
+fn align_to_past_end() {
+    let mut bytes = ByteCode::new(&[0; 6]);
+    bytes.skip(1);
+    bytes.align_to(8);
+}
+
+#[test]
+#[should_panic]
+fn align_to_zero() {
+    ByteCode::new(&[0; 4]).align_to(0);
+}
+
+#[test]
+fn padding_until() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5]);
+    bytes.skip(2);
+    assert_eq!(bytes.padding_until(2), []);
+    assert_eq!(bytes.padding_until(5), [2, 3, 4]);
+    assert_eq!(bytes.padding_until(6), [5]);
+    assert!(bytes.is_end());
+}
+
+#[test]
+#[should_panic]
+fn padding_until_backwards() {
+    let mut bytes = ByteCode::new(&[0; 4]);
+    bytes.skip(2);
+    bytes.padding_until(1);
+}
@@ -0,0 +1,611 @@
+use alloc::{borrow::ToOwned, collections::BTreeMap, format, string::String, vec::Vec};
+use core::{fmt::Write, ops::Range};
+
+use crate::{ByteCode, Endian, HexDump};
+
+/// The type a field was decoded as, used to pick the matching type in exported templates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    /// Raw bytes, exported as a `u8` array.
+    Bytes,
+    /// Text, exported as a `char` array.
+    Char,
+}
+
+impl FieldType {
+    fn size(self) -> Option<usize> {
+        match self {
+            FieldType::U8 | FieldType::I8 => Some(1),
+            FieldType::U16 | FieldType::I16 => Some(2),
+            FieldType::U32 | FieldType::I32 | FieldType::F32 => Some(4),
+            FieldType::U64 | FieldType::I64 | FieldType::F64 => Some(8),
+            FieldType::Bytes | FieldType::Char => None,
+        }
+    }
+
+    /// Returns the name used by [`snapshot_format`](ByteCode::snapshot_format), which must not change.
+    pub(crate) fn snapshot_name(self) -> &'static str {
+        match self {
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::I8 => "i8",
+            FieldType::I16 => "i16",
+            FieldType::I32 => "i32",
+            FieldType::I64 => "i64",
+            FieldType::F32 => "f32",
+            FieldType::F64 => "f64",
+            FieldType::Bytes => "bytes",
+            FieldType::Char => "char",
+        }
+    }
+
+    fn imhex(self) -> &'static str {
+        match self {
+            FieldType::U8 | FieldType::Bytes => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::I8 => "s8",
+            FieldType::I16 => "s16",
+            FieldType::I32 => "s32",
+            FieldType::I64 => "s64",
+            FieldType::F32 => "float",
+            FieldType::F64 => "double",
+            FieldType::Char => "char",
+        }
+    }
+
+    fn template(self) -> &'static str {
+        match self {
+            FieldType::U8 | FieldType::Bytes => "uchar",
+            FieldType::U16 => "ushort",
+            FieldType::U32 => "uint",
+            FieldType::U64 => "uint64",
+            FieldType::I8 => "char",
+            FieldType::I16 => "short",
+            FieldType::I32 => "int",
+            FieldType::I64 => "int64",
+            FieldType::F32 => "float",
+            FieldType::F64 => "double",
+            FieldType::Char => "char",
+        }
+    }
+}
+
+/// What a recorded [`Span`] describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpanKind {
+    Field(FieldType),
+    /// A group of fields, exported as a struct named `type_name`.
+    Struct {
+        type_name: String,
+        children: Vec<Span>,
+    },
+}
+
+/// A named range of bytes decoded by a parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub name: String,
+    /// The range of positions in the recorded `ByteCode`.
+    pub range: Range<usize>,
+    pub kind: SpanKind,
+}
+
+/// Records which bytes each field of a parser was decoded from.
+///
+/// The recorded layout can be exported as an ImHex pattern or a 010 Editor template,
+/// so the decoded structure can be inspected in a hex editor.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, Endian, FieldType, Recorder};
+///
+/// let mut bytes = ByteCode::new(&[0x00, 0x02, 0x66, 0x6f]);
+/// let mut recorder = Recorder::new(Endian::Big);
+/// recorder.begin(&bytes, "header", "Header");
+/// let len = recorder.field(&mut bytes, "len", FieldType::U16, |b| b.take_into_u16());
+/// recorder.field(&mut bytes, "name", FieldType::Char, |b| b.take_into_string(len as usize));
+/// recorder.end(&bytes);
+///
+/// assert!(recorder.to_imhex_pattern().contains("Header header @ 0x0;"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    endian: Endian,
+    spans: Vec<Span>,
+    open: Vec<(String, String, usize, Vec<Span>)>,
+}
+
+impl Recorder {
+    /// Creates an empty `Recorder` for a format with the given byte order.
+    pub fn new(endian: Endian) -> Self {
+        Recorder {
+            endian,
+            spans: Vec::new(),
+            open: Vec::new(),
+        }
+    }
+
+    /// Runs `read` on `bytes` and records the consumed bytes as a field.
+    pub fn field<'a, T>(
+        &mut self,
+        bytes: &mut ByteCode<'a>,
+        name: &str,
+        field_type: FieldType,
+        read: impl FnOnce(&mut ByteCode<'a>) -> T,
+    ) -> T {
+        let start = bytes.pos();
+        let value = read(bytes);
+        self.push(Span {
+            name: name.to_owned(),
+            range: start..bytes.pos(),
+            kind: SpanKind::Field(field_type),
+        });
+        value
+    }
+
+    /// Starts a struct at the current position. Fields recorded until the matching [`end`](Recorder::end)
+    /// become its members.
+    pub fn begin(&mut self, bytes: &ByteCode, name: &str, type_name: &str) {
+        self.open.push((
+            name.to_owned(),
+            type_name.to_owned(),
+            bytes.pos(),
+            Vec::new(),
+        ));
+    }
+
+    /// Ends the innermost struct at the current position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no struct started by [`begin`](Recorder::begin).
+    pub fn end(&mut self, bytes: &ByteCode) {
+        let (name, type_name, start, children) =
+            self.open.pop().expect("`end` called without `begin`");
+        self.push(Span {
+            name,
+            range: start..bytes.pos(),
+            kind: SpanKind::Struct {
+                type_name,
+                children,
+            },
+        });
+    }
+
+    /// Returns the completed top-level spans.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    fn push(&mut self, span: Span) {
+        match self.open.last_mut() {
+            Some((_, _, _, children)) => children.push(span),
+            None => self.spans.push(span),
+        }
+    }
+
+    /// Exports the recorded layout as an ImHex pattern.
+    ///
+    /// Top-level spans are placed at their recorded offsets, and gaps inside structs become `padding`.
+    pub fn to_imhex_pattern(&self) -> String {
+        let mut exporter = Exporter::new(Dialect::ImHex);
+        let mut body = String::new();
+        for span in &self.spans {
+            let declaration = exporter.declaration(span);
+            writeln!(body, "{} @ {:#x};", declaration, span.range.start).unwrap();
+        }
+        let endian = match self.endian {
+            Endian::Big => "big",
+            Endian::Little => "little",
+        };
+        format!(
+            "#pragma endian {}\n\n{}{}",
+            endian, exporter.definitions, body
+        )
+    }
+
+    /// Exports the recorded layout as a 010 Editor binary template.
+    ///
+    /// Top-level spans are placed with `FSeek`, and gaps inside structs are skipped with `FSkip`.
+    pub fn to_010_template(&self) -> String {
+        let mut exporter = Exporter::new(Dialect::Template);
+        let mut body = String::new();
+        for span in &self.spans {
+            let declaration = exporter.declaration(span);
+            writeln!(body, "FSeek({:#x});\n{};", span.range.start, declaration).unwrap();
+        }
+        let endian = match self.endian {
+            Endian::Big => "BigEndian();",
+            Endian::Little => "LittleEndian();",
+        };
+        format!("{}\n\n{}{}", endian, exporter.definitions, body)
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Starts recording the reads made through the `*_labeled` methods as [`Span`]s.
+    ///
+    /// Recording is off by default, and labelled reads behave like plain reads until it is started.
+    /// Child readers created from this one do not record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x2a, 0x00, 0x03]);
+    /// bytes.record_spans();
+    /// let irep_size = bytes.take_into_u32_labeled("irep_size");
+    /// let nlocals = bytes.take_into_u16_labeled("nlocals");
+    /// assert_eq!((irep_size, nlocals), (42, 3));
+    ///
+    /// let spans = bytes.spans();
+    /// assert_eq!((spans[0].name.as_str(), spans[0].range.clone()), ("irep_size", 0..4));
+    /// assert_eq!((spans[1].name.as_str(), spans[1].range.clone()), ("nlocals", 4..6));
+    /// ```
+    pub fn record_spans(&mut self) {
+        self.spans.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the spans recorded so far, in the order they were read.
+    pub fn spans(&self) -> &[Span] {
+        self.spans.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the spans recorded so far and clears them. Recording continues.
+    pub fn take_spans(&mut self) -> Vec<Span> {
+        self.spans.as_mut().map(core::mem::take).unwrap_or_default()
+    }
+
+    /// Runs `read` and records the bytes it consumed as a field named `label`,
+    /// typed as [`FieldType::Bytes`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"RITE0300");
+    /// bytes.record_spans();
+    /// let magic = bytes.labeled("magic", |bytes| bytes.take(4));
+    /// assert_eq!(magic, b"RITE");
+    /// assert_eq!(bytes.spans()[0].range, 0..4);
+    /// ```
+    pub fn labeled<T>(&mut self, label: &str, read: impl FnOnce(&mut Self) -> T) -> T {
+        self.record(label, FieldType::Bytes, read)
+    }
+
+    /// Returns the first byte, recording it as a field named `label`.
+    /// Moves the pointer forward 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty.
+    pub fn take_into_u8_labeled(&mut self, label: &str) -> u8 {
+        self.record(label, FieldType::U8, |bytes| bytes.take_into_u8())
+    }
+
+    /// Returns the first 2 bytes converted into `u16`, recording them as a field named `label`.
+    /// Moves the pointer forward 2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than 2 elements remain.
+    pub fn take_into_u16_labeled(&mut self, label: &str) -> u16 {
+        self.record(label, FieldType::U16, |bytes| bytes.take_into_u16())
+    }
+
+    /// Returns the first 4 bytes converted into `u32`, recording them as a field named `label`.
+    /// Moves the pointer forward 4.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than 4 elements remain.
+    pub fn take_into_u32_labeled(&mut self, label: &str) -> u32 {
+        self.record(label, FieldType::U32, |bytes| bytes.take_into_u32())
+    }
+
+    /// Returns the first 8 bytes converted into `u64`, recording them as a field named `label`.
+    /// Moves the pointer forward 8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than 8 elements remain.
+    pub fn take_into_u64_labeled(&mut self, label: &str) -> u64 {
+        self.record(label, FieldType::U64, |bytes| bytes.take_into_u64())
+    }
+
+    /// Returns a [`hexdump`](ByteCode::hexdump) with each recorded span labelled,
+    /// and colored in turn when color is on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0xff, 0xff]);
+    /// bytes.record_spans();
+    /// bytes.take_into_u16_labeled("version");
+    /// bytes.take_into_u16_labeled("flags");
+    /// assert_eq!(
+    ///     bytes.annotated_dump().ascii(false).to_string().lines().nth(1).unwrap(),
+    ///     "00000000 00 01 FF FF                                      version, flags"
+    /// );
+    /// ```
+    pub fn annotated_dump(&self) -> HexDump<'a> {
+        self.spans().iter().fold(self.hexdump(), |dump, span| {
+            dump.label(span.range.clone(), &span.name)
+        })
+    }
+
+    fn record<T>(
+        &mut self,
+        label: &str,
+        field_type: FieldType,
+        read: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let start = self.pos;
+        let value = read(self);
+        if let Some(spans) = &mut self.spans {
+            spans.push(Span {
+                name: label.to_owned(),
+                range: start..self.pos,
+                kind: SpanKind::Field(field_type),
+            });
+        }
+        value
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    ImHex,
+    Template,
+}
+
+struct Exporter {
+    dialect: Dialect,
+    definitions: String,
+    /// Struct bodies already emitted, by exported type name.
+    types: BTreeMap<String, String>,
+}
+
+impl Exporter {
+    fn new(dialect: Dialect) -> Self {
+        Exporter {
+            dialect,
+            definitions: String::new(),
+            types: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the declaration of `span` without a trailing `;`, emitting any struct definitions it needs.
+    fn declaration(&mut self, span: &Span) -> String {
+        let name = identifier(&span.name);
+        match &span.kind {
+            SpanKind::Field(field_type) => {
+                let (type_name, bytes_name) = match self.dialect {
+                    Dialect::ImHex => (field_type.imhex(), FieldType::Bytes.imhex()),
+                    Dialect::Template => (field_type.template(), FieldType::Bytes.template()),
+                };
+                let len = span.range.len();
+                match field_type.size() {
+                    Some(size) if size == len => format!("{} {}", type_name, name),
+                    Some(size) if len % size == 0 => {
+                        format!("{} {}[{}]", type_name, name, len / size)
+                    }
+                    // A read that does not cover whole elements is exported as raw bytes.
+                    Some(_) => format!("{} {}[{}]", bytes_name, name, len),
+                    None => format!("{} {}[{}]", type_name, name, len),
+                }
+            }
+            SpanKind::Struct {
+                type_name,
+                children,
+            } => {
+                let type_name = self.define(type_name, span.range.start, children);
+                format!("{} {}", type_name, name)
+            }
+        }
+    }
+
+    /// Emits a struct definition unless an identical one exists, and returns its exported name.
+    /// Structs sharing a name but not a layout get a numeric suffix.
+    fn define(&mut self, type_name: &str, start: usize, children: &[Span]) -> String {
+        let mut body = String::new();
+        let mut pos = start;
+        for child in children {
+            if child.range.start > pos {
+                let gap = child.range.start - pos;
+                match self.dialect {
+                    Dialect::ImHex => writeln!(body, "    padding[{}];", gap).unwrap(),
+                    Dialect::Template => writeln!(body, "    FSkip({});", gap).unwrap(),
+                }
+            }
+            let declaration = self.declaration(child);
+            writeln!(body, "    {};", declaration).unwrap();
+            pos = pos.max(child.range.end);
+        }
+
+        let base = identifier(type_name);
+        let mut exported = base.clone();
+        let mut suffix = 1;
+        loop {
+            match self.types.get(&exported) {
+                Some(existing) if *existing == body => return exported,
+                Some(_) => {
+                    exported = format!("{}_{}", base, suffix);
+                    suffix += 1;
+                }
+                None => break,
+            }
+        }
+        match self.dialect {
+            Dialect::ImHex => writeln!(self.definitions, "struct {} {{\n{}}};\n", exported, body),
+            Dialect::Template => {
+                writeln!(
+                    self.definitions,
+                    "typedef struct {{\n{}}} {};\n",
+                    body, exported
+                )
+            }
+        }
+        .unwrap();
+        self.types.insert(exported.clone(), body);
+        exported
+    }
+}
+
+/// Replaces characters that are not valid in identifiers of either language.
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+#[test]
+fn recorder_spans() {
+    let data = [
+        0x42, 0x43, 0x00, 0x02, // magic, count
+        0x01, 0x00, 0x00, 0x00, 0x10, // entry 0
+        0x02, 0x00, 0x00, 0x00, 0x20, // entry 1
+        0xff, 0xff, // unused
+        0x68, 0x69, // name
+    ];
+    let mut bytes = ByteCode::new(&data);
+    let mut recorder = Recorder::new(Endian::Big);
+    recorder.begin(&bytes, "header", "Header");
+    recorder.field(&mut bytes, "magic", FieldType::Char, |b| b.take(2));
+    let count = recorder.field(&mut bytes, "count", FieldType::U16, |b| b.take_into_u16());
+    recorder.end(&bytes);
+    for i in 0..count {
+        recorder.begin(&bytes, &format!("entry {}", i), "Entry");
+        recorder.field(&mut bytes, "kind", FieldType::U8, |b| b.take_into_u8());
+        recorder.field(&mut bytes, "offset", FieldType::U32, |b| b.take_into_u32());
+        recorder.end(&bytes);
+    }
+    bytes.skip(2);
+    recorder.field(&mut bytes, "name", FieldType::Char, |b| {
+        b.take_into_string(2)
+    });
+
+    let spans = recorder.spans();
+    assert_eq!(spans.len(), 4);
+    assert_eq!(spans[0].range, 0..4);
+    assert_eq!(spans[2].name, "entry 1");
+    assert_eq!(spans[2].range, 9..14);
+    assert_eq!(spans[3].range, 16..18);
+    let SpanKind::Struct { children, .. } = &spans[0].kind else {
+        panic!("expected a struct: {:?}", spans[0]);
+    };
+    assert_eq!(children[1].range, 2..4);
+    assert_eq!(children[1].kind, SpanKind::Field(FieldType::U16));
+
+    assert_eq!(
+        recorder.to_imhex_pattern(),
+        "#pragma endian big\n\n\
+         struct Header {\n    char magic[2];\n    u16 count;\n};\n\n\
+         struct Entry {\n    u8 kind;\n    u32 offset;\n};\n\n\
+         Header header @ 0x0;\n\
+         Entry entry_0 @ 0x4;\n\
+         Entry entry_1 @ 0x9;\n\
+         char name[2] @ 0x10;\n"
+    );
+}
+
+#[test]
+fn recorder_to_010_template() {
+    let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00, 0x02, 0x00, 0x03]);
+    let mut recorder = Recorder::new(Endian::Little);
+    recorder.begin(&bytes, "a", "Item");
+    recorder.field(&mut bytes, "x", FieldType::U8, |b| b.take_into_u8());
+    recorder.end(&bytes);
+    recorder.begin(&bytes, "b", "Item");
+    bytes.skip(1);
+    recorder.field(&mut bytes, "x", FieldType::I16, |b| b.take(2));
+    recorder.end(&bytes);
+    assert_eq!(
+        recorder.to_010_template(),
+        "LittleEndian();\n\n\
+         typedef struct {\n    uchar x;\n} Item;\n\n\
+         typedef struct {\n    FSkip(1);\n    short x;\n} Item_1;\n\n\
+         FSeek(0x0);\nItem a;\n\
+         FSeek(0x1);\nItem_1 b;\n"
+    );
+}
+
+#[test]
+fn recorder_partial_elements() {
+    let mut bytes = ByteCode::new(&[0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04]);
+    let mut recorder = Recorder::new(Endian::Big);
+    recorder.field(&mut bytes, "words", FieldType::U16, |b| b.take(4));
+    recorder.field(&mut bytes, "odd", FieldType::U16, |b| b.take(3));
+    assert_eq!(
+        recorder.to_imhex_pattern(),
+        "#pragma endian big\n\n\
+         u16 words[2] @ 0x0;\n\
+         u8 odd[3] @ 0x4;\n"
+    );
+    assert_eq!(
+        recorder.to_010_template(),
+        "BigEndian();\n\n\
+         FSeek(0x0);\nushort words[2];\n\
+         FSeek(0x4);\nuchar odd[3];\n"
+    );
+}
+
+#[test]
+fn labeled_reads() {
+    let mut bytes = ByteCode::new(&[0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0xff]);
+    assert_eq!(bytes.take_into_u8_labeled("off"), 1);
+    assert!(bytes.spans().is_empty());
+
+    bytes.record_spans();
+    assert_eq!(bytes.take_into_u16_labeled("a"), 2);
+    let checkpoint = bytes.checkpoint();
+    assert_eq!(bytes.take_into_u32_labeled("b"), 3);
+    bytes.restore(checkpoint);
+    assert_eq!(
+        bytes.labeled("c", |bytes| bytes.take(5)),
+        [0, 0, 0, 3, 0xff]
+    );
+    assert_eq!(
+        bytes.take_spans(),
+        [
+            Span {
+                name: "a".to_owned(),
+                range: 1..3,
+                kind: SpanKind::Field(FieldType::U16),
+            },
+            Span {
+                name: "c".to_owned(),
+                range: 3..8,
+                kind: SpanKind::Field(FieldType::Bytes),
+            },
+        ]
+    );
+    assert!(bytes.spans().is_empty());
+    bytes.reset();
+    bytes.take_into_u8_labeled("again");
+    assert_eq!(bytes.spans().len(), 1);
+}
@@ -0,0 +1,200 @@
+use alloc::vec::Vec;
+
+use crate::{ByteCode, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Returns the first byte with its bit order reversed.
+    /// Moves the pointer forward 1.
+    ///
+    /// Useful for data shifted out LSB-first, such as reflected CRCs and some serial captures.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0b0000_0001]);
+    /// assert_eq!(bytes.take_u8_bitrev(), 0b1000_0000);
+    /// ```
+    pub fn take_u8_bitrev(&mut self) -> u8 {
+        self.try_take_u8_bitrev()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first byte with its bit order reversed.
+    /// Moves the pointer forward 1. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_u8_bitrev`](ByteCode::take_u8_bitrev).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0b0000_0001]);
+    /// assert_eq!(bytes.try_take_u8_bitrev().unwrap(), 0b1000_0000);
+    /// assert!(bytes.try_take_u8_bitrev().is_err());
+    /// ```
+    pub fn try_take_u8_bitrev(&mut self) -> Result<u8, Error> {
+        Ok(self.try_take_into_u8()?.reverse_bits())
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16`, with all 16 bits reversed.
+    /// Moves the pointer forward 2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01]);
+    /// assert_eq!(bytes.take_u16_bitrev(), 0x8000);
+    /// ```
+    pub fn take_u16_bitrev(&mut self) -> u16 {
+        self.try_take_u16_bitrev()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16`, with all 16 bits reversed.
+    /// Moves the pointer forward 2. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_u16_bitrev`](ByteCode::take_u16_bitrev).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00]);
+    /// assert_eq!(bytes.try_take_u16_bitrev().unwrap(), 0x8000);
+    /// assert!(bytes.try_take_u16_bitrev().is_err());
+    /// ```
+    pub fn try_take_u16_bitrev(&mut self) -> Result<u16, Error> {
+        Ok(self.try_take_into_u16()?.reverse_bits())
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32`, with all 32 bits reversed.
+    /// Moves the pointer forward 4.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x03]);
+    /// assert_eq!(bytes.take_u32_bitrev(), 0xc000_0000);
+    /// ```
+    pub fn take_u32_bitrev(&mut self) -> u32 {
+        self.try_take_u32_bitrev()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32`, with all 32 bits reversed.
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_u32_bitrev`](ByteCode::take_u32_bitrev).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x03, 0x00]);
+    /// assert_eq!(bytes.try_take_u32_bitrev().unwrap(), 0xc000_0000);
+    /// assert!(bytes.try_take_u32_bitrev().is_err());
+    /// ```
+    pub fn try_take_u32_bitrev(&mut self) -> Result<u32, Error> {
+        Ok(self.try_take_into_u32()?.reverse_bits())
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size,
+    /// with the bit order of each byte reversed.
+    /// Moves the pointer forward by the length of subslice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x80, 0x0f]);
+    /// assert_eq!(bytes.take_bitrev(3), [0x80, 0x01, 0xf0]);
+    /// ```
+    pub fn take_bitrev(&mut self, num: usize) -> Vec<u8> {
+        self.try_take_bitrev(num)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size,
+    /// with the bit order of each byte reversed.
+    /// Moves the pointer forward by the length of subslice. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_bitrev`](ByteCode::take_bitrev).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x80, 0x0f]);
+    /// assert_eq!(bytes.try_take_bitrev(2).unwrap(), [0x80, 0x01]);
+    /// assert!(bytes.try_take_bitrev(2).is_err());
+    /// ```
+    pub fn try_take_bitrev(&mut self, num: usize) -> Result<Vec<u8>, Error> {
+        let mut result = self.try_take(num)?;
+        reverse_bits_per_byte(&mut result);
+        Ok(result)
+    }
+}
+
+/// Reverses the bit order of each byte in place.
+///
+/// # Examples
+///
+/// ```
+/// let mut data = [0x01, 0x02];
+/// bytecode::reverse_bits_per_byte(&mut data);
+/// assert_eq!(data, [0x80, 0x40]);
+/// ```
+pub fn reverse_bits_per_byte(data: &mut [u8]) {
+    for byte in data {
+        *byte = byte.reverse_bits();
+    }
+}
+
+/// Reverses the bit order of each `width`-byte word in place, mirroring all of its bits.
+/// A trailing partial word is reversed as a shorter word.
+///
+/// # Panics
+///
+/// Panics if `width` is 0.
+///
+/// # Examples
+///
+/// ```
+/// let mut data = [0x00, 0x01, 0x80, 0x00];
+/// bytecode::reverse_bits_per_word(&mut data, 2);
+/// assert_eq!(data, [0x80, 0x00, 0x00, 0x01]);
+/// ```
+pub fn reverse_bits_per_word(data: &mut [u8], width: usize) {
+    for word in data.chunks_mut(width) {
+        word.reverse();
+        reverse_bits_per_byte(word);
+    }
+}
+
+#[test]
+fn take_bitrev() {
+    let mut bytes = ByteCode::new(&[0xb0, 0x12, 0x34, 0x00, 0x00, 0x00, 0x01, 0xf0, 0x0f]);
+    assert_eq!(bytes.take_u8_bitrev(), 0x0d);
+    assert_eq!(bytes.take_u16_bitrev(), 0x2c48);
+    assert_eq!(bytes.take_u32_bitrev(), 0x8000_0000);
+    assert_eq!(bytes.take_bitrev(2), [0x0f, 0xf0]);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn reverse_bits_per_word_partial() {
+    let mut data = [0x01, 0x00, 0x00, 0x00, 0x03, 0x80];
+    reverse_bits_per_word(&mut data, 4);
+    assert_eq!(data, [0x00, 0x00, 0x00, 0x80, 0x01, 0xc0]);
+}
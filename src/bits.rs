@@ -0,0 +1,237 @@
+use alloc::format;
+
+use crate::{ByteCode, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Returns a view reading bits most significant first.
+    ///
+    /// The view shares the position of this `ByteCode`: the pointer moves forward
+    /// once all 8 bits of a byte are taken. Dropping the view partway through a byte
+    /// consumes the rest of that byte, so byte reads always resume on a byte boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0b1010_0110, 0xff, 0x12]);
+    /// let mut bits = bytes.bits();
+    /// assert_eq!(bits.take_bits(3), 0b101);
+    /// assert_eq!(bits.take_bits(9), 0b0_0110_1111);
+    /// drop(bits);
+    /// assert_eq!(bytes.take_into_u8(), 0x12);
+    /// ```
+    pub fn bits<'b>(&'b mut self) -> BitReader<'b, 'a> {
+        BitReader {
+            bytes: self,
+            offset: 0,
+        }
+    }
+}
+
+/// A view over a [`ByteCode`] reading bits most significant first, created by [`ByteCode::bits`].
+#[derive(Debug)]
+pub struct BitReader<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    /// The number of bits of the current byte that have been taken, in `0..8`.
+    offset: usize,
+}
+
+impl BitReader<'_, '_> {
+    /// Returns the next `num` bits as the low bits of a `u64` without moving forward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` is greater than 64 or fewer than `num` bits are left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xf0]);
+    /// let bits = bytes.bits();
+    /// assert_eq!(bits.peek_bits(5), 0b11110);
+    /// assert_eq!(bits.peek_bits(5), 0b11110);
+    /// ```
+    pub fn peek_bits(&self, num: usize) -> u64 {
+        self.try_peek_bits(num)
+            .unwrap_or_else(|error| self.bytes.fail(error))
+    }
+
+    /// Returns the next `num` bits as the low bits of a `u64` without moving forward.
+    ///
+    /// Fallible version of [`peek_bits`](BitReader::peek_bits),
+    /// which also reports a `num` greater than 64 as [`Error::InvalidArgument`].
+    pub fn try_peek_bits(&self, num: usize) -> Result<u64, Error> {
+        if num > 64 {
+            return Err(Error::InvalidArgument {
+                pos: self.bytes.pos(),
+                message: format!("cannot read {} bits into u64", num),
+            });
+        }
+        if num > self.remaining() {
+            return Err(Error::OutOfBounds {
+                pos: self.bytes.pos(),
+                requested: (self.offset + num).div_ceil(8),
+                available: self.bytes.as_slice().len(),
+            });
+        }
+        let slice = self.bytes.as_slice();
+        Ok((self.offset..self.offset + num).fold(0, |acc, i| {
+            let bit = (slice[i / 8] >> (7 - i % 8)) & 1;
+            (acc << 1) | bit as u64
+        }))
+    }
+
+    /// Returns the next `num` bits as the low bits of a `u64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` is greater than 64 or fewer than `num` bits are left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12, 0x34]);
+    /// let mut bits = bytes.bits();
+    /// assert_eq!(bits.take_bits(4), 0x1);
+    /// assert_eq!(bits.take_bits(12), 0x234);
+    /// ```
+    pub fn take_bits(&mut self, num: usize) -> u64 {
+        self.try_take_bits(num)
+            .unwrap_or_else(|error| self.bytes.fail(error))
+    }
+
+    /// Returns the next `num` bits as the low bits of a `u64`. Nothing is consumed on error.
+    ///
+    /// Fallible version of [`take_bits`](BitReader::take_bits).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12]);
+    /// let mut bits = bytes.bits();
+    /// assert!(bits.try_take_bits(65).is_err());
+    /// assert_eq!(bits.try_take_bits(4).unwrap(), 0x1);
+    /// assert!(bits.try_take_bits(5).is_err());
+    /// assert_eq!(bits.try_take_bits(4).unwrap(), 0x2);
+    /// ```
+    pub fn try_take_bits(&mut self, num: usize) -> Result<u64, Error> {
+        let value = self.try_peek_bits(num)?;
+        let end = self.offset + num;
+        self.bytes.skip(end / 8);
+        self.offset = end % 8;
+        Ok(value)
+    }
+
+    /// Returns the next bit as a `bool`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no bits left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x80]);
+    /// let mut bits = bytes.bits();
+    /// assert!(bits.take_bit());
+    /// assert!(!bits.take_bit());
+    /// ```
+    pub fn take_bit(&mut self) -> bool {
+        self.take_bits(1) == 1
+    }
+
+    /// Returns `true` if the next bit starts a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Skips the rest of the current byte, if any of its bits have been taken.
+    pub fn align_to_byte(&mut self) {
+        if self.offset != 0 {
+            self.bytes.next();
+            self.offset = 0;
+        }
+    }
+
+    /// Returns the number of bits left.
+    pub fn remaining(&self) -> usize {
+        self.bytes.as_slice().len() * 8 - self.offset
+    }
+}
+
+impl Drop for BitReader<'_, '_> {
+    fn drop(&mut self) {
+        self.align_to_byte();
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn bits() {
+    let mut bytes = ByteCode::new(&[0x00, 0b1100_1010, 0b0101_1111, 0xab]);
+    bytes.next();
+    let mut bits = bytes.bits();
+    assert_eq!(bits.remaining(), 24);
+    assert!(bits.take_bit());
+    assert_eq!(bits.take_bits(0), 0);
+    assert_eq!(bits.take_bits(2), 0b10);
+    assert!(!bits.is_aligned());
+    assert_eq!(bits.remaining(), 21);
+    assert_eq!(bits.take_bits(7), 0b010_1001);
+    assert_eq!(bits.peek_bits(2), 0b01);
+    bits.align_to_byte();
+    assert!(bits.is_aligned());
+    assert_eq!(bits.take_bits(8), 0xab);
+    assert_eq!(bits.remaining(), 0);
+    drop(bits);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn bits_interleaved() {
+    let mut bytes = ByteCode::new(&[0x01, 0b1110_0000, 0x02, 0xff]);
+    assert_eq!(bytes.take_into_u8(), 0x01);
+    let flags = bytes.bits().take_bits(3);
+    assert_eq!(flags, 0b111);
+    assert_eq!(bytes.take_into_u8(), 0x02);
+    assert_eq!(bytes.pos(), 3);
+}
+
+#[test]
+fn bits_wide() {
+    let data: Vec<u8> = (1..=9).collect();
+    let mut bytes = ByteCode::new(&data);
+    let mut bits = bytes.bits();
+    bits.take_bits(4);
+    assert_eq!(bits.take_bits(64), 0x1020_3040_5060_7080);
+    assert_eq!(bits.take_bits(4), 0x9);
+    drop(bits);
+    assert!(bytes.is_end());
+}
+
+#[test]
+#[should_panic]
+fn bits_out_of_range() {
+    let mut bytes = ByteCode::new(&[0xff]);
+    let mut bits = bytes.bits();
+    bits.take_bits(5);
+    bits.take_bits(4);
+}
+
+#[test]
+#[should_panic]
+fn bits_too_wide() {
+    let mut bytes = ByteCode::new(&[0xff; 16]);
+    bytes.bits().take_bits(65);
+}
@@ -0,0 +1,223 @@
+use alloc::format;
+
+use crate::{ByteCode, Endian, Error};
+
+/// Layout of repeated length-prefixed blocks.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{Block, Endian};
+///
+/// let layout = Block::new(4).endian(Endian::Little).inclusive().zero_terminated();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    length_width: usize,
+    endian: Endian,
+    inclusive: bool,
+    zero_terminated: bool,
+}
+
+impl Block {
+    /// Creates a new big-endian `Block` layout with the given length width in bytes,
+    /// where the length counts the body only.
+    ///
+    /// The width must be in `1..=8`; it is checked by [`ByteCode::blocks`].
+    pub fn new(length_width: usize) -> Self {
+        Block {
+            length_width,
+            endian: Endian::Big,
+            inclusive: false,
+            zero_terminated: false,
+        }
+    }
+
+    /// Sets the byte order of the length field.
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Makes the length count the length field itself as well as the body.
+    pub fn inclusive(mut self) -> Self {
+        self.inclusive = true;
+        self
+    }
+
+    /// Makes a length of 0 end the sequence. The terminating length field is consumed.
+    pub fn zero_terminated(mut self) -> Self {
+        self.zero_terminated = true;
+        self
+    }
+
+    fn check(&self, pos: usize) -> Result<(), Error> {
+        if self.length_width == 0 || self.length_width > 8 {
+            return Err(Error::InvalidArgument {
+                pos,
+                message: format!("block length width {} must be in 1..=8", self.length_width),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over length-prefixed blocks.
+///
+/// This struct is created by [`ByteCode::blocks`].
+pub struct BlockIter<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    layout: Block,
+    done: bool,
+}
+
+impl<'a> Iterator for BlockIter<'_, 'a> {
+    type Item = ByteCode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let layout = self.layout;
+        let width = layout.length_width;
+        let inner: &'a [u8] = self.bytes.inner;
+        if self.done || inner.len() < width {
+            return None;
+        }
+        let len = usize::try_from(layout.endian.read_uint(&inner[..width])).ok()?;
+        if len == 0 && layout.zero_terminated {
+            self.bytes.skip(width);
+            self.done = true;
+            return None;
+        }
+        let len = match layout.inclusive {
+            true => len.checked_sub(width)?,
+            false => len,
+        };
+        if len > inner.len() - width {
+            return None;
+        }
+        self.bytes.skip(width + len);
+        Some(self.bytes.child(&inner[width..width + len], 0))
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns an iterator over length-prefixed blocks with the given layout,
+    /// yielding a new `ByteCode` over each body.
+    /// Moves the pointer forward past each block as it is yielded.
+    ///
+    /// Each body reader inherits the byte order, strictness and panic style of this one.
+    ///
+    /// The iteration stops at the end of the slice, after a zero terminator if the layout has one,
+    /// or at a block whose length field or body is truncated or whose inclusive length is too small,
+    /// in which case the pointer is left at the beginning of that block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length width of the layout is 0 or greater than 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{Block, ByteCode};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x02, 0x66, 0x6f, 0x00, 0x01, 0x6f, 0x00, 0x00, 0xff]);
+    /// let blocks: Vec<_> = bytes
+    ///     .blocks(Block::new(2).zero_terminated())
+    ///     .map(|block| block.as_slice())
+    ///     .collect();
+    /// assert_eq!(blocks, [&[0x66, 0x6f][..], &[0x6f][..]]);
+    /// assert_eq!(bytes.pos(), 9);
+    /// ```
+    pub fn blocks<'b>(&'b mut self, layout: Block) -> BlockIter<'b, 'a> {
+        if let Err(error) = layout.check(self.pos) {
+            self.fail(error);
+        }
+        BlockIter {
+            bytes: self,
+            layout,
+            done: false,
+        }
+    }
+
+    /// Returns an iterator over length-prefixed blocks with the given layout.
+    ///
+    /// Fallible version of [`blocks`](ByteCode::blocks),
+    /// which reports an invalid layout as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{Block, ByteCode};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0xaa]);
+    /// assert!(bytes.try_blocks(Block::new(9)).is_err());
+    /// assert_eq!(bytes.try_blocks(Block::new(1)).unwrap().count(), 1);
+    /// ```
+    pub fn try_blocks<'b>(&'b mut self, layout: Block) -> Result<BlockIter<'b, 'a>, Error> {
+        layout.check(self.pos)?;
+        Ok(BlockIter {
+            bytes: self,
+            layout,
+            done: false,
+        })
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn blocks() {
+    let mut bytes = ByteCode::new(&[0x01, 0xaa, 0x00, 0x02, 0xbb, 0xcc]);
+    let blocks: Vec<_> = bytes
+        .blocks(Block::new(1))
+        .map(|block| block.as_slice())
+        .collect();
+    assert_eq!(blocks, [&[0xaa][..], &[][..], &[0xbb, 0xcc][..]]);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn blocks_inclusive_little_endian() {
+    let mut bytes = ByteCode::new(&[
+        0x05, 0x00, 0x00, 0x00, 0xaa, //
+        0x04, 0x00, 0x00, 0x00, //
+        0x02, 0x00, 0x00, 0x00,
+    ]);
+    let mut blocks = bytes.blocks(Block::new(4).endian(Endian::Little).inclusive());
+    assert_eq!(blocks.next().unwrap().as_slice(), [0xaa]);
+    assert!(blocks.next().unwrap().is_end());
+    assert!(blocks.next().is_none());
+    assert_eq!(bytes.pos(), 9);
+}
+
+#[test]
+fn blocks_inherit_settings() {
+    let mut bytes = ByteCode::new(&[0x02, 0x01, 0x00]);
+    bytes.set_endian(Endian::Little);
+    bytes.set_strictness(crate::Strictness::Strict);
+    bytes.set_panic_style(crate::PanicStyle::Context);
+    let mut block = bytes.blocks(Block::new(1)).next().unwrap();
+    assert_eq!(block.endian(), Endian::Little);
+    assert_eq!(block.strictness(), crate::Strictness::Strict);
+    assert_eq!(block.panic_style(), crate::PanicStyle::Context);
+    assert_eq!(block.take_into_u16(), 1);
+}
+
+#[test]
+fn blocks_truncated() {
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0xaa, 0x00, 0x03, 0xbb]);
+    assert_eq!(bytes.blocks(Block::new(2)).count(), 1);
+    assert_eq!(bytes.pos(), 3);
+
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x01]);
+    let mut blocks = bytes.blocks(Block::new(2).zero_terminated());
+    assert!(blocks.next().is_none());
+    assert!(blocks.next().is_none());
+    assert_eq!(bytes.pos(), 2);
+}
+
+#[test]
+#[should_panic]
+fn blocks_invalid_layout() {
+    ByteCode::new(&[]).blocks(Block::new(9));
+}
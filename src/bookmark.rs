@@ -0,0 +1,101 @@
+use alloc::{borrow::ToOwned, string::String};
+
+use crate::{ByteCode, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Records the current pointer position under `name`, replacing any earlier bookmark with the same name.
+    ///
+    /// Bookmarks let multi-pass parsers return to sections found earlier without keeping offsets around.
+    /// Child readers created from this one start with no bookmarks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"\x01\x02main\0");
+    /// bytes.skip(2);
+    /// bytes.mark("string_table");
+    /// assert_eq!(bytes.take_cstring(), "main");
+    /// bytes.jump_to("string_table");
+    /// assert_eq!(bytes.pos(), 2);
+    /// assert_eq!(bytes.bookmark("string_table"), Some(2));
+    /// ```
+    pub fn mark(&mut self, name: impl Into<String>) {
+        self.bookmarks.insert(name.into(), self.pos);
+    }
+
+    /// Returns the position recorded under `name`, if any.
+    pub fn bookmark(&self, name: &str) -> Option<usize> {
+        self.bookmarks.get(name).copied()
+    }
+
+    /// Moves the pointer to the position recorded under `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no bookmark with the name.
+    pub fn jump_to(&mut self, name: &str) {
+        self.try_jump_to(name)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Moves the pointer to the position recorded under `name`. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`jump_to`](ByteCode::jump_to).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    /// assert_eq!(
+    ///     bytes.try_jump_to("code"),
+    ///     Err(Error::UnknownBookmark { pos: 0, name: "code".to_owned() })
+    /// );
+    /// ```
+    pub fn try_jump_to(&mut self, name: &str) -> Result<(), Error> {
+        let offset = self.bookmark(name).ok_or_else(|| Error::UnknownBookmark {
+            pos: self.pos,
+            name: name.to_owned(),
+        })?;
+        self.seek(offset);
+        Ok(())
+    }
+}
+
+#[test]
+fn mark_and_jump() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes.skip(2);
+    bytes.mark("a");
+    bytes.skip(4);
+    bytes.mark("b");
+    bytes.jump_to("a");
+    assert_eq!(bytes.pos(), 2);
+    bytes.jump_to("b");
+    assert_eq!(bytes.take_into_u8(), 6);
+
+    bytes.mark("a");
+    bytes.reset();
+    bytes.jump_to("a");
+    assert_eq!(bytes.pos(), 7);
+}
+
+#[test]
+fn jump_to_unknown() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    bytes.skip(1);
+    bytes.mark("a");
+    let child = bytes.take_bytecode(2);
+    assert_eq!(child.bookmark("a"), None);
+    assert!(bytes.try_jump_to("b").is_err());
+    assert_eq!(bytes.pos(), 3);
+}
+
+#[test]
+#[should_panic]
+fn jump_to_unknown_panics() {
+    ByteCode::new(&[0]).jump_to("missing");
+}
@@ -0,0 +1,85 @@
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::ByteCode;
+
+// Implemented for `&mut ByteCode` rather than `ByteCode`, so that `Buf::take` and `Buf::chain`
+// never shadow the inherent methods of the same name, as with `io::Read`.
+
+/// Consumes from the pointer, so the same parsing code can run over `bytes` buffers and plain slices.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Buf;
+///
+/// use bytecode::ByteCode;
+///
+/// fn parse(mut buf: impl Buf) -> (u8, u16) {
+///     (buf.get_u8(), buf.get_u16_le())
+/// }
+///
+/// let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03, 0x04]);
+/// assert_eq!(parse(&mut bytes), (0x01, 0x0302));
+/// assert_eq!(bytes.pos(), 3);
+/// ```
+impl Buf for &mut ByteCode<'_> {
+    fn remaining(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.inner
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        **self += cnt;
+    }
+}
+
+impl<'a> From<&'a Bytes> for ByteCode<'a> {
+    /// Creates a new `ByteCode` over the contents of a [`Bytes`].
+    fn from(bytes: &'a Bytes) -> Self {
+        ByteCode::new(bytes)
+    }
+}
+
+impl<'a> From<&'a BytesMut> for ByteCode<'a> {
+    /// Creates a new `ByteCode` over the contents of a [`BytesMut`].
+    fn from(bytes: &'a BytesMut) -> Self {
+        ByteCode::new(bytes)
+    }
+}
+
+#[test]
+fn buf() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes.skip(1);
+    let mut buf = &mut bytes;
+    assert_eq!(buf.remaining(), 7);
+    assert_eq!(buf.chunk(), [1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(buf.get_u16(), 0x0102);
+    buf.advance(2);
+    assert_eq!(buf.copy_to_bytes(2), Bytes::from_static(&[5, 6]));
+    assert_eq!(buf.remaining(), 1);
+    assert_eq!(bytes.pos, 7);
+    assert_eq!(bytes.take_into_u8(), 7);
+}
+
+#[test]
+#[should_panic]
+fn advance_out_of_range() {
+    let mut bytes = ByteCode::new(&[0, 1, 2]);
+    (&mut bytes).advance(4);
+}
+
+#[test]
+fn from_bytes() {
+    let shared = Bytes::from_static(b"\x00\x2afoo");
+    let mut bytes = ByteCode::from(&shared);
+    assert_eq!(bytes.take_into_u16(), 42);
+    assert_eq!(bytes.as_slice(), b"foo");
+
+    let owned = BytesMut::from(&b"bar"[..]);
+    let bytes = ByteCode::from(&owned);
+    assert_eq!(bytes.as_slice(), b"bar");
+}
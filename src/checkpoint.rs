@@ -0,0 +1,271 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{ByteCode, Endian, Error};
+
+/// A saved pointer position, byte order, and diagnostic and span counts, created by [`ByteCode::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pos: usize,
+    endian: Endian,
+    diagnostics: usize,
+    spans: usize,
+}
+
+impl Checkpoint {
+    /// Returns the saved pointer position.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Saves the current pointer position and byte order so they can be restored later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// bytes.skip(2);
+    /// let checkpoint = bytes.checkpoint();
+    /// bytes.skip(4);
+    /// bytes.restore(checkpoint);
+    /// assert_eq!(bytes.pos(), 2);
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            endian: self.endian,
+            diagnostics: self.diagnostics.len(),
+            spans: self.spans().len(),
+        }
+    }
+
+    /// Moves the pointer back (or forward) to a saved position and restores the saved byte order.
+    /// Diagnostics and [spans](ByteCode::spans) recorded since the checkpoint are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint is beyond the end of the slice,
+    /// which can only happen if it was taken from a different `ByteCode`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00]);
+    /// let checkpoint = bytes.checkpoint();
+    /// bytes.set_endian(Endian::Little);
+    /// assert_eq!(bytes.take_into_u16(), 1);
+    /// bytes.restore(checkpoint);
+    /// assert_eq!(bytes.take_into_u16(), 256);
+    /// ```
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        if checkpoint.pos < self.pos {
+            *self -= self.pos - checkpoint.pos;
+        } else {
+            *self += checkpoint.pos - self.pos;
+        }
+        self.endian = checkpoint.endian;
+        self.diagnostics.truncate(checkpoint.diagnostics);
+        if let Some(spans) = &mut self.spans {
+            spans.truncate(checkpoint.spans);
+        }
+    }
+
+    /// Runs `f`, restoring the pointer position and byte order if it returns an error.
+    ///
+    /// Nested transactions roll back independently, which makes backtracking parsers straightforward.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x03, 0x66, 0x6f]);
+    /// let result = bytes.transaction(|b| {
+    ///     let len = b.try_take_into_u16()? as usize;
+    ///     b.try_take_into_string(len)
+    /// });
+    /// assert!(result.is_err());
+    /// assert_eq!(bytes.pos(), 0);
+    ///
+    /// let prefix = bytes.transaction(|b| b.try_take(2));
+    /// assert_eq!(prefix, Ok(vec![0x00, 0x03]));
+    /// assert_eq!(bytes.pos(), 2);
+    /// ```
+    pub fn transaction<T, E>(
+        &mut self,
+        f: impl FnOnce(&mut ByteCode<'a>) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let checkpoint = self.checkpoint();
+        let result = f(self);
+        if result.is_err() {
+            self.restore(checkpoint);
+        }
+        result
+    }
+
+    /// Returns a guard that panics when dropped if the pointer has moved in the meantime.
+    ///
+    /// The guard dereferences to this `ByteCode`, so it can be passed to helpers that are meant
+    /// to only peek, as a debugging aid for checking that they really do not consume input.
+    /// Use [`FrozenPosition::release`] to get an error instead of a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// fn is_header(bytes: &mut ByteCode) -> bool {
+    ///     bytes.starts_with(b"HD")
+    /// }
+    ///
+    /// let mut bytes = ByteCode::new(b"HD\x01");
+    /// let mut frozen = bytes.freeze_position();
+    /// assert!(is_header(&mut frozen));
+    /// assert!(frozen.release().is_ok());
+    /// ```
+    ///
+    /// ```should_panic
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// let mut frozen = bytes.freeze_position();
+    /// frozen.skip(1);
+    /// drop(frozen); // panics
+    /// ```
+    pub fn freeze_position<'b>(&'b mut self) -> FrozenPosition<'b, 'a> {
+        FrozenPosition {
+            pos: self.pos,
+            bytes: self,
+        }
+    }
+}
+
+/// A guard asserting that the pointer does not move, created by [`ByteCode::freeze_position`].
+///
+/// # Panics
+///
+/// Panics when dropped if the pointer is not at the position it was frozen at.
+#[derive(Debug)]
+pub struct FrozenPosition<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    pos: usize,
+}
+
+impl FrozenPosition<'_, '_> {
+    /// Consumes the guard, returning an error instead of panicking if the pointer has moved.
+    pub fn release(self) -> Result<(), Error> {
+        let result = self.check();
+        core::mem::forget(self);
+        result
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        match self.bytes.pos == self.pos {
+            true => Ok(()),
+            false => Err(Error::PositionMoved {
+                pos: self.bytes.pos,
+                frozen: self.pos,
+            }),
+        }
+    }
+}
+
+impl<'a> Deref for FrozenPosition<'_, 'a> {
+    type Target = ByteCode<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.bytes
+    }
+}
+
+impl<'a> DerefMut for FrozenPosition<'_, 'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.bytes
+    }
+}
+
+impl Drop for FrozenPosition<'_, '_> {
+    fn drop(&mut self) {
+        if let Err(error) = self.check() {
+            #[cfg(feature = "std")]
+            if std::thread::panicking() {
+                return;
+            }
+            self.bytes.fail(error);
+        }
+    }
+}
+
+#[test]
+fn checkpoint_restore() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    let start = bytes.checkpoint();
+    bytes.skip(5);
+    let middle = bytes.checkpoint();
+    assert_eq!(middle.pos(), 5);
+    bytes.restore(start);
+    assert_eq!(bytes.pos(), 0);
+    bytes.restore(middle);
+    assert_eq!(bytes.pos(), 5);
+    assert_eq!(bytes.take_into_u8(), 5);
+}
+
+#[test]
+fn transaction_nested() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03]);
+    let result: Result<u8, &str> = bytes.transaction(|b| {
+        let first = b.take_into_u8();
+        let inner: Result<(), &str> = b.transaction(|b| {
+            b.skip(2);
+            Err("inner")
+        });
+        assert!(inner.is_err());
+        assert_eq!(b.pos(), 1);
+        b.set_endian(Endian::Little);
+        Err(if first == 1 { "outer" } else { "unexpected" })
+    });
+    assert_eq!(result, Err("outer"));
+    assert_eq!(bytes.pos(), 0);
+    assert_eq!(bytes.endian(), Endian::Big);
+}
+
+#[test]
+#[should_panic]
+fn restore_foreign_checkpoint() {
+    let mut long = ByteCode::new(&[0; 8]);
+    long.skip(8);
+    let checkpoint = long.checkpoint();
+    ByteCode::new(&[0; 4]).restore(checkpoint);
+}
+
+#[test]
+fn freeze_position() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    bytes.skip(1);
+    let mut frozen = bytes.freeze_position();
+    frozen.skip(2);
+    frozen.prev();
+    frozen.prev();
+    drop(frozen);
+
+    let mut frozen = bytes.freeze_position();
+    frozen.next();
+    assert_eq!(
+        frozen.release(),
+        Err(Error::PositionMoved { pos: 2, frozen: 1 })
+    );
+    assert_eq!(bytes.pos(), 2);
+}
+
+#[test]
+#[should_panic(expected = "position moved")]
+fn freeze_position_moved() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    let mut frozen = bytes.freeze_position();
+    frozen.take_into_u8();
+}
@@ -0,0 +1,158 @@
+use alloc::format;
+use core::ops::Range;
+
+use crate::{ByteCode, Error};
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC-32 (IEEE 802.3) of the given bytes, as used by zlib, PNG and GPT.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Computes the Adler-32 checksum of the given bytes, as used by zlib and VCDIFF.
+pub(crate) fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the largest run that cannot overflow `b` before reduction.
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns the elements from the absolute position `from_pos` up to the pointer,
+    /// such as a record just parsed, for hashing or verifying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from_pos` is after the pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x02, 0xaa, 0xbb, 0xcc]);
+    /// let start = bytes.pos();
+    /// let len = bytes.take_into_u16() as usize;
+    /// bytes.skip(len);
+    /// assert_eq!(bytes.consumed_since(start), [0x00, 0x02, 0xaa, 0xbb]);
+    /// ```
+    pub fn consumed_since(&self, from_pos: usize) -> &'a [u8] {
+        if from_pos > self.pos {
+            self.fail(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!(
+                    "position {:#x} is after the current position {:#x}",
+                    from_pos, self.pos
+                ),
+            });
+        }
+        &self.original()[from_pos..self.pos]
+    }
+
+    /// Computes the CRC-32 (IEEE 802.3) of the elements in `range`, given as absolute positions,
+    /// regardless of the pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"IEND\xae\x42\x60\x82");
+    /// let start = bytes.pos();
+    /// bytes.skip(4);
+    /// assert_eq!(bytes.crc32(start..bytes.pos()), bytes.take_into_u32());
+    /// ```
+    pub fn crc32(&self, range: Range<usize>) -> u32 {
+        crc32(self.original_range(range))
+    }
+
+    /// Computes the Adler-32 checksum of the elements in `range`, given as absolute positions,
+    /// regardless of the pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(b"Wikipedia");
+    /// assert_eq!(bytes.adler32(0..9), 0x11e6_0398);
+    /// ```
+    pub fn adler32(&self, range: Range<usize>) -> u32 {
+        adler32(self.original_range(range))
+    }
+}
+
+#[test]
+fn crc32_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    assert_eq!(crc32(b""), 0);
+}
+
+#[test]
+fn adler32_check_value() {
+    assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    assert_eq!(adler32(b""), 1);
+    assert_eq!(adler32(&[0xff; 100_000]), 0x149a_302c);
+}
+
+#[test]
+fn checksum_consumed() {
+    let mut bytes = ByteCode::new(b"xx123456789");
+    bytes.skip(2);
+    let start = bytes.pos();
+    bytes.skip(9);
+    assert_eq!(bytes.consumed_since(start), b"123456789");
+    assert_eq!(bytes.consumed_since(bytes.pos()), []);
+    assert_eq!(bytes.crc32(start..bytes.pos()), 0xcbf4_3926);
+    assert_eq!(bytes.adler32(0..0), 1);
+}
+
+#[test]
+#[should_panic]
+fn consumed_since_ahead() {
+    let bytes = ByteCode::new(&[0; 4]);
+    bytes.consumed_since(1);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds at 0x2: requested 4 bytes, but only 2 remain")]
+fn checksum_range_out_of_bounds() {
+    ByteCode::new(&[0; 4]).crc32(2..6);
+}
@@ -0,0 +1,384 @@
+use alloc::vec::Vec;
+
+use crate::{ByteCode, Error, FourCc};
+
+/// A type that can be read from a [`ByteCode`].
+///
+/// Implemented for the primitive integer and float types (in the byte order set by
+/// [`set_endian`](ByteCode::set_endian)), [`FourCc`], fixed-size arrays and tuples.
+/// Implement it for your own types to compose parsers with [`ByteCode::take_into`].
+/// With the `derive` feature, `#[derive(FromByteCode)]` implements it for structs field by field.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, Error, FromByteCode};
+///
+/// struct Header {
+///     magic: [u8; 2],
+///     version: (u8, u8),
+///     len: u16,
+/// }
+///
+/// impl FromByteCode<'_> for Header {
+///     fn from_bytecode(bytes: &mut ByteCode) -> Result<Self, Error> {
+///         Ok(Header {
+///             magic: bytes.try_take_into()?,
+///             version: bytes.try_take_into()?,
+///             len: bytes.try_take_into()?,
+///         })
+///     }
+/// }
+///
+/// let mut bytes = ByteCode::new(&[0x4d, 0x5a, 0x01, 0x02, 0x00, 0x10]);
+/// let header: Header = bytes.take_into();
+/// assert_eq!(header.magic, *b"MZ");
+/// assert_eq!(header.version, (1, 2));
+/// assert_eq!(header.len, 16);
+/// ```
+pub trait FromByteCode<'a>: Sized {
+    /// Reads a value, moving the pointer forward past it.
+    ///
+    /// Implementations provided by this crate do not move the pointer on error;
+    /// use [`ByteCode::try_take_into`] to get that guarantee for any implementation.
+    fn from_bytecode(bytes: &mut ByteCode<'a>) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_bytecode {
+    ($($ty:ty => $method:ident,)*) => {
+        $(
+            impl FromByteCode<'_> for $ty {
+                fn from_bytecode(bytes: &mut ByteCode) -> Result<Self, Error> {
+                    bytes.$method()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_bytecode! {
+    u8 => try_take_into_u8,
+    u16 => try_take_into_u16,
+    u32 => try_take_into_u32,
+    u64 => try_take_into_u64,
+    u128 => try_take_into_u128,
+    i8 => try_take_into_i8,
+    i16 => try_take_into_i16,
+    i32 => try_take_into_i32,
+    i64 => try_take_into_i64,
+    f32 => try_take_into_f32,
+    f64 => try_take_into_f64,
+    FourCc => try_take_fourcc,
+}
+
+impl<'a, T: FromByteCode<'a>, const N: usize> FromByteCode<'a> for [T; N] {
+    fn from_bytecode(bytes: &mut ByteCode<'a>) -> Result<Self, Error> {
+        bytes.transaction(|bytes| {
+            let mut items = Vec::with_capacity(N);
+            for _ in 0..N {
+                items.push(T::from_bytecode(bytes)?);
+            }
+            match items.try_into() {
+                Ok(array) => Ok(array),
+                Err(_) => unreachable!(),
+            }
+        })
+    }
+}
+
+macro_rules! impl_from_bytecode_tuple {
+    ($($name:ident)+) => {
+        impl<'a, $($name: FromByteCode<'a>),+> FromByteCode<'a> for ($($name,)+) {
+            fn from_bytecode(bytes: &mut ByteCode<'a>) -> Result<Self, Error> {
+                bytes.transaction(|bytes| Ok(($($name::from_bytecode(bytes)?,)+)))
+            }
+        }
+    };
+}
+
+impl_from_bytecode_tuple!(A);
+impl_from_bytecode_tuple!(A B);
+impl_from_bytecode_tuple!(A B C);
+impl_from_bytecode_tuple!(A B C D);
+impl_from_bytecode_tuple!(A B C D E);
+impl_from_bytecode_tuple!(A B C D E F);
+impl_from_bytecode_tuple!(A B C D E F G);
+impl_from_bytecode_tuple!(A B C D E F G H);
+
+/// A type whose encoded size is known at compile time.
+///
+/// Useful for preallocation and for the stride of fixed-size tables.
+/// Implemented for the same primitives, arrays and tuples as [`FromByteCode`];
+/// with the `derive` feature, `#[derive(FixedSize)]` implements it for structs.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::FixedSize;
+///
+/// assert_eq!(<(u32, [u16; 3])>::SIZE, 10);
+/// ```
+pub trait FixedSize {
+    /// The number of bytes the type occupies when read.
+    const SIZE: usize;
+}
+
+macro_rules! impl_fixed_size {
+    ($($ty:ty),*) => {
+        $(
+            impl FixedSize for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+            }
+        )*
+    };
+}
+
+impl_fixed_size!(u8, u16, u32, u64, u128, i8, i16, i32, i64, f32, f64);
+
+impl FixedSize for FourCc {
+    const SIZE: usize = 4;
+}
+
+impl<T: FixedSize, const N: usize> FixedSize for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+}
+
+macro_rules! impl_fixed_size_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: FixedSize),+> FixedSize for ($($name,)+) {
+            const SIZE: usize = 0 $(+ $name::SIZE)+;
+        }
+    };
+}
+
+impl_fixed_size_tuple!(A);
+impl_fixed_size_tuple!(A B);
+impl_fixed_size_tuple!(A B C);
+impl_fixed_size_tuple!(A B C D);
+impl_fixed_size_tuple!(A B C D E);
+impl_fixed_size_tuple!(A B C D E F);
+impl_fixed_size_tuple!(A B C D E F G);
+impl_fixed_size_tuple!(A B C D E F G H);
+
+/// Reads `count` values into a vector, as for a `#[bytecode(count = ...)]` field.
+#[doc(hidden)]
+pub fn take_vec<'a, T: FromByteCode<'a>>(
+    bytes: &mut ByteCode<'a>,
+    count: usize,
+) -> Result<Vec<T>, Error> {
+    // The count comes from the input, so do not trust it for the allocation.
+    let mut items = Vec::with_capacity(count.min(bytes.as_slice().len()));
+    for _ in 0..count {
+        items.push(T::from_bytecode(bytes)?);
+    }
+    Ok(items)
+}
+
+impl<'a> ByteCode<'a> {
+    /// Reads a value of any type implementing [`FromByteCode`].
+    /// Moves the pointer forward past it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0xff, 0x00, 0x00, 0x00, 0x02]);
+    /// let (a, b, c) = bytes.take_into::<(u16, i8, u32)>();
+    /// assert_eq!((a, b, c), (1, -1, 2));
+    /// ```
+    pub fn take_into<T: FromByteCode<'a>>(&mut self) -> T {
+        self.try_take_into()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Reads a value of any type implementing [`FromByteCode`].
+    /// Moves the pointer forward past it. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into`](ByteCode::take_into).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0x02]);
+    /// assert!(bytes.try_take_into::<[u16; 2]>().is_err());
+    /// assert_eq!(bytes.pos(), 0);
+    /// assert_eq!(bytes.try_take_into::<[u8; 3]>().unwrap(), [0, 1, 2]);
+    /// ```
+    pub fn try_take_into<T: FromByteCode<'a>>(&mut self) -> Result<T, Error> {
+        self.transaction(T::from_bytecode)
+    }
+
+    /// Reads a value of a type with a fixed encoded size, checking up front that
+    /// [`FixedSize::SIZE`] bytes remain. Moves the pointer forward by that size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer bytes remain, or if reading the value did not consume exactly
+    /// [`FixedSize::SIZE`] bytes, which means the two trait implementations disagree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, FixedSize};
+    ///
+    /// let data = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04];
+    /// let mut bytes = ByteCode::new(&data);
+    /// let mut entries = Vec::with_capacity(data.len() / <(u16, u16)>::SIZE);
+    /// while !bytes.is_end() {
+    ///     entries.push(bytes.take_exact_struct_size::<(u16, u16)>());
+    /// }
+    /// assert_eq!(entries, [(1, 2), (3, 4)]);
+    /// ```
+    pub fn take_exact_struct_size<T: FromByteCode<'a> + FixedSize>(&mut self) -> T {
+        self.try_take_exact_struct_size()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Reads a value of a type with a fixed encoded size, checking up front that
+    /// [`FixedSize::SIZE`] bytes remain. Moves the pointer forward by that size.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_exact_struct_size`](ByteCode::take_exact_struct_size).
+    /// Returns [`Error::Layout`] if reading the value did not consume exactly [`FixedSize::SIZE`] bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00]);
+    /// assert_eq!(
+    ///     bytes.try_take_exact_struct_size::<[u16; 2]>(),
+    ///     Err(Error::OutOfBounds { pos: 0, requested: 4, available: 3 })
+    /// );
+    /// ```
+    pub fn try_take_exact_struct_size<T: FromByteCode<'a> + FixedSize>(
+        &mut self,
+    ) -> Result<T, Error> {
+        self.try_peek(T::SIZE)?;
+        self.transaction(|bytes| {
+            let start = bytes.pos;
+            let value = T::from_bytecode(bytes)?;
+            if bytes.pos - start != T::SIZE {
+                return Err(Error::Layout {
+                    pos: bytes.pos,
+                    field: core::any::type_name::<T>().into(),
+                    expected: start + T::SIZE,
+                    start: false,
+                });
+            }
+            Ok(value)
+        })
+    }
+}
+
+#[test]
+fn take_into_primitives() {
+    use crate::Endian;
+
+    let mut bytes = ByteCode::new(&[
+        0x01, 0x02, 0x00, 0x00, 0x00, 0xc0, 0x3f, 0x66, 0x6f, 0x6f, 0x20,
+    ]);
+    assert_eq!(bytes.take_into::<u8>(), 1);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.take_into::<i16>(), 2);
+    assert_eq!(bytes.take_into::<f32>(), 1.5);
+    assert_eq!(bytes.take_into::<FourCc>(), "foo ");
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn take_into_composite() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]);
+    let value: ([(u8, u8); 2], u16, u8) = bytes.take_into();
+    assert_eq!(value, ([(1, 2), (3, 4)], 0x0506, 7));
+    assert_eq!(bytes.take_into::<[u32; 0]>(), []);
+}
+
+#[test]
+fn try_take_into_rollback() {
+    struct Unchecked(u8, u8);
+
+    impl FromByteCode<'_> for Unchecked {
+        fn from_bytecode(bytes: &mut ByteCode) -> Result<Self, Error> {
+            let first = bytes.try_take_into_u8()?;
+            Ok(Unchecked(first, bytes.try_take_into_u8()?))
+        }
+    }
+
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03]);
+    let Unchecked(a, b) = bytes.try_take_into().unwrap();
+    assert_eq!((a, b), (1, 2));
+    assert!(bytes.try_take_into::<Unchecked>().is_err());
+    assert_eq!(bytes.pos(), 2);
+    assert!(bytes.try_take_into::<(u8, u8)>().is_err());
+    assert_eq!(bytes.pos(), 2);
+}
+
+#[test]
+#[should_panic]
+fn take_into_out_of_range() {
+    let mut bytes = ByteCode::new(&[0x01]);
+    bytes.take_into::<(u8, u8)>();
+}
+
+#[test]
+fn fixed_size() {
+    assert_eq!(u8::SIZE, 1);
+    assert_eq!(f64::SIZE, 8);
+    assert_eq!(FourCc::SIZE, 4);
+    assert_eq!(<[(u16, u8); 4]>::SIZE, 12);
+    assert_eq!(<[u32; 0]>::SIZE, 0);
+}
+
+#[test]
+fn take_exact_struct_size() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03, 0x04, 0x05]);
+    assert_eq!(bytes.take_exact_struct_size::<(u8, u16)>(), (1, 0x0203));
+    assert!(bytes.try_take_exact_struct_size::<u32>().is_err());
+    assert_eq!(bytes.pos(), 3);
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct Lying;
+
+#[cfg(test)]
+impl FromByteCode<'_> for Lying {
+    fn from_bytecode(bytes: &mut ByteCode) -> Result<Self, Error> {
+        bytes.try_skip(1)?;
+        Ok(Lying)
+    }
+}
+
+#[cfg(test)]
+impl FixedSize for Lying {
+    const SIZE: usize = 2;
+}
+
+#[test]
+#[should_panic(expected = "but its layout ends at 0x2")]
+fn take_exact_struct_size_mismatch() {
+    ByteCode::new(&[0x00, 0x00]).take_exact_struct_size::<Lying>();
+}
+
+#[test]
+fn try_take_exact_struct_size_mismatch() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00]);
+    assert!(matches!(
+        bytes.try_take_exact_struct_size::<Lying>(),
+        Err(Error::Layout {
+            pos: 1,
+            expected: 2,
+            ..
+        })
+    ));
+    assert_eq!(bytes.pos(), 0);
+}
@@ -1,58 +1,39 @@
-use std::{
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::{
     fmt::Debug,
-    ops::{AddAssign, Index, SubAssign},
+    ops::{Add, AddAssign, Index, Range, Sub, SubAssign},
     slice::SliceIndex,
 };
 
-use tiny_ansi::TinyAnsi;
+use crate::{Diagnostic, Endian, Error, PanicStyle, Span, Strictness};
 
 pub struct ByteCode<'a> {
     pub(crate) inner: &'a [u8],
     pub(crate) pos: usize,
+    pub(crate) endian: Endian,
+    pub(crate) strictness: Strictness,
+    pub(crate) panic_style: PanicStyle,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    pub(crate) bookmarks: BTreeMap<String, usize>,
+    pub(crate) spans: Option<Vec<Span>>,
+    pub(crate) baseline: Option<&'a [u8]>,
 }
 
 impl Debug for ByteCode<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut result = String::new();
-        let header = "         00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F".cyan();
-        result.push_str(&header);
-        result.push('\n');
-
-        let current_pos = self.pos;
-        let mut replica = ByteCode {
-            inner: self.inner,
-            pos: self.pos,
-        };
-        replica.reset();
-
-        // If the amount of data is large, it may be better to use a lookup table.
-        let mut content: Vec<String> = replica
-            .inner
-            .iter()
-            .map(|byte| format!("{:02X}", byte))
-            .collect();
-        if let Some(byte) = content.get_mut(current_pos) {
-            *byte = byte.green();
-        }
-        for (i, line) in content.chunks(16).map(|line| line.join(" ")).enumerate() {
-            let line_number = {
-                let mut line_number = "0".repeat(8);
-                let hex = format!("{:02X}", i);
-                line_number.replace_range((8 - hex.len() - 1)..7, &hex);
-                line_number
-            };
-            result.push_str(&line_number);
-            result.push(' ');
-            result.push_str(&line);
-            result.push('\n');
-        }
-        write!(f, "\n{}", result)
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let dump = self.hexdump().ascii(false);
+        #[cfg(feature = "std")]
+        let dump = dump.color(true);
+        write!(f, "\n{}", dump)
     }
 }
 
 impl<'a> ByteCode<'a> {
     /// Creates a new `ByteCode`.
     ///
+    /// Multi-byte values are read in big-endian order until changed with [`set_endian`](ByteCode::set_endian),
+    /// and format violations are recorded as [diagnostics](ByteCode::diagnostics) until changed with [`set_strictness`](ByteCode::set_strictness).
+    ///
     /// # Examples
     ///
     /// ```
@@ -64,6 +45,13 @@ impl<'a> ByteCode<'a> {
         ByteCode {
             inner: slice,
             pos: 0,
+            endian: Endian::Big,
+            strictness: Strictness::Permissive,
+            panic_style: PanicStyle::Plain,
+            diagnostics: Vec::new(),
+            bookmarks: BTreeMap::new(),
+            spans: None,
+            baseline: None,
         }
     }
 
@@ -83,6 +71,94 @@ impl<'a> ByteCode<'a> {
         self.inner
     }
 
+    /// Extracts the whole slice this `ByteCode` was created over, including consumed elements.
+    ///
+    /// Unlike `&bytes[..]`, indices into it do not change meaning as the pointer moves,
+    /// so it suits offsets stored in headers and tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// bytes += 5;
+    /// assert_eq!(bytes.original()[2..4], [2, 3]);
+    /// assert_eq!(bytes[..], [5, 6, 7]);
+    /// ```
+    pub fn original(&self) -> &'a [u8] {
+        // SAFETY: `inner` always starts `pos` elements into the slice this `ByteCode` was created over.
+        unsafe {
+            let ptr = self.inner.as_ptr().sub(self.pos);
+            core::slice::from_raw_parts(ptr, self.inner.len() + self.pos)
+        }
+    }
+
+    /// Creates a `ByteCode` over `inner`, with the pointer reported as `pos`,
+    /// that inherits the byte order, strictness and panic style of this one but none of its
+    /// diagnostics, bookmarks, spans or baseline.
+    ///
+    /// `inner` must start `pos` elements into the slice the new `ByteCode` stands for.
+    pub(crate) fn child(&self, inner: &'a [u8], pos: usize) -> ByteCode<'a> {
+        ByteCode {
+            inner,
+            pos,
+            endian: self.endian,
+            strictness: self.strictness,
+            panic_style: self.panic_style,
+            diagnostics: Vec::new(),
+            bookmarks: BTreeMap::new(),
+            spans: None,
+            baseline: None,
+        }
+    }
+
+    /// Returns the elements in `range`, given as absolute positions, regardless of the pointer.
+    ///
+    /// Fails through the [panic style](ByteCode::set_panic_style) if `range` is out of range.
+    pub(crate) fn original_range(&self, range: Range<usize>) -> &'a [u8] {
+        let original = self.original();
+        if range.start > range.end {
+            self.fail(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!(
+                    "range start {:#x} is after its end {:#x}",
+                    range.start, range.end
+                ),
+            });
+        }
+        if range.end > original.len() {
+            self.fail(Error::OutOfBounds {
+                pos: range.start,
+                requested: range.len(),
+                available: original.len().saturating_sub(range.start),
+            });
+        }
+        &original[range]
+    }
+
+    /// Returns the element at an absolute index from the beginning of the slice, regardless of the pointer.
+    ///
+    /// Equivalent to `bytes.original()[index]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// bytes += 5;
+    /// assert_eq!(bytes.at_absolute(1), 1);
+    /// assert_eq!(bytes[1], 6);
+    /// ```
+    pub fn at_absolute(&self, index: usize) -> u8 {
+        self.original()[index]
+    }
+
     /// Returns the number of elements.
     ///
     /// Note that consumed elements are also counted.
@@ -95,6 +171,7 @@ impl<'a> ByteCode<'a> {
     /// let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
     /// assert_eq!(bytes.len(), 8);
     /// ```
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.inner.len() + self.pos
     }
@@ -132,6 +209,32 @@ impl<'a> ByteCode<'a> {
         *self -= self.pos;
     }
 
+    /// Moves the pointer to an absolute position from the beginning of the slice,
+    /// as with offsets stored in headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is beyond the end of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// bytes.seek(6);
+    /// assert_eq!(bytes.as_slice(), [6, 7]);
+    /// bytes.seek(2);
+    /// assert_eq!(bytes.pos(), 2);
+    /// ```
+    pub fn seek(&mut self, offset: usize) {
+        if offset < self.pos {
+            *self -= self.pos - offset;
+        } else {
+            *self += offset - self.pos;
+        }
+    }
+
     /// Returns `true` if all elements have been consumed.
     ///
     /// # Examples
@@ -152,39 +255,88 @@ impl<'a> ByteCode<'a> {
     }
 }
 
+impl<'a> ByteCode<'a> {
+    /// Moves the pointer forward without checking the remaining length.
+    pub(crate) fn move_forward(&mut self, num: usize) {
+        debug_assert!(num <= self.inner.len());
+        self.inner = unsafe {
+            let ptr = self.inner.as_ptr().add(num);
+            core::slice::from_raw_parts(ptr, self.inner.len() - num)
+        };
+        self.pos += num;
+    }
+
+    /// Moves the pointer back without checking the current position.
+    pub(crate) fn move_back(&mut self, num: usize) {
+        debug_assert!(num <= self.pos);
+        self.inner = unsafe {
+            let ptr = self.inner.as_ptr().sub(num);
+            core::slice::from_raw_parts(ptr, self.inner.len() + num)
+        };
+        self.pos -= num;
+    }
+}
+
 impl<'a> AddAssign<usize> for ByteCode<'a> {
     /// Move the pointer to the next.
     fn add_assign(&mut self, rhs: usize) {
-        if rhs > self.inner.len() {
-            panic!(
-                "index out of bounds: the slice can only move forward {}, but tried to move {}",
-                self.inner.len(),
-                rhs
-            );
-        }
-        self.inner = unsafe {
-            let ptr = self.inner.as_ptr().add(rhs);
-            std::slice::from_raw_parts(ptr, self.inner.len() - rhs)
-        };
-        self.pos += rhs;
+        self.try_skip(rhs).unwrap_or_else(|error| self.fail(error))
     }
 }
 
 impl<'a> SubAssign<usize> for ByteCode<'a> {
     /// Move the pointer to the prev.
     fn sub_assign(&mut self, rhs: usize) {
-        if rhs > self.pos {
-            panic!(
-                "index out of bounds: the slice can only move back {}, but tried to move {}",
-                self.pos, rhs
-            );
-        }
+        self.try_rewind(rhs)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+}
 
-        self.inner = unsafe {
-            let ptr = self.inner.as_ptr().sub(rhs);
-            std::slice::from_raw_parts(ptr, self.inner.len() + rhs)
+impl<'a> Add<usize> for ByteCode<'a> {
+    type Output = ByteCode<'a>;
+
+    /// Returns the `ByteCode` with the pointer moved forward.
+    fn add(mut self, rhs: usize) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<'a> Add<usize> for &ByteCode<'a> {
+    type Output = ByteCode<'a>;
+
+    /// Returns a new `ByteCode` over the same slice with the pointer moved forward,
+    /// leaving this one unchanged.
+    fn add(self, rhs: usize) -> Self::Output {
+        let replica = ByteCode {
+            baseline: self.baseline,
+            ..self.child(self.inner, self.pos)
         };
-        self.pos -= rhs;
+        replica + rhs
+    }
+}
+
+impl<'a> Sub<usize> for ByteCode<'a> {
+    type Output = ByteCode<'a>;
+
+    /// Returns the `ByteCode` with the pointer moved back.
+    fn sub(mut self, rhs: usize) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<'a> Sub<usize> for &ByteCode<'a> {
+    type Output = ByteCode<'a>;
+
+    /// Returns a new `ByteCode` over the same slice with the pointer moved back,
+    /// leaving this one unchanged.
+    fn sub(self, rhs: usize) -> Self::Output {
+        let replica = ByteCode {
+            baseline: self.baseline,
+            ..self.child(self.inner, self.pos)
+        };
+        replica - rhs
     }
 }
 
@@ -195,6 +347,9 @@ impl<'a, I: SliceIndex<[u8]>> Index<I> for ByteCode<'a> {
     }
 }
 
+#[cfg(test)]
+use alloc::vec;
+
 #[test]
 fn new() {
     let v = vec![0, 1, 2, 3, 4, 5, 6, 7];
@@ -210,6 +365,25 @@ fn as_slice() {
     assert_eq!(bytes.as_slice(), v);
 }
 
+#[test]
+fn original() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes += 3;
+    assert_eq!(bytes.original(), [0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(bytes.at_absolute(7), 7);
+    bytes -= 2;
+    assert_eq!(bytes.at_absolute(0), 0);
+    assert_eq!(bytes.pos, 1);
+}
+
+#[test]
+#[should_panic]
+fn at_absolute_out_of_range() {
+    let mut bytes = ByteCode::new(&[0, 1, 2]);
+    bytes += 1;
+    bytes.at_absolute(3);
+}
+
 #[test]
 fn len() {
     let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
@@ -237,6 +411,25 @@ fn reset() {
     assert_eq!(bytes.pos, 0);
 }
 
+#[test]
+fn seek() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes.seek(8);
+    assert!(bytes.is_end());
+    bytes.seek(3);
+    assert_eq!(bytes.inner, [3, 4, 5, 6, 7]);
+    bytes.seek(3);
+    assert_eq!(bytes.pos, 3);
+    bytes.seek(0);
+    assert_eq!(bytes.pos, 0);
+}
+
+#[test]
+#[should_panic]
+fn seek_out_of_range() {
+    ByteCode::new(&[0, 1, 2]).seek(4);
+}
+
 #[test]
 fn is_end() {
     let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
@@ -274,6 +467,41 @@ fn sub_assign() {
     assert_eq!(bytes.pos, 4);
 }
 
+#[test]
+fn add() {
+    let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+
+    let shifted = &bytes + 3;
+    assert_eq!(shifted.inner, [3, 4, 5, 6, 7]);
+    assert_eq!(shifted.pos, 3);
+    assert_eq!(bytes.pos, 0);
+
+    let shifted = shifted + 5;
+    assert_eq!(shifted.inner, []);
+    assert_eq!(shifted.pos, 8);
+}
+
+#[test]
+fn sub() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes += 6;
+
+    let shifted = &bytes - 2;
+    assert_eq!(shifted.inner, [4, 5, 6, 7]);
+    assert_eq!(shifted.pos, 4);
+    assert_eq!(bytes.pos, 6);
+
+    let shifted = shifted - 4;
+    assert_eq!(shifted.pos, 0);
+}
+
+#[test]
+#[should_panic]
+fn add_out_of_range() {
+    let bytes = ByteCode::new(&[0, 1, 2]);
+    let _ = &bytes + 4;
+}
+
 #[test]
 fn index() {
     let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
@@ -0,0 +1,307 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::ops::Range;
+
+use crate::{ByteCode, Error};
+
+/// A range of bytes consumed by a named field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub name: String,
+    /// The range of positions in the tracked `ByteCode`.
+    pub range: Range<usize>,
+}
+
+/// Two claims sharing bytes, reported by [`Coverage::overlaps`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap {
+    /// The name of the claim starting first.
+    pub first: String,
+    pub second: String,
+    /// The range of positions claimed by both.
+    pub range: Range<usize>,
+}
+
+/// Tracks which field consumed each byte, so a parser can verify that it accounts for every byte of its input.
+///
+/// Each byte may be claimed by at most one field, unless overlaps are [allowed](Coverage::allow_overlaps).
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, Coverage};
+///
+/// let mut bytes = ByteCode::new(&[0x00, 0x02, 0x66, 0x6f, 0x00, 0x00]);
+/// let mut coverage = Coverage::new(&bytes);
+/// let len = coverage.consume(&mut bytes, "len", |b| b.take_into_u16()).unwrap();
+/// coverage.consume(&mut bytes, "name", |b| b.take_str(len as usize)).unwrap();
+///
+/// assert_eq!(coverage.uncovered_ranges(), [4..6]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    len: usize,
+    /// Sorted by start position, and never overlapping unless `allow_overlaps` is set.
+    claims: Vec<Claim>,
+    allow_overlaps: bool,
+}
+
+impl Coverage {
+    /// Creates a `Coverage` with nothing claimed, spanning the whole slice of `bytes`.
+    pub fn new(bytes: &ByteCode) -> Self {
+        Coverage {
+            len: bytes.len(),
+            claims: Vec::new(),
+            allow_overlaps: false,
+        }
+    }
+
+    /// Accepts claims that overlap earlier ones, so that all conflicts can be reported
+    /// afterwards by [`overlaps`](Coverage::overlaps) instead of failing on the first.
+    ///
+    /// This suits section tables read from headers, where sharing bytes may be legitimate
+    /// or a sign of a malformed file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Coverage, Overlap};
+    ///
+    /// let bytes = ByteCode::new(&[0; 32]);
+    /// let mut sections = Coverage::new(&bytes).allow_overlaps();
+    /// sections.claim(".text", 0..16).unwrap();
+    /// sections.claim(".data", 12..24).unwrap();
+    ///
+    /// assert_eq!(
+    ///     sections.overlaps(),
+    ///     [Overlap {
+    ///         first: ".text".to_owned(),
+    ///         second: ".data".to_owned(),
+    ///         range: 12..16,
+    ///     }]
+    /// );
+    /// ```
+    pub fn allow_overlaps(mut self) -> Self {
+        self.allow_overlaps = true;
+        self
+    }
+
+    /// Marks `range` as consumed by the field `name`.
+    ///
+    /// Empty ranges are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AlreadyClaimed`] if any byte of `range` is already claimed by another field
+    /// and overlaps are not allowed, or [`Error::OutOfBounds`] if `range` extends past the end of the slice.
+    pub fn claim(&mut self, name: &str, range: Range<usize>) -> Result<(), Error> {
+        if range.end > self.len {
+            return Err(Error::OutOfBounds {
+                pos: range.start,
+                requested: range.end.saturating_sub(range.start),
+                available: self.len.saturating_sub(range.start),
+            });
+        }
+        if range.is_empty() {
+            return Ok(());
+        }
+        let index = self
+            .claims
+            .partition_point(|claim| claim.range.start <= range.start);
+        if self.allow_overlaps {
+            self.claims.insert(
+                index,
+                Claim {
+                    name: name.to_owned(),
+                    range,
+                },
+            );
+            return Ok(());
+        }
+        let overlapping = [index.checked_sub(1), Some(index)]
+            .into_iter()
+            .flatten()
+            .filter_map(|index| self.claims.get(index))
+            .find(|claim| claim.range.start < range.end && range.start < claim.range.end);
+        if let Some(claim) = overlapping {
+            return Err(Error::AlreadyClaimed {
+                pos: range.start,
+                len: range.len(),
+                by: claim.name.clone(),
+            });
+        }
+        self.claims.insert(
+            index,
+            Claim {
+                name: name.to_owned(),
+                range,
+            },
+        );
+        Ok(())
+    }
+
+    /// Runs `read` on `bytes` and claims the consumed bytes for the field `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AlreadyClaimed`] if the consumed bytes overlap an earlier claim.
+    /// The pointer is left where `read` moved it.
+    pub fn consume<'a, T>(
+        &mut self,
+        bytes: &mut ByteCode<'a>,
+        name: &str,
+        read: impl FnOnce(&mut ByteCode<'a>) -> T,
+    ) -> Result<T, Error> {
+        let start = bytes.pos();
+        let value = read(bytes);
+        self.claim(name, start..bytes.pos())?;
+        Ok(value)
+    }
+
+    /// Returns the claims, in order of position.
+    pub fn claims(&self) -> &[Claim] {
+        &self.claims
+    }
+
+    /// Returns the field that consumed the byte at `pos`, if any.
+    ///
+    /// If overlapping claims contain `pos`, the one starting first is returned.
+    pub fn claimed_by(&self, pos: usize) -> Option<&str> {
+        let end = self
+            .claims
+            .partition_point(|claim| claim.range.start <= pos);
+        self.claims[..end]
+            .iter()
+            .find(|claim| claim.range.contains(&pos))
+            .map(|claim| claim.name.as_str())
+    }
+
+    /// Returns every pair of claims sharing bytes, in order of position.
+    ///
+    /// This is always empty unless overlaps are [allowed](Coverage::allow_overlaps).
+    pub fn overlaps(&self) -> Vec<Overlap> {
+        let mut overlaps = Vec::new();
+        for (i, first) in self.claims.iter().enumerate() {
+            for second in &self.claims[i + 1..] {
+                if second.range.start >= first.range.end {
+                    break;
+                }
+                overlaps.push(Overlap {
+                    first: first.name.clone(),
+                    second: second.name.clone(),
+                    range: second.range.start..first.range.end.min(second.range.end),
+                });
+            }
+        }
+        overlaps
+    }
+
+    /// Returns the maximal ranges of bytes not claimed by any field, in order of position.
+    pub fn uncovered_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        let mut pos = 0;
+        for claim in &self.claims {
+            if claim.range.start > pos {
+                ranges.push(pos..claim.range.start);
+            }
+            pos = pos.max(claim.range.end);
+        }
+        if pos < self.len {
+            ranges.push(pos..self.len);
+        }
+        ranges
+    }
+
+    /// Returns `true` if every byte has been claimed.
+    pub fn is_complete(&self) -> bool {
+        self.uncovered_ranges().is_empty()
+    }
+}
+
+#[cfg(test)]
+use alloc::string::ToString;
+
+#[test]
+fn uncovered_ranges() {
+    let mut bytes = ByteCode::new(&[0; 16]);
+    let mut coverage = Coverage::new(&bytes);
+    assert_eq!(coverage.uncovered_ranges().first(), Some(&(0..16)));
+
+    bytes.skip(2);
+    coverage.consume(&mut bytes, "a", |b| b.skip(4)).unwrap();
+    coverage.claim("c", 12..14).unwrap();
+    coverage.claim("b", 6..8).unwrap();
+    coverage.claim("empty", 9..9).unwrap();
+    assert_eq!(coverage.uncovered_ranges(), [0..2, 8..12, 14..16]);
+    assert!(!coverage.is_complete());
+
+    let names: Vec<_> = coverage.claims().iter().map(|claim| &claim.name).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+    assert_eq!(coverage.claimed_by(5), Some("a"));
+    assert_eq!(coverage.claimed_by(6), Some("b"));
+    assert_eq!(coverage.claimed_by(8), None);
+    assert_eq!(coverage.claimed_by(16), None);
+
+    coverage.claim("rest", 0..2).unwrap();
+    coverage.claim("rest", 8..12).unwrap();
+    coverage.claim("rest", 14..16).unwrap();
+    assert!(coverage.is_complete());
+}
+
+#[test]
+fn claim_is_exclusive() {
+    let bytes = ByteCode::new(&[0; 8]);
+    let mut coverage = Coverage::new(&bytes);
+    coverage.claim("header", 2..6).unwrap();
+    assert_eq!(
+        coverage.claim("body", 4..8),
+        Err(Error::AlreadyClaimed {
+            pos: 4,
+            len: 4,
+            by: "header".to_owned(),
+        })
+    );
+    assert_eq!(
+        coverage.claim("magic", 0..3).unwrap_err().to_string(),
+        "3 bytes at 0x0 overlap bytes already claimed by `header`"
+    );
+    assert!(coverage.claim("inner", 3..4).is_err());
+    assert!(coverage.claim("outer", 0..8).is_err());
+    assert!(matches!(
+        coverage.claim("trailer", 6..9),
+        Err(Error::OutOfBounds { .. })
+    ));
+    coverage.claim("magic", 0..2).unwrap();
+    coverage.claim("body", 6..8).unwrap();
+    assert!(coverage.is_complete());
+}
+
+#[test]
+fn overlaps() {
+    let bytes = ByteCode::new(&[0; 32]);
+    let mut sections = Coverage::new(&bytes).allow_overlaps();
+    sections.claim("a", 0..8).unwrap();
+    sections.claim("c", 20..24).unwrap();
+    sections.claim("b", 4..16).unwrap();
+    sections.claim("d", 6..7).unwrap();
+    sections.claim("e", 20..22).unwrap();
+    assert!(sections.claim("f", 30..33).is_err());
+
+    let overlaps: Vec<_> = sections
+        .overlaps()
+        .into_iter()
+        .map(|overlap| (overlap.first, overlap.second, overlap.range))
+        .collect();
+    assert_eq!(
+        overlaps,
+        [
+            ("a".to_owned(), "b".to_owned(), 4..8),
+            ("a".to_owned(), "d".to_owned(), 6..7),
+            ("b".to_owned(), "d".to_owned(), 6..7),
+            ("c".to_owned(), "e".to_owned(), 20..22),
+        ]
+    );
+    assert_eq!(sections.claimed_by(6), Some("a"));
+    assert_eq!(sections.claimed_by(12), Some("b"));
+    assert_eq!(sections.claimed_by(23), Some("c"));
+    assert_eq!(sections.uncovered_ranges(), [16..20, 24..32]);
+}
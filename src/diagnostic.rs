@@ -0,0 +1,101 @@
+use alloc::{string::String, string::ToString, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+use crate::{ByteCode, Error};
+
+/// A non-fatal anomaly found while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The position the anomaly was found at.
+    pub pos: usize,
+    /// A human-readable description of the anomaly.
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "{:#x}: {}", self.pos, self.message)
+    }
+}
+
+impl From<Error> for Diagnostic {
+    fn from(error: Error) -> Self {
+        Diagnostic {
+            pos: error.pos(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Records a non-fatal anomaly found at position `pos`, so that parsers can report it
+    /// instead of printing or dropping it.
+    ///
+    /// Format violations reported in [permissive](crate::Strictness::Permissive) mode are recorded here too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x09]);
+    /// let count = bytes.take_into_u16();
+    /// if count > 8 {
+    ///     bytes.warn(0, format!("count {} exceeds the documented maximum", count));
+    /// }
+    /// assert_eq!(bytes.diagnostics()[0].to_string(), "0x0: count 9 exceeds the documented maximum");
+    /// ```
+    pub fn warn(&mut self, pos: usize, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            pos,
+            message: message.into(),
+        });
+    }
+
+    /// Returns the recorded diagnostics, in the order they were found.
+    ///
+    /// Child readers created from this one start with no diagnostics.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Removes and returns the recorded diagnostics.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        core::mem::take(&mut self.diagnostics)
+    }
+}
+
+#[cfg(test)]
+use alloc::borrow::ToOwned;
+
+#[test]
+fn warn() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    bytes.warn(2, "odd value");
+    bytes.warn(1, String::from("another"));
+    assert_eq!(
+        bytes.diagnostics(),
+        [
+            Diagnostic {
+                pos: 2,
+                message: "odd value".to_owned()
+            },
+            Diagnostic {
+                pos: 1,
+                message: "another".to_owned()
+            },
+        ]
+    );
+    assert_eq!(bytes.take_diagnostics().len(), 2);
+    assert!(bytes.diagnostics().is_empty());
+}
+
+#[test]
+fn from_error() {
+    let diagnostic = Diagnostic::from(Error::NonZeroPadding { pos: 16, len: 2 });
+    assert_eq!(diagnostic.pos, 16);
+    assert_eq!(
+        diagnostic.to_string(),
+        "0x10: non-zero padding in 2 bytes at 0x10"
+    );
+}
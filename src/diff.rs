@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{ByteCode, Error};
+
+/// A value read by [`ByteCode::compare`], with the value read from the baseline at the same position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compared<T> {
+    /// The value read from the data.
+    pub value: T,
+    /// The value read from the baseline, or `None` if it could not be read there.
+    pub baseline: Option<T>,
+    /// The absolute positions of the bytes the value was read from.
+    pub range: Range<usize>,
+    /// Whether those bytes differ from the baseline.
+    pub changed: bool,
+}
+
+impl<'a> ByteCode<'a> {
+    /// Attaches a baseline, such as an earlier version of the same firmware,
+    /// to compare reads against. Positions in the baseline are absolute, as with [`original`](ByteCode::original).
+    ///
+    /// Readers created with `+` and `-` keep the baseline; child readers over a part of the slice do not.
+    pub fn set_baseline(&mut self, baseline: &'a [u8]) {
+        self.baseline = Some(baseline);
+    }
+
+    /// Returns the attached baseline.
+    pub fn baseline(&self) -> Option<&'a [u8]> {
+        self.baseline
+    }
+
+    /// Runs `read` on this reader and again on the baseline from the same position,
+    /// reporting whether the bytes it consumed differ from the baseline.
+    /// Moves the pointer as `read` does.
+    ///
+    /// Bytes past the end of the baseline, or any bytes if no baseline is attached, count as changed.
+    /// The baseline read runs with the same byte order and strictness, and its errors are reported
+    /// as a missing baseline value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `read` on this reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let old = [0x00, 0x01, 0x00, 0x02];
+    /// let new = [0x00, 0x01, 0x00, 0x03];
+    /// let mut bytes = ByteCode::new(&new);
+    /// bytes.set_baseline(&old);
+    ///
+    /// let version = bytes.compare(|bytes| bytes.try_take_into_u16()).unwrap();
+    /// assert!(!version.changed);
+    /// let build = bytes.compare(|bytes| bytes.try_take_into_u16()).unwrap();
+    /// assert!(build.changed);
+    /// assert_eq!((build.baseline, build.value), (Some(2), 3));
+    /// ```
+    pub fn compare<T>(
+        &mut self,
+        mut read: impl FnMut(&mut ByteCode<'a>) -> Result<T, Error>,
+    ) -> Result<Compared<T>, Error> {
+        let start = self.pos;
+        let value = read(self)?;
+        let range = start..self.pos;
+        let baseline = self.baseline.unwrap_or_default();
+        let baseline_value = (start <= baseline.len())
+            .then(|| {
+                let mut reader = self.child(baseline, 0);
+                reader.skip(start);
+                read(&mut reader).ok()
+            })
+            .flatten();
+        let changed = baseline.get(range.clone()) != Some(&self.original()[range.clone()]);
+        Ok(Compared {
+            value,
+            baseline: baseline_value,
+            range,
+            changed,
+        })
+    }
+
+    /// Returns `true` if the next `num` elements differ from the baseline at the same position,
+    /// without moving the pointer.
+    ///
+    /// Elements past the end of the baseline, or any elements if no baseline is attached, count as changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` exceeds the remaining elements.
+    pub fn differs_from_baseline(&self, num: usize) -> bool {
+        let bytes = self.peek(num);
+        let baseline = self.baseline.unwrap_or_default();
+        self.pos
+            .checked_add(num)
+            .and_then(|end| baseline.get(self.pos..end))
+            != Some(bytes)
+    }
+
+    /// Returns the absolute ranges of the whole slice that differ from the baseline, in order.
+    ///
+    /// Adjacent changed elements are merged, and elements past the end of the baseline form one range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 9, 9, 4, 5, 6]);
+    /// bytes.set_baseline(&[0, 1, 2, 3, 4]);
+    /// assert_eq!(bytes.changed_ranges(), [2..4, 5..7]);
+    /// ```
+    pub fn changed_ranges(&self) -> Vec<Range<usize>> {
+        let data = self.original();
+        let baseline = self.baseline.unwrap_or_default();
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (pos, byte) in data.iter().enumerate() {
+            if baseline.get(pos) == Some(byte) {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == pos => last.end += 1,
+                _ => ranges.push(pos..pos + 1),
+            }
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn compare() {
+    let old = [0x01, 0x02, 0x03];
+    let new = [0x01, 0x02, 0x03, 0x04, 0x05];
+    let mut bytes = ByteCode::new(&new);
+    bytes.set_baseline(&old);
+    let first = bytes.compare(|bytes| bytes.try_take_into_u8()).unwrap();
+    assert_eq!(
+        first,
+        Compared {
+            value: 1,
+            baseline: Some(1),
+            range: 0..1,
+            changed: false
+        }
+    );
+    let truncated = bytes.compare(|bytes| bytes.try_take_into_u32()).unwrap();
+    assert_eq!((truncated.baseline, truncated.changed), (None, true));
+    assert!(bytes.compare(|bytes| bytes.try_take_into_u8()).is_err());
+    assert_eq!(bytes.pos(), 5);
+
+    let mut bytes = ByteCode::new(&new);
+    assert!(!bytes.compare(|bytes| bytes.try_take(0)).unwrap().changed);
+    assert!(bytes.differs_from_baseline(1));
+    bytes.set_baseline(&old);
+    assert!(!bytes.differs_from_baseline(3));
+    assert!(bytes.differs_from_baseline(4));
+    assert_eq!((&bytes + 1).baseline(), Some(&old[..]));
+}
+
+#[test]
+fn changed_ranges() {
+    let mut bytes = ByteCode::new(&[0, 0, 0]);
+    assert_eq!(bytes.changed_ranges(), vec![0..3]);
+    bytes.set_baseline(&[0, 1, 0, 0]);
+    assert_eq!(bytes.changed_ranges(), vec![1..2]);
+    bytes.set_baseline(&[0, 0, 0]);
+    assert!(bytes.changed_ranges().is_empty());
+}
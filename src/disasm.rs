@@ -0,0 +1,660 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::{
+    hexdump::{printable, Color},
+    ByteCode, Endian, Error, HexDump,
+};
+
+/// How an operand is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandKind {
+    /// A register number.
+    Register,
+    /// An unsigned immediate value.
+    Unsigned,
+    /// A signed immediate value.
+    Signed,
+    /// A signed branch displacement, relative to the end of the instruction.
+    Relative,
+    /// An index into a constant pool, symbol table or similar.
+    Index,
+}
+
+/// The definition of an opcode in an [`InstructionSet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opcode {
+    pub mnemonic: &'static str,
+    /// The operands that follow the opcode byte, in order, with their widths in bytes (1 to 8).
+    pub operands: &'static [(OperandKind, usize)],
+}
+
+/// A decoded operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operand {
+    pub kind: OperandKind,
+    /// The value, sign-extended for [`Signed`](OperandKind::Signed) and [`Relative`](OperandKind::Relative) operands.
+    /// 8-byte unsigned values above `i64::MAX` wrap.
+    pub value: i64,
+}
+
+/// A decoded instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// The absolute positions the instruction was decoded from, including the opcode byte.
+    pub span: Range<usize>,
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    pub operands: Vec<Operand>,
+}
+
+impl Instruction {
+    /// Returns the absolute position targeted by the first [`Relative`](OperandKind::Relative) operand, if any.
+    pub fn branch_target(&self) -> Option<usize> {
+        let operand = self
+            .operands
+            .iter()
+            .find(|operand| operand.kind == OperandKind::Relative)?;
+        let target = (self.span.end as i64).checked_add(operand.value)?;
+        usize::try_from(target).ok()
+    }
+}
+
+impl Display for Operand {
+    /// Formats registers as `r<n>`, indices as `[<n>]` and branch displacements with an explicit sign.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            OperandKind::Register => write!(f, "r{}", self.value),
+            OperandKind::Unsigned | OperandKind::Signed => write!(f, "{}", self.value),
+            OperandKind::Relative => write!(f, "{:+}", self.value),
+            OperandKind::Index => write!(f, "[{}]", self.value),
+        }
+    }
+}
+
+impl Display for Instruction {
+    /// Formats the mnemonic followed by the operands, separated by commas.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mnemonic)?;
+        for (i, operand) in self.operands.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}", separator, operand)?;
+        }
+        Ok(())
+    }
+}
+
+/// A table of opcodes, each one byte long and followed by fixed-width operands.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, InstructionSet, Opcode, OperandKind};
+///
+/// struct StackVm;
+///
+/// impl InstructionSet for StackVm {
+///     fn opcode(&self, byte: u8) -> Option<Opcode> {
+///         let (mnemonic, operands): (_, &'static [_]) = match byte {
+///             0x00 => ("halt", &[]),
+///             0x01 => ("push", &[(OperandKind::Signed, 1)]),
+///             0x02 => ("jmp", &[(OperandKind::Relative, 2)]),
+///             _ => return None,
+///         };
+///         Some(Opcode { mnemonic, operands })
+///     }
+/// }
+///
+/// let mut code = ByteCode::new(&[0x01, 0xff, 0x02, 0xff, 0xfb, 0x00]);
+/// let listing: Vec<String> = code
+///     .disassemble(StackVm)
+///     .map(|instruction| instruction.unwrap().to_string())
+///     .collect();
+/// assert_eq!(listing, ["push -1", "jmp -5", "halt"]);
+/// ```
+pub trait InstructionSet {
+    /// Returns the definition of the opcode `byte`, or `None` if it is undefined.
+    fn opcode(&self, byte: u8) -> Option<Opcode>;
+
+    /// Returns the byte order of multi-byte operands. Big-endian by default.
+    fn endian(&self) -> Endian {
+        Endian::Big
+    }
+
+    /// Decodes one instruction from the beginning of the slice.
+    /// Moves the pointer forward past the instruction; the pointer is not moved on error.
+    ///
+    /// This can be passed to [`decode_instructions`](ByteCode::decode_instructions)
+    /// to build an [`InstructionMap`](crate::InstructionMap).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownOpcode`] if the opcode is undefined,
+    /// [`Error::InvalidArgument`] if an operand width of its definition is not 1 to 8,
+    /// or [`Error::OutOfBounds`] if the instruction is truncated.
+    fn decode(&self, bytes: &mut ByteCode) -> Result<Instruction, Error> {
+        let pos = bytes.pos();
+        let byte = *bytes.try_peek(1)?.first().unwrap();
+        let opcode = self
+            .opcode(byte)
+            .ok_or(Error::UnknownOpcode { pos, opcode: byte })?;
+        if let Some((_, width)) = opcode
+            .operands
+            .iter()
+            .find(|(_, width)| !(1..=8).contains(width))
+        {
+            return Err(Error::InvalidArgument {
+                pos,
+                message: format!(
+                    "operand width {} of opcode {:#04x} is not 1 to 8 bytes",
+                    width, byte
+                ),
+            });
+        }
+        let len = 1 + opcode
+            .operands
+            .iter()
+            .map(|(_, width)| width)
+            .sum::<usize>();
+        let mut instruction = ByteCode::new(bytes.try_peek(len)?);
+        instruction.skip(1);
+        let operands = opcode
+            .operands
+            .iter()
+            .map(|(kind, width)| {
+                let value = self.endian().read_uint(instruction.take_ref(*width));
+                let value = match kind {
+                    OperandKind::Signed | OperandKind::Relative if *width < 8 => {
+                        let shift = 64 - width * 8;
+                        ((value << shift) as i64) >> shift
+                    }
+                    _ => value as i64,
+                };
+                Operand { kind: *kind, value }
+            })
+            .collect();
+        bytes.skip(len);
+        Ok(Instruction {
+            span: pos..pos + len,
+            opcode: byte,
+            mnemonic: opcode.mnemonic,
+            operands,
+        })
+    }
+}
+
+/// An iterator over the instructions in the rest of a slice.
+///
+/// This struct is created by [`ByteCode::disassemble`].
+pub struct Disassembler<'b, 'a, I> {
+    bytes: &'b mut ByteCode<'a>,
+    set: I,
+    failed: bool,
+}
+
+impl<I: InstructionSet> Iterator for Disassembler<'_, '_, I> {
+    type Item = Result<Instruction, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.bytes.is_end() {
+            return None;
+        }
+        let result = self.set.decode(self.bytes);
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns an iterator decoding the instructions of `set` from the pointer to the end of the slice,
+    /// moving the pointer past each.
+    ///
+    /// After an error, the iterator ends with the pointer at the start of the instruction that could not be decoded.
+    pub fn disassemble<I: InstructionSet>(&mut self, set: I) -> Disassembler<'_, 'a, I> {
+        Disassembler {
+            bytes: self,
+            set,
+            failed: false,
+        }
+    }
+}
+
+type OperandFormatter<'a> = Box<dyn Fn(&Operand, &Instruction) -> String + 'a>;
+
+/// A renderer of disassembly listings, with one line per instruction holding its position,
+/// bytes, mnemonic and operands in aligned columns. Created by [`listing`](ByteCode::listing).
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, InstructionSet, Opcode, OperandKind};
+///
+/// struct Vm;
+///
+/// impl InstructionSet for Vm {
+///     fn opcode(&self, byte: u8) -> Option<Opcode> {
+///         let (mnemonic, operands): (_, &'static [_]) = match byte {
+///             0x00 => ("ret", &[]),
+///             0x01 => ("getsym", &[(OperandKind::Register, 1), (OperandKind::Index, 1)]),
+///             _ => return None,
+///         };
+///         Some(Opcode { mnemonic, operands })
+///     }
+/// }
+///
+/// let symbols = ["puts", "print"];
+/// let mut code = ByteCode::new(&[0x01, 0x02, 0x00, 0x00]);
+/// let instructions: Vec<_> = code.disassemble(Vm).map(Result::unwrap).collect();
+/// let listing = code
+///     .listing()
+///     .format_operand(OperandKind::Index, |operand, _| {
+///         format!(":{}", symbols[operand.value as usize])
+///     })
+///     .render(&instructions);
+/// assert_eq!(
+///     listing,
+///     "00000000  01 02 00  getsym  r2, :puts\n\
+///      00000003  00        ret\n"
+/// );
+/// ```
+pub struct Listing<'a> {
+    bytes: &'a [u8],
+    formatters: Vec<(OperandKind, OperandFormatter<'a>)>,
+    color: bool,
+}
+
+impl<'a> Listing<'a> {
+    /// Formats operands of `kind` with `format` instead of their [`Display`] impl.
+    /// `format` also receives the instruction, for operands such as branch displacements
+    /// that are best shown relative to it.
+    pub fn format_operand(
+        mut self,
+        kind: OperandKind,
+        format: impl Fn(&Operand, &Instruction) -> String + 'a,
+    ) -> Self {
+        self.formatters.retain(|(other, _)| *other != kind);
+        self.formatters.push((kind, Box::new(format)));
+        self
+    }
+
+    /// Sets whether ANSI colors are used for positions and mnemonics. Off by default.
+    #[cfg(feature = "std")]
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the operands of `instruction`, formatted and separated by commas.
+    pub fn operands(&self, instruction: &Instruction) -> String {
+        instruction
+            .operands
+            .iter()
+            .map(|operand| {
+                match self
+                    .formatters
+                    .iter()
+                    .find(|(kind, _)| *kind == operand.kind)
+                {
+                    Some((_, format)) => format(operand, instruction),
+                    None => operand.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Renders one line per instruction, each ending with a newline.
+    ///
+    /// Columns are as wide as the longest bytes and mnemonic among `instructions`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an instruction span is out of range of the slice the listing was created from.
+    pub fn render<'i>(&self, instructions: impl IntoIterator<Item = &'i Instruction>) -> String {
+        let instructions: Vec<&Instruction> = instructions.into_iter().collect();
+        let hex_width = instructions
+            .iter()
+            .map(|instruction| instruction.span.len() * 3 - 1)
+            .max()
+            .unwrap_or(0);
+        let mnemonic_width = instructions
+            .iter()
+            .map(|instruction| instruction.mnemonic.len())
+            .max()
+            .unwrap_or(0);
+        let mut result = String::new();
+        for instruction in instructions {
+            let hex = self.bytes[instruction.span.clone()]
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let offset = format!("{:08X}", instruction.span.start);
+            let mnemonic = format!("{:width$}", instruction.mnemonic, width = mnemonic_width);
+            let (offset, mnemonic) = match self.color {
+                true => (Color::Cyan.paint(&offset), Color::Green.paint(&mnemonic)),
+                false => (offset, mnemonic),
+            };
+            let line = format!(
+                "{}  {:hex_width$}  {}  {}",
+                offset,
+                hex,
+                mnemonic,
+                self.operands(instruction),
+                hex_width = hex_width
+            );
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Renders the rows of `dump` with the bytes of `instructions` shown as one line per instruction,
+    /// as `objdump -d` does.
+    ///
+    /// Bytes not covered by an instruction are shown as data lines of up to one hex dump row,
+    /// with the printable characters if the dump has its ASCII column on.
+    /// Each label of the dump gets a line of its own, `<label>:`, before the line where its range starts.
+    /// The window and the byte colors of the dump apply; instructions outside its window are left out.
+    ///
+    /// `instructions` are expected in order of position, as decoded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an instruction span is out of range of the slice the dump was created from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, InstructionSet, Opcode, OperandKind};
+    ///
+    /// struct Vm;
+    ///
+    /// impl InstructionSet for Vm {
+    ///     fn opcode(&self, byte: u8) -> Option<Opcode> {
+    ///         match byte {
+    ///             0x00 => Some(Opcode { mnemonic: "ret", operands: &[] }),
+    ///             0x01 => Some(Opcode { mnemonic: "push", operands: &[(OperandKind::Unsigned, 1)] }),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut code = ByteCode::new(b"\x01\x2a\x00hi\x00");
+    /// let instructions: Vec<_> = code.disassemble(Vm).map_while(Result::ok).collect();
+    /// let dump = code.hexdump().label(0..3, "main").label(3..6, "greeting");
+    /// assert_eq!(
+    ///     code.listing().render_dump(&dump, &instructions),
+    ///     "00000000  <main>:\n\
+    ///      00000000  01 2A     push  42\n\
+    ///      00000002  00        ret\n\
+    ///      00000003  <greeting>:\n\
+    ///      00000003  68 69 00  |hi.|\n"
+    /// );
+    /// ```
+    pub fn render_dump<'i>(
+        &self,
+        dump: &HexDump<'_>,
+        instructions: impl IntoIterator<Item = &'i Instruction>,
+    ) -> String {
+        let shown = dump.byte_range();
+        let mut lines: Vec<(Range<usize>, Option<&Instruction>)> = Vec::new();
+        let mut cursor = shown.start;
+        for instruction in instructions {
+            let span = &instruction.span;
+            if span.end <= shown.start || span.start >= shown.end {
+                continue;
+            }
+            data_lines(&mut lines, cursor..span.start);
+            lines.push((span.clone(), Some(instruction)));
+            cursor = cursor.max(span.end);
+        }
+        data_lines(&mut lines, cursor..shown.end);
+
+        let hex_width = lines
+            .iter()
+            .map(|(range, _)| range.len() * 3 - 1)
+            .max()
+            .unwrap_or(0);
+        let mnemonic_width = lines
+            .iter()
+            .filter_map(|(_, instruction)| Some(instruction.as_ref()?.mnemonic.len()))
+            .max()
+            .unwrap_or(0);
+        let paint_offset = |offset: usize| {
+            let offset = format!("{:08X}", offset);
+            match self.color {
+                true => Color::Cyan.paint(&offset),
+                false => offset,
+            }
+        };
+        let mut result = String::new();
+        for (range, instruction) in lines {
+            for (start, label) in dump.labels_starting_in(range.clone()) {
+                result.push_str(&format!("{}  <{}>:\n", paint_offset(start), label));
+            }
+            let hex = range
+                .clone()
+                .map(|pos| dump.paint_byte(pos))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let padding = " ".repeat(hex_width - (range.len() * 3 - 1));
+            let text = match instruction {
+                Some(instruction) => {
+                    let mnemonic =
+                        format!("{:width$}", instruction.mnemonic, width = mnemonic_width);
+                    let mnemonic = match self.color {
+                        true => Color::Green.paint(&mnemonic),
+                        false => mnemonic,
+                    };
+                    format!("{}  {}", mnemonic, self.operands(instruction))
+                }
+                None if dump.shows_ascii() => {
+                    format!("|{}|", printable(&dump.bytes()[range.clone()]))
+                }
+                None => String::new(),
+            };
+            let line = format!(
+                "{}  {}{}  {}",
+                paint_offset(range.start),
+                hex,
+                padding,
+                text
+            );
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+        result
+    }
+}
+
+/// Appends data lines for the bytes in `range`, split at hex dump row boundaries.
+fn data_lines(lines: &mut Vec<(Range<usize>, Option<&Instruction>)>, range: Range<usize>) {
+    let mut start = range.start;
+    while start < range.end {
+        let end = ((start / 16 + 1) * 16).min(range.end);
+        lines.push((start..end, None));
+        start = end;
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns a renderer of disassembly listings for instructions decoded from this slice.
+    pub fn listing(&self) -> Listing<'a> {
+        Listing {
+            bytes: self.original(),
+            formatters: Vec::new(),
+            color: false,
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::{borrow::ToOwned, vec};
+
+#[cfg(all(test, feature = "std"))]
+use tiny_ansi::TinyAnsi;
+
+#[cfg(test)]
+struct TestSet;
+
+#[cfg(test)]
+impl InstructionSet for TestSet {
+    fn opcode(&self, byte: u8) -> Option<Opcode> {
+        let (mnemonic, operands): (_, &'static [_]) = match byte {
+            0x00 => ("ret", &[]),
+            0x01 => (
+                "load",
+                &[(OperandKind::Register, 1), (OperandKind::Index, 2)],
+            ),
+            0x02 => ("jz", &[(OperandKind::Relative, 1)]),
+            0x03 => (
+                "big",
+                &[(OperandKind::Signed, 8), (OperandKind::Unsigned, 3)],
+            ),
+            0x04 => ("empty", &[(OperandKind::Signed, 0)]),
+            0x05 => ("wide", &[(OperandKind::Unsigned, 9)]),
+            _ => return None,
+        };
+        Some(Opcode { mnemonic, operands })
+    }
+
+    fn endian(&self) -> Endian {
+        Endian::Little
+    }
+}
+
+#[test]
+fn disassemble() {
+    let mut code = ByteCode::new(&[
+        0x01, 0x02, 0x34, 0x12, 0x02, 0xfb, 0x03, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x80, 0x00, 0xff,
+    ]);
+    let instructions: Vec<_> = code.disassemble(TestSet).collect();
+    assert_eq!(instructions.len(), 5);
+    let load = instructions[0].as_ref().unwrap();
+    assert_eq!(load.span, 0..4);
+    assert_eq!(load.to_string(), "load r2, [4660]");
+    let jz = instructions[1].as_ref().unwrap();
+    assert_eq!(jz.to_string(), "jz -5");
+    assert_eq!(jz.branch_target(), Some(1));
+    assert_eq!(load.branch_target(), None);
+    assert_eq!(
+        instructions[2].as_ref().unwrap().to_string(),
+        "big -2, 8388609"
+    );
+    assert_eq!(instructions[3].as_ref().unwrap().mnemonic, "ret");
+    assert_eq!(
+        instructions[4],
+        Err(Error::UnknownOpcode {
+            pos: 19,
+            opcode: 0xff
+        })
+    );
+    assert_eq!(code.pos(), 19);
+}
+
+#[test]
+fn decode_invalid_width() {
+    for opcode in [0x04, 0x05] {
+        let data = [0x00, opcode, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut code = ByteCode::new(&data);
+        code.skip(1);
+        assert!(matches!(
+            TestSet.decode(&mut code),
+            Err(Error::InvalidArgument { pos: 1, .. })
+        ));
+        assert_eq!(code.pos(), 1);
+    }
+}
+
+#[test]
+fn decode_truncated() {
+    let mut code = ByteCode::new(&[0x00, 0x01, 0x02, 0x34]);
+    let map = code.decode_instructions(|code| TestSet.decode(code).ok());
+    assert_eq!(map.len(), 1);
+    assert!(matches!(
+        TestSet.decode(&mut code),
+        Err(Error::OutOfBounds { pos: 1, .. })
+    ));
+    assert_eq!(code.pos(), 1);
+}
+
+#[test]
+fn listing() {
+    let mut code = ByteCode::new(&[0x02, 0x04, 0x01, 0x07, 0x00, 0x01, 0x00, 0x00]);
+    let instructions: Vec<_> = code.disassemble(TestSet).map(Result::unwrap).collect();
+    let listing = code
+        .listing()
+        .format_operand(OperandKind::Relative, |_, instruction| {
+            format!("{:#x}", instruction.branch_target().unwrap())
+        });
+    assert_eq!(
+        listing.render(&instructions),
+        "00000000  02 04        jz    0x6\n\
+         00000002  01 07 00 01  load  r7, [256]\n\
+         00000006  00           ret\n\
+         00000007  00           ret\n"
+    );
+    assert_eq!(listing.operands(&instructions[1]), "r7, [256]");
+    let listing = listing.format_operand(OperandKind::Relative, |_, _| "here".to_owned());
+    assert!(listing.render(&instructions[..1]).ends_with("jz  here\n"));
+
+    #[cfg(feature = "std")]
+    {
+        let colored = listing.color(true).render(&instructions[2..3]);
+        assert_eq!(
+            colored,
+            format!("{}  00  {}\n", "00000006".cyan(), "ret".green())
+        );
+    }
+    assert_eq!(code.listing().render([]), "");
+}
+
+#[test]
+fn render_dump() {
+    let mut data = vec![0x00, 0xff, 0xfe];
+    data.extend(0x40..0x54);
+    data.extend([0x01, 0x03, 0x00, 0x00]);
+    let mut code = ByteCode::new(&data);
+    let mut instructions: Vec<_> = code.disassemble(TestSet).map_while(Result::ok).collect();
+    code.skip(0x16);
+    instructions.extend(code.disassemble(TestSet).map_while(Result::ok));
+    let listing = code.listing();
+    let dump = code.hexdump().label(1..2, "data").label(0x17..0x1b, "tail");
+    assert_eq!(
+        listing.render_dump(&dump, &instructions),
+        "00000000  00                                            ret\n\
+         00000001  <data>:\n\
+         00000001  FF FE 40 41 42 43 44 45 46 47 48 49 4A 4B 4C  |..@ABCDEFGHIJKL|\n\
+         00000010  4D 4E 4F 50 51 52 53                          |MNOPQRS|\n\
+         00000017  <tail>:\n\
+         00000017  01 03 00 00                                   load  r3, [0]\n"
+    );
+    assert_eq!(
+        listing
+            .render_dump(&dump.clone().ascii(false).window(0), &instructions)
+            .lines()
+            .collect::<Vec<_>>(),
+        [
+            "00000010  4D 4E 4F 50 51 52 53",
+            "00000017  <tail>:",
+            "00000017  01 03 00 00           load  r3, [0]",
+        ]
+    );
+
+    #[cfg(feature = "std")]
+    {
+        let colored = listing.render_dump(&dump.color(true), &instructions[..1]);
+        assert!(colored.contains(&format!("00000001  {} FE", "FF".blue())));
+        assert!(colored.contains(&"01".magenta()));
+    }
+}
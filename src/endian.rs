@@ -0,0 +1,104 @@
+use crate::ByteCode;
+
+/// Byte order of a multi-byte value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endian {
+    /// Most significant byte first (network order).
+    #[default]
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+impl Endian {
+    /// Converts up to 8 bytes into `u64` in this byte order.
+    pub(crate) fn read_uint(self, bytes: &[u8]) -> u64 {
+        debug_assert!(bytes.len() <= 8);
+        match self {
+            Endian::Big => bytes.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64),
+            Endian::Little => bytes
+                .iter()
+                .rev()
+                .fold(0, |acc, byte| (acc << 8) | *byte as u64),
+        }
+    }
+}
+
+/// A byte order known at compile time, for the `take_into_*_endian` reads.
+///
+/// Reads generic over `ByteOrder` are monomorphized for each order,
+/// so they do not branch on the byte order at run time as reads using [`set_endian`](ByteCode::set_endian) do.
+pub trait ByteOrder {
+    /// The byte order as a run-time value.
+    const ENDIAN: Endian;
+}
+
+/// Most significant byte first, as a type for [`ByteOrder`]-generic reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BigEndian;
+
+/// Least significant byte first, as a type for [`ByteOrder`]-generic reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LittleEndian;
+
+impl ByteOrder for BigEndian {
+    const ENDIAN: Endian = Endian::Big;
+}
+
+impl ByteOrder for LittleEndian {
+    const ENDIAN: Endian = Endian::Little;
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns the byte order used by `take_into_*` reads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// assert_eq!(bytes.endian(), Endian::Big);
+    /// ```
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Sets the byte order used by `take_into_*` reads.
+    ///
+    /// `take_be_*` and `take_le_*` reads ignore this setting.
+    /// Child readers inherit this setting: those returned by [`take_bytecode`](ByteCode::take_bytecode),
+    /// [`peek_bytecode`](ByteCode::peek_bytecode), [`tlv`](ByteCode::tlv) and [`blocks`](ByteCode::blocks).
+    /// A reader created with [`ByteCode::new`] always starts out big-endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// bytes.set_endian(Endian::Little);
+    /// assert_eq!(bytes.take_into_u16(), 1);
+    /// ```
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+}
+
+#[test]
+fn read_uint() {
+    assert_eq!(Endian::Big.read_uint(&[0x01, 0x02, 0x03]), 0x010203);
+    assert_eq!(Endian::Little.read_uint(&[0x01, 0x02, 0x03]), 0x030201);
+    assert_eq!(Endian::Big.read_uint(&[]), 0);
+}
+
+#[test]
+fn set_endian() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x01, 0x02, 0x00, 0x00, 0x00, 0x01]);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.take_into_u16(), 0x0201);
+    bytes.set_endian(Endian::Big);
+    assert_eq!(bytes.take_into_u16(), 0x0102);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.take_be_u32(), 1);
+}
@@ -0,0 +1,279 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+/// An error returned by the fallible `try_*` methods of [`ByteCode`](crate::ByteCode),
+/// or a format violation reported by the [strictness](crate::Strictness) checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// More bytes were requested than remain.
+    OutOfBounds {
+        /// The pointer position at which the read was attempted.
+        pos: usize,
+        requested: usize,
+        available: usize,
+    },
+    /// The bytes are not valid UTF-8, or modified UTF-8 for the MUTF-8 reads.
+    InvalidUtf8 { pos: usize, len: usize },
+    /// The bytes are not valid UTF-16.
+    InvalidUtf16 { pos: usize, len: usize },
+    /// No terminating NUL byte remains.
+    Unterminated { pos: usize },
+    /// An integer, such as a variable-length one or a position, does not fit in the requested type.
+    Overflow { pos: usize },
+    /// The pointer moved while its position was frozen.
+    PositionMoved {
+        /// The position the pointer moved to.
+        pos: usize,
+        /// The position at which it was frozen.
+        frozen: usize,
+    },
+    /// The bytes overlap a range already claimed by another field of a [`Coverage`](crate::Coverage).
+    AlreadyClaimed {
+        pos: usize,
+        len: usize,
+        /// The name of the field that claimed the bytes first.
+        by: String,
+    },
+    /// Reserved bits are set.
+    ReservedBits {
+        /// The position the value was read from.
+        pos: usize,
+        /// The reserved bits that are set.
+        bits: u64,
+    },
+    /// Padding that should be zero is not.
+    NonZeroPadding { pos: usize, len: usize },
+    /// No bookmark with the name was set.
+    UnknownBookmark {
+        /// The pointer position at which the jump was attempted.
+        pos: usize,
+        name: String,
+    },
+    /// The opcode is not defined by the [`InstructionSet`](crate::InstructionSet).
+    UnknownOpcode { pos: usize, opcode: u8 },
+    /// The bytes differ from the expected ones, such as a magic number or version.
+    Unexpected {
+        pos: usize,
+        expected: Vec<u8>,
+        /// The bytes found instead, which are fewer than expected if the slice ends early.
+        found: Vec<u8>,
+    },
+    /// The bytes fall in a gap between the segments of a [`SparseBuffer`](crate::SparseBuffer).
+    Gap {
+        /// The start of the gap within the read.
+        pos: usize,
+        /// The number of bytes of the read in the gap.
+        len: usize,
+    },
+    /// A field of a struct deriving `FromByteCode` with `#[bytecode(check_layout)]` did not start
+    /// or end where its layout says.
+    Layout {
+        /// The position the field started or ended at.
+        pos: usize,
+        field: String,
+        /// The position the field should have started or ended at, or at least reached.
+        expected: usize,
+        /// Whether the field started, rather than ended, at the wrong position.
+        start: bool,
+    },
+    /// An argument of the read is invalid, such as a stride shorter than its element.
+    InvalidArgument {
+        /// The pointer position at which the read was attempted.
+        pos: usize,
+        message: String,
+    },
+    /// The source of a [`ByteCodeStream`](crate::ByteCodeStream) failed with an error other than ending early.
+    #[cfg(feature = "std")]
+    Io {
+        /// The pointer position at which the read was attempted.
+        pos: usize,
+        kind: std::io::ErrorKind,
+    },
+}
+
+impl Error {
+    /// Returns the pointer position at which the failing read was attempted.
+    pub fn pos(&self) -> usize {
+        match self {
+            Error::OutOfBounds { pos, .. }
+            | Error::InvalidUtf8 { pos, .. }
+            | Error::InvalidUtf16 { pos, .. }
+            | Error::Unterminated { pos }
+            | Error::Overflow { pos }
+            | Error::PositionMoved { pos, .. }
+            | Error::AlreadyClaimed { pos, .. }
+            | Error::ReservedBits { pos, .. }
+            | Error::NonZeroPadding { pos, .. }
+            | Error::UnknownBookmark { pos, .. }
+            | Error::UnknownOpcode { pos, .. }
+            | Error::Unexpected { pos, .. }
+            | Error::Gap { pos, .. }
+            | Error::Layout { pos, .. }
+            | Error::InvalidArgument { pos, .. } => *pos,
+            #[cfg(feature = "std")]
+            Error::Io { pos, .. } => *pos,
+        }
+    }
+
+    /// Moves the position forward by `by`, for errors from readers over a part of the data.
+    pub(crate) fn offset_by(mut self, by: usize) -> Self {
+        match &mut self {
+            Error::OutOfBounds { pos, .. }
+            | Error::InvalidUtf8 { pos, .. }
+            | Error::InvalidUtf16 { pos, .. }
+            | Error::Unterminated { pos }
+            | Error::Overflow { pos }
+            | Error::PositionMoved { pos, .. }
+            | Error::AlreadyClaimed { pos, .. }
+            | Error::ReservedBits { pos, .. }
+            | Error::NonZeroPadding { pos, .. }
+            | Error::UnknownBookmark { pos, .. }
+            | Error::UnknownOpcode { pos, .. }
+            | Error::Unexpected { pos, .. }
+            | Error::Gap { pos, .. }
+            | Error::InvalidArgument { pos, .. } => *pos += by,
+            #[cfg(feature = "std")]
+            Error::Io { pos, .. } => *pos += by,
+            Error::Layout { pos, expected, .. } => {
+                *pos += by;
+                *expected += by;
+            }
+        }
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        match self {
+            Error::OutOfBounds {
+                pos,
+                requested,
+                available,
+            } => write!(
+                f,
+                "out of bounds at {:#x}: requested {} bytes, but only {} remain",
+                pos, requested, available
+            ),
+            Error::InvalidUtf8 { pos, len } => {
+                write!(f, "invalid UTF-8 in {} bytes at {:#x}", len, pos)
+            }
+            Error::InvalidUtf16 { pos, len } => {
+                write!(f, "invalid UTF-16 in {} bytes at {:#x}", len, pos)
+            }
+            Error::Unterminated { pos } => {
+                write!(f, "unterminated string at {:#x}", pos)
+            }
+            Error::Overflow { pos } => write!(f, "integer overflow at {:#x}", pos),
+            Error::PositionMoved { pos, frozen } => write!(
+                f,
+                "position moved from {:#x} to {:#x} while frozen",
+                frozen, pos
+            ),
+            Error::AlreadyClaimed { pos, len, by } => write!(
+                f,
+                "{} bytes at {:#x} overlap bytes already claimed by `{}`",
+                len, pos, by
+            ),
+            Error::ReservedBits { pos, bits } => {
+                write!(f, "reserved bits {:#x} set at {:#x}", bits, pos)
+            }
+            Error::NonZeroPadding { pos, len } => {
+                write!(f, "non-zero padding in {} bytes at {:#x}", len, pos)
+            }
+            Error::UnknownBookmark { pos, name } => {
+                write!(f, "unknown bookmark `{}` at {:#x}", name, pos)
+            }
+            Error::UnknownOpcode { pos, opcode } => {
+                write!(f, "unknown opcode {:#04x} at {:#x}", opcode, pos)
+            }
+            Error::Unexpected {
+                pos,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} at {:#x}, found {}",
+                HexBytes(expected),
+                pos,
+                HexBytes(found)
+            ),
+            Error::Gap { pos, len } => {
+                write!(f, "{} bytes at {:#x} fall in a gap", len, pos)
+            }
+            Error::Layout {
+                pos,
+                field,
+                expected,
+                start: true,
+            } => write!(
+                f,
+                "field `{}` started at {:#x}, but its layout starts at {:#x}",
+                field, pos, expected
+            ),
+            Error::Layout {
+                pos,
+                field,
+                expected,
+                start: false,
+            } => write!(
+                f,
+                "field `{}` ended at {:#x}, but its layout ends at {:#x}",
+                field, pos, expected
+            ),
+            Error::InvalidArgument { pos, message } => {
+                write!(f, "invalid argument at {:#x}: {}", pos, message)
+            }
+            #[cfg(feature = "std")]
+            Error::Io { pos, kind } => write!(f, "I/O error at {:#x}: {}", pos, kind),
+        }
+    }
+}
+
+/// Displays bytes as `[7F 45 4C 46]`.
+struct HexBytes<'a>(&'a [u8]);
+
+impl Display for HexBytes<'_> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "[")?;
+        for (i, byte) in self.0.iter().enumerate() {
+            match i {
+                0 => write!(f, "{:02X}", byte)?,
+                _ => write!(f, " {:02X}", byte)?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+use alloc::string::ToString;
+
+#[test]
+fn error_display() {
+    let error = Error::OutOfBounds {
+        pos: 16,
+        requested: 4,
+        available: 2,
+    };
+    assert_eq!(error.pos(), 16);
+    assert_eq!(
+        error.to_string(),
+        "out of bounds at 0x10: requested 4 bytes, but only 2 remain"
+    );
+
+    let error = Error::Layout {
+        pos: 5,
+        field: String::from("value"),
+        expected: 4,
+        start: true,
+    };
+    assert_eq!(
+        error.to_string(),
+        "field `value` started at 0x5, but its layout starts at 0x4"
+    );
+}
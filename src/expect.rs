@@ -0,0 +1,125 @@
+use crate::{ByteCode, Endian, Error};
+
+macro_rules! expect_uint {
+    ($($ty:ident, $method:ident, $example:literal;)*) => {
+        impl<'a> ByteCode<'a> {
+            $(
+                #[doc = concat!("Checks that the next `", stringify!($ty), "` in the given byte order is `value`")]
+                /// and moves the pointer forward past it.
+                ///
+                /// # Errors
+                ///
+                /// Returns [`Error::Unexpected`] and leaves the pointer unchanged if the value differs
+                /// or the slice ends early.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::{ByteCode, Endian};
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert!(bytes.", stringify!($method), "(1, Endian::Little).is_ok());")]
+                #[doc = concat!("assert!(bytes.", stringify!($method), "(1, Endian::Little).is_err());")]
+                /// ```
+                pub fn $method(&mut self, value: $ty, endian: Endian) -> Result<(), Error> {
+                    match endian {
+                        Endian::Big => self.expect(&value.to_be_bytes()),
+                        Endian::Little => self.expect(&value.to_le_bytes()),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+expect_uint! {
+    u16, expect_u16, "[0x01, 0x00, 0x00, 0x01]";
+    u32, expect_u32, "[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]";
+    u64, expect_u64, "[0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]";
+}
+
+impl<'a> ByteCode<'a> {
+    /// Checks that the slice starts with `expected` and moves the pointer forward past it.
+    ///
+    /// Use this for magic numbers and other fixed fields, which would otherwise need
+    /// [`starts_with`](ByteCode::starts_with), a [`skip`](ByteCode::skip) and a hand-made error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unexpected`] with the bytes found instead, and leaves the pointer unchanged,
+    /// if the slice does not start with `expected`, including when it ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(b"\x7fELF\x02\x01");
+    /// bytes.expect(b"\x7fELF").unwrap();
+    /// assert_eq!(bytes.pos(), 4);
+    ///
+    /// let error = bytes.expect(&[0x01]).unwrap_err();
+    /// assert_eq!(error.to_string(), "expected [01] at 0x4, found [02]");
+    /// assert_eq!(bytes.pos(), 4);
+    /// ```
+    pub fn expect(&mut self, expected: &[u8]) -> Result<(), Error> {
+        if !self.inner.starts_with(expected) {
+            let found = &self.inner[..expected.len().min(self.inner.len())];
+            return Err(Error::Unexpected {
+                pos: self.pos,
+                expected: expected.to_vec(),
+                found: found.to_vec(),
+            });
+        }
+        self.skip(expected.len());
+        Ok(())
+    }
+
+    /// Checks that the next byte is `value` and moves the pointer forward past it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unexpected`] and leaves the pointer unchanged if the byte differs
+    /// or the slice is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x03]);
+    /// assert!(bytes.expect_u8(0x03).is_ok());
+    /// assert!(bytes.expect_u8(0x03).is_err());
+    /// ```
+    pub fn expect_u8(&mut self, value: u8) -> Result<(), Error> {
+        self.expect(&[value])
+    }
+}
+
+#[cfg(test)]
+use alloc::{string::ToString, vec};
+
+#[test]
+fn expect() {
+    let mut bytes = ByteCode::new(&[0xca, 0xfe, 0x00, 0x02]);
+    bytes.expect(&[]).unwrap();
+    bytes.expect(&[0xca, 0xfe]).unwrap();
+    assert_eq!(
+        bytes.expect(&[0x00, 0x02, 0x00]),
+        Err(Error::Unexpected {
+            pos: 2,
+            expected: vec![0x00, 0x02, 0x00],
+            found: vec![0x00, 0x02],
+        })
+    );
+    assert_eq!(
+        bytes.expect_u16(2, Endian::Little).unwrap_err().to_string(),
+        "expected [02 00] at 0x2, found [00 02]"
+    );
+    bytes.expect_u16(2, Endian::Big).unwrap();
+    assert!(bytes.is_end());
+    assert_eq!(
+        bytes.expect_u8(0).unwrap_err().to_string(),
+        "expected [00] at 0x4, found []"
+    );
+}
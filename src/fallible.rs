@@ -0,0 +1,676 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+
+use crate::{
+    util::{gather_strided, strided_span},
+    ByteCode, Endian, Error, FourCc,
+};
+
+impl<'a> ByteCode<'a> {
+    fn check(&self, num: usize) -> Result<(), Error> {
+        if num > self.inner.len() {
+            return Err(Error::OutOfBounds {
+                pos: self.pos,
+                requested: num,
+                available: self.inner.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to subslice corresponding to the given size.
+    ///
+    /// Fallible version of [`peek`](ByteCode::peek).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert_eq!(bytes.try_peek(2).unwrap(), [0, 1]);
+    /// assert!(bytes.try_peek(4).is_err());
+    /// ```
+    pub fn try_peek(&self, num: usize) -> Result<&'a [u8], Error> {
+        self.check(num)?;
+        Ok(&self.inner[..num])
+    }
+
+    /// Move the pointer forward by the given number.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`skip`](ByteCode::skip) and `bytes += num`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert!(bytes.try_skip(2).is_ok());
+    /// assert!(bytes.try_skip(2).is_err());
+    /// assert_eq!(bytes.pos(), 2);
+    /// ```
+    pub fn try_skip(&mut self, num: usize) -> Result<(), Error> {
+        self.check(num)?;
+        self.move_forward(num);
+        Ok(())
+    }
+
+    /// Move the pointer back by the given number.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of `bytes -= num`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// bytes.skip(2);
+    /// assert!(bytes.try_rewind(1).is_ok());
+    /// assert!(bytes.try_rewind(2).is_err());
+    /// assert_eq!(bytes.pos(), 1);
+    /// ```
+    pub fn try_rewind(&mut self, num: usize) -> Result<(), Error> {
+        if num > self.pos {
+            return Err(Error::OutOfBounds {
+                pos: self.pos,
+                requested: num,
+                available: self.pos,
+            });
+        }
+        self.move_back(num);
+        Ok(())
+    }
+
+    /// Moves the pointer to an absolute position from the beginning of the slice.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`seek`](ByteCode::seek).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert!(bytes.try_seek(3).is_ok());
+    /// assert!(bytes.try_seek(4).is_err());
+    /// assert_eq!(bytes.pos(), 3);
+    /// ```
+    pub fn try_seek(&mut self, offset: usize) -> Result<(), Error> {
+        if offset > self.len() {
+            return Err(Error::OutOfBounds {
+                pos: self.pos,
+                requested: offset - self.pos,
+                available: self.inner.len(),
+            });
+        }
+        self.seek(offset);
+        Ok(())
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size.
+    /// Moves the pointer forward by the length of subslice. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take`](ByteCode::take).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert_eq!(bytes.try_take(2).unwrap(), [0, 1]);
+    /// assert_eq!(
+    ///     bytes.try_take(2),
+    ///     Err(Error::OutOfBounds { pos: 2, requested: 2, available: 1 })
+    /// );
+    /// ```
+    pub fn try_take(&mut self, num: usize) -> Result<Vec<u8>, Error> {
+        Ok(self.try_take_ref(num)?.to_owned())
+    }
+
+    /// Returns a reference to subslice corresponding to the given size, without copying.
+    /// Moves the pointer forward by the length of subslice. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_ref`](ByteCode::take_ref).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert_eq!(bytes.try_take_ref(2).unwrap(), [0, 1]);
+    /// assert!(bytes.try_take_ref(2).is_err());
+    /// ```
+    pub fn try_take_ref(&mut self, num: usize) -> Result<&'a [u8], Error> {
+        let result = self.try_peek(num)?;
+        self.skip(num);
+        Ok(result)
+    }
+
+    /// Returns the first `N` elements of the slice as an array.
+    /// Moves the pointer forward `N`. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_array`](ByteCode::take_array).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert_eq!(bytes.try_take_array::<2>().unwrap(), [0, 1]);
+    /// assert!(bytes.try_take_array::<2>().is_err());
+    /// ```
+    pub fn try_take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let array = self.try_peek_array()?;
+        self.skip(N);
+        Ok(array)
+    }
+
+    /// Returns the first `N` elements of the slice as an array without moving the pointer.
+    ///
+    /// Fallible version of [`peek_array`](ByteCode::peek_array).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert_eq!(bytes.try_peek_array::<3>().unwrap(), [0, 1, 2]);
+    /// assert!(bytes.try_peek_array::<4>().is_err());
+    /// ```
+    pub fn try_peek_array<const N: usize>(&self) -> Result<[u8; N], Error> {
+        match self.inner.first_chunk::<N>() {
+            Some(array) => Ok(*array),
+            None => Err(Error::OutOfBounds {
+                pos: self.pos,
+                requested: N,
+                available: self.inner.len(),
+            }),
+        }
+    }
+
+    /// Returns a new `ByteCode` over the first `num` elements of the slice.
+    /// Moves the pointer forward by given number. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_bytecode`](ByteCode::take_bytecode).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2]);
+    /// assert_eq!(bytes.try_take_bytecode(2).unwrap().as_slice(), [0, 1]);
+    /// assert!(bytes.try_take_bytecode(2).is_err());
+    /// assert_eq!(bytes.pos(), 2);
+    /// ```
+    pub fn try_take_bytecode(&mut self, num: usize) -> Result<ByteCode<'a>, Error> {
+        self.check(num)?;
+        Ok(self.take_bytecode(num))
+    }
+
+    /// Returns the first byte.
+    /// Moves the pointer forward 1. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u8`](ByteCode::take_into_u8).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xff]);
+    /// assert_eq!(bytes.try_take_into_u8().unwrap(), u8::MAX);
+    /// assert!(bytes.try_take_into_u8().is_err());
+    /// ```
+    pub fn try_take_into_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.try_take_array::<1>()?[0])
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+    /// Moves the pointer forward 2. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u16`](ByteCode::take_into_u16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xff, 0xff, 0x00]);
+    /// assert_eq!(bytes.try_take_into_u16().unwrap(), u16::MAX);
+    /// assert!(bytes.try_take_into_u16().is_err());
+    /// ```
+    pub fn try_take_into_u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.try_take_array()?;
+        Ok(match self.endian {
+            Endian::Big => u16::from_be_bytes(bytes),
+            Endian::Little => u16::from_le_bytes(bytes),
+        })
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u32`](ByteCode::take_into_u32).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xff, 0xff, 0xff, 0xff, 0x00]);
+    /// assert_eq!(bytes.try_take_into_u32().unwrap(), u32::MAX);
+    /// assert!(bytes.try_take_into_u32().is_err());
+    /// ```
+    pub fn try_take_into_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.try_take_array()?;
+        Ok(match self.endian {
+            Endian::Big => u32::from_be_bytes(bytes),
+            Endian::Little => u32::from_le_bytes(bytes),
+        })
+    }
+
+    /// Returns the first byte without moving the pointer.
+    ///
+    /// Fallible version of [`peek_into_u8`](ByteCode::peek_into_u8).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0xff]);
+    /// assert_eq!(bytes.try_peek_into_u8().unwrap(), u8::MAX);
+    /// assert!(ByteCode::new(&[]).try_peek_into_u8().is_err());
+    /// ```
+    pub fn try_peek_into_u8(&self) -> Result<u8, Error> {
+        Ok(self.try_peek_array::<1>()?[0])
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian), without moving the pointer.
+    ///
+    /// Fallible version of [`peek_into_u16`](ByteCode::peek_into_u16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0x01, 0x00]);
+    /// assert_eq!(bytes.try_peek_into_u16().unwrap(), 256);
+    /// assert!(ByteCode::new(&[0x01]).try_peek_into_u16().is_err());
+    /// ```
+    pub fn try_peek_into_u16(&self) -> Result<u16, Error> {
+        let bytes = self.try_peek_array()?;
+        Ok(match self.endian {
+            Endian::Big => u16::from_be_bytes(bytes),
+            Endian::Little => u16::from_le_bytes(bytes),
+        })
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian), without moving the pointer.
+    ///
+    /// Fallible version of [`peek_into_u32`](ByteCode::peek_into_u32).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00]);
+    /// assert_eq!(bytes.try_peek_into_u32().unwrap(), 256);
+    /// assert!(ByteCode::new(&[0x00, 0x00, 0x01]).try_peek_into_u32().is_err());
+    /// ```
+    pub fn try_peek_into_u32(&self) -> Result<u32, Error> {
+        let bytes = self.try_peek_array()?;
+        Ok(match self.endian {
+            Endian::Big => u32::from_be_bytes(bytes),
+            Endian::Little => u32::from_le_bytes(bytes),
+        })
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16` in network (big-endian) order.
+    /// Moves the pointer forward 2. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_be_u16`](ByteCode::take_be_u16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0xbb, 0x00]);
+    /// assert_eq!(bytes.try_take_be_u16().unwrap(), 443);
+    /// assert!(bytes.try_take_be_u16().is_err());
+    /// ```
+    pub fn try_take_be_u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_be_bytes(self.try_take_array()?))
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32` in network (big-endian) order.
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_be_u32`](ByteCode::take_be_u32).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00, 0x00]);
+    /// assert_eq!(bytes.try_take_be_u32().unwrap(), 256);
+    /// assert!(bytes.try_take_be_u32().is_err());
+    /// ```
+    pub fn try_take_be_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.try_take_array()?))
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16` in little-endian order.
+    /// Moves the pointer forward 2. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_le_u16`](ByteCode::take_le_u16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xbb, 0x01, 0x00]);
+    /// assert_eq!(bytes.try_take_le_u16().unwrap(), 443);
+    /// assert!(bytes.try_take_le_u16().is_err());
+    /// ```
+    pub fn try_take_le_u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.try_take_array()?))
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32` in little-endian order.
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_le_u32`](ByteCode::take_le_u32).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.try_take_le_u32().unwrap(), 256);
+    /// assert!(bytes.try_take_le_u32().is_err());
+    /// ```
+    pub fn try_take_le_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.try_take_array()?))
+    }
+
+    /// Returns the first 4 elements of the slice decoded as a 28-bit syncsafe integer.
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_syncsafe_u32`](ByteCode::take_into_syncsafe_u32),
+    /// which also reports set most significant bits as [`Error::ReservedBits`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x80, 0x01]);
+    /// assert_eq!(
+    ///     bytes.try_take_into_syncsafe_u32(),
+    ///     Err(Error::ReservedBits { pos: 0, bits: 0x8000 })
+    /// );
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn try_take_into_syncsafe_u32(&mut self) -> Result<u32, Error> {
+        let bytes: [u8; 4] = self.try_peek_array()?;
+        let reserved = u32::from_be_bytes(bytes) & 0x8080_8080;
+        if reserved != 0 {
+            return Err(Error::ReservedBits {
+                pos: self.pos,
+                bits: reserved as u64,
+            });
+        }
+        self.skip(4);
+        Ok(bytes.iter().fold(0, |acc, byte| (acc << 7) | *byte as u32))
+    }
+
+    /// Returns the string consisting of the given number of bytes from the beginning of the slice.
+    /// Moves the pointer forward by given number. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_string`](ByteCode::take_into_string),
+    /// which also reports invalid UTF-8 instead of panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x66, 0x6f, 0x6f, 0xff]);
+    /// assert_eq!(bytes.try_take_into_string(3).unwrap(), "foo");
+    /// assert_eq!(bytes.try_take_into_string(1), Err(Error::InvalidUtf8 { pos: 3, len: 1 }));
+    /// ```
+    pub fn try_take_into_string(&mut self, num: usize) -> Result<String, Error> {
+        Ok(self.try_take_str(num)?.to_owned())
+    }
+
+    /// Returns the string slice consisting of the given number of bytes from the beginning of the slice,
+    /// without copying. Moves the pointer forward by given number. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_str`](ByteCode::take_str).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x66, 0x6f, 0x6f, 0xff]);
+    /// assert_eq!(bytes.try_take_str(3).unwrap(), "foo");
+    /// assert_eq!(bytes.try_take_str(1), Err(Error::InvalidUtf8 { pos: 3, len: 1 }));
+    /// ```
+    pub fn try_take_str(&mut self, num: usize) -> Result<&'a str, Error> {
+        let bytes = self.try_peek(num)?;
+        let string = core::str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8 {
+            pos: self.pos,
+            len: num,
+        })?;
+        self.skip(num);
+        Ok(string)
+    }
+
+    /// Returns the next 4 bytes as a [`FourCc`].
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_fourcc`](ByteCode::take_fourcc).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"RIFF");
+    /// assert_eq!(bytes.try_take_fourcc().unwrap(), "RIFF");
+    /// assert!(bytes.try_take_fourcc().is_err());
+    /// ```
+    pub fn try_take_fourcc(&mut self) -> Result<FourCc, Error> {
+        Ok(FourCc(self.try_take_array()?))
+    }
+
+    /// Returns `count` elements of `element_size` bytes each, taken every `stride` bytes, concatenated into a vector.
+    /// Moves the pointer forward to the end of the last element. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_strided`](ByteCode::take_strided),
+    /// which also reports a `stride` less than `element_size` as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0xaa, 0x02, 0xaa, 0x03]);
+    /// assert!(bytes.try_take_strided(2, 1, 1).is_err());
+    /// assert_eq!(bytes.try_take_strided(1, 2, 3).unwrap(), [0x01, 0x02, 0x03]);
+    /// assert!(bytes.try_take_strided(1, 2, 1).is_err());
+    /// ```
+    pub fn try_take_strided(
+        &mut self,
+        element_size: usize,
+        stride: usize,
+        count: usize,
+    ) -> Result<Vec<u8>, Error> {
+        if stride < element_size {
+            return Err(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!(
+                    "stride {} is less than element size {}",
+                    stride, element_size
+                ),
+            });
+        }
+        let span = strided_span(element_size, stride, count);
+        let result = gather_strided(self.try_peek(span)?, element_size, stride);
+        self.skip(span);
+        Ok(result)
+    }
+
+    /// Returns `len`-byte subslices located at the given absolute offsets from the beginning of the slice.
+    /// The pointer is not moved.
+    ///
+    /// Fallible version of [`take_at_offsets`](ByteCode::take_at_offsets).
+    /// The error reports the first offending offset as its position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0, 1, 2, 3]);
+    /// assert_eq!(bytes.try_take_at_offsets(&[2, 0], 2).unwrap(), [&[2, 3], &[0, 1]]);
+    /// assert!(bytes.try_take_at_offsets(&[0, 3], 2).is_err());
+    /// ```
+    pub fn try_take_at_offsets(
+        &self,
+        offsets: &[usize],
+        len: usize,
+    ) -> Result<Vec<&'a [u8]>, Error> {
+        let whole = self.original();
+        if let Some(offset) = offsets
+            .iter()
+            .find(|offset| offset.checked_add(len).is_none_or(|end| end > whole.len()))
+        {
+            return Err(Error::OutOfBounds {
+                pos: *offset,
+                requested: len,
+                available: whole.len().saturating_sub(*offset),
+            });
+        }
+        Ok(offsets
+            .iter()
+            .map(|offset| &whole[*offset..*offset + len])
+            .collect())
+    }
+}
+
+#[test]
+fn try_peek() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    bytes.skip(1);
+    assert_eq!(bytes.try_peek(3).unwrap(), [1, 2, 3]);
+    assert_eq!(
+        bytes.try_peek(4),
+        Err(Error::OutOfBounds {
+            pos: 1,
+            requested: 4,
+            available: 3
+        })
+    );
+}
+
+#[test]
+fn try_take_into_integers() {
+    let mut bytes = ByteCode::new(&[0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x04]);
+    assert_eq!(bytes.try_take_into_u8().unwrap(), 1);
+    assert_eq!(bytes.try_take_into_u16().unwrap(), 2);
+    assert_eq!(bytes.try_take_into_u32().unwrap(), 3);
+    let error = bytes.try_take_into_u16().unwrap_err();
+    assert_eq!(error.pos(), 7);
+    assert_eq!(bytes.pos(), 7);
+    assert_eq!(bytes.try_take_into_u8().unwrap(), 4);
+    assert!(bytes.try_take_into_u8().is_err());
+    assert!(bytes.try_skip(0).is_ok());
+}
+
+#[test]
+fn try_take_into_string_invalid() {
+    let mut bytes = ByteCode::new(&[0x66, 0xc3, 0x28]);
+    assert!(matches!(
+        bytes.try_take_into_string(3),
+        Err(Error::InvalidUtf8 { pos: 0, len: 3 })
+    ));
+    assert_eq!(bytes.pos(), 0);
+    assert!(bytes.try_take_into_string(4).is_err());
+    assert_eq!(bytes.try_take_into_string(1).unwrap(), "f");
+}
+
+#[test]
+fn try_take_strided_out_of_range() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(
+        bytes.try_take_strided(2, 4, 3),
+        Err(Error::OutOfBounds {
+            pos: 0,
+            requested: 10,
+            available: 8
+        })
+    );
+    assert_eq!(bytes.pos(), 0);
+    assert!(bytes.try_take_strided(1, usize::MAX, 3).is_err());
+}
+
+#[test]
+fn try_take_at_offsets() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes.skip(4);
+    assert_eq!(bytes.try_take_at_offsets(&[1], 3).unwrap(), [&[1, 2, 3]]);
+    assert_eq!(
+        bytes.try_take_at_offsets(&[0, 9, 6], 3),
+        Err(Error::OutOfBounds {
+            pos: 9,
+            requested: 3,
+            available: 0
+        })
+    );
+    assert!(bytes.try_take_at_offsets(&[usize::MAX], 1).is_err());
+}
+
+#[test]
+fn try_take_strided_invalid_stride() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    let error = bytes.try_take_strided(2, 1, 2).unwrap_err();
+    assert!(matches!(error, Error::InvalidArgument { pos: 0, .. }));
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+fn try_take_into_syncsafe_u32() {
+    let mut bytes = ByteCode::new(&[0x80, 0x00, 0x00, 0x81, 0x00, 0x00, 0x02, 0x01]);
+    assert_eq!(
+        bytes.try_take_into_syncsafe_u32(),
+        Err(Error::ReservedBits {
+            pos: 0,
+            bits: 0x8000_0080
+        })
+    );
+    bytes.skip(4);
+    assert_eq!(bytes.try_take_into_syncsafe_u32().unwrap(), 257);
+    assert!(bytes.try_take_into_syncsafe_u32().is_err());
+}
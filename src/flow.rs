@@ -0,0 +1,206 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::InstructionMap;
+
+/// How control leaves an instruction, as reported to [`InstructionMap::basic_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Flow {
+    /// Continues to the next instruction.
+    Next,
+    /// Branches to the instructions at the given indices, and also falls through
+    /// to the next instruction if `falls_through` is `true`, as conditional branches do.
+    Branch {
+        targets: Vec<usize>,
+        falls_through: bool,
+    },
+    /// Ends execution, as returns and throws do.
+    Terminate,
+}
+
+/// A run of instructions that is entered only at its first instruction and left only after its last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// The indices of the instructions in the block.
+    pub instructions: Range<usize>,
+    /// The indices of the blocks control can pass to, without duplicates.
+    pub successors: Vec<usize>,
+}
+
+/// A control-flow graph of basic blocks, created by [`InstructionMap::basic_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    /// Returns the blocks, in order of position. The first block is the entry.
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+
+    /// Returns the index of the block containing the instruction at `index`.
+    pub fn block_of(&self, index: usize) -> Option<usize> {
+        let block = self
+            .blocks
+            .partition_point(|block| block.instructions.start <= index)
+            .checked_sub(1)?;
+        self.blocks[block]
+            .instructions
+            .contains(&index)
+            .then_some(block)
+    }
+
+    /// Returns the indices of the blocks that can pass control to `block`, in order.
+    pub fn predecessors(&self, block: usize) -> Vec<usize> {
+        (0..self.blocks.len())
+            .filter(|i| self.blocks[*i].successors.contains(&block))
+            .collect()
+    }
+}
+
+impl<T> InstructionMap<T> {
+    /// Splits the instructions into basic blocks, using `flow` to tell how control leaves each one.
+    ///
+    /// `flow` is called once per instruction, in order, with its index. Branch targets are instruction
+    /// indices, as returned by [`branch_target`](InstructionMap::branch_target); targets outside the map are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Flow};
+    ///
+    /// // 0x10 dd: branch if zero by dd from the next instruction, 0x20: return, others: 1-byte ops
+    /// let mut code = ByteCode::new(&[0x01, 0x10, 0x02, 0x02, 0x20, 0x03, 0x20]);
+    /// let map = code.decode_instructions(|code| {
+    ///     let opcode = code.take_into_u8();
+    ///     let operand = if opcode == 0x10 { code.take_into_u8() } else { 0 };
+    ///     Some((opcode, operand))
+    /// });
+    /// let cfg = map.basic_blocks(|index, (opcode, operand)| match opcode {
+    ///     0x10 => Flow::Branch {
+    ///         targets: map.branch_target(index, *operand as i64).into_iter().collect(),
+    ///         falls_through: true,
+    ///     },
+    ///     0x20 => Flow::Terminate,
+    ///     _ => Flow::Next,
+    /// });
+    /// let blocks = cfg.blocks();
+    /// assert_eq!(blocks.len(), 3);
+    /// assert_eq!((blocks[0].instructions.clone(), blocks[0].successors.clone()), (0..2, vec![2, 1]));
+    /// assert_eq!((blocks[1].instructions.clone(), blocks[1].successors.clone()), (2..4, vec![]));
+    /// assert_eq!((blocks[2].instructions.clone(), blocks[2].successors.clone()), (4..6, vec![]));
+    /// ```
+    pub fn basic_blocks(&self, mut flow: impl FnMut(usize, &T) -> Flow) -> ControlFlowGraph {
+        let len = self.len();
+        let flows: Vec<Flow> = self
+            .iter()
+            .enumerate()
+            .map(|(index, (_, instruction))| flow(index, instruction))
+            .collect();
+
+        let mut leaders = vec![false; len];
+        if len > 0 {
+            leaders[0] = true;
+        }
+        for (index, flow) in flows.iter().enumerate() {
+            match flow {
+                Flow::Next => continue,
+                Flow::Branch { targets, .. } => {
+                    for target in targets.iter().filter(|target| **target < len) {
+                        leaders[*target] = true;
+                    }
+                }
+                Flow::Terminate => {}
+            }
+            if index + 1 < len {
+                leaders[index + 1] = true;
+            }
+        }
+
+        let starts: Vec<usize> = (0..len).filter(|index| leaders[*index]).collect();
+        let ends = starts.iter().skip(1).copied().chain([len]);
+        let ranges: Vec<Range<usize>> = starts.iter().zip(ends).map(|(s, e)| *s..e).collect();
+        let block_of = |index: usize| ranges.partition_point(|range| range.start <= index) - 1;
+
+        let blocks = ranges
+            .iter()
+            .map(|range| {
+                let last = range.end - 1;
+                let mut successors = Vec::new();
+                let (targets, falls_through) = match &flows[last] {
+                    Flow::Next => (&[][..], true),
+                    Flow::Branch {
+                        targets,
+                        falls_through,
+                    } => (&targets[..], *falls_through),
+                    Flow::Terminate => (&[][..], false),
+                };
+                let next = (falls_through && range.end < len).then_some(range.end);
+                for target in targets
+                    .iter()
+                    .copied()
+                    .filter(|target| *target < len)
+                    .chain(next)
+                {
+                    let block = block_of(target);
+                    if !successors.contains(&block) {
+                        successors.push(block);
+                    }
+                }
+                BasicBlock {
+                    instructions: range.clone(),
+                    successors,
+                }
+            })
+            .collect();
+        ControlFlowGraph { blocks }
+    }
+}
+
+#[test]
+fn basic_blocks() {
+    // 0: nop, 1: branch to 4 or fall through, 2: nop, 3: jump to 1, 4: branch to 0 and 9, 5: return
+    let map: InstructionMap<u8> = (0..6).map(|i| (i..i + 1, i as u8)).collect();
+    let cfg = map.basic_blocks(|index, _| match index {
+        1 => Flow::Branch {
+            targets: vec![4],
+            falls_through: true,
+        },
+        3 => Flow::Branch {
+            targets: vec![1],
+            falls_through: false,
+        },
+        4 => Flow::Branch {
+            targets: vec![0, 9, 0],
+            falls_through: false,
+        },
+        5 => Flow::Terminate,
+        _ => Flow::Next,
+    });
+    let blocks: Vec<_> = cfg
+        .blocks()
+        .iter()
+        .map(|block| (block.instructions.clone(), block.successors.clone()))
+        .collect();
+    assert_eq!(
+        blocks,
+        [
+            (0..1, vec![1]),
+            (1..2, vec![3, 2]),
+            (2..4, vec![1]),
+            (4..5, vec![0]),
+            (5..6, vec![]),
+        ]
+    );
+    assert_eq!(cfg.block_of(3), Some(2));
+    assert_eq!(cfg.block_of(6), None);
+    assert_eq!(cfg.predecessors(1), [0, 2]);
+    assert_eq!(cfg.predecessors(4), []);
+}
+
+#[test]
+fn basic_blocks_empty() {
+    let map: InstructionMap<()> = InstructionMap::new();
+    assert!(map.basic_blocks(|_, _| Flow::Next).blocks().is_empty());
+}
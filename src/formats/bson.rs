@@ -0,0 +1,540 @@
+//! BSON documents, as stored in MongoDB dumps.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::bson::Document, ByteCode};
+//!
+//! let dump = std::fs::read("collection.bson").unwrap();
+//! let mut bytes = ByteCode::new(&dump);
+//! while !bytes.is_end() {
+//!     let document = Document::parse(&mut bytes).unwrap();
+//!     for element in document.iter() {
+//!         println!("{}: {:?}", element.key, element.value);
+//!     }
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{ByteCode, ByteCodeWriter, Endian};
+
+/// The deepest nesting of documents and arrays [`Document::parse`] accepts.
+pub const MAX_DEPTH: usize = 100;
+
+/// A BSON document or array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Document<'a> {
+    /// The element list, without the length prefix and the terminating NUL.
+    elements: &'a [u8],
+}
+
+impl<'a> Document<'a> {
+    /// Parses and validates a document, including all nested documents, at the beginning of the slice.
+    /// Moves the pointer forward past the document.
+    ///
+    /// Returns `None` without moving the pointer if the document is truncated or malformed,
+    /// or nests documents more than [`MAX_DEPTH`] levels deep.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{formats::bson::{Document, Value}, ByteCode};
+    ///
+    /// // {"a": 1}
+    /// let mut bytes = ByteCode::new(&[
+    ///     0x0c, 0x00, 0x00, 0x00, 0x10, 0x61, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    /// ]);
+    /// let document = Document::parse(&mut bytes).unwrap();
+    /// assert_eq!(document.get("a"), Some(Value::Int32(1)));
+    /// assert!(bytes.is_end());
+    /// ```
+    pub fn parse(bytes: &mut ByteCode<'a>) -> Option<Document<'a>> {
+        Document::parse_nested(bytes, 0)
+    }
+
+    fn parse_nested(bytes: &mut ByteCode<'a>, depth: usize) -> Option<Document<'a>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let (document, len) = Document::frame(bytes.as_slice())?;
+        let mut elements = document.iter();
+        while elements.decode(Some(depth + 1))?.is_some() {}
+
+        bytes.skip(len);
+        Some(document)
+    }
+
+    /// Reads the length prefix and the terminating NUL of a document without validating its elements,
+    /// returning the document and its length.
+    fn frame(inner: &'a [u8]) -> Option<(Document<'a>, usize)> {
+        let len = usize::try_from(Endian::Little.read_uint(inner.get(..4)?)).ok()?;
+        if len < 5 || inner.get(len - 1) != Some(&0) {
+            return None;
+        }
+        let elements = &inner[4..len - 1];
+        Some((Document { elements }, len))
+    }
+
+    /// Returns an iterator over the elements in order.
+    pub fn iter(&self) -> Elements<'a> {
+        let mut bytes = ByteCode::new(self.elements);
+        bytes.set_endian(Endian::Little);
+        Elements { bytes }
+    }
+
+    /// Returns the value of the first element with the given key.
+    pub fn get(&self, key: &str) -> Option<Value<'a>> {
+        self.iter()
+            .find(|element| element.key == key)
+            .map(|element| element.value)
+    }
+}
+
+impl Document<'_> {
+    /// Writes a document with the given elements at the end of `out`, recomputing the length prefixes
+    /// of the document, its strings and every nested document, so that a parsed document can be edited and re-emitted.
+    ///
+    /// The byte order of `out` is ignored, as BSON is always little-endian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key or a regular expression contains a NUL byte, or a length does not fit in `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{formats::bson::{Document, Element, Value}, ByteCode, ByteCodeWriter};
+    ///
+    /// // {"a": 1}
+    /// let data = [0x0c, 0x00, 0x00, 0x00, 0x10, 0x61, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00];
+    /// let document = Document::parse(&mut ByteCode::new(&data)).unwrap();
+    /// let mut elements: Vec<_> = document.iter().collect();
+    /// elements.push(Element { key: "b", value: Value::String("foo") });
+    ///
+    /// let mut out = ByteCodeWriter::new();
+    /// Document::write(elements, &mut out);
+    /// let edited = Document::parse(&mut ByteCode::new(out.as_slice())).unwrap();
+    /// assert_eq!(edited.get("b"), Some(Value::String("foo")));
+    /// ```
+    pub fn write<'e>(elements: impl IntoIterator<Item = Element<'e>>, out: &mut ByteCodeWriter) {
+        let mut document = Vec::new();
+        encode_document(&mut document, elements);
+        out.put_slice(&document);
+    }
+}
+
+impl<'a> IntoIterator for &Document<'a> {
+    type Item = Element<'a>;
+    type IntoIter = Elements<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A key-value pair of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element<'a> {
+    pub key: &'a str,
+    pub value: Value<'a>,
+}
+
+/// A typed BSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Double(f64),
+    String(&'a str),
+    Document(Document<'a>),
+    /// An array, stored as a document with the keys `"0"`, `"1"`, ...
+    Array(Document<'a>),
+    Binary {
+        subtype: u8,
+        data: &'a [u8],
+    },
+    Undefined,
+    ObjectId([u8; 12]),
+    Boolean(bool),
+    /// Milliseconds since the Unix epoch.
+    DateTime(i64),
+    Null,
+    Regex {
+        pattern: &'a str,
+        options: &'a str,
+    },
+    DbPointer {
+        namespace: &'a str,
+        id: [u8; 12],
+    },
+    JavaScript(&'a str),
+    Symbol(&'a str),
+    JavaScriptWithScope {
+        code: &'a str,
+        scope: Document<'a>,
+    },
+    Int32(i32),
+    Timestamp(u64),
+    Int64(i64),
+    Decimal128([u8; 16]),
+    MinKey,
+    MaxKey,
+}
+
+/// An iterator over the elements of a document.
+///
+/// This struct is created by [`Document::iter`].
+pub struct Elements<'a> {
+    bytes: ByteCode<'a>,
+}
+
+impl<'a> Elements<'a> {
+    /// Decodes the next element, returning `Some(None)` at the end and `None` if it is malformed.
+    ///
+    /// Nested documents are validated at `depth` if given, or only framed if they have been validated already.
+    fn decode(&mut self, depth: Option<usize>) -> Option<Option<Element<'a>>> {
+        let nested = |bytes: &mut ByteCode<'a>| match depth {
+            Some(depth) => Document::parse_nested(bytes, depth),
+            None => {
+                let (document, len) = Document::frame(bytes.as_slice())?;
+                bytes.skip(len);
+                Some(document)
+            }
+        };
+        let bytes = &mut self.bytes;
+        if bytes.is_end() {
+            return Some(None);
+        }
+        let element_type = bytes.take_into_u8();
+        let key = take_cstr(bytes)?;
+        let value = match element_type {
+            0x01 => Value::Double(bytes.try_take_into_f64().ok()?),
+            0x02 => Value::String(take_string(bytes)?),
+            0x03 => Value::Document(nested(bytes)?),
+            0x04 => Value::Array(nested(bytes)?),
+            0x05 => {
+                let len = usize::try_from(bytes.try_take_into_u32().ok()?).ok()?;
+                let subtype = bytes.try_take_into_u8().ok()?;
+                Value::Binary {
+                    subtype,
+                    data: bytes.try_take_ref(len).ok()?,
+                }
+            }
+            0x06 => Value::Undefined,
+            0x07 => Value::ObjectId(bytes.try_take_array().ok()?),
+            0x08 => match bytes.try_take_into_u8().ok()? {
+                0 => Value::Boolean(false),
+                1 => Value::Boolean(true),
+                _ => return None,
+            },
+            0x09 => Value::DateTime(bytes.try_take_into_i64().ok()?),
+            0x0a => Value::Null,
+            0x0b => Value::Regex {
+                pattern: take_cstr(bytes)?,
+                options: take_cstr(bytes)?,
+            },
+            0x0c => Value::DbPointer {
+                namespace: take_string(bytes)?,
+                id: bytes.try_take_array().ok()?,
+            },
+            0x0d => Value::JavaScript(take_string(bytes)?),
+            0x0e => Value::Symbol(take_string(bytes)?),
+            0x0f => {
+                let len = usize::try_from(bytes.try_take_into_u32().ok()?).ok()?;
+                let mut scoped = bytes.try_take_bytecode(len.checked_sub(4)?).ok()?;
+                let code = take_string(&mut scoped)?;
+                let scope = nested(&mut scoped)?;
+                if !scoped.is_end() {
+                    return None;
+                }
+                Value::JavaScriptWithScope { code, scope }
+            }
+            0x10 => Value::Int32(bytes.try_take_into_i32().ok()?),
+            0x11 => Value::Timestamp(bytes.try_take_into_u64().ok()?),
+            0x12 => Value::Int64(bytes.try_take_into_i64().ok()?),
+            0x13 => Value::Decimal128(bytes.try_take_array().ok()?),
+            0xff => Value::MinKey,
+            0x7f => Value::MaxKey,
+            _ => return None,
+        };
+        Some(Some(Element { key, value }))
+    }
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = Element<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The document has been validated by `Document::parse`.
+        self.decode(None).flatten()
+    }
+}
+
+impl Value<'_> {
+    fn element_type(&self) -> u8 {
+        match self {
+            Value::Double(_) => 0x01,
+            Value::String(_) => 0x02,
+            Value::Document(_) => 0x03,
+            Value::Array(_) => 0x04,
+            Value::Binary { .. } => 0x05,
+            Value::Undefined => 0x06,
+            Value::ObjectId(_) => 0x07,
+            Value::Boolean(_) => 0x08,
+            Value::DateTime(_) => 0x09,
+            Value::Null => 0x0a,
+            Value::Regex { .. } => 0x0b,
+            Value::DbPointer { .. } => 0x0c,
+            Value::JavaScript(_) => 0x0d,
+            Value::Symbol(_) => 0x0e,
+            Value::JavaScriptWithScope { .. } => 0x0f,
+            Value::Int32(_) => 0x10,
+            Value::Timestamp(_) => 0x11,
+            Value::Int64(_) => 0x12,
+            Value::Decimal128(_) => 0x13,
+            Value::MinKey => 0xff,
+            Value::MaxKey => 0x7f,
+        }
+    }
+}
+
+fn encode_document<'e>(out: &mut Vec<u8>, elements: impl IntoIterator<Item = Element<'e>>) {
+    let start = out.len();
+    out.extend([0; 4]);
+    for element in elements {
+        out.push(element.value.element_type());
+        encode_cstring(out, element.key);
+        encode_value(out, &element.value);
+    }
+    out.push(0);
+    patch_len(out, start);
+}
+
+fn encode_value(out: &mut Vec<u8>, value: &Value) {
+    match *value {
+        Value::Double(value) => out.extend(value.to_le_bytes()),
+        Value::String(string) | Value::JavaScript(string) | Value::Symbol(string) => {
+            encode_string(out, string)
+        }
+        Value::Document(document) | Value::Array(document) => encode_document(out, &document),
+        Value::Binary { subtype, data } => {
+            out.extend(len_i32(data.len()).to_le_bytes());
+            out.push(subtype);
+            out.extend(data);
+        }
+        Value::Undefined | Value::Null | Value::MinKey | Value::MaxKey => {}
+        Value::ObjectId(id) => out.extend(id),
+        Value::Boolean(value) => out.push(value as u8),
+        Value::DateTime(value) | Value::Int64(value) => out.extend(value.to_le_bytes()),
+        Value::Regex { pattern, options } => {
+            encode_cstring(out, pattern);
+            encode_cstring(out, options);
+        }
+        Value::DbPointer { namespace, id } => {
+            encode_string(out, namespace);
+            out.extend(id);
+        }
+        Value::JavaScriptWithScope { code, scope } => {
+            let start = out.len();
+            out.extend([0; 4]);
+            encode_string(out, code);
+            encode_document(out, &scope);
+            patch_len(out, start);
+        }
+        Value::Int32(value) => out.extend(value.to_le_bytes()),
+        Value::Timestamp(value) => out.extend(value.to_le_bytes()),
+        Value::Decimal128(value) => out.extend(value),
+    }
+}
+
+fn encode_cstring(out: &mut Vec<u8>, string: &str) {
+    if string.contains('\0') {
+        panic!(
+            "BSON key or regular expression {:?} contains a NUL byte",
+            string
+        );
+    }
+    out.extend(string.as_bytes());
+    out.push(0);
+}
+
+fn encode_string(out: &mut Vec<u8>, string: &str) {
+    out.extend(len_i32(string.len() + 1).to_le_bytes());
+    out.extend(string.as_bytes());
+    out.push(0);
+}
+
+/// Writes the length of everything from `start` to the end into the 4 bytes at `start`.
+fn patch_len(out: &mut [u8], start: usize) {
+    let len = len_i32(out.len() - start);
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+}
+
+fn len_i32(len: usize) -> i32 {
+    i32::try_from(len).unwrap_or_else(|_| panic!("BSON length {} does not fit in i32", len))
+}
+
+/// Returns the string up to the next NUL byte, without copying, and moves the pointer past the NUL.
+fn take_cstr<'a>(bytes: &mut ByteCode<'a>) -> Option<&'a str> {
+    let len = bytes.find(&[0])?;
+    let string = bytes.try_take_str(len).ok()?;
+    bytes.skip(1);
+    Some(string)
+}
+
+/// Returns a BSON string: a 4-byte length, then the string and a NUL byte it counts.
+fn take_string<'a>(bytes: &mut ByteCode<'a>) -> Option<&'a str> {
+    match bytes
+        .try_take_length_prefixed_slice_u32()
+        .ok()?
+        .split_last()
+    {
+        Some((0, string)) => core::str::from_utf8(string).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn document_parse() {
+    let mut bytes = ByteCode::new(&[
+        0x54, 0x00, 0x00, 0x00, // length
+        0x02, b'n', b'a', b'm', b'e', 0x00, // name
+        0x04, 0x00, 0x00, 0x00, b'f', b'o', b'o', 0x00, // "foo"
+        0x01, b'p', b'i', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x40, // 3.5
+        0x04, b'l', b'i', b's', b't', 0x00, 0x10, 0x00, 0x00, 0x00, // [1, true]
+        0x10, b'0', 0x00, 0x01, 0x00, 0x00, 0x00, //
+        0x08, b'1', 0x00, 0x01, 0x00, //
+        0x05, b'b', b'i', b'n', 0x00, 0x02, 0x00, 0x00, 0x00, 0x80, 0xab, 0xcd, // binary
+        0x12, b'b', b'i', b'g', 0x00, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // -2
+        0x0a, b'n', b'u', b'l', b'l', 0x00, // null
+        0x00,
+    ]);
+    let parsed = Document::parse(&mut bytes).unwrap();
+    assert!(bytes.is_end());
+
+    let elements: Vec<_> = parsed.iter().collect();
+    assert_eq!(elements.len(), 6);
+    assert_eq!(elements[0].key, "name");
+    assert_eq!(elements[0].value, Value::String("foo"));
+    assert_eq!(parsed.get("pi"), Some(Value::Double(3.5)));
+    assert_eq!(
+        parsed.get("bin"),
+        Some(Value::Binary {
+            subtype: 0x80,
+            data: &[0xab, 0xcd]
+        })
+    );
+    assert_eq!(parsed.get("big"), Some(Value::Int64(-2)));
+    assert_eq!(parsed.get("null"), Some(Value::Null));
+    assert_eq!(parsed.get("missing"), None);
+
+    match parsed.get("list") {
+        Some(Value::Array(list)) => {
+            let values: Vec<_> = list.iter().map(|element| element.value).collect();
+            assert_eq!(values, [Value::Int32(1), Value::Boolean(true)]);
+        }
+        value => panic!("unexpected value: {:?}", value),
+    }
+}
+
+#[test]
+fn document_parse_malformed() {
+    // {"name": "foo"}
+    let document = b"\x13\0\0\0\x02name\0\x04\0\0\0foo\0\0";
+    assert!(Document::parse(&mut ByteCode::new(document)).is_some());
+    assert!(Document::parse(&mut ByteCode::new(&document[..document.len() - 1])).is_none());
+
+    // Unknown element type
+    let mut invalid = *document;
+    invalid[4] = 0x20;
+    assert!(Document::parse(&mut ByteCode::new(&invalid)).is_none());
+
+    // String without the terminating NUL
+    let mut invalid = *document;
+    invalid[17] = b'!';
+    let mut bytes = ByteCode::new(&invalid);
+    assert!(Document::parse(&mut bytes).is_none());
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+fn document_parse_depth() {
+    // [[[...[]...]]] nested `depth` levels below the top-level document
+    let nest = |depth: usize| {
+        let mut document = vec![0x05, 0x00, 0x00, 0x00, 0x00];
+        for _ in 0..depth {
+            let mut parent = ((document.len() + 8) as u32).to_le_bytes().to_vec();
+            parent.extend(b"\x040\0");
+            parent.extend(&document);
+            parent.push(0);
+            document = parent;
+        }
+        document
+    };
+    assert!(Document::parse(&mut ByteCode::new(&nest(MAX_DEPTH))).is_some());
+    assert!(Document::parse(&mut ByteCode::new(&nest(MAX_DEPTH + 1))).is_none());
+}
+
+#[test]
+fn write_round_trip() {
+    // {"name": "foo", "list": [1, true], "n": 7}
+    let document = b"\x30\0\0\0\x02name\0\x04\0\0\0foo\0\x04list\0\x10\0\0\0\x100\0\x01\0\0\0\x081\0\x01\0\x10n\0\x07\0\0\0\0";
+    let parsed = Document::parse(&mut ByteCode::new(document)).unwrap();
+    let mut out = ByteCodeWriter::new();
+    Document::write(&parsed, &mut out);
+    assert_eq!(out.as_slice(), document);
+
+    let mut list = ByteCodeWriter::new();
+    Document::write(
+        [Element {
+            key: "0",
+            value: Value::Regex {
+                pattern: "^a",
+                options: "i",
+            },
+        }],
+        &mut list,
+    );
+    let list = Document::parse(&mut ByteCode::new(list.as_slice())).unwrap();
+    let elements = parsed.iter().map(|element| match element.key {
+        "name" => Element {
+            key: "name",
+            value: Value::String("renamed"),
+        },
+        "list" => Element {
+            key: "list",
+            value: Value::Array(list),
+        },
+        _ => element,
+    });
+    let mut out = ByteCodeWriter::new();
+    Document::write(elements, &mut out);
+    let edited = Document::parse(&mut ByteCode::new(out.as_slice())).unwrap();
+    assert_eq!(edited.get("name"), Some(Value::String("renamed")));
+    assert_eq!(edited.get("list"), Some(Value::Array(list)));
+    assert_eq!(edited.get("n"), Some(Value::Int32(7)));
+}
+
+#[test]
+fn javascript_with_scope() {
+    // {"f": code_w_s("x", {})}
+    let mut bytes = ByteCode::new(&[
+        0x17, 0x00, 0x00, 0x00, 0x0f, 0x66, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x78, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ]);
+    let document = Document::parse(&mut bytes).unwrap();
+    match document.get("f") {
+        Some(Value::JavaScriptWithScope { code, scope }) => {
+            assert_eq!(code, "x");
+            assert_eq!(scope.iter().count(), 0);
+        }
+        value => panic!("unexpected value: {:?}", value),
+    }
+
+    let mut out = ByteCodeWriter::new();
+    Document::write(&document, &mut out);
+    bytes.reset();
+    assert_eq!(out.as_slice(), bytes.as_slice());
+}
@@ -0,0 +1,442 @@
+//! DNS wire-format messages (RFC 1035), including compressed names.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::dns::{Message, RecordData}, ByteCode};
+//!
+//! # let packet: Vec<u8> = vec![];
+//! let message = Message::parse(&mut ByteCode::new(&packet)).unwrap();
+//! for answer in &message.answers {
+//!     if let Some(RecordData::A(address)) = message.record_data(answer) {
+//!         println!("{} {:?}", answer.name, address);
+//!     }
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::ByteCode;
+
+const MAX_NAME_LENGTH: usize = 255;
+
+/// A record type code.
+pub mod record_type {
+    pub const A: u16 = 1;
+    pub const NS: u16 = 2;
+    pub const CNAME: u16 = 5;
+    pub const SOA: u16 = 6;
+    pub const PTR: u16 = 12;
+    pub const MX: u16 = 15;
+    pub const TXT: u16 = 16;
+    pub const AAAA: u16 = 28;
+}
+
+/// The 12-byte message header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub id: u16,
+    pub flags: u16,
+    pub question_count: u16,
+    pub answer_count: u16,
+    pub authority_count: u16,
+    pub additional_count: u16,
+}
+
+impl Header {
+    /// Returns `true` if the message is a response.
+    pub fn is_response(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+
+    pub fn opcode(&self) -> u8 {
+        ((self.flags >> 11) & 0x0f) as u8
+    }
+
+    pub fn is_authoritative(&self) -> bool {
+        self.flags & 0x0400 != 0
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.flags & 0x0200 != 0
+    }
+
+    pub fn recursion_desired(&self) -> bool {
+        self.flags & 0x0100 != 0
+    }
+
+    pub fn recursion_available(&self) -> bool {
+        self.flags & 0x0080 != 0
+    }
+
+    pub fn rcode(&self) -> u8 {
+        (self.flags & 0x000f) as u8
+    }
+}
+
+/// A domain name, with compression pointers already resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Name<'a> {
+    pub labels: Vec<&'a [u8]>,
+}
+
+impl Display for Name<'_> {
+    /// Formats the name in dotted form, with non-printable bytes and dots within labels escaped.
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        if self.labels.is_empty() {
+            return write!(f, ".");
+        }
+        for (i, label) in self.labels.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            for byte in *label {
+                match byte {
+                    b'.' | b'\\' => write!(f, "\\{}", *byte as char)?,
+                    0x21..=0x7e => write!(f, "{}", *byte as char)?,
+                    _ => write!(f, "\\{:03}", byte)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entry of the question section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question<'a> {
+    pub name: Name<'a>,
+    pub qtype: u16,
+    pub qclass: u16,
+}
+
+/// A resource record of the answer, authority or additional section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    pub name: Name<'a>,
+    pub rtype: u16,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: &'a [u8],
+    /// Offset of `data` from the beginning of the message, needed to resolve names inside it.
+    pub data_offset: usize,
+}
+
+/// Decoded record data of common types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordData<'a> {
+    A([u8; 4]),
+    Aaaa([u8; 16]),
+    /// `NS`, `CNAME` and `PTR`.
+    Name(Name<'a>),
+    Mx {
+        preference: u16,
+        exchange: Name<'a>,
+    },
+    Soa {
+        mname: Name<'a>,
+        rname: Name<'a>,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    Txt(Vec<&'a [u8]>),
+    Other(&'a [u8]),
+}
+
+/// A DNS message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message<'a> {
+    pub header: Header,
+    pub questions: Vec<Question<'a>>,
+    pub answers: Vec<Record<'a>>,
+    pub authorities: Vec<Record<'a>>,
+    pub additionals: Vec<Record<'a>>,
+    message: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    /// Parses a message occupying the rest of the slice, as in a UDP payload.
+    /// Moves the pointer forward past the last record.
+    ///
+    /// Returns `None` without moving the pointer if any section is truncated or a name is malformed.
+    pub fn parse(bytes: &mut ByteCode<'a>) -> Option<Message<'a>> {
+        let message: &'a [u8] = bytes.as_slice();
+        let mut reader = ByteCode::new(message);
+        if message.len() < 12 {
+            return None;
+        }
+        let header = Header {
+            id: reader.take_be_u16(),
+            flags: reader.take_be_u16(),
+            question_count: reader.take_be_u16(),
+            answer_count: reader.take_be_u16(),
+            authority_count: reader.take_be_u16(),
+            additional_count: reader.take_be_u16(),
+        };
+
+        let mut questions = Vec::new();
+        for _ in 0..header.question_count {
+            let name = take_name(message, &mut reader)?;
+            if reader.as_slice().len() < 4 {
+                return None;
+            }
+            questions.push(Question {
+                name,
+                qtype: reader.take_be_u16(),
+                qclass: reader.take_be_u16(),
+            });
+        }
+        let answers = take_records(message, &mut reader, header.answer_count)?;
+        let authorities = take_records(message, &mut reader, header.authority_count)?;
+        let additionals = take_records(message, &mut reader, header.additional_count)?;
+
+        bytes.skip(reader.pos());
+        Some(Message {
+            header,
+            questions,
+            answers,
+            authorities,
+            additionals,
+            message,
+        })
+    }
+
+    /// Decodes the data of a record of this message, resolving compressed names.
+    ///
+    /// Returns `None` if the data is malformed for its type.
+    pub fn record_data(&self, record: &Record<'a>) -> Option<RecordData<'a>> {
+        let mut data = ByteCode::new(self.message);
+        data.try_skip(record.data_offset).ok()?;
+        let end = record.data_offset.checked_add(record.data.len())?;
+        let record_data = match record.rtype {
+            record_type::A => RecordData::A(record.data.try_into().ok()?),
+            record_type::AAAA => RecordData::Aaaa(record.data.try_into().ok()?),
+            record_type::NS | record_type::CNAME | record_type::PTR => {
+                RecordData::Name(take_name(self.message, &mut data)?)
+            }
+            record_type::MX => {
+                if record.data.len() < 2 {
+                    return None;
+                }
+                RecordData::Mx {
+                    preference: data.try_take_be_u16().ok()?,
+                    exchange: take_name(self.message, &mut data)?,
+                }
+            }
+            record_type::SOA => {
+                let mname = take_name(self.message, &mut data)?;
+                let rname = take_name(self.message, &mut data)?;
+                if end.checked_sub(data.pos())? != 20 {
+                    return None;
+                }
+                RecordData::Soa {
+                    mname,
+                    rname,
+                    serial: data.try_take_be_u32().ok()?,
+                    refresh: data.try_take_be_u32().ok()?,
+                    retry: data.try_take_be_u32().ok()?,
+                    expire: data.try_take_be_u32().ok()?,
+                    minimum: data.try_take_be_u32().ok()?,
+                }
+            }
+            record_type::TXT => {
+                let mut strings = Vec::new();
+                let mut rest = record.data;
+                while let Some((len, tail)) = rest.split_first() {
+                    strings.push(tail.get(..*len as usize)?);
+                    rest = &tail[*len as usize..];
+                }
+                return Some(RecordData::Txt(strings));
+            }
+            _ => return Some(RecordData::Other(record.data)),
+        };
+        if data.pos() > end {
+            return None;
+        }
+        Some(record_data)
+    }
+}
+
+fn take_records<'a>(
+    message: &'a [u8],
+    reader: &mut ByteCode<'a>,
+    count: u16,
+) -> Option<Vec<Record<'a>>> {
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let name = take_name(message, reader)?;
+        if reader.as_slice().len() < 10 {
+            return None;
+        }
+        let rtype = reader.take_be_u16();
+        let class = reader.take_be_u16();
+        let ttl = reader.take_be_u32();
+        let len = reader.take_be_u16() as usize;
+        let data_offset = reader.pos();
+        let data = message.get(data_offset..data_offset + len)?;
+        reader.skip(len);
+        records.push(Record {
+            name,
+            rtype,
+            class,
+            ttl,
+            data,
+            data_offset,
+        });
+    }
+    Some(records)
+}
+
+/// Reads a possibly compressed name at the current position of `reader`, which must be a reader over `message`.
+/// Termination is guaranteed by the `MAX_NAME_LENGTH` check, which bounds the labels followed,
+/// together with compression pointers having to point strictly backwards.
+fn take_name<'a>(message: &'a [u8], reader: &mut ByteCode) -> Option<Name<'a>> {
+    let mut labels = Vec::new();
+    let mut offset = reader.pos();
+    let mut end = None;
+    let mut length = 0;
+    loop {
+        let len = *message.get(offset)? as usize;
+        match len & 0xc0 {
+            0x00 if len == 0 => {
+                end.get_or_insert(offset + 1);
+                break;
+            }
+            0x00 => {
+                let label = message.get(offset + 1..offset + 1 + len)?;
+                length += len + 1;
+                if length > MAX_NAME_LENGTH {
+                    return None;
+                }
+                labels.push(label);
+                offset += 1 + len;
+            }
+            0xc0 => {
+                let low = *message.get(offset + 1)? as usize;
+                let target = ((len & 0x3f) << 8) | low;
+                end.get_or_insert(offset + 2);
+                if target >= offset {
+                    return None;
+                }
+                offset = target;
+            }
+            _ => return None,
+        }
+    }
+    reader.skip(end? - reader.pos());
+    Some(Name { labels })
+}
+
+#[cfg(test)]
+use alloc::{string::ToString, vec};
+
+#[test]
+fn message_parse() {
+    let packet = [
+        &b"\x12\x34\x81\x80\x00\x01\x00\x03\x00\x00\x00\x00"[..],
+        // example.com A IN
+        b"\x07example\x03com\x00\x00\x01\x00\x01",
+        // example.com (pointer to 12) A IN 300 93.184.216.34
+        b"\xc0\x0c\x00\x01\x00\x01\x00\x00\x01\x2c\x00\x04\x5d\xb8\xd8\x22",
+        // www.example.com CNAME example.com
+        b"\x03www\xc0\x0c\x00\x05\x00\x01\x00\x00\x01\x2c\x00\x02\xc0\x0c",
+        // example.com MX 10 mail.example.com
+        b"\xc0\x0c\x00\x0f\x00\x01\x00\x00\x01\x2c\x00\x09\x00\x0a\x04mail\xc0\x0c",
+    ]
+    .concat();
+    let mut bytes = ByteCode::new(&packet);
+    let message = Message::parse(&mut bytes).unwrap();
+    assert!(bytes.is_end());
+
+    assert_eq!(message.header.id, 0x1234);
+    assert!(message.header.is_response());
+    assert!(message.header.recursion_desired());
+    assert!(message.header.recursion_available());
+    assert_eq!(message.header.rcode(), 0);
+
+    assert_eq!(message.questions.len(), 1);
+    assert_eq!(message.questions[0].name.to_string(), "example.com");
+    assert_eq!(message.questions[0].qtype, record_type::A);
+
+    assert_eq!(message.answers.len(), 3);
+    let a = &message.answers[0];
+    assert_eq!(a.name.to_string(), "example.com");
+    assert_eq!(a.ttl, 300);
+    assert_eq!(
+        message.record_data(a),
+        Some(RecordData::A([93, 184, 216, 34]))
+    );
+
+    let cname = &message.answers[1];
+    assert_eq!(cname.name.to_string(), "www.example.com");
+    match message.record_data(cname) {
+        Some(RecordData::Name(name)) => assert_eq!(name.to_string(), "example.com"),
+        data => panic!("unexpected data: {:?}", data),
+    }
+
+    match message.record_data(&message.answers[2]) {
+        Some(RecordData::Mx {
+            preference,
+            exchange,
+        }) => {
+            assert_eq!(preference, 10);
+            assert_eq!(exchange.to_string(), "mail.example.com");
+        }
+        data => panic!("unexpected data: {:?}", data),
+    }
+
+    // records edited to point outside the message
+    let mut mx = message.answers[2].clone();
+    for offset in [packet.len() - 1, packet.len() + 1, usize::MAX] {
+        mx.data_offset = offset;
+        assert_eq!(message.record_data(&mx), None);
+    }
+}
+
+#[test]
+fn message_parse_pointer_loop() {
+    let mut packet = vec![
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    packet.extend(b"\xc0\x0c\x00\x01\x00\x01");
+    assert!(Message::parse(&mut ByteCode::new(&packet)).is_none());
+}
+
+#[test]
+fn message_parse_truncated() {
+    // example.com A IN
+    let packet =
+        b"\x12\x34\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\x07example\x03com\x00\x00\x01\x00\x01";
+    for len in [0, 11, 20, packet.len() - 1] {
+        let mut bytes = ByteCode::new(&packet[..len]);
+        assert!(Message::parse(&mut bytes).is_none());
+        assert_eq!(bytes.pos(), 0);
+    }
+}
+
+#[test]
+fn txt_record_data() {
+    let mut packet = vec![
+        0x00, 0x00, 0x81, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    ];
+    packet.extend(b"\x00\x00\x10\x00\x01\x00\x00\x00\x00\x00\x07\x02hi\x03foo");
+    let message = Message::parse(&mut ByteCode::new(&packet)).unwrap();
+    let record = &message.answers[0];
+    assert_eq!(record.name.to_string(), ".");
+    assert_eq!(
+        message.record_data(record),
+        Some(RecordData::Txt(vec![b"hi", b"foo"]))
+    );
+}
+
+#[test]
+fn name_display_escapes() {
+    let name = Name {
+        labels: vec![b"a.b", b"\x00c"],
+    };
+    assert_eq!(name.to_string(), "a\\.b.\\000c");
+}
@@ -0,0 +1,726 @@
+//! DWARF `.debug_abbrev` tables and `.debug_line` line-number programs.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytecode::{formats::dwarf::parse_abbrevs, ByteCode};
+//!
+//! // code 1, DW_TAG_compile_unit, DW_CHILDREN_yes, (DW_AT_name, DW_FORM_string)
+//! let mut debug_abbrev = ByteCode::new(&[0x01, 0x11, 0x01, 0x03, 0x08, 0x00, 0x00, 0x00]);
+//! let abbrevs = parse_abbrevs(&mut debug_abbrev).unwrap();
+//! assert_eq!(abbrevs[0].tag, 0x11);
+//! assert!(abbrevs[0].has_children);
+//! ```
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{ByteCode, Endian};
+
+const DW_FORM_BLOCK: u64 = 0x09;
+const DW_FORM_BLOCK1: u64 = 0x0a;
+const DW_FORM_DATA1: u64 = 0x0b;
+const DW_FORM_DATA2: u64 = 0x05;
+const DW_FORM_DATA4: u64 = 0x06;
+const DW_FORM_DATA8: u64 = 0x07;
+const DW_FORM_DATA16: u64 = 0x1e;
+const DW_FORM_STRING: u64 = 0x08;
+const DW_FORM_STRP: u64 = 0x0e;
+const DW_FORM_LINE_STRP: u64 = 0x1f;
+const DW_FORM_UDATA: u64 = 0x0f;
+const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+
+const DW_LNCT_PATH: u64 = 0x1;
+const DW_LNCT_DIRECTORY_INDEX: u64 = 0x2;
+const DW_LNCT_TIMESTAMP: u64 = 0x3;
+const DW_LNCT_SIZE: u64 = 0x4;
+const DW_LNCT_MD5: u64 = 0x5;
+
+/// An attribute specification of an abbreviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeSpec {
+    /// `DW_AT_*`
+    pub name: u64,
+    /// `DW_FORM_*`
+    pub form: u64,
+    /// Only present for `DW_FORM_implicit_const`.
+    pub implicit_const: Option<i64>,
+}
+
+/// An entry of the `.debug_abbrev` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviation {
+    pub code: u64,
+    /// `DW_TAG_*`
+    pub tag: u64,
+    pub has_children: bool,
+    pub attributes: Vec<AttributeSpec>,
+}
+
+/// Parses one abbreviation table, up to and including its terminating null entry.
+/// Moves the pointer forward past the table.
+///
+/// Returns `None` if the table is truncated.
+pub fn parse_abbrevs(bytes: &mut ByteCode) -> Option<Vec<Abbreviation>> {
+    let mut abbrevs = Vec::new();
+    loop {
+        let code = bytes.try_take_uleb128().ok()?;
+        if code == 0 {
+            return Some(abbrevs);
+        }
+        let tag = bytes.try_take_uleb128().ok()?;
+        let has_children = bytes.try_take_into_u8().ok()? != 0;
+        let mut attributes = Vec::new();
+        loop {
+            let name = bytes.try_take_uleb128().ok()?;
+            let form = bytes.try_take_uleb128().ok()?;
+            if name == 0 && form == 0 {
+                break;
+            }
+            let implicit_const = if form == DW_FORM_IMPLICIT_CONST {
+                Some(bytes.try_take_sleb128().ok()?)
+            } else {
+                None
+            };
+            attributes.push(AttributeSpec {
+                name,
+                form,
+                implicit_const,
+            });
+        }
+        abbrevs.push(Abbreviation {
+            code,
+            tag,
+            has_children,
+            attributes,
+        });
+    }
+}
+
+/// A string in a line-number program header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineString {
+    /// `DW_FORM_string`
+    Inline(String),
+    /// `DW_FORM_strp`, an offset into `.debug_str`.
+    Str(u64),
+    /// `DW_FORM_line_strp`, an offset into `.debug_line_str`.
+    LineStr(u64),
+}
+
+/// An entry of the file name table of a line-number program header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: LineString,
+    pub directory_index: u64,
+    pub timestamp: u64,
+    pub size: u64,
+    pub md5: Option<[u8; 16]>,
+}
+
+/// The header of a line-number program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineProgramHeader {
+    pub unit_length: u64,
+    pub dwarf64: bool,
+    pub version: u16,
+    /// Only present in DWARF 5.
+    pub address_size: Option<u8>,
+    pub header_length: u64,
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    pub standard_opcode_lengths: Vec<u8>,
+    pub include_directories: Vec<LineString>,
+    pub file_names: Vec<FileEntry>,
+}
+
+/// A line-number program unit of the `.debug_line` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineProgram<'a> {
+    pub header: LineProgramHeader,
+    endian: Endian,
+    program: &'a [u8],
+}
+
+impl<'a> LineProgram<'a> {
+    /// Parses a line-number program unit at the beginning of the slice. DWARF versions 2 to 5 are supported.
+    /// Moves the pointer forward past the unit.
+    ///
+    /// Returns `None` if the unit is truncated, an inline string is not valid UTF-8,
+    /// or its version is not supported.
+    pub fn parse(bytes: &mut ByteCode<'a>, endian: Endian) -> Option<LineProgram<'a>> {
+        let (unit_length, dwarf64) = match bytes.try_take_uint(4, endian).ok()? {
+            0xffff_ffff => (bytes.try_take_uint(8, endian).ok()?, true),
+            length => (length, false),
+        };
+        let offset_size = if dwarf64 { 8 } else { 4 };
+        let mut unit = bytes
+            .try_take_bytecode(usize::try_from(unit_length).ok()?)
+            .ok()?;
+
+        let version = unit.try_take_uint(2, endian).ok()? as u16;
+        if !(2..=5).contains(&version) {
+            return None;
+        }
+        let address_size = if version >= 5 {
+            let address_size = unit.try_take_into_u8().ok()?;
+            unit.try_take_into_u8().ok()?; // segment_selector_size
+            Some(address_size)
+        } else {
+            None
+        };
+        let header_length = unit.try_take_uint(offset_size, endian).ok()?;
+        let inner: &'a [u8] = unit.as_slice();
+        let program = inner.get(usize::try_from(header_length).ok()?..)?;
+
+        let minimum_instruction_length = unit.try_take_into_u8().ok()?;
+        let maximum_operations_per_instruction = if version >= 4 {
+            unit.try_take_into_u8().ok()?
+        } else {
+            1
+        };
+        let default_is_stmt = unit.try_take_into_u8().ok()? != 0;
+        let line_base = unit.try_take_into_u8().ok()? as i8;
+        let line_range = unit.try_take_into_u8().ok()?;
+        let opcode_base = unit.try_take_into_u8().ok()?;
+        let standard_opcode_lengths = unit
+            .try_take_ref(opcode_base.saturating_sub(1) as usize)
+            .ok()?
+            .to_vec();
+
+        let (include_directories, file_names) = if version >= 5 {
+            let directories = parse_entries(&mut unit, endian, offset_size)?
+                .into_iter()
+                .map(|entry| entry.path)
+                .collect();
+            (directories, parse_entries(&mut unit, endian, offset_size)?)
+        } else {
+            let mut directories = Vec::new();
+            loop {
+                let directory = unit.try_take_cstring().ok()?;
+                if directory.is_empty() {
+                    break;
+                }
+                directories.push(LineString::Inline(directory));
+            }
+            let mut files = Vec::new();
+            loop {
+                let path = unit.try_take_cstring().ok()?;
+                if path.is_empty() {
+                    break;
+                }
+                files.push(FileEntry {
+                    path: LineString::Inline(path),
+                    directory_index: unit.try_take_uleb128().ok()?,
+                    timestamp: unit.try_take_uleb128().ok()?,
+                    size: unit.try_take_uleb128().ok()?,
+                    md5: None,
+                });
+            }
+            (directories, files)
+        };
+
+        Some(LineProgram {
+            header: LineProgramHeader {
+                unit_length,
+                dwarf64,
+                version,
+                address_size,
+                header_length,
+                minimum_instruction_length,
+                maximum_operations_per_instruction,
+                default_is_stmt,
+                line_base,
+                line_range,
+                opcode_base,
+                standard_opcode_lengths,
+                include_directories,
+                file_names,
+            },
+            endian,
+            program,
+        })
+    }
+
+    /// Returns an iterator over the opcodes of the program.
+    /// The iteration stops at the end of the program or at a truncated opcode.
+    pub fn instructions(&self) -> LineInstructions<'_, 'a> {
+        LineInstructions {
+            program: self,
+            bytes: ByteCode::new(self.program),
+        }
+    }
+
+    /// Executes the program and returns the rows of the resulting line-number matrix.
+    ///
+    /// Returns `None` if the program is truncated.
+    pub fn rows(&self) -> Option<Vec<LineRow>> {
+        let header = &self.header;
+        let max_ops = header.maximum_operations_per_instruction.max(1) as u64;
+        let line_range = header.line_range.max(1);
+        let mut rows = Vec::new();
+        let mut row = LineRow::new(header.default_is_stmt);
+
+        let advance = |row: &mut LineRow, operation_advance: u64| {
+            let op_index = row.op_index.wrapping_add(operation_advance);
+            row.address = row.address.wrapping_add(
+                (header.minimum_instruction_length as u64).wrapping_mul(op_index / max_ops),
+            );
+            row.op_index = op_index % max_ops;
+        };
+
+        let mut instructions = self.instructions();
+        for instruction in &mut instructions {
+            match instruction {
+                LineInstruction::Special(opcode) => {
+                    let adjusted = opcode - header.opcode_base;
+                    advance(&mut row, (adjusted / line_range) as u64);
+                    row.line = row.line.wrapping_add_signed(
+                        header.line_base as i64 + (adjusted % line_range) as i64,
+                    );
+                    rows.push(row);
+                    row.discriminator = 0;
+                    row.basic_block = false;
+                    row.prologue_end = false;
+                    row.epilogue_begin = false;
+                }
+                LineInstruction::Copy => {
+                    rows.push(row);
+                    row.discriminator = 0;
+                    row.basic_block = false;
+                    row.prologue_end = false;
+                    row.epilogue_begin = false;
+                }
+                LineInstruction::AdvancePc(operation_advance) => {
+                    advance(&mut row, operation_advance)
+                }
+                LineInstruction::AdvanceLine(delta) => {
+                    row.line = row.line.wrapping_add_signed(delta)
+                }
+                LineInstruction::SetFile(file) => row.file = file,
+                LineInstruction::SetColumn(column) => row.column = column,
+                LineInstruction::NegateStmt => row.is_stmt = !row.is_stmt,
+                LineInstruction::SetBasicBlock => row.basic_block = true,
+                LineInstruction::ConstAddPc => {
+                    advance(&mut row, ((255 - header.opcode_base) / line_range) as u64)
+                }
+                LineInstruction::FixedAdvancePc(delta) => {
+                    row.address = row.address.wrapping_add(delta as u64);
+                    row.op_index = 0;
+                }
+                LineInstruction::SetPrologueEnd => row.prologue_end = true,
+                LineInstruction::SetEpilogueBegin => row.epilogue_begin = true,
+                LineInstruction::SetIsa(isa) => row.isa = isa,
+                LineInstruction::EndSequence => {
+                    row.end_sequence = true;
+                    rows.push(row);
+                    row = LineRow::new(header.default_is_stmt);
+                }
+                LineInstruction::SetAddress(address) => {
+                    row.address = address;
+                    row.op_index = 0;
+                }
+                LineInstruction::SetDiscriminator(discriminator) => {
+                    row.discriminator = discriminator
+                }
+                LineInstruction::UnknownStandard(..) | LineInstruction::UnknownExtended(..) => {}
+            }
+        }
+        if !instructions.bytes.is_end() {
+            return None;
+        }
+        Some(rows)
+    }
+}
+
+/// An opcode of a line-number program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineInstruction<'a> {
+    Special(u8),
+    Copy,
+    AdvancePc(u64),
+    AdvanceLine(i64),
+    SetFile(u64),
+    SetColumn(u64),
+    NegateStmt,
+    SetBasicBlock,
+    ConstAddPc,
+    FixedAdvancePc(u16),
+    SetPrologueEnd,
+    SetEpilogueBegin,
+    SetIsa(u64),
+    EndSequence,
+    SetAddress(u64),
+    SetDiscriminator(u64),
+    /// A standard opcode not known to this crate, with its ULEB128 operands.
+    UnknownStandard(u8, Vec<u64>),
+    /// An extended opcode not known to this crate (including `DW_LNE_define_file`), with its raw operands.
+    UnknownExtended(u8, &'a [u8]),
+}
+
+/// An iterator over the opcodes of a line-number program.
+///
+/// This struct is created by [`LineProgram::instructions`].
+pub struct LineInstructions<'p, 'a> {
+    program: &'p LineProgram<'a>,
+    bytes: ByteCode<'a>,
+}
+
+impl<'a> LineInstructions<'_, 'a> {
+    fn decode(&mut self) -> Option<LineInstruction<'a>> {
+        let header = &self.program.header;
+        let bytes = &mut self.bytes;
+        let opcode = bytes.try_take_into_u8().ok()?;
+        let instruction = match opcode {
+            _ if opcode >= header.opcode_base => LineInstruction::Special(opcode),
+            0 => {
+                let len = usize::try_from(bytes.try_take_uleb128().ok()?).ok()?;
+                let mut operands = bytes.try_take_bytecode(len).ok()?;
+                match operands.try_take_into_u8().ok()? {
+                    0x01 => LineInstruction::EndSequence,
+                    0x02 => {
+                        let size = operands.as_slice().len();
+                        LineInstruction::SetAddress(
+                            operands.try_take_uint(size, self.program.endian).ok()?,
+                        )
+                    }
+                    0x04 => LineInstruction::SetDiscriminator(operands.try_take_uleb128().ok()?),
+                    extended => LineInstruction::UnknownExtended(extended, operands.as_slice()),
+                }
+            }
+            0x01 => LineInstruction::Copy,
+            0x02 => LineInstruction::AdvancePc(bytes.try_take_uleb128().ok()?),
+            0x03 => LineInstruction::AdvanceLine(bytes.try_take_sleb128().ok()?),
+            0x04 => LineInstruction::SetFile(bytes.try_take_uleb128().ok()?),
+            0x05 => LineInstruction::SetColumn(bytes.try_take_uleb128().ok()?),
+            0x06 => LineInstruction::NegateStmt,
+            0x07 => LineInstruction::SetBasicBlock,
+            0x08 => LineInstruction::ConstAddPc,
+            0x09 => LineInstruction::FixedAdvancePc(
+                bytes.try_take_uint(2, self.program.endian).ok()? as u16,
+            ),
+            0x0a => LineInstruction::SetPrologueEnd,
+            0x0b => LineInstruction::SetEpilogueBegin,
+            0x0c => LineInstruction::SetIsa(bytes.try_take_uleb128().ok()?),
+            _ => {
+                let count = header
+                    .standard_opcode_lengths
+                    .get(opcode as usize - 1)
+                    .copied()
+                    .unwrap_or(0);
+                let operands = (0..count)
+                    .map(|_| bytes.try_take_uleb128().ok())
+                    .collect::<Option<_>>()?;
+                LineInstruction::UnknownStandard(opcode, operands)
+            }
+        };
+        Some(instruction)
+    }
+}
+
+impl<'a> Iterator for LineInstructions<'_, 'a> {
+    type Item = LineInstruction<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.bytes.pos();
+        let instruction = self.decode();
+        if instruction.is_none() {
+            self.bytes.reset();
+            self.bytes.skip(pos);
+        }
+        instruction
+    }
+}
+
+/// A row of the line-number matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+    pub op_index: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    pub basic_block: bool,
+    pub end_sequence: bool,
+    pub prologue_end: bool,
+    pub epilogue_begin: bool,
+    pub isa: u64,
+    pub discriminator: u64,
+}
+
+impl LineRow {
+    fn new(default_is_stmt: bool) -> Self {
+        LineRow {
+            address: 0,
+            op_index: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+            basic_block: false,
+            end_sequence: false,
+            prologue_end: false,
+            epilogue_begin: false,
+            isa: 0,
+            discriminator: 0,
+        }
+    }
+}
+
+fn parse_entries<'a>(
+    bytes: &mut ByteCode<'a>,
+    endian: Endian,
+    offset_size: usize,
+) -> Option<Vec<FileEntry>> {
+    let format_count = bytes.try_take_into_u8().ok()?;
+    let formats = (0..format_count)
+        .map(|_| {
+            Some((
+                bytes.try_take_uleb128().ok()?,
+                bytes.try_take_uleb128().ok()?,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let count = bytes.try_take_uleb128().ok()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let mut entry = FileEntry {
+            path: LineString::Inline(String::new()),
+            directory_index: 0,
+            timestamp: 0,
+            size: 0,
+            md5: None,
+        };
+        for &(content_type, form) in &formats {
+            let value = match form {
+                DW_FORM_STRING => {
+                    FormValue::String(LineString::Inline(bytes.try_take_cstring().ok()?))
+                }
+                DW_FORM_STRP => FormValue::String(LineString::Str(
+                    bytes.try_take_uint(offset_size, endian).ok()?,
+                )),
+                DW_FORM_LINE_STRP => FormValue::String(LineString::LineStr(
+                    bytes.try_take_uint(offset_size, endian).ok()?,
+                )),
+                DW_FORM_UDATA => FormValue::Udata(bytes.try_take_uleb128().ok()?),
+                DW_FORM_DATA1 => FormValue::Udata(bytes.try_take_uint(1, endian).ok()?),
+                DW_FORM_DATA2 => FormValue::Udata(bytes.try_take_uint(2, endian).ok()?),
+                DW_FORM_DATA4 => FormValue::Udata(bytes.try_take_uint(4, endian).ok()?),
+                DW_FORM_DATA8 => FormValue::Udata(bytes.try_take_uint(8, endian).ok()?),
+                DW_FORM_DATA16 => FormValue::Block(bytes.try_take_ref(16).ok()?),
+                DW_FORM_BLOCK => {
+                    let len = usize::try_from(bytes.try_take_uleb128().ok()?).ok()?;
+                    FormValue::Block(bytes.try_take_ref(len).ok()?)
+                }
+                DW_FORM_BLOCK1 => {
+                    let len = bytes.try_take_into_u8().ok()? as usize;
+                    FormValue::Block(bytes.try_take_ref(len).ok()?)
+                }
+                _ => return None,
+            };
+            match (content_type, value) {
+                (DW_LNCT_PATH, FormValue::String(path)) => entry.path = path,
+                (DW_LNCT_DIRECTORY_INDEX, FormValue::Udata(index)) => entry.directory_index = index,
+                (DW_LNCT_TIMESTAMP, FormValue::Udata(timestamp)) => entry.timestamp = timestamp,
+                (DW_LNCT_SIZE, FormValue::Udata(size)) => entry.size = size,
+                (DW_LNCT_MD5, FormValue::Block(md5)) => entry.md5 = md5.try_into().ok(),
+                _ => {}
+            }
+        }
+        entries.push(entry);
+    }
+    Some(entries)
+}
+
+enum FormValue<'a> {
+    String(LineString),
+    Udata(u64),
+    Block(&'a [u8]),
+}
+
+#[cfg(test)]
+fn sample_line_program(version: u16) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.push(1); // minimum_instruction_length
+    if version >= 4 {
+        header.push(1); // maximum_operations_per_instruction
+    }
+    header.extend([1, (-5i8) as u8, 14, 13]);
+    header.extend([0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1]);
+    if version >= 5 {
+        // directories: (DW_LNCT_path, DW_FORM_string)
+        header.extend([1, 0x01, 0x08, 1]);
+        header.extend(b"/src\0");
+        // files: (DW_LNCT_path, DW_FORM_string), (DW_LNCT_directory_index, DW_FORM_udata)
+        header.extend([2, 0x01, 0x08, 0x02, 0x0f, 1]);
+        header.extend(b"a.c\0");
+        header.push(1);
+    } else {
+        header.extend(b"/src\0\0");
+        header.extend(b"a.c\0\x01\x00\x00\0");
+    }
+
+    let program = [
+        0x00, 0x09, 0x02, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // set_address 0x1000
+        0x05, 0x03, // set_column 3
+        0x0a, // set_prologue_end
+        0x01, // copy
+        0x21, // special: address += 1, line += 1
+        0x03, 0x7e, // advance_line -2
+        0x09, 0x04, 0x00, // fixed_advance_pc 4
+        0x01, // copy
+        0x02, 0x02, // advance_pc 2
+        0x00, 0x01, 0x01, // end_sequence
+    ];
+
+    let mut unit = Vec::new();
+    unit.extend(version.to_le_bytes());
+    if version >= 5 {
+        unit.extend([8, 0]);
+    }
+    unit.extend((header.len() as u32).to_le_bytes());
+    unit.extend(header);
+    unit.extend(program);
+
+    let mut section = (unit.len() as u32).to_le_bytes().to_vec();
+    section.extend(unit);
+    section
+}
+
+#[test]
+fn line_program_v4() {
+    let section = sample_line_program(4);
+    let mut bytes = ByteCode::new(&section);
+    let program = LineProgram::parse(&mut bytes, Endian::Little).unwrap();
+    assert!(bytes.is_end());
+
+    let header = &program.header;
+    assert_eq!(header.version, 4);
+    assert_eq!(header.line_base, -5);
+    assert_eq!(header.standard_opcode_lengths.len(), 12);
+    assert_eq!(
+        header.include_directories,
+        [LineString::Inline(String::from("/src"))]
+    );
+    assert_eq!(
+        header.file_names[0].path,
+        LineString::Inline(String::from("a.c"))
+    );
+    assert_eq!(header.file_names[0].directory_index, 1);
+
+    let rows = program.rows().unwrap();
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.address,
+                row.line,
+                row.column,
+                row.prologue_end,
+                row.end_sequence,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (0x1000, 1, 3, true, false),
+            (0x1001, 2, 3, false, false),
+            (0x1005, 0, 3, false, false),
+            (0x1007, 0, 3, false, true),
+        ]
+    );
+}
+
+#[test]
+fn line_program_v5() {
+    let section = sample_line_program(5);
+    let program = LineProgram::parse(&mut ByteCode::new(&section), Endian::Little).unwrap();
+    let header = &program.header;
+    assert_eq!(header.address_size, Some(8));
+    assert_eq!(
+        header.include_directories,
+        [LineString::Inline(String::from("/src"))]
+    );
+    assert_eq!(
+        header.file_names[0].path,
+        LineString::Inline(String::from("a.c"))
+    );
+    assert_eq!(header.file_names[0].directory_index, 1);
+    assert_eq!(program.rows().unwrap().len(), 4);
+}
+
+#[test]
+fn line_program_instructions() {
+    let section = sample_line_program(3);
+    let program = LineProgram::parse(&mut ByteCode::new(&section), Endian::Little).unwrap();
+    let instructions: Vec<_> = program.instructions().collect();
+    assert_eq!(instructions[0], LineInstruction::SetAddress(0x1000));
+    assert_eq!(instructions[4], LineInstruction::Special(0x21));
+    assert_eq!(instructions[5], LineInstruction::AdvanceLine(-2));
+    assert_eq!(instructions.last(), Some(&LineInstruction::EndSequence));
+}
+
+#[test]
+fn line_program_advance_overflow() {
+    let section = sample_line_program(4);
+    let mut program = LineProgram::parse(&mut ByteCode::new(&section), Endian::Little).unwrap();
+    program.header.minimum_instruction_length = 4;
+    // advance_pc u64::MAX twice, then end_sequence
+    let advance = [
+        0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+    ];
+    let code = [&advance[..], &advance, &[0x00, 0x01, 0x01]].concat();
+    program.program = &code;
+    let rows = program.rows().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].address, 4u64.wrapping_mul(u64::MAX).wrapping_mul(2));
+}
+
+#[test]
+fn line_program_truncated() {
+    let mut section = sample_line_program(4);
+    section.truncate(section.len() - 10);
+    assert!(LineProgram::parse(&mut ByteCode::new(&section), Endian::Little).is_none());
+
+    let mut section = sample_line_program(4);
+    section[4] = 6;
+    assert!(LineProgram::parse(&mut ByteCode::new(&section), Endian::Little).is_none());
+}
+
+#[test]
+fn abbrevs() {
+    let mut bytes = ByteCode::new(&[
+        0x01, 0x11, 0x01, 0x03, 0x08, 0x13, 0x21, 0x7f, 0x00, 0x00, // compile_unit
+        0x02, 0x2e, 0x00, 0x00, 0x00, // subprogram
+        0x00, 0xff,
+    ]);
+    let abbrevs = parse_abbrevs(&mut bytes).unwrap();
+    assert_eq!(bytes.pos(), 16);
+    assert_eq!(abbrevs.len(), 2);
+    assert_eq!(
+        abbrevs[0].attributes,
+        [
+            AttributeSpec {
+                name: 0x03,
+                form: 0x08,
+                implicit_const: None
+            },
+            AttributeSpec {
+                name: 0x13,
+                form: 0x21,
+                implicit_const: Some(-1)
+            },
+        ]
+    );
+    assert_eq!(abbrevs[1].code, 2);
+    assert!(!abbrevs[1].has_children);
+    assert!(abbrevs[1].attributes.is_empty());
+
+    assert!(parse_abbrevs(&mut ByteCode::new(&[0x01, 0x11])).is_none());
+}
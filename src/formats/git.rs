@@ -0,0 +1,467 @@
+//! Git loose objects, packfile entries and deltas.
+//!
+//! Loose objects and packfile entries are zlib-compressed.
+//! With the `zlib` feature, [`inflate`] and [`read_loose_object`] take care of decompression.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytecode::{formats::git::{PackEntryHeader, PackObjectType}, ByteCode};
+//!
+//! // blob of 100 bytes: 0b1_011_0100, 0b0000_0110
+//! let mut bytes = ByteCode::new(&[0xb4, 0x06]);
+//! let header = PackEntryHeader::parse(&mut bytes).unwrap();
+//! assert_eq!(header.object_type, PackObjectType::Blob);
+//! assert_eq!(header.size, 100);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::ByteCode;
+
+const PACK_SIGNATURE: &[u8; 4] = b"PACK";
+
+/// The type of a git object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+}
+
+impl ObjectKind {
+    fn from_name(name: &[u8]) -> Option<ObjectKind> {
+        match name {
+            b"commit" => Some(ObjectKind::Commit),
+            b"tree" => Some(ObjectKind::Tree),
+            b"blob" => Some(ObjectKind::Blob),
+            b"tag" => Some(ObjectKind::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// An inflated loose object: `<kind> <size>\0<content>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LooseObject<'a> {
+    pub kind: ObjectKind,
+    pub content: &'a [u8],
+}
+
+impl<'a> LooseObject<'a> {
+    /// Parses the header of an inflated loose object. The pointer is moved to the end.
+    ///
+    /// Returns `None` without moving the pointer if the header is malformed
+    /// or the declared size does not match the content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{formats::git::{LooseObject, ObjectKind}, ByteCode};
+    ///
+    /// let mut bytes = ByteCode::new(b"blob 3\0foo");
+    /// let object = LooseObject::parse(&mut bytes).unwrap();
+    /// assert_eq!(object.kind, ObjectKind::Blob);
+    /// assert_eq!(object.content, b"foo");
+    /// ```
+    pub fn parse(bytes: &mut ByteCode<'a>) -> Option<LooseObject<'a>> {
+        let inner: &'a [u8] = bytes.as_slice();
+        let nul = inner.iter().position(|byte| *byte == 0)?;
+        let header = &inner[..nul];
+        let space = header.iter().position(|byte| *byte == b' ')?;
+        let kind = ObjectKind::from_name(&header[..space])?;
+        let size: usize = core::str::from_utf8(&header[space + 1..])
+            .ok()?
+            .parse()
+            .ok()?;
+        let content = &inner[nul + 1..];
+        if content.len() != size {
+            return None;
+        }
+        bytes.skip(inner.len());
+        Some(LooseObject { kind, content })
+    }
+}
+
+/// The header of a packfile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackHeader {
+    pub version: u32,
+    pub object_count: u32,
+}
+
+impl PackHeader {
+    /// Parses the 12-byte packfile header.
+    /// Moves the pointer forward 12.
+    ///
+    /// Returns `None` without moving the pointer if the signature is missing or the header is truncated.
+    pub fn parse(bytes: &mut ByteCode) -> Option<PackHeader> {
+        if bytes.as_slice().len() < 12 || !bytes.starts_with(PACK_SIGNATURE) {
+            return None;
+        }
+        bytes.skip(4);
+        Some(PackHeader {
+            version: bytes.take_be_u32(),
+            object_count: bytes.take_be_u32(),
+        })
+    }
+}
+
+/// The type of a packfile entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackObjectType {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+    OfsDelta,
+    RefDelta,
+}
+
+impl PackObjectType {
+    /// Returns the object kind, or `None` for deltas.
+    pub fn kind(self) -> Option<ObjectKind> {
+        match self {
+            PackObjectType::Commit => Some(ObjectKind::Commit),
+            PackObjectType::Tree => Some(ObjectKind::Tree),
+            PackObjectType::Blob => Some(ObjectKind::Blob),
+            PackObjectType::Tag => Some(ObjectKind::Tag),
+            PackObjectType::OfsDelta | PackObjectType::RefDelta => None,
+        }
+    }
+}
+
+/// The base object of a deltified packfile entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaBase {
+    /// The distance back from the start of this entry to the start of the base entry.
+    Offset(u64),
+    /// The object name of the base.
+    Ref([u8; 20]),
+}
+
+/// The header of a packfile entry, which is followed by the zlib-compressed data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackEntryHeader {
+    pub object_type: PackObjectType,
+    /// The size of the inflated data.
+    pub size: u64,
+    pub delta_base: Option<DeltaBase>,
+}
+
+impl PackEntryHeader {
+    /// Parses the variable-length type and size, and the delta base reference if any.
+    /// Moves the pointer forward to the compressed data.
+    ///
+    /// Returns `None` without moving the pointer if the header is truncated or the type is invalid.
+    pub fn parse(bytes: &mut ByteCode) -> Option<PackEntryHeader> {
+        let inner = bytes.as_slice();
+        let first = *inner.first()?;
+        let object_type = match (first >> 4) & 0x07 {
+            1 => PackObjectType::Commit,
+            2 => PackObjectType::Tree,
+            3 => PackObjectType::Blob,
+            4 => PackObjectType::Tag,
+            6 => PackObjectType::OfsDelta,
+            7 => PackObjectType::RefDelta,
+            _ => return None,
+        };
+        let mut size = (first & 0x0f) as u64;
+        let mut len = 1;
+        let mut byte = first;
+        while byte & 0x80 != 0 {
+            byte = *inner.get(len)?;
+            size |= shl_exact((byte & 0x7f) as u64, 4 + 7 * (len as u32 - 1))?;
+            len += 1;
+        }
+
+        let delta_base = match object_type {
+            PackObjectType::OfsDelta => {
+                let mut byte = *inner.get(len)?;
+                let mut offset = (byte & 0x7f) as u64;
+                len += 1;
+                while byte & 0x80 != 0 {
+                    byte = *inner.get(len)?;
+                    offset = shl_exact(offset.checked_add(1)?, 7)? | (byte & 0x7f) as u64;
+                    len += 1;
+                }
+                Some(DeltaBase::Offset(offset))
+            }
+            PackObjectType::RefDelta => {
+                let name = inner.get(len..len + 20)?.try_into().unwrap();
+                len += 20;
+                Some(DeltaBase::Ref(name))
+            }
+            _ => None,
+        };
+        bytes.skip(len);
+        Some(PackEntryHeader {
+            object_type,
+            size,
+            delta_base,
+        })
+    }
+}
+
+/// Applies a git delta to its base object, returning the reconstructed object.
+///
+/// Returns `None` if the delta is malformed or does not match the base.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::formats::git::apply_delta;
+///
+/// // base size 3, result size 5, copy 3 bytes from offset 0, insert "ar"
+/// let delta = [0x03, 0x05, 0x90, 0x03, 0x02, b'a', b'r'];
+/// assert_eq!(apply_delta(b"foo", &delta).unwrap(), b"fooar");
+/// ```
+pub fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    let mut delta = ByteCode::new(delta);
+    let base_size = take_size(&mut delta)?;
+    let result_size = take_size(&mut delta)?;
+    if base_size != base.len() as u64 {
+        return None;
+    }
+    let result_size = usize::try_from(result_size).ok()?;
+    // Every byte of the result comes from the base or the delta, so a larger size only grows on demand.
+    let mut result = Vec::with_capacity(result_size.min(base.len() + delta.as_slice().len()));
+    while !delta.is_end() {
+        let instruction = delta.take_into_u8();
+        if instruction & 0x80 != 0 {
+            let mut offset = 0usize;
+            let mut size = 0usize;
+            for i in 0..7 {
+                if instruction & (1 << i) != 0 {
+                    if delta.is_end() {
+                        return None;
+                    }
+                    let byte = delta.take_into_u8() as usize;
+                    if i < 4 {
+                        offset |= byte << (8 * i);
+                    } else {
+                        size |= byte << (8 * (i - 4));
+                    }
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            if result.len() + size > result_size {
+                return None;
+            }
+            result.extend_from_slice(base.get(offset..offset.checked_add(size)?)?);
+        } else if instruction != 0 {
+            let size = instruction as usize;
+            if delta.as_slice().len() < size || result.len() + size > result_size {
+                return None;
+            }
+            result.extend(delta.take(size));
+        } else {
+            return None;
+        }
+    }
+    if result.len() != result_size {
+        return None;
+    }
+    Some(result)
+}
+
+/// Shifts `value` left, returning `None` if any set bit is shifted out.
+fn shl_exact(value: u64, shift: u32) -> Option<u64> {
+    let shifted = value.checked_shl(shift)?;
+    (shifted >> shift == value).then_some(shifted)
+}
+
+fn take_size(bytes: &mut ByteCode) -> Option<u64> {
+    let mut size = 0u64;
+    for (i, byte) in bytes.as_slice().iter().enumerate() {
+        size |= shl_exact((byte & 0x7f) as u64, 7 * i as u32)?;
+        if byte & 0x80 == 0 {
+            bytes.skip(i + 1);
+            return Some(size);
+        }
+    }
+    None
+}
+
+/// Inflates the zlib stream at the beginning of the slice, which must inflate to at most `max_len` bytes.
+/// Moves the pointer forward past the compressed stream, so consecutive packfile entries can be read.
+/// For a packfile entry, `max_len` is the [`size`](PackEntryHeader::size) from its header.
+///
+/// Returns `None` without moving the pointer if the stream is corrupted or truncated,
+/// or if it inflates to more than `max_len` bytes. Decompression stops as soon as the limit is passed.
+#[cfg(feature = "zlib")]
+pub fn inflate(bytes: &mut ByteCode, max_len: usize) -> Option<Vec<u8>> {
+    use flate2::{Decompress, FlushDecompress, Status};
+
+    let input = bytes.as_slice();
+    let limit = max_len.saturating_add(1);
+    let mut decompress = Decompress::new(true);
+    let mut output = Vec::with_capacity(input.len().saturating_mul(2).min(limit));
+    loop {
+        if output.len() == output.capacity() {
+            output.reserve_exact(output.capacity().max(64).min(limit - output.len()));
+        }
+        let consumed = decompress.total_in() as usize;
+        let status = decompress
+            .decompress_vec(&input[consumed..], &mut output, FlushDecompress::None)
+            .ok()?;
+        if output.len() > max_len {
+            return None;
+        }
+        match status {
+            Status::StreamEnd => break,
+            Status::Ok => {}
+            Status::BufError if output.len() < output.capacity() => return None,
+            Status::BufError => {}
+        }
+    }
+    bytes.skip(decompress.total_in() as usize);
+    Some(output)
+}
+
+/// Inflates and parses a loose object file (`.git/objects/xx/...`) of at most `max_len` inflated bytes, header included.
+///
+/// Returns `None` if the stream is corrupted or longer than `max_len`, or the object header is malformed.
+#[cfg(feature = "zlib")]
+pub fn read_loose_object(file: &[u8], max_len: usize) -> Option<(ObjectKind, Vec<u8>)> {
+    let inflated = inflate(&mut ByteCode::new(file), max_len)?;
+    let object = LooseObject::parse(&mut ByteCode::new(&inflated))?;
+    Some((object.kind, object.content.to_vec()))
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn loose_object_parse() {
+    let mut bytes = ByteCode::new(b"tree 0\0");
+    let object = LooseObject::parse(&mut bytes).unwrap();
+    assert_eq!(object.kind, ObjectKind::Tree);
+    assert!(object.content.is_empty());
+    assert!(bytes.is_end());
+
+    assert!(LooseObject::parse(&mut ByteCode::new(b"blob 4\0foo")).is_none());
+    assert!(LooseObject::parse(&mut ByteCode::new(b"note 3\0foo")).is_none());
+    assert!(LooseObject::parse(&mut ByteCode::new(b"blob 3foo")).is_none());
+}
+
+#[test]
+fn pack_header_parse() {
+    let mut bytes = ByteCode::new(b"PACK\x00\x00\x00\x02\x00\x00\x00\x03");
+    let header = PackHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.version, 2);
+    assert_eq!(header.object_count, 3);
+    assert!(bytes.is_end());
+
+    assert!(PackHeader::parse(&mut ByteCode::new(b"KCAP\x00\x00\x00\x02")).is_none());
+}
+
+#[test]
+fn pack_entry_header_parse() {
+    // commit, size 15, no continuation
+    let mut bytes = ByteCode::new(&[0x1f, 0x78]);
+    let header = PackEntryHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.object_type, PackObjectType::Commit);
+    assert_eq!(header.size, 15);
+    assert_eq!(header.delta_base, None);
+    assert_eq!(bytes.pos(), 1);
+
+    // ofs-delta, size 5, base offset 128 + 1
+    let mut bytes = ByteCode::new(&[0x65, 0x80, 0x01, 0x78]);
+    let header = PackEntryHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.object_type, PackObjectType::OfsDelta);
+    assert_eq!(header.object_type.kind(), None);
+    assert_eq!(header.delta_base, Some(DeltaBase::Offset(129)));
+    assert_eq!(bytes.pos(), 3);
+
+    // ref-delta
+    let mut entry = vec![0x75];
+    entry.extend([0xab; 20]);
+    let mut bytes = ByteCode::new(&entry);
+    let header = PackEntryHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.delta_base, Some(DeltaBase::Ref([0xab; 20])));
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn pack_entry_header_parse_invalid() {
+    let mut bytes = ByteCode::new(&[0x05]);
+    assert!(PackEntryHeader::parse(&mut bytes).is_none());
+
+    let mut bytes = ByteCode::new(&[0x9f]);
+    assert!(PackEntryHeader::parse(&mut bytes).is_none());
+    assert_eq!(bytes.pos(), 0);
+
+    let mut bytes = ByteCode::new(&[0x75, 0x00]);
+    assert!(PackEntryHeader::parse(&mut bytes).is_none());
+
+    // size bits shifted out past 64 bits
+    let mut header = vec![0x9f];
+    header.extend([0xff; 8]);
+    header.push(0x7f);
+    assert!(PackEntryHeader::parse(&mut ByteCode::new(&header)).is_none());
+
+    // base offset bits shifted out past 64 bits
+    let mut header = vec![0x65];
+    header.extend([0xff; 9]);
+    header.push(0x7f);
+    assert!(PackEntryHeader::parse(&mut ByteCode::new(&header)).is_none());
+}
+
+#[test]
+fn apply_delta_invalid() {
+    // base size mismatch
+    assert!(apply_delta(b"fo", &[0x03, 0x03, 0x90, 0x03]).is_none());
+    // copy out of range
+    assert!(apply_delta(b"foo", &[0x03, 0x04, 0x90, 0x04]).is_none());
+    // result size mismatch
+    assert!(apply_delta(b"foo", &[0x03, 0x04, 0x90, 0x03]).is_none());
+    // reserved instruction
+    assert!(apply_delta(b"foo", &[0x03, 0x00, 0x00]).is_none());
+    // result larger than declared, rejected before copying
+    assert!(apply_delta(b"foo", &[0x03, 0x02, 0x90, 0x03]).is_none());
+    assert!(apply_delta(b"foo", &[0x03, 0x01, 0x02, 0x61, 0x62]).is_none());
+    // size varint bits shifted out past 64 bits
+    let mut delta = vec![0x03];
+    delta.extend([0xff; 9]);
+    delta.push(0x7f);
+    assert!(apply_delta(b"foo", &delta).is_none());
+}
+
+#[cfg(feature = "zlib")]
+#[test]
+fn inflate_consecutive_streams() {
+    use std::io::Write;
+
+    let compress = |data: &[u8]| {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    };
+    let mut pack = compress(b"blob 3\0foo");
+    pack.extend(compress(b"bar"));
+
+    assert_eq!(
+        read_loose_object(&pack, 10).unwrap(),
+        (ObjectKind::Blob, b"foo".to_vec())
+    );
+
+    let mut bytes = ByteCode::new(&pack);
+    inflate(&mut bytes, 10).unwrap();
+    assert_eq!(inflate(&mut bytes, 3).unwrap(), b"bar");
+    assert!(bytes.is_end());
+
+    assert!(inflate(&mut ByteCode::new(&pack[..4]), 10).is_none());
+    assert!(read_loose_object(&pack, 9).is_none());
+
+    // 1 MiB of zeros from a stream of about a kilobyte
+    let bomb = compress(&vec![0; 1 << 20]);
+    let mut bytes = ByteCode::new(&bomb);
+    assert!(inflate(&mut bytes, 1 << 10).is_none());
+    assert_eq!(bytes.pos(), 0);
+    assert_eq!(inflate(&mut bytes, 1 << 20).unwrap().len(), 1 << 20);
+}
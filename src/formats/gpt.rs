@@ -0,0 +1,548 @@
+//! GUID partition table (GPT) and master boot record (MBR).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::gpt::Gpt, ByteCode, ByteCodeWriter};
+//!
+//! let image = std::fs::read("disk.img").unwrap();
+//! let gpt = Gpt::parse(&ByteCode::new(&image), 512).unwrap();
+//! assert!(gpt.header.crc_valid && gpt.entries_crc_valid);
+//! for partition in &gpt.partitions {
+//!     println!("{} {:?}", partition.name, partition.byte_range(512));
+//! }
+//!
+//! let mut gpt = gpt;
+//! gpt.partitions[0].name = "boot".to_owned();
+//! let mut writer = ByteCodeWriter::from(image);
+//! gpt.write(&mut writer, 512);
+//! std::fs::write("disk.img", writer.into_inner()).unwrap();
+//! ```
+
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+
+use crate::{checksum::crc32, ByteCode, ByteCodeWriter, LittleEndian};
+
+const MBR_SIGNATURE: [u8; 2] = [0x55, 0xaa];
+const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+const PROTECTIVE_PARTITION_TYPE: u8 = 0xee;
+
+/// A GUID stored in the mixed-endian layout used by GPT and other Microsoft formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    /// Returns `true` if all bytes are zero, which marks an unused partition entry.
+    pub fn is_nil(&self) -> bool {
+        self.0 == [0; 16]
+    }
+}
+
+impl Display for Guid {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:02X}{:02X}{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-",
+            b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6], b[8], b[9]
+        )?;
+        for byte in &b[10..] {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// A partition entry in the MBR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MbrPartition {
+    pub bootable: bool,
+    pub partition_type: u8,
+    pub first_lba: u32,
+    pub sector_count: u32,
+}
+
+/// The master boot record at LBA 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mbr {
+    pub disk_signature: u32,
+    pub partitions: [MbrPartition; 4],
+}
+
+impl Mbr {
+    /// Parses the MBR from the first 512 bytes.
+    /// Moves the pointer forward 512.
+    ///
+    /// Returns `None` without moving the pointer if fewer than 512 bytes remain or the boot signature is missing.
+    pub fn parse(bytes: &mut ByteCode) -> Option<Mbr> {
+        let mut reader = ByteCode::new(bytes.as_slice());
+        reader.try_skip(440).ok()?;
+        let disk_signature = reader.try_take_into_u32_endian::<LittleEndian>().ok()?;
+        reader.try_skip(2).ok()?;
+        let mut partitions = [MbrPartition::default(); 4];
+        for partition in &mut partitions {
+            let status = reader.try_take_into_u8().ok()?;
+            reader.try_skip(3).ok()?; // CHS address of first sector
+            let partition_type = reader.try_take_into_u8().ok()?;
+            reader.try_skip(3).ok()?; // CHS address of last sector
+            *partition = MbrPartition {
+                bootable: status == 0x80,
+                partition_type,
+                first_lba: reader.try_take_into_u32_endian::<LittleEndian>().ok()?,
+                sector_count: reader.try_take_into_u32_endian::<LittleEndian>().ok()?,
+            };
+        }
+        if reader.try_take_array().ok()? != MBR_SIGNATURE {
+            return None;
+        }
+        bytes.skip(reader.pos());
+        Some(Mbr {
+            disk_signature,
+            partitions,
+        })
+    }
+
+    /// Returns `true` if this is a protective MBR guarding a GPT disk.
+    pub fn is_protective(&self) -> bool {
+        self.partitions
+            .iter()
+            .any(|partition| partition.partition_type == PROTECTIVE_PARTITION_TYPE)
+    }
+}
+
+/// The GPT header, normally at LBA 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptHeader {
+    pub revision: u32,
+    pub header_size: u32,
+    pub header_crc32: u32,
+    /// `true` if `header_crc32` matches the header contents.
+    pub crc_valid: bool,
+    pub current_lba: u64,
+    pub backup_lba: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    pub disk_guid: Guid,
+    pub partition_entry_lba: u64,
+    pub partition_entry_count: u32,
+    pub partition_entry_size: u32,
+    pub partition_entries_crc32: u32,
+}
+
+impl GptHeader {
+    /// Parses a GPT header from the beginning of the slice and verifies its CRC.
+    /// Moves the pointer forward by `header_size`.
+    ///
+    /// Returns `None` without moving the pointer if the signature is missing, the header is truncated,
+    /// or the reserved field is not zero in [strict](crate::Strictness::Strict) mode.
+    pub fn parse(bytes: &mut ByteCode) -> Option<GptHeader> {
+        let start = bytes.checkpoint();
+        let header = GptHeader::take(bytes);
+        if header.is_none() {
+            bytes.restore(start);
+        }
+        header
+    }
+
+    fn take(bytes: &mut ByteCode) -> Option<GptHeader> {
+        let slice = bytes.as_slice();
+        if bytes.try_take_array().ok()? != *GPT_SIGNATURE {
+            return None;
+        }
+        let revision = bytes.try_take_into_u32_endian::<LittleEndian>().ok()?;
+        let header_size = bytes.try_take_into_u32_endian::<LittleEndian>().ok()?;
+        let header_crc32 = bytes.try_take_into_u32_endian::<LittleEndian>().ok()?;
+        bytes.skip_padding(4).ok()?;
+        let header_len = usize::try_from(header_size).ok()?;
+        let rest = header_len.checked_sub(92)?;
+        let mut zeroed = slice.get(..header_len)?.to_vec();
+        zeroed[16..20].fill(0);
+        let header = GptHeader {
+            revision,
+            header_size,
+            header_crc32,
+            crc_valid: header_crc32 == crc32(&zeroed),
+            current_lba: bytes.try_take_into_u64_endian::<LittleEndian>().ok()?,
+            backup_lba: bytes.try_take_into_u64_endian::<LittleEndian>().ok()?,
+            first_usable_lba: bytes.try_take_into_u64_endian::<LittleEndian>().ok()?,
+            last_usable_lba: bytes.try_take_into_u64_endian::<LittleEndian>().ok()?,
+            disk_guid: Guid(bytes.try_take_array().ok()?),
+            partition_entry_lba: bytes.try_take_into_u64_endian::<LittleEndian>().ok()?,
+            partition_entry_count: bytes.try_take_into_u32_endian::<LittleEndian>().ok()?,
+            partition_entry_size: bytes.try_take_into_u32_endian::<LittleEndian>().ok()?,
+            partition_entries_crc32: bytes.try_take_into_u32_endian::<LittleEndian>().ok()?,
+        };
+        bytes.try_skip(rest).ok()?;
+        Some(header)
+    }
+}
+
+/// A used entry in the GPT partition entry array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptPartition {
+    /// Index of the entry in the partition entry array.
+    pub index: usize,
+    pub type_guid: Guid,
+    pub unique_guid: Guid,
+    pub first_lba: u64,
+    /// Inclusive.
+    pub last_lba: u64,
+    pub attributes: u64,
+    pub name: String,
+}
+
+impl GptPartition {
+    /// Returns the byte range of the partition, relative to the start of the disk image.
+    ///
+    /// Returns `None` if the range does not fit in a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::formats::gpt::{GptPartition, Guid};
+    ///
+    /// let partition = GptPartition {
+    ///     index: 0,
+    ///     type_guid: Guid::default(),
+    ///     unique_guid: Guid::default(),
+    ///     first_lba: 34,
+    ///     last_lba: 35,
+    ///     attributes: 0,
+    ///     name: String::new(),
+    /// };
+    /// assert_eq!(partition.byte_range(512), Some(17408..18432));
+    /// ```
+    pub fn byte_range(&self, sector_size: usize) -> Option<Range<u64>> {
+        let sector_size = sector_size as u64;
+        let start = self.first_lba.checked_mul(sector_size)?;
+        let end = self.last_lba.checked_add(1)?.checked_mul(sector_size)?;
+        Some(start..end)
+    }
+}
+
+/// A GPT disk layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpt {
+    pub mbr: Mbr,
+    pub header: GptHeader,
+    /// `true` if `header.partition_entries_crc32` matches the partition entry array.
+    pub entries_crc_valid: bool,
+    /// Used entries only, in table order.
+    pub partitions: Vec<GptPartition>,
+}
+
+impl Gpt {
+    /// Parses the protective MBR, the primary GPT header and the partition entry array
+    /// of a disk image starting at the current position. The pointer is not moved.
+    ///
+    /// Checksum mismatches do not cause a failure and are reported through
+    /// `header.crc_valid` and `entries_crc_valid` instead.
+    /// Returns `None` if the MBR or GPT header is missing, or the partition entry array is out of range.
+    /// The GPT header is checked with the [strictness](ByteCode::strictness) of `image`.
+    pub fn parse(image: &ByteCode, sector_size: usize) -> Option<Gpt> {
+        let strictness = image.strictness();
+        let image = image.as_slice();
+        let mbr = Mbr::parse(&mut ByteCode::new(image))?;
+        let mut header = ByteCode::new(image.get(sector_size..)?);
+        header.set_strictness(strictness);
+        let header = GptHeader::parse(&mut header)?;
+
+        let entry_size = header.partition_entry_size as usize;
+        if entry_size < 128 {
+            return None;
+        }
+        let start = usize::try_from(header.partition_entry_lba)
+            .ok()?
+            .checked_mul(sector_size)?;
+        let len = entry_size.checked_mul(header.partition_entry_count as usize)?;
+        let entries = image.get(start..start.checked_add(len)?)?;
+
+        let partitions = entries
+            .chunks_exact(entry_size)
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let mut entry = ByteCode::new(entry);
+                let type_guid = Guid(entry.try_take_array().ok()?);
+                if type_guid.is_nil() {
+                    return None;
+                }
+                let unique_guid = Guid(entry.try_take_array().ok()?);
+                let first_lba = entry.try_take_into_u64_endian::<LittleEndian>().ok()?;
+                let last_lba = entry.try_take_into_u64_endian::<LittleEndian>().ok()?;
+                let attributes = entry.try_take_into_u64_endian::<LittleEndian>().ok()?;
+                let name: Vec<u16> = entry
+                    .try_take_ref(72)
+                    .ok()?
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                    .take_while(|unit| *unit != 0)
+                    .collect();
+                Some(GptPartition {
+                    index,
+                    type_guid,
+                    unique_guid,
+                    first_lba,
+                    last_lba,
+                    attributes,
+                    name: String::from_utf16_lossy(&name),
+                })
+            })
+            .collect();
+
+        Some(Gpt {
+            mbr,
+            entries_crc_valid: crc32(entries) == header.partition_entries_crc32,
+            header,
+            partitions,
+        })
+    }
+}
+
+impl Gpt {
+    /// Writes the MBR, the GPT header and the partition entry array back into a disk image
+    /// held by `image`, recomputing both CRCs, so that a parsed layout can be edited and re-emitted.
+    ///
+    /// The header is written at `header.current_lba`, so a backup header is written back in place.
+    /// `header.header_crc32`, `header.partition_entries_crc32` and the validity flags are ignored.
+    /// The boot code, the CHS addresses in the MBR and the other copy of the GPT are left unchanged,
+    /// and partition names longer than 36 UTF-16 code units are truncated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `image` is too short for the layout, or a partition index is not less than
+    /// `header.partition_entry_count`.
+    pub fn write(&self, image: &mut ByteCodeWriter, sector_size: usize) {
+        image.patch_slice(440, &self.mbr.disk_signature.to_le_bytes());
+        for (i, partition) in self.mbr.partitions.iter().enumerate() {
+            let pos = 446 + i * 16;
+            image.patch_u8(pos, if partition.bootable { 0x80 } else { 0x00 });
+            image.patch_u8(pos + 4, partition.partition_type);
+            image.patch_slice(pos + 8, &partition.first_lba.to_le_bytes());
+            image.patch_slice(pos + 12, &partition.sector_count.to_le_bytes());
+        }
+        image.patch_slice(510, &MBR_SIGNATURE);
+
+        let header = &self.header;
+        let entry_size = header.partition_entry_size as usize;
+        let mut entries = vec![0; entry_size * header.partition_entry_count as usize];
+        for partition in &self.partitions {
+            let entry = &mut entries[partition.index * entry_size..][..128];
+            entry[0..16].copy_from_slice(&partition.type_guid.0);
+            entry[16..32].copy_from_slice(&partition.unique_guid.0);
+            entry[32..40].copy_from_slice(&partition.first_lba.to_le_bytes());
+            entry[40..48].copy_from_slice(&partition.last_lba.to_le_bytes());
+            entry[48..56].copy_from_slice(&partition.attributes.to_le_bytes());
+            for (i, unit) in partition.name.encode_utf16().take(36).enumerate() {
+                entry[56 + i * 2..58 + i * 2].copy_from_slice(&unit.to_le_bytes());
+            }
+        }
+        image.patch_slice(header.partition_entry_lba as usize * sector_size, &entries);
+
+        let mut bytes = vec![0; header.header_size as usize];
+        bytes[0..8].copy_from_slice(GPT_SIGNATURE);
+        bytes[8..12].copy_from_slice(&header.revision.to_le_bytes());
+        bytes[12..16].copy_from_slice(&header.header_size.to_le_bytes());
+        bytes[24..32].copy_from_slice(&header.current_lba.to_le_bytes());
+        bytes[32..40].copy_from_slice(&header.backup_lba.to_le_bytes());
+        bytes[40..48].copy_from_slice(&header.first_usable_lba.to_le_bytes());
+        bytes[48..56].copy_from_slice(&header.last_usable_lba.to_le_bytes());
+        bytes[56..72].copy_from_slice(&header.disk_guid.0);
+        bytes[72..80].copy_from_slice(&header.partition_entry_lba.to_le_bytes());
+        bytes[80..84].copy_from_slice(&header.partition_entry_count.to_le_bytes());
+        bytes[84..88].copy_from_slice(&header.partition_entry_size.to_le_bytes());
+        bytes[88..92].copy_from_slice(&crc32(&entries).to_le_bytes());
+        let crc = crc32(&bytes);
+        bytes[16..20].copy_from_slice(&crc.to_le_bytes());
+        image.patch_slice(header.current_lba as usize * sector_size, &bytes);
+    }
+}
+
+#[cfg(test)]
+use alloc::{borrow::ToOwned, string::ToString};
+
+#[cfg(test)]
+fn sample_image() -> Vec<u8> {
+    const SECTOR: usize = 512;
+    let mut image = vec![0; SECTOR * 40];
+
+    // Protective MBR
+    image[446 + 4] = PROTECTIVE_PARTITION_TYPE;
+    image[446 + 8..446 + 12].copy_from_slice(&1u32.to_le_bytes());
+    image[446 + 12..446 + 16].copy_from_slice(&39u32.to_le_bytes());
+    image[510..512].copy_from_slice(&MBR_SIGNATURE);
+
+    // Partition entry array at LBA 2, 4 entries of 128 bytes
+    let entries = 2 * SECTOR;
+    let entry = &mut image[entries..entries + 128];
+    entry[0..16].copy_from_slice(&[0xaf; 16]);
+    entry[16..32].copy_from_slice(&[0x01; 16]);
+    entry[32..40].copy_from_slice(&34u64.to_le_bytes());
+    entry[40..48].copy_from_slice(&35u64.to_le_bytes());
+    for (i, unit) in "EFI".encode_utf16().enumerate() {
+        entry[56 + i * 2..58 + i * 2].copy_from_slice(&unit.to_le_bytes());
+    }
+    let entries_crc = crc32(&image[entries..entries + 4 * 128]);
+
+    // GPT header at LBA 1
+    let header = &mut image[SECTOR..SECTOR + 92];
+    header[0..8].copy_from_slice(GPT_SIGNATURE);
+    header[8..12].copy_from_slice(&0x0001_0000u32.to_le_bytes());
+    header[12..16].copy_from_slice(&92u32.to_le_bytes());
+    header[24..32].copy_from_slice(&1u64.to_le_bytes());
+    header[32..40].copy_from_slice(&39u64.to_le_bytes());
+    header[40..48].copy_from_slice(&34u64.to_le_bytes());
+    header[48..56].copy_from_slice(&38u64.to_le_bytes());
+    header[56..72].copy_from_slice(&[0x02; 16]);
+    header[72..80].copy_from_slice(&2u64.to_le_bytes());
+    header[80..84].copy_from_slice(&4u32.to_le_bytes());
+    header[84..88].copy_from_slice(&128u32.to_le_bytes());
+    header[88..92].copy_from_slice(&entries_crc.to_le_bytes());
+    let header_crc = crc32(header);
+    header[16..20].copy_from_slice(&header_crc.to_le_bytes());
+
+    image
+}
+
+#[test]
+fn parse() {
+    let image = sample_image();
+    let gpt = Gpt::parse(&ByteCode::new(&image), 512).unwrap();
+
+    assert!(gpt.mbr.is_protective());
+    assert_eq!(gpt.mbr.partitions[0].sector_count, 39);
+    assert!(gpt.header.crc_valid);
+    assert!(gpt.entries_crc_valid);
+    assert_eq!(gpt.header.revision, 0x0001_0000);
+    assert_eq!(gpt.header.partition_entry_count, 4);
+
+    assert_eq!(gpt.partitions.len(), 1);
+    let partition = &gpt.partitions[0];
+    assert_eq!(partition.index, 0);
+    assert_eq!(partition.name, "EFI");
+    assert_eq!(partition.byte_range(512), Some(34 * 512..36 * 512));
+
+    let mut partition = partition.clone();
+    partition.last_lba = u64::MAX;
+    assert_eq!(partition.byte_range(512), None);
+    partition.first_lba = u64::MAX / 2;
+    partition.last_lba = u64::MAX / 2;
+    assert_eq!(partition.byte_range(512), None);
+}
+
+#[test]
+fn parse_corrupted_crc() {
+    let mut image = sample_image();
+    image[512 + 24] = 0xff;
+    image[1024 + 40] = 0xff;
+    let gpt = Gpt::parse(&ByteCode::new(&image), 512).unwrap();
+    assert!(!gpt.header.crc_valid);
+    assert!(!gpt.entries_crc_valid);
+}
+
+#[test]
+fn parse_missing_signature() {
+    let mut image = sample_image();
+    image[512] = 0;
+    assert!(Gpt::parse(&ByteCode::new(&image), 512).is_none());
+
+    let mut image = sample_image();
+    image[511] = 0;
+    assert!(Gpt::parse(&ByteCode::new(&image), 512).is_none());
+
+    assert!(Gpt::parse(&ByteCode::new(&image[..600]), 512).is_none());
+}
+
+#[test]
+fn parse_reserved_not_zero() {
+    let mut image = sample_image();
+    image[512 + 20] = 0x01;
+    let mut bytes = ByteCode::new(&image[512..]);
+    let header = GptHeader::parse(&mut bytes).unwrap();
+    assert!(!header.crc_valid);
+    assert_eq!(
+        bytes.diagnostics(),
+        [crate::Error::NonZeroPadding { pos: 20, len: 4 }.into()]
+    );
+
+    let mut bytes = ByteCode::new(&image[512..]);
+    bytes.set_strictness(crate::Strictness::Strict);
+    assert!(GptHeader::parse(&mut bytes).is_none());
+    assert_eq!(bytes.pos(), 0);
+    let mut image_bytes = ByteCode::new(&image);
+    image_bytes.set_strictness(crate::Strictness::Strict);
+    assert!(Gpt::parse(&image_bytes, 512).is_none());
+}
+
+#[test]
+fn mbr_parse() {
+    let image = sample_image();
+    let mut bytes = ByteCode::new(&image);
+    let mbr = Mbr::parse(&mut bytes).unwrap();
+    assert_eq!(bytes.pos(), 512);
+    assert_eq!(mbr.partitions[0].first_lba, 1);
+    assert!(!mbr.partitions[0].bootable);
+}
+
+#[test]
+fn guid_display() {
+    let guid = Guid([
+        0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11, 0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9,
+        0x3b,
+    ]);
+    assert_eq!(guid.to_string(), "C12A7328-F81F-11D2-BA4B-00A0C93EC93B");
+}
+
+#[test]
+fn write_round_trip() {
+    let image = sample_image();
+    let gpt = Gpt::parse(&ByteCode::new(&image), 512).unwrap();
+    let mut writer = ByteCodeWriter::from(image.clone());
+    gpt.write(&mut writer, 512);
+    assert_eq!(writer.as_slice(), image);
+
+    let mut edited = gpt.clone();
+    edited.mbr.disk_signature = 0x1234_5678;
+    edited.header.last_usable_lba = 37;
+    edited.partitions[0].name = "boot".to_owned();
+    edited.partitions.push(GptPartition {
+        index: 3,
+        type_guid: Guid([0x11; 16]),
+        unique_guid: Guid([0x22; 16]),
+        first_lba: 36,
+        last_lba: 37,
+        attributes: 1,
+        name: "x".repeat(40),
+    });
+    edited.write(&mut writer, 512);
+    let reparsed = Gpt::parse(&ByteCode::new(writer.as_slice()), 512).unwrap();
+    assert!(reparsed.header.crc_valid && reparsed.entries_crc_valid);
+    assert_eq!(reparsed.mbr, edited.mbr);
+    assert_eq!(reparsed.header.last_usable_lba, 37);
+    assert_eq!(reparsed.partitions[0].name, "boot");
+    assert_eq!(reparsed.partitions[1].index, 3);
+    assert_eq!(reparsed.partitions[1].name, "x".repeat(36));
+}
+
+#[test]
+fn write_backup_header() {
+    let image = sample_image();
+    let mut gpt = Gpt::parse(&ByteCode::new(&image), 512).unwrap();
+    gpt.header.current_lba = 39;
+    gpt.header.backup_lba = 1;
+    let mut writer = ByteCodeWriter::from(image.clone());
+    gpt.write(&mut writer, 512);
+    assert_eq!(writer.as_slice()[512..1024], image[512..1024]);
+    assert_eq!(&writer.as_slice()[39 * 512..][..8], GPT_SIGNATURE);
+}
+
+#[test]
+#[should_panic]
+fn write_image_too_short() {
+    let image = sample_image();
+    let gpt = Gpt::parse(&ByteCode::new(&image), 512).unwrap();
+    gpt.write(&mut ByteCodeWriter::from(image[..1024].to_vec()), 512);
+}
@@ -0,0 +1,12 @@
+//! Parsers for well-known binary formats, built on top of [`ByteCode`](crate::ByteCode).
+
+pub mod bson;
+pub mod dns;
+pub mod dwarf;
+pub mod git;
+pub mod gpt;
+pub mod patch;
+pub mod pcap;
+pub mod sfnt;
+pub mod sniff;
+pub mod sqlite;
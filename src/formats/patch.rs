@@ -0,0 +1,765 @@
+//! Binary patches: bsdiff (`BSDIFF40`) and VCDIFF (RFC 3284).
+//!
+//! The control, diff and extra blocks of a bsdiff patch are bzip2-compressed.
+//! With the `bzip2` feature, [`bspatch`] takes care of decompression;
+//! otherwise [`apply_bsdiff_blocks`] applies blocks that were decompressed elsewhere.
+//!
+//! VCDIFF patches using the default code table and no secondary compression are applied by [`apply_vcdiff`].
+//!
+//! # Examples
+//!
+//! ```
+//! use bytecode::{formats::patch::apply_vcdiff, ByteCode};
+//!
+//! let patch = [
+//!     0xd6, 0xc3, 0xc4, 0x00, 0x00, // header
+//!     0x01, 0x03, 0x00, 0x0b, // window over source bytes 0..3
+//!     0x05, 0x00, 0x02, 0x03, 0x01, // lengths
+//!     b'h', b'i', // data
+//!     0x03, 0x13, 0x03, // ADD 2, COPY 3 (mode 0)
+//!     0x00, // address 0
+//! ];
+//! let target = apply_vcdiff(&ByteCode::new(b"abc"), &mut ByteCode::new(&patch), 1024).unwrap();
+//! assert_eq!(target, b"hiabc");
+//! ```
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{checksum::adler32, ByteCode};
+
+const BSDIFF_MAGIC: &[u8; 8] = b"BSDIFF40";
+const VCDIFF_MAGIC: &[u8; 4] = &[0xd6, 0xc3, 0xc4, 0x00];
+
+const VCD_DECOMPRESS: u8 = 0x01;
+const VCD_CODETABLE: u8 = 0x02;
+const VCD_APPHEADER: u8 = 0x04;
+
+const VCD_SOURCE: u8 = 0x01;
+const VCD_TARGET: u8 = 0x02;
+/// Extension used by open-vcdiff and xdelta3.
+const VCD_ADLER32: u8 = 0x04;
+
+const NEAR_CACHE_SIZE: usize = 4;
+const SAME_CACHE_SIZE: usize = 3;
+
+/// The header and still-compressed blocks of a bsdiff patch.
+#[derive(Debug)]
+pub struct Bsdiff<'a> {
+    pub new_size: u64,
+    pub control: ByteCode<'a>,
+    pub diff: ByteCode<'a>,
+    pub extra: ByteCode<'a>,
+}
+
+impl<'a> Bsdiff<'a> {
+    /// Parses a bsdiff patch starting at the current position.
+    /// Moves the pointer forward to the end of the patch, which is the end of the data.
+    ///
+    /// Returns `None` if the magic is wrong or a block length is negative or out of range.
+    pub fn parse(bytes: &mut ByteCode<'a>) -> Option<Bsdiff<'a>> {
+        let inner: &'a [u8] = bytes.as_slice();
+        if inner.len() < 32 || !inner.starts_with(BSDIFF_MAGIC) {
+            return None;
+        }
+        let control_len = usize::try_from(offtin(&inner[8..16])).ok()?;
+        let diff_len = usize::try_from(offtin(&inner[16..24])).ok()?;
+        let new_size = u64::try_from(offtin(&inner[24..32])).ok()?;
+        let blocks = &inner[32..];
+        let diff_start = control_len;
+        let extra_start = diff_start.checked_add(diff_len)?;
+        if extra_start > blocks.len() {
+            return None;
+        }
+        bytes.skip(inner.len());
+        Some(Bsdiff {
+            new_size,
+            control: ByteCode::new(&blocks[..diff_start]),
+            diff: ByteCode::new(&blocks[diff_start..extra_start]),
+            extra: ByteCode::new(&blocks[extra_start..]),
+        })
+    }
+}
+
+/// Reads a bsdiff integer: little-endian sign-magnitude, with the sign in the top bit.
+fn offtin(bytes: &[u8]) -> i64 {
+    let raw = u64::from_le_bytes(bytes.try_into().unwrap());
+    let magnitude = (raw & !(1 << 63)) as i64;
+    if raw >> 63 == 1 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Applies decompressed bsdiff blocks to `source`, producing a target of `new_size` bytes.
+///
+/// Returns `None` if the control block is malformed or a block is too short.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{formats::patch::apply_bsdiff_blocks, ByteCode};
+///
+/// // add 3 bytes of diff, then copy 1 extra byte
+/// let control = [[3, 0, 0, 0, 0, 0, 0, 0], [1, 0, 0, 0, 0, 0, 0, 0], [0; 8]].concat();
+/// let target = apply_bsdiff_blocks(&ByteCode::new(b"abc"), 4, &control, &[0, 1, 0], b"!").unwrap();
+/// assert_eq!(target, b"acc!");
+/// ```
+pub fn apply_bsdiff_blocks(
+    source: &ByteCode,
+    new_size: u64,
+    control: &[u8],
+    diff: &[u8],
+    extra: &[u8],
+) -> Option<Vec<u8>> {
+    let old = source.as_slice();
+    let new_size = usize::try_from(new_size).ok()?;
+    let mut control = ByteCode::new(control);
+    let mut diff = ByteCode::new(diff);
+    let mut extra = ByteCode::new(extra);
+    let mut target = Vec::new();
+    let mut old_pos: i64 = 0;
+    while target.len() < new_size {
+        let triple = control.try_take_ref(24).ok()?;
+        let add_len = usize::try_from(offtin(&triple[..8])).ok()?;
+        let copy_len = usize::try_from(offtin(&triple[8..16])).ok()?;
+        let seek = offtin(&triple[16..]);
+
+        if add_len > new_size - target.len() {
+            return None;
+        }
+        let add = diff.try_take_ref(add_len).ok()?;
+        target.extend(add.iter().enumerate().map(|(i, byte)| {
+            let old_byte = old_pos
+                .checked_add(i as i64)
+                .and_then(|pos| usize::try_from(pos).ok())
+                .and_then(|pos| old.get(pos));
+            byte.wrapping_add(old_byte.copied().unwrap_or(0))
+        }));
+        old_pos = old_pos.checked_add(add_len as i64)?;
+
+        if copy_len > new_size - target.len() {
+            return None;
+        }
+        target.extend(extra.try_take_ref(copy_len).ok()?);
+        old_pos = old_pos.checked_add(seek)?;
+    }
+    Some(target)
+}
+
+/// Decompresses and applies a bsdiff patch to `source`.
+///
+/// Returns `None` if the patch or any of its bzip2 streams is malformed,
+/// or if a stream decompresses to more than the patch can use: `new_size` bytes for the diff and
+/// extra blocks, and one control triple per target byte, plus one, for the control block.
+#[cfg(feature = "bzip2")]
+pub fn bspatch(source: &ByteCode, patch: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let patch = Bsdiff::parse(&mut ByteCode::new(patch))?;
+    let decompress = |block: &ByteCode, limit: u64| {
+        let mut output = Vec::new();
+        bzip2::read::BzDecoder::new(block.as_slice())
+            .take(limit.checked_add(1)?)
+            .read_to_end(&mut output)
+            .ok()?;
+        if output.len() as u64 > limit {
+            return None;
+        }
+        Some(output)
+    };
+    let control_limit = patch.new_size.checked_add(1)?.checked_mul(24)?;
+    apply_bsdiff_blocks(
+        source,
+        patch.new_size,
+        &decompress(&patch.control, control_limit)?,
+        &decompress(&patch.diff, patch.new_size)?,
+        &decompress(&patch.extra, patch.new_size)?,
+    )
+}
+
+/// The file header of a VCDIFF patch.
+#[derive(Debug)]
+pub struct VcdiffHeader<'a> {
+    /// The secondary compressor id, if the patch declares one.
+    pub secondary_compressor: Option<u8>,
+    /// The encoded custom code table, if the patch does not use the default one.
+    pub code_table: Option<ByteCode<'a>>,
+    pub application_header: Option<ByteCode<'a>>,
+}
+
+impl<'a> VcdiffHeader<'a> {
+    /// Parses a VCDIFF header starting at the current position.
+    /// Moves the pointer forward to the first window.
+    ///
+    /// Returns `None` without moving the pointer if the header is malformed.
+    pub fn parse(bytes: &mut ByteCode<'a>) -> Option<VcdiffHeader<'a>> {
+        let mut reader = ByteCode::new(bytes.as_slice());
+        if !reader.as_slice().starts_with(VCDIFF_MAGIC) {
+            return None;
+        }
+        reader.skip(4);
+        let indicator = reader.try_take_into_u8().ok()?;
+        if indicator & !(VCD_DECOMPRESS | VCD_CODETABLE | VCD_APPHEADER) != 0 {
+            return None;
+        }
+        let secondary_compressor = match indicator & VCD_DECOMPRESS {
+            0 => None,
+            _ => Some(reader.try_take_into_u8().ok()?),
+        };
+        let code_table = match indicator & VCD_CODETABLE {
+            0 => None,
+            _ => Some(take_sized(&mut reader)?),
+        };
+        let application_header = match indicator & VCD_APPHEADER {
+            0 => None,
+            _ => Some(take_sized(&mut reader)?),
+        };
+        bytes.skip(reader.pos());
+        Some(VcdiffHeader {
+            secondary_compressor,
+            code_table,
+            application_header,
+        })
+    }
+}
+
+/// Where the source segment of a window is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentOrigin {
+    /// The source file.
+    Source,
+    /// The target decoded by earlier windows.
+    Target,
+}
+
+/// The part of the source or earlier target a window copies from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSegment {
+    pub origin: SegmentOrigin,
+    pub position: u64,
+    pub len: u64,
+}
+
+/// A window of a VCDIFF patch, producing one contiguous part of the target.
+#[derive(Debug)]
+pub struct VcdiffWindow<'a> {
+    pub source: Option<SourceSegment>,
+    pub target_len: u64,
+    /// Non-zero if the sections use secondary compression.
+    pub delta_indicator: u8,
+    pub adler32: Option<u32>,
+    pub data: ByteCode<'a>,
+    pub instructions: ByteCode<'a>,
+    pub addresses: ByteCode<'a>,
+}
+
+impl<'a> VcdiffWindow<'a> {
+    /// Parses a window starting at the current position.
+    /// Moves the pointer forward to the next window.
+    ///
+    /// Returns `None` without moving the pointer if the window is malformed or truncated.
+    pub fn parse(bytes: &mut ByteCode<'a>) -> Option<VcdiffWindow<'a>> {
+        let mut reader = ByteCode::new(bytes.as_slice());
+        let indicator = reader.try_take_into_u8().ok()?;
+        let origin = match indicator & (VCD_SOURCE | VCD_TARGET) {
+            0 => None,
+            VCD_SOURCE => Some(SegmentOrigin::Source),
+            VCD_TARGET => Some(SegmentOrigin::Target),
+            _ => return None,
+        };
+        if indicator & !(VCD_SOURCE | VCD_TARGET | VCD_ADLER32) != 0 {
+            return None;
+        }
+        let source = match origin {
+            Some(origin) => {
+                let len = take_varint(&mut reader)?;
+                let position = take_varint(&mut reader)?;
+                Some(SourceSegment {
+                    origin,
+                    position,
+                    len,
+                })
+            }
+            None => None,
+        };
+        let mut delta = take_sized(&mut reader)?;
+        let target_len = take_varint(&mut delta)?;
+        let delta_indicator = delta.try_take_into_u8().ok()?;
+        let data_len = usize::try_from(take_varint(&mut delta)?).ok()?;
+        let instructions_len = usize::try_from(take_varint(&mut delta)?).ok()?;
+        let addresses_len = usize::try_from(take_varint(&mut delta)?).ok()?;
+        let adler32 = match indicator & VCD_ADLER32 {
+            0 => None,
+            _ => Some(u32::from_be_bytes(delta.try_take_array().ok()?)),
+        };
+        let window = VcdiffWindow {
+            source,
+            target_len,
+            delta_indicator,
+            adler32,
+            data: delta.try_take_bytecode(data_len).ok()?,
+            instructions: delta.try_take_bytecode(instructions_len).ok()?,
+            addresses: delta.try_take_bytecode(addresses_len).ok()?,
+        };
+        if !delta.is_end() {
+            return None;
+        }
+        bytes.skip(reader.pos());
+        Some(window)
+    }
+
+    /// Decodes the window and appends its target to `target`, which holds the output of the earlier windows.
+    ///
+    /// Returns `None` if the window uses secondary compression, refers outside the source,
+    /// decodes to the wrong length or fails its checksum, or if `target` would grow past `max_target_len` bytes.
+    /// `target` is left unchanged in that case.
+    pub fn apply(
+        &self,
+        source: &ByteCode,
+        target: &mut Vec<u8>,
+        max_target_len: usize,
+    ) -> Option<()> {
+        if self.delta_indicator != 0 {
+            return None;
+        }
+        let segment: Cow<[u8]> = match self.source {
+            None => Cow::Borrowed(&[]),
+            Some(segment) => {
+                let start = usize::try_from(segment.position).ok()?;
+                let range = start..start.checked_add(usize::try_from(segment.len).ok()?)?;
+                match segment.origin {
+                    SegmentOrigin::Source => Cow::Borrowed(source.as_slice().get(range)?),
+                    SegmentOrigin::Target => Cow::Owned(target.get(range)?.to_vec()),
+                }
+            }
+        };
+        let target_len = usize::try_from(self.target_len).ok()?;
+        if target_len > max_target_len.saturating_sub(target.len()) {
+            return None;
+        }
+        let mut data = ByteCode::new(self.data.as_slice());
+        let mut instructions = ByteCode::new(self.instructions.as_slice());
+        let mut addresses = ByteCode::new(self.addresses.as_slice());
+        let mut cache = AddressCache::new();
+        let mut window = Vec::new();
+
+        while !instructions.is_end() {
+            let code = instructions.try_take_into_u8().ok()?;
+            for instruction in DEFAULT_CODE_TABLE[code as usize] {
+                let size = match instruction {
+                    Instruction::Noop => continue,
+                    Instruction::Add(0) | Instruction::Run(0) | Instruction::Copy(0, _) => {
+                        usize::try_from(take_varint(&mut instructions)?).ok()?
+                    }
+                    Instruction::Add(size)
+                    | Instruction::Run(size)
+                    | Instruction::Copy(size, _) => size as usize,
+                };
+                if size > target_len - window.len() {
+                    return None;
+                }
+                match instruction {
+                    Instruction::Noop => unreachable!(),
+                    Instruction::Add(_) => window.extend(data.try_take_ref(size).ok()?),
+                    Instruction::Run(_) => {
+                        let byte = data.try_take_into_u8().ok()?;
+                        window.resize(window.len() + size, byte);
+                    }
+                    Instruction::Copy(_, mode) => {
+                        let here = segment.len() + window.len();
+                        let address = cache.decode(&mut addresses, here, mode)?;
+                        for i in address..address + size {
+                            let byte = match segment.get(i) {
+                                Some(byte) => *byte,
+                                None => window[i - segment.len()],
+                            };
+                            window.push(byte);
+                        }
+                    }
+                }
+            }
+        }
+
+        if window.len() != target_len {
+            return None;
+        }
+        if self
+            .adler32
+            .is_some_and(|checksum| checksum != adler32(&window))
+        {
+            return None;
+        }
+        target.extend(window);
+        Some(())
+    }
+}
+
+/// Applies a whole VCDIFF patch to `source` and returns the target.
+/// Moves the pointer of `patch` forward past the consumed windows.
+///
+/// Returns `None` if the patch is malformed, uses a custom code table or secondary compression,
+/// or declares a target longer than `max_target_len` bytes.
+/// The target lengths are checked before anything is decoded, so `max_target_len` bounds the memory used.
+pub fn apply_vcdiff(
+    source: &ByteCode,
+    patch: &mut ByteCode,
+    max_target_len: usize,
+) -> Option<Vec<u8>> {
+    let mut reader = ByteCode::new(patch.as_slice());
+    let header = VcdiffHeader::parse(&mut reader)?;
+    if header.code_table.is_some() {
+        return None;
+    }
+    let mut target = Vec::new();
+    while !reader.is_end() {
+        VcdiffWindow::parse(&mut reader)?.apply(source, &mut target, max_target_len)?;
+    }
+    patch.skip(reader.pos());
+    Some(target)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Noop,
+    /// A size of 0 means the size follows in the instruction section.
+    Add(u8),
+    Run(u8),
+    Copy(u8, u8),
+}
+
+/// The default code table of RFC 3284 section 5.6, as pairs of instructions.
+static DEFAULT_CODE_TABLE: [[Instruction; 2]; 256] = {
+    use Instruction::*;
+
+    let mut table = [[Noop, Noop]; 256];
+    table[0] = [Run(0), Noop];
+    let mut i = 1;
+    let mut size = 0;
+    while size <= 17 {
+        table[i] = [Add(size), Noop];
+        i += 1;
+        size += 1;
+    }
+    let mut mode = 0;
+    while mode <= 8 {
+        table[i] = [Copy(0, mode), Noop];
+        i += 1;
+        let mut size = 4;
+        while size <= 18 {
+            table[i] = [Copy(size, mode), Noop];
+            i += 1;
+            size += 1;
+        }
+        mode += 1;
+    }
+    let mut mode = 0;
+    while mode <= 8 {
+        let copy_sizes = if mode <= 5 { 4..=6 } else { 4..=4 };
+        let mut add = 1;
+        while add <= 4 {
+            let mut copy = *copy_sizes.start();
+            while copy <= *copy_sizes.end() {
+                table[i] = [Add(add), Copy(copy, mode)];
+                i += 1;
+                copy += 1;
+            }
+            add += 1;
+        }
+        mode += 1;
+    }
+    let mut mode = 0;
+    while mode <= 8 {
+        table[i] = [Copy(4, mode), Add(1)];
+        i += 1;
+        mode += 1;
+    }
+    table
+};
+
+/// The `near` and `same` address caches of RFC 3284 section 5.1.
+struct AddressCache {
+    near: [usize; NEAR_CACHE_SIZE],
+    next_slot: usize,
+    same: [usize; SAME_CACHE_SIZE * 256],
+}
+
+impl AddressCache {
+    fn new() -> Self {
+        AddressCache {
+            near: [0; NEAR_CACHE_SIZE],
+            next_slot: 0,
+            same: [0; SAME_CACHE_SIZE * 256],
+        }
+    }
+
+    fn decode(&mut self, addresses: &mut ByteCode, here: usize, mode: u8) -> Option<usize> {
+        let mode = mode as usize;
+        let address = match mode {
+            0 => usize::try_from(take_varint(addresses)?).ok()?,
+            1 => here.checked_sub(usize::try_from(take_varint(addresses)?).ok()?)?,
+            _ if mode < 2 + NEAR_CACHE_SIZE => {
+                self.near[mode - 2].checked_add(usize::try_from(take_varint(addresses)?).ok()?)?
+            }
+            _ => {
+                let index = (mode - 2 - NEAR_CACHE_SIZE) * 256
+                    + addresses.try_take_into_u8().ok()? as usize;
+                *self.same.get(index)?
+            }
+        };
+        if address >= here {
+            return None;
+        }
+        self.near[self.next_slot] = address;
+        self.next_slot = (self.next_slot + 1) % NEAR_CACHE_SIZE;
+        self.same[address % (SAME_CACHE_SIZE * 256)] = address;
+        Some(address)
+    }
+}
+
+/// Reads a VCDIFF integer: base-128 big-endian, with the top bit set on all but the last byte.
+fn take_varint(bytes: &mut ByteCode) -> Option<u64> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.as_slice().iter().enumerate() {
+        if value >> 57 != 0 {
+            return None;
+        }
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            bytes.skip(i + 1);
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn take_sized<'a>(bytes: &mut ByteCode<'a>) -> Option<ByteCode<'a>> {
+    let len = usize::try_from(take_varint(bytes)?).ok()?;
+    bytes.try_take_bytecode(len).ok()
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[cfg(test)]
+fn bsdiff_int(value: i64) -> [u8; 8] {
+    let mut raw = value.unsigned_abs();
+    if value < 0 {
+        raw |= 1 << 63;
+    }
+    raw.to_le_bytes()
+}
+
+#[test]
+fn default_code_table() {
+    assert_eq!(DEFAULT_CODE_TABLE[0][0], Instruction::Run(0));
+    assert_eq!(DEFAULT_CODE_TABLE[18][0], Instruction::Add(17));
+    assert_eq!(DEFAULT_CODE_TABLE[19][0], Instruction::Copy(0, 0));
+    assert_eq!(DEFAULT_CODE_TABLE[162][0], Instruction::Copy(18, 8));
+    assert_eq!(
+        DEFAULT_CODE_TABLE[163],
+        [Instruction::Add(1), Instruction::Copy(4, 0)]
+    );
+    assert_eq!(
+        DEFAULT_CODE_TABLE[246],
+        [Instruction::Add(4), Instruction::Copy(4, 8)]
+    );
+    assert_eq!(
+        DEFAULT_CODE_TABLE[255],
+        [Instruction::Copy(4, 8), Instruction::Add(1)]
+    );
+}
+
+#[test]
+fn vcdiff_apply() {
+    let source = ByteCode::new(b"abcdefgh");
+    let plain = [
+        0xd6, 0xc3, 0xc4, 0x00, 0x04, 0x02, b'h', b'i', // header, application data
+        0x01, 0x08, 0x00, 0x10, // window: source 0..8, delta length
+        0x12, 0x00, 0x04, 0x05, 0x02, // target length, sections
+        b'X', b'Y', b'Z', b'!', // data
+        0x14, 0x04, 0x28, 0x00, 0x03, // COPY 4, ADD 3, COPY 8 (overlapping), RUN 3
+        0x00, 0x07, // addresses
+    ];
+    let checked = [
+        0xd6, 0xc3, 0xc4, 0x00, 0x04, 0x02, b'h', b'i', // header, application data
+        0x05, 0x08, 0x00, 0x14, // window: source 0..8, delta length
+        0x12, 0x00, 0x04, 0x05, 0x02, // target length, sections
+        0x3e, 0x23, 0x05, 0xef, // adler32
+        b'X', b'Y', b'Z', b'!', // data
+        0x14, 0x04, 0x28, 0x00, 0x03, // COPY 4, ADD 3, COPY 8 (overlapping), RUN 3
+        0x00, 0x07, // addresses
+    ];
+    for patch in [&plain[..], &checked] {
+        let mut bytes = ByteCode::new(patch);
+        assert_eq!(
+            apply_vcdiff(&source, &mut bytes, 18).unwrap(),
+            b"abcdXYZabcdXYZa!!!"
+        );
+        assert!(bytes.is_end());
+    }
+
+    let mut bytes = ByteCode::new(&plain);
+    let header = VcdiffHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.application_header.unwrap().as_slice(), b"hi");
+    let window = VcdiffWindow::parse(&mut bytes).unwrap();
+    assert_eq!(
+        window.source,
+        Some(SourceSegment {
+            origin: SegmentOrigin::Source,
+            position: 0,
+            len: 8
+        })
+    );
+    assert_eq!(window.target_len, 18);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn vcdiff_target_segment() {
+    let mut patch = vec![0xd6, 0xc3, 0xc4, 0x00, 0x00];
+    // window 1: ADD "XYZ"
+    patch.extend([
+        0x00, 0x09, 0x03, 0x00, 0x03, 0x01, 0x00, b'X', b'Y', b'Z', 0x04,
+    ]);
+    // window 2: COPY 6 from target bytes 0..3, overlapping its own output
+    patch.extend([
+        0x02, 0x03, 0x00, 0x07, 0x06, 0x00, 0x00, 0x01, 0x01, 0x16, 0x00,
+    ]);
+    let target = apply_vcdiff(&ByteCode::new(&[]), &mut ByteCode::new(&patch), 9).unwrap();
+    assert_eq!(target, b"XYZXYZXYZ");
+}
+
+#[test]
+fn vcdiff_invalid() {
+    let source = ByteCode::new(b"abcdefgh");
+
+    let mut patch = [
+        0xd6, 0xc3, 0xc4, 0x00, 0x04, 0x02, b'h', b'i', // header, application data
+        0x05, 0x08, 0x00, 0x14, // window: source 0..8, delta length
+        0x12, 0x00, 0x04, 0x05, 0x02, // target length, sections
+        0x3e, 0x23, 0x05, 0xef, // adler32
+        b'X', b'Y', b'Z', b'!', // data
+        0x14, 0x04, 0x28, 0x00, 0x03, // COPY 4, ADD 3, COPY 8 (overlapping), RUN 3
+        0x00, 0x07, // addresses
+    ];
+    patch[17] ^= 1;
+    let mut bytes = ByteCode::new(&patch);
+    assert!(apply_vcdiff(&source, &mut bytes, 1024).is_none());
+    assert_eq!(bytes.pos(), 0);
+
+    let plain = [
+        0xd6, 0xc3, 0xc4, 0x00, 0x04, 0x02, b'h', b'i', // header, application data
+        0x01, 0x08, 0x00, 0x10, // window: source 0..8, delta length
+        0x12, 0x00, 0x04, 0x05, 0x02, // target length, sections
+        b'X', b'Y', b'Z', b'!', // data
+        0x14, 0x04, 0x28, 0x00, 0x03, // COPY 4, ADD 3, COPY 8 (overlapping), RUN 3
+        0x00, 0x07, // addresses
+    ];
+    assert!(apply_vcdiff(&ByteCode::new(b"abc"), &mut ByteCode::new(&plain), 1024).is_none());
+    for len in 0..plain.len() {
+        assert!(
+            apply_vcdiff(&source, &mut ByteCode::new(&plain[..len]), 1024).is_none() || len == 8
+        );
+    }
+
+    // COPY from an address that has not been decoded yet
+    let patch = [
+        0xd6, 0xc3, 0xc4, 0x00, 0x00, 0x00, 0x07, 0x04, 0x00, 0x00, 0x01, 0x01, 0x14, 0x00,
+    ];
+    assert!(apply_vcdiff(&source, &mut ByteCode::new(&patch), 1024).is_none());
+
+    // target longer than the limit
+    assert!(apply_vcdiff(&source, &mut ByteCode::new(&plain), 17).is_none());
+    let patch = [
+        0xd6, 0xc3, 0xc4, 0x00, 0x00, // header
+        0x00, 0x12, 0xa0, 0x80, 0x80, 0x80, 0x80,
+        0x00, // window: delta length, target length 2^40
+        0x00, 0x01, 0x07, 0x00, b'A', // sections, data
+        0x00, 0xa0, 0x80, 0x80, 0x80, 0x80, 0x00, // RUN 2^40
+    ];
+    assert!(apply_vcdiff(&source, &mut ByteCode::new(&patch), 1 << 20).is_none());
+}
+
+#[test]
+fn bsdiff_apply_blocks() {
+    let source = ByteCode::new(b"hello world");
+    let mut control = Vec::new();
+    for value in [5, 2, 0, 6, 0, -11] {
+        control.extend(bsdiff_int(value));
+    }
+    let diff = [0, 0, 0, 0, b'O'.wrapping_sub(b'o'), 0, 0, 0, 0, 0, 0];
+    let target = apply_bsdiff_blocks(&source, 13, &control, &diff, b"!!").unwrap();
+    assert_eq!(target, b"hellO!! world");
+
+    assert!(apply_bsdiff_blocks(&source, 14, &control, &diff, b"!!").is_none());
+    assert!(apply_bsdiff_blocks(&source, 13, &control[..40], &diff, b"!!").is_none());
+    assert!(apply_bsdiff_blocks(&source, 13, &control, &diff[..4], b"!!").is_none());
+}
+
+#[test]
+fn bsdiff_apply_blocks_seek_overflow() {
+    let source = ByteCode::new(b"hello world");
+    let mut control = Vec::new();
+    for value in [0, 0, i64::MAX, 2, 0, 0] {
+        control.extend(bsdiff_int(value));
+    }
+    assert!(apply_bsdiff_blocks(&source, 2, &control, &[1, 2], &[]).is_none());
+}
+
+#[test]
+fn bsdiff_parse() {
+    let mut patch = BSDIFF_MAGIC.to_vec();
+    patch.extend(bsdiff_int(2));
+    patch.extend(bsdiff_int(1));
+    patch.extend(bsdiff_int(10));
+    patch.extend([1, 2, 3, 4, 5]);
+    let mut bytes = ByteCode::new(&patch);
+    let parsed = Bsdiff::parse(&mut bytes).unwrap();
+    assert_eq!(parsed.new_size, 10);
+    assert_eq!(parsed.control.as_slice(), [1, 2]);
+    assert_eq!(parsed.diff.as_slice(), [3]);
+    assert_eq!(parsed.extra.as_slice(), [4, 5]);
+    assert!(bytes.is_end());
+
+    patch[16..24].copy_from_slice(&bsdiff_int(-1));
+    assert!(Bsdiff::parse(&mut ByteCode::new(&patch)).is_none());
+    patch[16..24].copy_from_slice(&bsdiff_int(4));
+    assert!(Bsdiff::parse(&mut ByteCode::new(&patch)).is_none());
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn bspatch_compressed() {
+    use std::io::Write;
+
+    let compress = |data: &[u8]| {
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    };
+    let build = |diff: &[u8]| {
+        let mut control = Vec::new();
+        for value in [3, 1, 0] {
+            control.extend(bsdiff_int(value));
+        }
+        let control = compress(&control);
+        let diff = compress(diff);
+        let mut patch = BSDIFF_MAGIC.to_vec();
+        patch.extend(bsdiff_int(control.len() as i64));
+        patch.extend(bsdiff_int(diff.len() as i64));
+        patch.extend(bsdiff_int(4));
+        patch.extend(control);
+        patch.extend(diff);
+        patch.extend(compress(b"!"));
+        patch
+    };
+
+    let patch = build(&[0, 1, 0]);
+    assert_eq!(bspatch(&ByteCode::new(b"abc"), &patch).unwrap(), b"acc!");
+    let len = patch.len();
+    assert!(bspatch(&ByteCode::new(b"abc"), &patch[..len - 4]).is_none());
+
+    // a diff block that decompresses to more than the 4-byte target
+    assert!(bspatch(&ByteCode::new(b"abc"), &build(&[0; 1 << 20])).is_none());
+}
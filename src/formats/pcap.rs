@@ -0,0 +1,428 @@
+//! PCAP capture files and PCAPNG blocks.
+//!
+//! The byte order is detected from the magic number, so captures written on either kind of host can be read.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::pcap::PcapHeader, ByteCode};
+//!
+//! let capture = std::fs::read("capture.pcap").unwrap();
+//! let mut bytes = ByteCode::new(&capture);
+//! let header = PcapHeader::parse(&mut bytes).unwrap();
+//! for packet in header.packets(&mut bytes) {
+//!     println!("{}.{:06} {} bytes", packet.timestamp_sec, packet.timestamp_frac, packet.data.len());
+//! }
+//! ```
+
+use crate::{ByteCode, Endian};
+
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+
+/// Block type of a PCAPNG section header block.
+pub const SECTION_HEADER_BLOCK: u32 = 0x0a0d_0d0a;
+/// Block type of a PCAPNG interface description block.
+pub const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+/// Block type of a PCAPNG simple packet block.
+pub const SIMPLE_PACKET_BLOCK: u32 = 0x0000_0003;
+/// Block type of a PCAPNG enhanced packet block.
+pub const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+
+/// The global header of a PCAP file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcapHeader {
+    pub endian: Endian,
+    /// `true` if the fractional part of timestamps is in nanoseconds rather than microseconds.
+    pub nanosecond: bool,
+    pub version_major: u16,
+    pub version_minor: u16,
+    pub thiszone: i32,
+    pub sigfigs: u32,
+    pub snaplen: u32,
+    pub link_type: u32,
+}
+
+impl PcapHeader {
+    /// Parses the 24-byte global header.
+    /// Moves the pointer forward 24.
+    ///
+    /// Returns `None` without moving the pointer if the magic number is unknown or the header is truncated.
+    pub fn parse(bytes: &mut ByteCode) -> Option<PcapHeader> {
+        let mut reader = ByteCode::new(bytes.as_slice());
+        let magic: [u8; 4] = reader.try_take_array().ok()?;
+        let (endian, nanosecond) =
+            [Endian::Big, Endian::Little]
+                .into_iter()
+                .find_map(|endian| match endian.read_uint(&magic) as u32 {
+                    PCAP_MAGIC_MICROS => Some((endian, false)),
+                    PCAP_MAGIC_NANOS => Some((endian, true)),
+                    _ => None,
+                })?;
+        reader.set_endian(endian);
+        let header = PcapHeader {
+            endian,
+            nanosecond,
+            version_major: reader.try_take_into_u16().ok()?,
+            version_minor: reader.try_take_into_u16().ok()?,
+            thiszone: reader.try_take_into_i32().ok()?,
+            sigfigs: reader.try_take_into_u32().ok()?,
+            snaplen: reader.try_take_into_u32().ok()?,
+            link_type: reader.try_take_into_u32().ok()?,
+        };
+        bytes.skip(reader.pos());
+        Some(header)
+    }
+
+    /// Returns an iterator over the packet records following the global header.
+    /// Moves the pointer forward past each record as it is yielded.
+    ///
+    /// The iteration stops at the end of the slice, or at a truncated record,
+    /// in which case the pointer is left at the beginning of that record.
+    pub fn packets<'b, 'a>(&self, bytes: &'b mut ByteCode<'a>) -> Packets<'b, 'a> {
+        Packets {
+            bytes,
+            endian: self.endian,
+        }
+    }
+}
+
+/// A packet record of a PCAP file.
+#[derive(Debug)]
+pub struct Packet<'a> {
+    pub timestamp_sec: u32,
+    /// Microseconds or nanoseconds, depending on [`PcapHeader::nanosecond`].
+    pub timestamp_frac: u32,
+    /// The length of the packet on the wire, which may exceed the captured length.
+    pub original_len: u32,
+    /// The captured bytes.
+    pub data: ByteCode<'a>,
+}
+
+/// An iterator over the packet records of a PCAP file.
+///
+/// This struct is created by [`PcapHeader::packets`].
+pub struct Packets<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    endian: Endian,
+}
+
+impl<'a> Iterator for Packets<'_, 'a> {
+    type Item = Packet<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut reader = ByteCode::new(self.bytes.as_slice());
+        reader.set_endian(self.endian);
+        let timestamp_sec = reader.try_take_into_u32().ok()?;
+        let timestamp_frac = reader.try_take_into_u32().ok()?;
+        let captured_len = usize::try_from(reader.try_take_into_u32().ok()?).ok()?;
+        let original_len = reader.try_take_into_u32().ok()?;
+        let data = reader.try_take_ref(captured_len).ok()?;
+        self.bytes.skip(reader.pos());
+        Some(Packet {
+            timestamp_sec,
+            timestamp_frac,
+            original_len,
+            data: ByteCode::new(data),
+        })
+    }
+}
+
+/// A block of a PCAPNG file.
+#[derive(Debug)]
+pub enum Block<'a> {
+    SectionHeader {
+        endian: Endian,
+        version_major: u16,
+        version_minor: u16,
+        /// -1 if the length is not specified.
+        section_length: i64,
+    },
+    InterfaceDescription {
+        link_type: u16,
+        snaplen: u32,
+    },
+    EnhancedPacket {
+        interface_id: u32,
+        /// In units of the interface's timestamp resolution, microseconds by default.
+        timestamp: u64,
+        original_len: u32,
+        data: ByteCode<'a>,
+    },
+    SimplePacket {
+        original_len: u32,
+        data: ByteCode<'a>,
+    },
+    /// Any other block, with its body excluding the type and length fields.
+    Other {
+        block_type: u32,
+        body: ByteCode<'a>,
+    },
+}
+
+/// An iterator over the blocks of a PCAPNG file.
+/// The byte order is taken from each section header block.
+///
+/// This struct is created by [`pcapng_blocks`].
+pub struct Blocks<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    endian: Endian,
+}
+
+/// Returns an iterator over the blocks of a PCAPNG file, which must start with a section header block.
+/// Moves the pointer forward past each block as it is yielded.
+///
+/// The iteration stops at the end of the slice, or at a truncated or malformed block,
+/// in which case the pointer is left at the beginning of that block.
+pub fn pcapng_blocks<'b, 'a>(bytes: &'b mut ByteCode<'a>) -> Blocks<'b, 'a> {
+    Blocks {
+        bytes,
+        endian: Endian::Little,
+    }
+}
+
+impl<'a> Blocks<'_, 'a> {
+    fn decode(&mut self) -> Option<(Block<'a>, usize)> {
+        let mut reader = ByteCode::new(self.bytes.as_slice());
+        let raw_type: [u8; 4] = reader.try_take_array().ok()?;
+        let raw_len: [u8; 4] = reader.try_take_array().ok()?;
+        // The section header block type reads the same in either byte order.
+        if u32::from_be_bytes(raw_type) == SECTION_HEADER_BLOCK {
+            let magic = reader.try_peek_into_u32().ok()?;
+            self.endian = if magic == PCAPNG_BYTE_ORDER_MAGIC {
+                Endian::Big
+            } else if magic.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC {
+                Endian::Little
+            } else {
+                return None;
+            };
+        }
+        let endian = self.endian;
+        reader.set_endian(endian);
+        let total_len = usize::try_from(endian.read_uint(&raw_len)).ok()?;
+        if total_len < 12 || total_len % 4 != 0 {
+            return None;
+        }
+        let body = reader.try_take_ref(total_len - 12).ok()?;
+        if usize::try_from(reader.try_take_into_u32().ok()?).ok()? != total_len {
+            return None;
+        }
+        let mut fields = ByteCode::new(body);
+        fields.set_endian(endian);
+
+        let block = match endian.read_uint(&raw_type) as u32 {
+            SECTION_HEADER_BLOCK => {
+                fields.try_skip(4).ok()?; // byte-order magic
+                Block::SectionHeader {
+                    endian,
+                    version_major: fields.try_take_into_u16().ok()?,
+                    version_minor: fields.try_take_into_u16().ok()?,
+                    section_length: fields.try_take_into_i64().ok()?,
+                }
+            }
+            INTERFACE_DESCRIPTION_BLOCK => {
+                let link_type = fields.try_take_into_u16().ok()?;
+                fields.try_skip(2).ok()?; // reserved
+                Block::InterfaceDescription {
+                    link_type,
+                    snaplen: fields.try_take_into_u32().ok()?,
+                }
+            }
+            ENHANCED_PACKET_BLOCK => {
+                let interface_id = fields.try_take_into_u32().ok()?;
+                let timestamp_high = fields.try_take_into_u32().ok()?;
+                let timestamp_low = fields.try_take_into_u32().ok()?;
+                let captured_len = usize::try_from(fields.try_take_into_u32().ok()?).ok()?;
+                Block::EnhancedPacket {
+                    interface_id,
+                    timestamp: u64::from(timestamp_high) << 32 | u64::from(timestamp_low),
+                    original_len: fields.try_take_into_u32().ok()?,
+                    data: ByteCode::new(fields.try_take_ref(captured_len).ok()?),
+                }
+            }
+            SIMPLE_PACKET_BLOCK => {
+                let original_len = fields.try_take_into_u32().ok()?;
+                let captured_len = (original_len as usize).min(fields.as_slice().len());
+                Block::SimplePacket {
+                    original_len,
+                    data: ByteCode::new(fields.try_take_ref(captured_len).ok()?),
+                }
+            }
+            block_type => Block::Other {
+                block_type,
+                body: ByteCode::new(body),
+            },
+        };
+        Some((block, total_len))
+    }
+}
+
+impl<'a> Iterator for Blocks<'_, 'a> {
+    type Item = Block<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let endian = self.endian;
+        match self.decode() {
+            Some((block, len)) => {
+                self.bytes.skip(len);
+                Some(block)
+            }
+            None => {
+                self.endian = endian;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn pcap_packets() {
+    let big: &[u8] = &[
+        0xa1, 0xb2, 0xc3, 0xd4, 0x00, 0x02, 0x00, 0x04, // magic, version
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // thiszone, sigfigs
+        0x00, 0x00, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01, // snaplen, link type
+        0x5f, 0x5e, 0x10, 0x00, 0x00, 0x00, 0x01, 0xf4, // timestamp
+        0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x3c, 0xaa, 0xbb, // lengths, data
+        0x5f, 0x5e, 0x10, 0x01, 0x00, 0x00, 0x01, 0xf4, // timestamp
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3c, 0xcc, // lengths, data
+    ];
+    let little: &[u8] = &[
+        0xd4, 0xc3, 0xb2, 0xa1, 0x02, 0x00, 0x04, 0x00, // magic, version
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // thiszone, sigfigs
+        0xff, 0xff, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, // snaplen, link type
+        0x00, 0x10, 0x5e, 0x5f, 0xf4, 0x01, 0x00, 0x00, // timestamp
+        0x02, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x00, 0x00, 0xaa, 0xbb, // lengths, data
+        0x01, 0x10, 0x5e, 0x5f, 0xf4, 0x01, 0x00, 0x00, // timestamp
+        0x01, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x00, 0x00, 0xcc, // lengths, data
+    ];
+    for (endian, file) in [(Endian::Big, big), (Endian::Little, little)] {
+        let mut bytes = ByteCode::new(file);
+        let header = PcapHeader::parse(&mut bytes).unwrap();
+        assert_eq!(header.endian, endian);
+        assert!(!header.nanosecond);
+        assert_eq!((header.version_major, header.version_minor), (2, 4));
+        assert_eq!(header.snaplen, 65535);
+        assert_eq!(header.link_type, 1);
+
+        let packets: Vec<_> = header.packets(&mut bytes).collect();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].timestamp_sec, 1_600_000_000);
+        assert_eq!(packets[0].timestamp_frac, 500);
+        assert_eq!(packets[0].original_len, 60);
+        assert_eq!(packets[0].data.as_slice(), [0xaa, 0xbb]);
+        assert_eq!(packets[1].data.as_slice(), [0xcc]);
+        assert!(bytes.is_end());
+    }
+}
+
+#[test]
+fn pcap_truncated() {
+    let file = [
+        &b"\xd4\xc3\xb2\xa1\x02\0\x04\0\0\0\0\0\0\0\0\0\xff\xff\0\0\x01\0\0\0"[..],
+        b"\0\x10\x5e\x5f\xf4\x01\0\0\x02\0\0\0\x3c\0\0\0\xaa\xbb",
+        b"\x01\x10\x5e\x5f\xf4\x01\0\0\x01\0\0\0\x3c\0\0\0\xcc",
+    ]
+    .concat();
+    let mut bytes = ByteCode::new(&file[..file.len() - 1]);
+    let header = PcapHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.packets(&mut bytes).count(), 1);
+    assert_eq!(bytes.pos(), 24 + 18);
+
+    assert!(PcapHeader::parse(&mut ByteCode::new(&file[..20])).is_none());
+    assert!(PcapHeader::parse(&mut ByteCode::new(&[0; 24])).is_none());
+
+    // captured length at the top of the range
+    let mut file = file;
+    file[24 + 8..24 + 12].fill(0xff);
+    let mut bytes = ByteCode::new(&file);
+    let header = PcapHeader::parse(&mut bytes).unwrap();
+    assert_eq!(header.packets(&mut bytes).count(), 0);
+    assert_eq!(bytes.pos(), 24);
+}
+
+#[test]
+fn pcapng() {
+    let mut bytes = ByteCode::new(&[
+        // Section header block (big-endian)
+        0x0a, 0x0d, 0x0d, 0x0a, 0x00, 0x00, 0x00, 0x1c, // type, length
+        0x1a, 0x2b, 0x3c, 0x4d, 0x00, 0x01, 0x00, 0x00, // byte order, version
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // section length
+        0x00, 0x00, 0x00, 0x1c, //
+        // Interface description block
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x14, // type, length
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, // link type, snaplen
+        0x00, 0x00, 0x00, 0x14, //
+        // Enhanced packet block with 3 bytes of data padded to 4
+        0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x24, // type, length
+        0x00, 0x00, 0x00, 0x00, // interface
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, // timestamp
+        0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x03, // lengths
+        0x01, 0x02, 0x03, 0x00, // data
+        0x00, 0x00, 0x00, 0x24, //
+        // Custom block
+        0x00, 0x00, 0x0b, 0xad, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x0c,
+    ]);
+    let blocks: Vec<_> = pcapng_blocks(&mut bytes).collect();
+    assert!(bytes.is_end());
+    assert_eq!(blocks.len(), 4);
+
+    match &blocks[0] {
+        Block::SectionHeader {
+            endian,
+            version_major,
+            section_length,
+            ..
+        } => {
+            assert_eq!(*endian, Endian::Big);
+            assert_eq!(*version_major, 1);
+            assert_eq!(*section_length, -1);
+        }
+        block => panic!("unexpected block: {:?}", block),
+    }
+    match &blocks[1] {
+        Block::InterfaceDescription { link_type, snaplen } => {
+            assert_eq!(*link_type, 1);
+            assert_eq!(*snaplen, 262144);
+        }
+        block => panic!("unexpected block: {:?}", block),
+    }
+    match &blocks[2] {
+        Block::EnhancedPacket {
+            timestamp,
+            original_len,
+            data,
+            ..
+        } => {
+            assert_eq!(*timestamp, (1 << 32) | 2);
+            assert_eq!(*original_len, 3);
+            assert_eq!(data.as_slice(), [0x01, 0x02, 0x03]);
+        }
+        block => panic!("unexpected block: {:?}", block),
+    }
+    match &blocks[3] {
+        Block::Other { block_type, body } => {
+            assert_eq!(*block_type, 0x0bad);
+            assert!(body.is_end());
+        }
+        block => panic!("unexpected block: {:?}", block),
+    }
+}
+
+#[test]
+fn pcapng_malformed() {
+    let mut bytes = ByteCode::new(&[
+        // Section header block (big-endian)
+        0x0a, 0x0d, 0x0d, 0x0a, 0x00, 0x00, 0x00, 0x1c, // type, length
+        0x1a, 0x2b, 0x3c, 0x4d, 0x00, 0x01, 0x00, 0x00, // byte order, version
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // section length
+        0x00, 0x00, 0x00, 0x1c, //
+        // Custom block whose trailing length does not match
+        0x00, 0x00, 0x0b, 0xad, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x10,
+    ]);
+    assert_eq!(pcapng_blocks(&mut bytes).count(), 1);
+    assert_eq!(bytes.pos(), 28);
+}
@@ -0,0 +1,361 @@
+//! TrueType and OpenType (sfnt) table directories.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::sfnt::Font, ByteCode};
+//!
+//! let file = std::fs::read("font.ttf").unwrap();
+//! let font = Font::parse(&ByteCode::new(&file)).unwrap();
+//! for record in &font.tables {
+//!     println!("{} {} {}", record.tag, record.length, font.verify_table(record));
+//! }
+//! let mut head = font.table(b"head").unwrap();
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{ByteCode, ByteCodeWriter, FourCc};
+
+const CHECKSUM_MAGIC: u32 = 0xb1b0_afba;
+
+/// An entry of the table directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableRecord {
+    pub tag: FourCc,
+    pub checksum: u32,
+    pub offset: u32,
+    pub length: u32,
+}
+
+/// A font file with its table directory.
+#[derive(Debug, Clone)]
+pub struct Font<'a> {
+    /// `0x00010000` for TrueType outlines, `OTTO` for CFF outlines, or `true` for legacy Apple fonts.
+    pub sfnt_version: FourCc,
+    pub tables: Vec<TableRecord>,
+    data: &'a [u8],
+}
+
+impl<'a> Font<'a> {
+    /// Parses the table directory of a font starting at the current position. The pointer is not moved.
+    ///
+    /// Returns `None` if the directory is truncated, the version is unknown,
+    /// or any table lies outside the file.
+    pub fn parse(bytes: &ByteCode<'a>) -> Option<Font<'a>> {
+        let data = bytes.as_slice();
+        let mut directory = ByteCode::new(data);
+        if data.len() < 12 {
+            return None;
+        }
+        let sfnt_version = directory.take_fourcc();
+        if !matches!(
+            &sfnt_version.0,
+            [0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true"
+        ) {
+            return None;
+        }
+        let table_count = directory.take_into_u16() as usize;
+        directory.skip(6); // searchRange, entrySelector, rangeShift
+        if directory.as_slice().len() < table_count * 16 {
+            return None;
+        }
+        let tables = (0..table_count)
+            .map(|_| {
+                let record = TableRecord {
+                    tag: directory.take_fourcc(),
+                    checksum: directory.take_into_u32(),
+                    offset: directory.take_into_u32(),
+                    length: directory.take_into_u32(),
+                };
+                table_data(data, &record).map(|_| record)
+            })
+            .collect::<Option<_>>()?;
+        Some(Font {
+            sfnt_version,
+            tables,
+            data,
+        })
+    }
+
+    /// Returns the record of the table with the given tag.
+    pub fn record(&self, tag: &[u8; 4]) -> Option<&TableRecord> {
+        self.tables.iter().find(|record| record.tag == tag)
+    }
+
+    /// Returns a new `ByteCode` over the table with the given tag.
+    pub fn table(&self, tag: &[u8; 4]) -> Option<ByteCode<'a>> {
+        let record = self.record(tag)?;
+        table_data(self.data, record).map(ByteCode::new)
+    }
+
+    /// Returns `true` if the checksum in the record matches the table contents.
+    /// For the `head` table, the `checksumAdjustment` field is excluded as the specification requires.
+    ///
+    /// Returns `false` if the record lies outside the file.
+    pub fn verify_table(&self, record: &TableRecord) -> bool {
+        let Some(table) = table_data(self.data, record) else {
+            return false;
+        };
+        let mut sum = checksum(table);
+        if record.tag == b"head" && table.len() >= 12 {
+            sum = sum.wrapping_sub(u32::from_be_bytes(table[8..12].try_into().unwrap()));
+        }
+        sum == record.checksum
+    }
+
+    /// Returns `true` if the `checksumAdjustment` field of the `head` table matches the whole font.
+    /// The font ends with the last table, padded to a multiple of 4 bytes; any bytes after it are not summed.
+    ///
+    /// Returns `false` if there is no `head` table or it lies outside the file.
+    pub fn verify_checksum_adjustment(&self) -> bool {
+        let Some(field) = self
+            .table(b"head")
+            .and_then(|head| head.as_slice().get(8..12))
+        else {
+            return false;
+        };
+        let adjustment = u32::from_be_bytes(field.try_into().unwrap());
+        let end = self
+            .tables
+            .iter()
+            // `table_data` checks that the end does not overflow
+            .filter(|record| table_data(self.data, record).is_some())
+            .map(|record| (record.offset as usize + record.length as usize).next_multiple_of(4))
+            .fold(12 + self.tables.len() * 16, usize::max)
+            .min(self.data.len());
+        let sum = checksum(&self.data[..end]).wrapping_sub(adjustment);
+        CHECKSUM_MAGIC.wrapping_sub(sum) == adjustment
+    }
+}
+
+impl Font<'_> {
+    /// Writes a font with the given tables at the end of `out`, laying out the table directory and
+    /// recomputing every table checksum and the `checksumAdjustment` field of the `head` table,
+    /// so that tables taken from a parsed font can be edited and re-emitted.
+    ///
+    /// Tables are written in the given order, each padded to a multiple of 4 bytes.
+    /// The byte order of `out` is ignored, as sfnt is always big-endian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than 65535 tables, or the font does not fit in 4 GiB.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use bytecode::{formats::sfnt::Font, ByteCode, ByteCodeWriter};
+    ///
+    /// let file = std::fs::read("font.ttf").unwrap();
+    /// let font = Font::parse(&ByteCode::new(&file)).unwrap();
+    /// let mut tables: Vec<_> = font
+    ///     .tables
+    ///     .iter()
+    ///     .map(|record| (record.tag, font.table(&record.tag.0).unwrap().as_slice()))
+    ///     .collect();
+    /// tables.retain(|(tag, _)| tag != "DSIG");
+    /// let mut out = ByteCodeWriter::new();
+    /// Font::write(font.sfnt_version, &tables, &mut out);
+    /// ```
+    pub fn write(sfnt_version: FourCc, tables: &[(FourCc, &[u8])], out: &mut ByteCodeWriter) {
+        let count = u16::try_from(tables.len())
+            .unwrap_or_else(|_| panic!("{} tables do not fit in a table directory", tables.len()));
+        let search_range = match count {
+            0 => 0,
+            _ => 1u32 << count.ilog2(),
+        };
+        // searchRange and rangeShift overflow from 4096 tables, so they are clamped
+        let clamp = |value: u32| u16::try_from(value).unwrap_or(u16::MAX);
+        let mut font = Vec::new();
+        font.extend(sfnt_version.0);
+        font.extend(count.to_be_bytes());
+        font.extend(clamp(search_range * 16).to_be_bytes());
+        font.extend((search_range.max(1).ilog2() as u16).to_be_bytes());
+        font.extend(clamp(u32::from(count) * 16 - search_range * 16).to_be_bytes());
+
+        let mut offset = 12 + tables.len() * 16;
+        let mut head = None;
+        for (tag, table) in tables {
+            let mut sum = checksum(table);
+            if tag == "head" && table.len() >= 12 {
+                sum = sum.wrapping_sub(u32::from_be_bytes(table[8..12].try_into().unwrap()));
+                head = Some(offset);
+            }
+            let offset_u32 = u32::try_from(offset).unwrap_or_else(|_| panic!("font exceeds 4 GiB"));
+            font.extend(tag.0);
+            font.extend(sum.to_be_bytes());
+            font.extend(offset_u32.to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            offset += table.len().next_multiple_of(4);
+        }
+        for (_, table) in tables {
+            font.extend(*table);
+            font.resize(font.len().next_multiple_of(4), 0);
+        }
+
+        if let Some(head) = head {
+            font[head + 8..head + 12].fill(0);
+            let adjustment = CHECKSUM_MAGIC.wrapping_sub(checksum(&font));
+            font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+        }
+        out.put_slice(&font);
+    }
+}
+
+/// Returns the bytes of the table described by `record`, or `None` if it lies outside `data`.
+fn table_data<'a>(data: &'a [u8], record: &TableRecord) -> Option<&'a [u8]> {
+    let start = usize::try_from(record.offset).ok()?;
+    let end = start.checked_add(usize::try_from(record.length).ok()?)?;
+    data.get(start..end)
+}
+
+/// Computes the sfnt checksum: the wrapping sum of big-endian `u32` words, with the last word zero-padded.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::formats::sfnt::checksum;
+///
+/// assert_eq!(checksum(&[0x00, 0x00, 0x00, 0x01, 0x01]), 0x0100_0001);
+/// ```
+pub fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[cfg(test)]
+fn sample_font() -> Vec<u8> {
+    let head = {
+        let mut head = vec![0; 54];
+        head[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5f0f_3cf5u32.to_be_bytes());
+        head
+    };
+    let name = b"abcde".to_vec();
+
+    let mut font = Vec::new();
+    font.extend(0x0001_0000u32.to_be_bytes());
+    font.extend(2u16.to_be_bytes());
+    font.extend([0x00, 0x20, 0x00, 0x01, 0x00, 0x00]);
+    let head_offset = 12 + 2 * 16;
+    let name_offset = head_offset + 56;
+    for (tag, table, offset) in [(b"head", &head, head_offset), (b"name", &name, name_offset)] {
+        font.extend(tag);
+        font.extend(checksum(table).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((table.len() as u32).to_be_bytes());
+    }
+    font.extend(&head);
+    font.extend([0, 0]);
+    font.extend(&name);
+    font.extend([0, 0, 0]);
+
+    let adjustment = CHECKSUM_MAGIC.wrapping_sub(checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
+
+#[test]
+fn font_parse() {
+    let file = sample_font();
+    let font = Font::parse(&ByteCode::new(&file)).unwrap();
+    assert_eq!(font.sfnt_version, [0x00, 0x01, 0x00, 0x00]);
+    assert_eq!(font.tables.len(), 2);
+    assert_eq!(font.tables[1].tag, "name");
+
+    let mut name = font.table(b"name").unwrap();
+    assert_eq!(name.take_into_string(5), "abcde");
+    assert!(font.table(b"glyf").is_none());
+
+    assert!(font.tables.iter().all(|record| font.verify_table(record)));
+    assert!(font.verify_checksum_adjustment());
+
+    // trailing bytes are not part of the font
+    let mut file = file;
+    file.extend([0xff; 5]);
+    let mut font = Font::parse(&ByteCode::new(&file)).unwrap();
+    assert!(font.verify_checksum_adjustment());
+
+    // an edited record pointing outside the file
+    font.tables[0].offset = u32::MAX;
+    assert!(!font.verify_checksum_adjustment());
+}
+
+#[test]
+fn write_round_trip() {
+    let file = sample_font();
+    let font = Font::parse(&ByteCode::new(&file)).unwrap();
+    let tables: Vec<_> = font
+        .tables
+        .iter()
+        .map(|record| (record.tag, font.table(&record.tag.0).unwrap().as_slice()))
+        .collect();
+    let mut out = ByteCodeWriter::new();
+    Font::write(font.sfnt_version, &tables, &mut out);
+    assert_eq!(out.as_slice(), file);
+
+    let name = b"renamed font".to_vec();
+    let edited = [
+        tables[0],
+        (FourCc(*b"name"), &name[..]),
+        (FourCc(*b"post"), &[]),
+    ];
+    let mut out = ByteCodeWriter::new();
+    Font::write(font.sfnt_version, &edited, &mut out);
+    let reparsed = Font::parse(&ByteCode::new(out.as_slice())).unwrap();
+    assert_eq!(reparsed.tables.len(), 3);
+    assert_eq!(reparsed.table(b"name").unwrap().as_slice(), name);
+    assert!(reparsed
+        .tables
+        .iter()
+        .all(|record| reparsed.verify_table(record)));
+    assert!(reparsed.verify_checksum_adjustment());
+}
+
+#[test]
+fn write_many_tables() {
+    let tables = [(FourCc(*b"zero"), &[][..]); 5000];
+    let mut out = ByteCodeWriter::new();
+    Font::write(FourCc([0, 1, 0, 0]), &tables, &mut out);
+    let header = &out.as_slice()[4..12];
+    assert_eq!(header, [0x13, 0x88, 0xff, 0xff, 0x00, 0x0c, 0x38, 0x80]);
+}
+
+#[test]
+fn font_corrupted() {
+    let mut file = sample_font();
+    let len = file.len();
+    file[len - 4] = b'x';
+    let font = Font::parse(&ByteCode::new(&file)).unwrap();
+    assert!(font.verify_table(&font.tables[0]));
+    assert!(!font.verify_table(&font.tables[1]));
+    assert!(!font.verify_checksum_adjustment());
+}
+
+#[test]
+fn font_parse_invalid() {
+    let file = sample_font();
+    assert!(Font::parse(&ByteCode::new(&file[..40])).is_none());
+    assert!(Font::parse(&ByteCode::new(&file[..100])).is_none());
+
+    let mut file = sample_font();
+    file[0] = 0xff;
+    assert!(Font::parse(&ByteCode::new(&file)).is_none());
+
+    // offset + length past the end of the address space
+    let mut file = sample_font();
+    file[20..28].fill(0xff);
+    assert!(Font::parse(&ByteCode::new(&file)).is_none());
+
+    let file = sample_font();
+    let font = Font::parse(&ByteCode::new(&file)).unwrap();
+    let mut record = font.tables[0];
+    record.offset = u32::MAX;
+    assert!(!font.verify_table(&record));
+}
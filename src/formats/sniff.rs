@@ -0,0 +1,185 @@
+//! File-type detection from well-known magic numbers.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::sniff::{sniff, FileType}, ByteCode};
+//!
+//! let file = std::fs::read("unknown.bin").unwrap();
+//! match sniff(&ByteCode::new(&file)) {
+//!     Some(FileType::Elf) => println!("ELF executable"),
+//!     Some(other) => println!("{:?}", other),
+//!     None => println!("unknown"),
+//! }
+//! ```
+
+use crate::ByteCode;
+
+/// A file type recognised by [`sniff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FileType {
+    /// ELF executable, object file or shared library.
+    Elf,
+    /// Windows PE executable, with an `MZ` stub pointing at a `PE` header.
+    Pe,
+    /// Mach-O executable, 32- or 64-bit, in either byte order.
+    MachO,
+    /// Mach-O universal binary.
+    MachOFat,
+    /// Java class file.
+    JavaClass,
+    /// Android Dalvik executable.
+    Dex,
+    /// WebAssembly binary module.
+    Wasm,
+    /// mruby compiled bytecode.
+    Rite,
+    /// Lua precompiled chunk.
+    Lua,
+    Png,
+    Jpeg,
+    Gif,
+    Pdf,
+    /// ZIP archive, including JAR, APK and Office documents.
+    Zip,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+    /// SQLite 3 database.
+    Sqlite,
+    /// Git pack file.
+    GitPack,
+    /// PCAP capture file.
+    Pcap,
+    /// PCAPNG capture file.
+    PcapNg,
+    /// TrueType or OpenType font.
+    Sfnt,
+    /// bsdiff patch.
+    Bsdiff,
+    /// VCDIFF patch.
+    Vcdiff,
+}
+
+/// Magic numbers found at the current position, most specific first.
+const MAGICS: &[(&[u8], FileType)] = &[
+    (b"\x7fELF", FileType::Elf),
+    (&[0xfe, 0xed, 0xfa, 0xce], FileType::MachO),
+    (&[0xce, 0xfa, 0xed, 0xfe], FileType::MachO),
+    (&[0xfe, 0xed, 0xfa, 0xcf], FileType::MachO),
+    (&[0xcf, 0xfa, 0xed, 0xfe], FileType::MachO),
+    (b"dex\n", FileType::Dex),
+    (b"\0asm", FileType::Wasm),
+    (b"RITE", FileType::Rite),
+    (b"\x1bLua", FileType::Lua),
+    (b"\x89PNG\r\n\x1a\n", FileType::Png),
+    (&[0xff, 0xd8, 0xff], FileType::Jpeg),
+    (b"GIF87a", FileType::Gif),
+    (b"GIF89a", FileType::Gif),
+    (b"%PDF-", FileType::Pdf),
+    (b"PK\x03\x04", FileType::Zip),
+    (b"PK\x05\x06", FileType::Zip),
+    (&[0x1f, 0x8b], FileType::Gzip),
+    (b"BZh", FileType::Bzip2),
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], FileType::Xz),
+    (&[0x28, 0xb5, 0x2f, 0xfd], FileType::Zstd),
+    (b"SQLite format 3\0", FileType::Sqlite),
+    (b"PACK", FileType::GitPack),
+    (&[0xa1, 0xb2, 0xc3, 0xd4], FileType::Pcap),
+    (&[0xd4, 0xc3, 0xb2, 0xa1], FileType::Pcap),
+    (&[0xa1, 0xb2, 0x3c, 0x4d], FileType::Pcap),
+    (&[0x4d, 0x3c, 0xb2, 0xa1], FileType::Pcap),
+    (&[0x0a, 0x0d, 0x0d, 0x0a], FileType::PcapNg),
+    (&[0x00, 0x01, 0x00, 0x00], FileType::Sfnt),
+    (b"OTTO", FileType::Sfnt),
+    (b"true", FileType::Sfnt),
+    (b"BSDIFF40", FileType::Bsdiff),
+    (&[0xd6, 0xc3, 0xc4, 0x00], FileType::Vcdiff),
+];
+
+/// The lowest major version of a Java class file, used to tell it apart
+/// from a Mach-O universal binary, which shares the `CAFEBABE` magic.
+const JAVA_CLASS_MIN_MAJOR: u16 = 45;
+
+/// Detects the file type from the magic number at the current position, without moving the pointer.
+///
+/// Returns `None` if no known magic number matches.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{formats::sniff::{sniff, FileType}, ByteCode};
+///
+/// let mut bytes = ByteCode::new(b"\x00\x00\x89PNG\r\n\x1a\n");
+/// assert_eq!(sniff(&bytes), None);
+/// bytes.skip(2);
+/// assert_eq!(sniff(&bytes), Some(FileType::Png));
+/// assert_eq!(bytes.pos(), 2);
+/// ```
+pub fn sniff(bytes: &ByteCode) -> Option<FileType> {
+    let inner = bytes.as_slice();
+    if inner.starts_with(&[0xca, 0xfe, 0xba, 0xbe]) {
+        let major = u16::from_be_bytes(inner.get(6..8)?.try_into().unwrap());
+        return match major >= JAVA_CLASS_MIN_MAJOR {
+            true => Some(FileType::JavaClass),
+            false => Some(FileType::MachOFat),
+        };
+    }
+    if inner.starts_with(b"MZ") {
+        return is_pe(inner).then_some(FileType::Pe);
+    }
+    MAGICS
+        .iter()
+        .find(|(magic, _)| inner.starts_with(magic))
+        .map(|(_, file_type)| *file_type)
+}
+
+/// Returns `true` if the DOS header's `e_lfanew` points at a `PE\0\0` signature.
+fn is_pe(inner: &[u8]) -> bool {
+    let Some(e_lfanew) = inner.get(0x3c..0x40) else {
+        return false;
+    };
+    let offset = u32::from_le_bytes(e_lfanew.try_into().unwrap()) as usize;
+    offset
+        .checked_add(4)
+        .and_then(|end| inner.get(offset..end))
+        .is_some_and(|signature| signature == b"PE\0\0")
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn sniff_magics() {
+    for (magic, file_type) in MAGICS {
+        let mut data = magic.to_vec();
+        data.extend([0x00; 8]);
+        assert_eq!(sniff(&ByteCode::new(&data)), Some(*file_type));
+    }
+    assert_eq!(sniff(&ByteCode::new(b"")), None);
+    assert_eq!(sniff(&ByteCode::new(b"\x7fEL")), None);
+    assert_eq!(sniff(&ByteCode::new(b"hello world")), None);
+}
+
+#[test]
+fn sniff_cafebabe() {
+    let class = [0xca, 0xfe, 0xba, 0xbe, 0x00, 0x00, 0x00, 0x34];
+    assert_eq!(sniff(&ByteCode::new(&class)), Some(FileType::JavaClass));
+    let fat = [0xca, 0xfe, 0xba, 0xbe, 0x00, 0x00, 0x00, 0x02];
+    assert_eq!(sniff(&ByteCode::new(&fat)), Some(FileType::MachOFat));
+    assert_eq!(sniff(&ByteCode::new(&fat[..6])), None);
+}
+
+#[test]
+fn sniff_pe() {
+    let mut data = vec![0x00; 0x84];
+    data[..2].copy_from_slice(b"MZ");
+    data[0x3c] = 0x80;
+    assert_eq!(sniff(&ByteCode::new(&data)), None);
+    data[0x80..].copy_from_slice(b"PE\0\0");
+    assert_eq!(sniff(&ByteCode::new(&data)), Some(FileType::Pe));
+    data[0x3c] = 0x81;
+    assert_eq!(sniff(&ByteCode::new(&data)), None);
+}
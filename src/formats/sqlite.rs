@@ -0,0 +1,536 @@
+//! SQLite database file header, b-tree pages and cells.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bytecode::{formats::sqlite::Database, ByteCode};
+//!
+//! let file = std::fs::read("app.db").unwrap();
+//! let db = Database::parse(&ByteCode::new(&file)).unwrap();
+//! let schema = db.page(1).unwrap();
+//! for cell in schema.cells().flatten() {
+//!     println!("{:?}", cell.record());
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{ByteCode, Endian, Error};
+
+const MAGIC: &[u8; 16] = b"SQLite format 3\0";
+const HEADER_SIZE: usize = 100;
+
+/// The 100-byte database header at the beginning of page 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Page size in bytes, with the special value 1 already converted into 65536.
+    pub page_size: u32,
+    pub write_version: u8,
+    pub read_version: u8,
+    pub reserved_space: u8,
+    pub max_payload_fraction: u8,
+    pub min_payload_fraction: u8,
+    pub leaf_payload_fraction: u8,
+    pub file_change_counter: u32,
+    pub database_size: u32,
+    pub first_freelist_trunk_page: u32,
+    pub freelist_page_count: u32,
+    pub schema_cookie: u32,
+    pub schema_format: u32,
+    pub default_cache_size: u32,
+    pub largest_root_page: u32,
+    /// 1 for UTF-8, 2 for UTF-16le and 3 for UTF-16be.
+    pub text_encoding: u32,
+    pub user_version: u32,
+    pub incremental_vacuum: u32,
+    pub application_id: u32,
+    pub version_valid_for: u32,
+    pub sqlite_version: u32,
+}
+
+impl Header {
+    /// Parses the database header from the beginning of the slice.
+    /// Moves the pointer forward 100.
+    ///
+    /// Returns `None` without moving the pointer if the magic string is missing,
+    /// the header is truncated, the page size is not a power of two between 512 and 65536,
+    /// or the space reserved for expansion is not zero in [strict](crate::Strictness::Strict) mode.
+    pub fn parse(bytes: &mut ByteCode) -> Option<Header> {
+        if bytes.as_slice().len() < HEADER_SIZE || !bytes.starts_with(MAGIC) {
+            return None;
+        }
+        let page_size = match Endian::Big.read_uint(&bytes[16..18]) as u32 {
+            1 => 65536,
+            size if size >= 512 && size.is_power_of_two() => size,
+            _ => return None,
+        };
+
+        if bytes[72..92].iter().any(|byte| *byte != 0) {
+            bytes
+                .violation(Error::NonZeroPadding {
+                    pos: bytes.pos() + 72,
+                    len: 20,
+                })
+                .ok()?;
+        }
+
+        bytes.skip(18);
+        let header = Header {
+            page_size,
+            write_version: bytes.take_into_u8(),
+            read_version: bytes.take_into_u8(),
+            reserved_space: bytes.take_into_u8(),
+            max_payload_fraction: bytes.take_into_u8(),
+            min_payload_fraction: bytes.take_into_u8(),
+            leaf_payload_fraction: bytes.take_into_u8(),
+            file_change_counter: bytes.take_be_u32(),
+            database_size: bytes.take_be_u32(),
+            first_freelist_trunk_page: bytes.take_be_u32(),
+            freelist_page_count: bytes.take_be_u32(),
+            schema_cookie: bytes.take_be_u32(),
+            schema_format: bytes.take_be_u32(),
+            default_cache_size: bytes.take_be_u32(),
+            largest_root_page: bytes.take_be_u32(),
+            text_encoding: bytes.take_be_u32(),
+            user_version: bytes.take_be_u32(),
+            incremental_vacuum: bytes.take_be_u32(),
+            application_id: bytes.take_be_u32(),
+            version_valid_for: {
+                bytes.skip(20); // reserved for expansion, checked above
+                bytes.take_be_u32()
+            },
+            sqlite_version: bytes.take_be_u32(),
+        };
+        Some(header)
+    }
+
+    /// Returns the number of usable bytes in each page, excluding the reserved space.
+    pub fn usable_size(&self) -> usize {
+        self.page_size as usize - self.reserved_space as usize
+    }
+}
+
+/// A SQLite database file.
+#[derive(Debug)]
+pub struct Database<'a> {
+    pub header: Header,
+    data: &'a [u8],
+}
+
+impl<'a> Database<'a> {
+    /// Parses the database header of a file starting at the current position. The pointer is not moved.
+    pub fn parse(bytes: &ByteCode<'a>) -> Option<Database<'a>> {
+        let data = bytes.as_slice();
+        let header = Header::parse(&mut ByteCode::new(data))?;
+        Some(Database { header, data })
+    }
+
+    /// Returns the number of pages present in the file.
+    pub fn page_count(&self) -> u32 {
+        (self.data.len() / self.header.page_size as usize) as u32
+    }
+
+    /// Returns the b-tree page with the given 1-based page number.
+    ///
+    /// Returns `None` if the page is out of range or is not a b-tree page.
+    pub fn page(&self, number: u32) -> Option<Page<'a>> {
+        let page_size = self.header.page_size as usize;
+        let start = (number as usize).checked_sub(1)?.checked_mul(page_size)?;
+        let data = self.data.get(start..start.checked_add(page_size)?)?;
+        Page::parse(data, number, self.header.usable_size())
+    }
+}
+
+/// The type of a b-tree page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageType {
+    InteriorIndex,
+    InteriorTable,
+    LeafIndex,
+    LeafTable,
+}
+
+impl PageType {
+    fn from_u8(flag: u8) -> Option<PageType> {
+        match flag {
+            0x02 => Some(PageType::InteriorIndex),
+            0x05 => Some(PageType::InteriorTable),
+            0x0a => Some(PageType::LeafIndex),
+            0x0d => Some(PageType::LeafTable),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` for interior pages.
+    pub fn is_interior(self) -> bool {
+        matches!(self, PageType::InteriorIndex | PageType::InteriorTable)
+    }
+}
+
+/// A b-tree page.
+#[derive(Debug, Clone)]
+pub struct Page<'a> {
+    pub number: u32,
+    pub page_type: PageType,
+    pub first_freeblock: u16,
+    pub cell_count: u16,
+    pub cell_content_start: u32,
+    pub fragmented_free_bytes: u8,
+    /// Only present on interior pages.
+    pub right_most_pointer: Option<u32>,
+    /// Offsets of the cells from the beginning of the page.
+    pub cell_pointers: Vec<u16>,
+    data: &'a [u8],
+    usable_size: usize,
+}
+
+impl<'a> Page<'a> {
+    fn parse(data: &'a [u8], number: u32, usable_size: usize) -> Option<Page<'a>> {
+        let header_offset = if number == 1 { HEADER_SIZE } else { 0 };
+        let mut bytes = ByteCode::new(data.get(header_offset..)?);
+        if bytes.as_slice().len() < 12 {
+            return None;
+        }
+        let page_type = PageType::from_u8(bytes.take_into_u8())?;
+        let first_freeblock = bytes.take_be_u16();
+        let cell_count = bytes.take_be_u16();
+        let cell_content_start = match bytes.take_be_u16() {
+            0 => 65536,
+            start => start as u32,
+        };
+        let fragmented_free_bytes = bytes.take_into_u8();
+        let right_most_pointer = page_type.is_interior().then(|| bytes.take_be_u32());
+        if bytes.as_slice().len() < cell_count as usize * 2 {
+            return None;
+        }
+        let cell_pointers = (0..cell_count).map(|_| bytes.take_be_u16()).collect();
+        Some(Page {
+            number,
+            page_type,
+            first_freeblock,
+            cell_count,
+            cell_content_start,
+            fragmented_free_bytes,
+            right_most_pointer,
+            cell_pointers,
+            data,
+            usable_size,
+        })
+    }
+
+    /// Returns the cell at the given index.
+    ///
+    /// Returns `None` if the index is out of range or the cell is malformed.
+    pub fn cell(&self, index: usize) -> Option<Cell<'a>> {
+        let offset = *self.cell_pointers.get(index)? as usize;
+        let mut bytes = ByteCode::new(self.data.get(offset..)?);
+        let left_child = if self.page_type.is_interior() {
+            if bytes.as_slice().len() < 4 {
+                return None;
+            }
+            Some(bytes.take_be_u32())
+        } else {
+            None
+        };
+        if self.page_type == PageType::InteriorTable {
+            let rowid = take_varint(&mut bytes)? as i64;
+            return Some(Cell {
+                left_child,
+                rowid: Some(rowid),
+                payload_size: 0,
+                payload: &[],
+                overflow_page: None,
+            });
+        }
+
+        let payload_size = take_varint(&mut bytes)?;
+        let rowid = match self.page_type {
+            PageType::LeafTable => Some(take_varint(&mut bytes)? as i64),
+            _ => None,
+        };
+        let local = self.local_payload_size(payload_size);
+        let remaining = bytes.as_slice();
+        let payload = remaining.get(..local)?;
+        let overflow_page = if (local as u64) < payload_size {
+            let pointer = remaining.get(local..local + 4)?;
+            Some(Endian::Big.read_uint(pointer) as u32)
+        } else {
+            None
+        };
+        Some(Cell {
+            left_child,
+            rowid,
+            payload_size,
+            payload,
+            overflow_page,
+        })
+    }
+
+    /// Returns an iterator over the cells of the page.
+    /// Malformed cells are yielded as `None`.
+    pub fn cells(&self) -> impl Iterator<Item = Option<Cell<'a>>> + '_ {
+        (0..self.cell_pointers.len()).map(move |index| self.cell(index))
+    }
+
+    fn local_payload_size(&self, payload_size: u64) -> usize {
+        let usable = self.usable_size as u64;
+        let max_local = match self.page_type {
+            PageType::LeafTable => usable - 35,
+            _ => (usable - 12) * 64 / 255 - 23,
+        };
+        if payload_size <= max_local {
+            return payload_size as usize;
+        }
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let local = min_local + (payload_size - min_local) % (usable - 4);
+        if local <= max_local {
+            local as usize
+        } else {
+            min_local as usize
+        }
+    }
+}
+
+/// A cell of a b-tree page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell<'a> {
+    /// Only present on interior pages.
+    pub left_child: Option<u32>,
+    /// Only present on table pages.
+    pub rowid: Option<i64>,
+    /// Total payload size, including the part stored in overflow pages.
+    pub payload_size: u64,
+    /// The part of the payload stored on this page.
+    pub payload: &'a [u8],
+    pub overflow_page: Option<u32>,
+}
+
+impl<'a> Cell<'a> {
+    /// Decodes the payload as a record.
+    ///
+    /// Returns `None` if the payload spills into overflow pages or is malformed.
+    pub fn record(&self) -> Option<Vec<Value<'a>>> {
+        if self.overflow_page.is_some() {
+            return None;
+        }
+        parse_record(self.payload)
+    }
+}
+
+/// A value of a record column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Blob(&'a [u8]),
+    /// Text in the database text encoding.
+    Text(&'a [u8]),
+}
+
+/// Decodes a record consisting of a header of varint serial types followed by the column values.
+///
+/// Returns `None` if the record is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::formats::sqlite::{parse_record, Value};
+///
+/// let record = parse_record(&[0x03, 0x01, 0x13, 0x2a, 0x66, 0x6f, 0x6f]).unwrap();
+/// assert_eq!(record, [Value::Integer(42), Value::Text(b"foo")]);
+/// ```
+pub fn parse_record(payload: &[u8]) -> Option<Vec<Value<'_>>> {
+    let mut header = ByteCode::new(payload);
+    let header_size = usize::try_from(take_varint(&mut header)?).ok()?;
+    let mut header = ByteCode::new(payload.get(header.pos()..header_size)?);
+    let mut body = ByteCode::new(&payload[header_size..]);
+
+    let mut values = Vec::new();
+    while !header.is_end() {
+        let serial_type = take_varint(&mut header)?;
+        let size = match serial_type {
+            0 | 8 | 9 => 0,
+            1..=4 => serial_type as usize,
+            5 => 6,
+            6 | 7 => 8,
+            10 | 11 => return None,
+            _ => ((serial_type - 12) / 2) as usize,
+        };
+        if body.as_slice().len() < size {
+            return None;
+        }
+        let inner = body.as_slice();
+        let data = &inner[..size];
+        body.skip(size);
+        let value = match serial_type {
+            0 => Value::Null,
+            1..=6 => {
+                let shift = 64 - size * 8;
+                Value::Integer(((Endian::Big.read_uint(data) << shift) as i64) >> shift)
+            }
+            7 => Value::Float(f64::from_bits(Endian::Big.read_uint(data))),
+            8 => Value::Integer(0),
+            9 => Value::Integer(1),
+            _ if serial_type % 2 == 0 => Value::Blob(data),
+            _ => Value::Text(data),
+        };
+        values.push(value);
+    }
+    Some(values)
+}
+
+/// Returns the SQLite variable-length integer at the beginning of the slice.
+/// Moves the pointer forward by its length of 1 to 9 bytes.
+///
+/// Returns `None` without moving the pointer if the varint is truncated.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{formats::sqlite::take_varint, ByteCode};
+///
+/// let mut bytes = ByteCode::new(&[0x81, 0x00, 0x7f]);
+/// assert_eq!(take_varint(&mut bytes), Some(128));
+/// assert_eq!(take_varint(&mut bytes), Some(127));
+/// ```
+pub fn take_varint(bytes: &mut ByteCode) -> Option<u64> {
+    let mut value = 0;
+    for (i, byte) in bytes.as_slice().iter().take(9).enumerate() {
+        if i == 8 {
+            bytes.skip(9);
+            return Some((value << 8) | *byte as u64);
+        }
+        value = (value << 7) | (*byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            bytes.skip(i + 1);
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[cfg(test)]
+fn sample_database() -> Vec<u8> {
+    let mut file = vec![0; 1024];
+    file[..16].copy_from_slice(MAGIC);
+    file[16..18].copy_from_slice(&512u16.to_be_bytes());
+    file[18..24].copy_from_slice(&[1, 1, 0, 64, 32, 32]);
+    file[28..32].copy_from_slice(&2u32.to_be_bytes());
+    file[56..60].copy_from_slice(&1u32.to_be_bytes());
+
+    // Page 1: empty leaf table page
+    file[100] = 0x0d;
+    file[105..107].copy_from_slice(&512u16.to_be_bytes());
+
+    // Page 2: leaf table page with a single cell
+    let cell = [0x07, 0x07, 0x03, 0x01, 0x13, 0x2a, 0x66, 0x6f, 0x6f];
+    let offset = 512 - cell.len();
+    file[512] = 0x0d;
+    file[515..517].copy_from_slice(&1u16.to_be_bytes());
+    file[517..519].copy_from_slice(&(offset as u16).to_be_bytes());
+    file[520..522].copy_from_slice(&(offset as u16).to_be_bytes());
+    file[512 + offset..].copy_from_slice(&cell);
+    file
+}
+
+#[test]
+fn header_parse() {
+    let file = sample_database();
+    let mut bytes = ByteCode::new(&file);
+    let header = Header::parse(&mut bytes).unwrap();
+    assert_eq!(bytes.pos(), 100);
+    assert_eq!(header.page_size, 512);
+    assert_eq!(header.database_size, 2);
+    assert_eq!(header.text_encoding, 1);
+    assert_eq!(header.usable_size(), 512);
+}
+
+#[test]
+fn header_parse_invalid() {
+    let mut file = sample_database();
+    file[17] = 0x01;
+    assert!(Header::parse(&mut ByteCode::new(&file)).is_none());
+    assert!(Header::parse(&mut ByteCode::new(&file[1..])).is_none());
+}
+
+#[test]
+fn header_parse_reserved_not_zero() {
+    let mut file = sample_database();
+    file[80] = 0x01;
+    let mut bytes = ByteCode::new(&file);
+    assert!(Header::parse(&mut bytes).is_some());
+    assert_eq!(
+        bytes.diagnostics(),
+        [Error::NonZeroPadding { pos: 72, len: 20 }.into()]
+    );
+
+    let mut bytes = ByteCode::new(&file);
+    bytes.set_strictness(crate::Strictness::Strict);
+    assert!(Header::parse(&mut bytes).is_none());
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+fn database_pages() {
+    let file = sample_database();
+    let db = Database::parse(&ByteCode::new(&file)).unwrap();
+    assert_eq!(db.page_count(), 2);
+
+    let schema = db.page(1).unwrap();
+    assert_eq!(schema.page_type, PageType::LeafTable);
+    assert_eq!(schema.cell_count, 0);
+
+    let page = db.page(2).unwrap();
+    assert_eq!(page.cell_count, 1);
+    let cell = page.cell(0).unwrap();
+    assert_eq!(cell.rowid, Some(7));
+    assert_eq!(cell.payload_size, 7);
+    assert_eq!(cell.overflow_page, None);
+    assert_eq!(
+        cell.record().unwrap(),
+        [Value::Integer(42), Value::Text(b"foo")]
+    );
+
+    assert!(db.page(0).is_none());
+    assert!(db.page(3).is_none());
+    assert!(db.page(u32::MAX).is_none());
+}
+
+#[test]
+fn local_payload_size() {
+    let file = sample_database();
+    let db = Database::parse(&ByteCode::new(&file)).unwrap();
+    let page = db.page(2).unwrap();
+    assert_eq!(page.local_payload_size(477), 477);
+    assert_eq!(page.local_payload_size(600), 92);
+    assert_eq!(page.local_payload_size(1000), 39);
+}
+
+#[test]
+fn parse_record_integers() {
+    let record = parse_record(&[0x05, 0x01, 0x02, 0x08, 0x00, 0xff, 0x01, 0x00]).unwrap();
+    assert_eq!(
+        record,
+        [
+            Value::Integer(-1),
+            Value::Integer(256),
+            Value::Integer(0),
+            Value::Null
+        ]
+    );
+}
+
+#[test]
+fn take_varint_nine_bytes() {
+    let mut bytes = ByteCode::new(&[0xff; 9]);
+    assert_eq!(take_varint(&mut bytes), Some(u64::MAX));
+    assert!(bytes.is_end());
+
+    let mut bytes = ByteCode::new(&[0x81, 0x81]);
+    assert_eq!(take_varint(&mut bytes), None);
+    assert_eq!(bytes.pos(), 0);
+}
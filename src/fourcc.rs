@@ -0,0 +1,105 @@
+use core::fmt::{Display, Formatter, Result};
+
+use crate::ByteCode;
+
+/// A four-character code, as used to identify chunks in RIFF, AIFF, MP4 and many other formats.
+///
+/// Displayed as ASCII, with non-printable bytes escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FourCc(pub [u8; 4]);
+
+impl FourCc {
+    /// Returns the four bytes of the code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::FourCc;
+    ///
+    /// assert_eq!(FourCc(*b"RIFF").as_bytes(), b"RIFF");
+    /// ```
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+}
+
+impl Display for FourCc {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for byte in self.0 {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                write!(f, "{}", byte as char)?;
+            } else {
+                write!(f, "\\x{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq<[u8; 4]> for FourCc {
+    fn eq(&self, other: &[u8; 4]) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<&[u8; 4]> for FourCc {
+    fn eq(&self, other: &&[u8; 4]) -> bool {
+        &self.0 == *other
+    }
+}
+
+impl PartialEq<str> for FourCc {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for FourCc {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl From<[u8; 4]> for FourCc {
+    fn from(bytes: [u8; 4]) -> Self {
+        FourCc(bytes)
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns the first 4 elements of the slice as a four-character code.
+    /// Moves the pointer forward 4.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x52, 0x49, 0x46, 0x46, 0x00, 0x00, 0x00, 0x00]);
+    /// let fourcc = bytes.take_fourcc();
+    /// assert_eq!(fourcc, "RIFF");
+    /// assert_eq!(fourcc.to_string(), "RIFF");
+    /// ```
+    pub fn take_fourcc(&mut self) -> FourCc {
+        FourCc(self.take_array())
+    }
+}
+
+#[cfg(test)]
+use alloc::string::ToString;
+
+#[test]
+fn take_fourcc() {
+    let mut bytes = ByteCode::new(&[0x66, 0x6d, 0x74, 0x20, 0x00, 0x00, 0x00, 0x00]);
+    let fourcc = bytes.take_fourcc();
+    assert_eq!(fourcc, b"fmt ");
+    assert_eq!(fourcc, "fmt ");
+    assert_ne!(fourcc, "fmt");
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+fn fourcc_display() {
+    assert_eq!(FourCc(*b"WAVE").to_string(), "WAVE");
+    assert_eq!(FourCc([0x00, 0x61, 0x73, 0x6d]).to_string(), "\\x00asm");
+}
@@ -0,0 +1,172 @@
+use alloc::format;
+use core::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::{scan::find_byte, ByteCode, Error, HexDump};
+
+/// A match found by [`ByteCode::grep`], with a hex dump of the bytes around it.
+///
+/// The [`Display`] output is a line with the position of the match followed by the dump.
+#[derive(Debug, Clone)]
+pub struct GrepMatch<'a> {
+    /// The absolute positions of the matched bytes.
+    pub range: Range<usize>,
+    /// A dump of the rows holding the match and its context, with the match highlighted.
+    pub context: HexDump<'a>,
+}
+
+impl Display for GrepMatch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "match at {:#010x}:", self.range.start)?;
+        write!(f, "{}", self.context)
+    }
+}
+
+/// An iterator over the matches of a masked pattern.
+///
+/// This struct is created by [`ByteCode::grep`].
+pub struct Grep<'a, 'p> {
+    dump: HexDump<'a>,
+    pos: usize,
+    pattern: &'p [u8],
+    mask: &'p [u8],
+    context: usize,
+}
+
+impl<'a> Iterator for Grep<'a, '_> {
+    type Item = GrepMatch<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.dump.bytes();
+        let len = self.pattern.len();
+        if len == 0 {
+            return None;
+        }
+        while bytes.len() - self.pos >= len {
+            // Jump between occurrences of the first byte when it has to match exactly.
+            let start = match self.mask[0] {
+                0xff => self.pos + find_byte(&bytes[self.pos..], self.pattern[0])?,
+                _ => self.pos,
+            };
+            let Some(candidate) = bytes.get(start..start + len) else {
+                self.pos = bytes.len();
+                return None;
+            };
+            let found = candidate
+                .iter()
+                .zip(self.pattern)
+                .zip(self.mask)
+                .all(|((byte, pattern), mask)| byte & mask == pattern & mask);
+            if found {
+                let range = start..start + len;
+                self.pos = range.end;
+                let context = self
+                    .dump
+                    .clone()
+                    .range(
+                        start.saturating_sub(self.context)..range.end.saturating_add(self.context),
+                    )
+                    .highlight(range.clone());
+                return Some(GrepMatch { range, context });
+            }
+            self.pos = start + 1;
+        }
+        None
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns an iterator over the non-overlapping matches of `pattern` in the remaining slice,
+    /// comparing only the bits set in `mask`, as [`matches_at`](ByteCode::matches_at) does.
+    /// The pointer is not moved.
+    ///
+    /// Each match comes with a hex dump of the rows holding it and `context` bytes on either side,
+    /// so that search tools can print the matches directly. An empty `pattern` matches nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` and `mask` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let data = b"....\x7fELF\x02........\x7fELF\x01....";
+    /// let bytes = ByteCode::new(data);
+    /// let matches: Vec<_> = bytes
+    ///     .grep(b"\x7fELF\x00", &[0xff, 0xff, 0xff, 0xff, 0x00], 1)
+    ///     .collect();
+    /// assert_eq!(matches.len(), 2);
+    /// assert_eq!(matches[1].range, 17..22);
+    /// assert_eq!(
+    ///     matches[1].to_string(),
+    ///     "match at 0x00000011:\n\
+    ///      \x20        00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n\
+    ///      00000010 2E 7F 45 4C 46 01 2E 2E 2E 2E                    |..ELF.....|\n"
+    /// );
+    /// ```
+    pub fn grep<'p>(&self, pattern: &'p [u8], mask: &'p [u8], context: usize) -> Grep<'a, 'p> {
+        if pattern.len() != mask.len() {
+            self.fail(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!(
+                    "pattern length {} does not match mask length {}",
+                    pattern.len(),
+                    mask.len()
+                ),
+            });
+        }
+        Grep {
+            dump: self.hexdump(),
+            pos: self.pos,
+            pattern,
+            mask,
+            context,
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::{string::ToString, vec::Vec};
+
+#[test]
+fn grep() {
+    let data = [0x12, 0x34, 0x12, 0x34, 0x56, 0x10, 0x34, 0x12];
+    let mut bytes = ByteCode::new(&data);
+    bytes.skip(1);
+    let ranges = |pattern: &[u8], mask: &[u8]| {
+        bytes
+            .grep(pattern, mask, 0)
+            .map(|found| (found.range.start, found.range.end))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ranges(&[0x12, 0x34], &[0xff, 0xff]), [(2, 4)]);
+    assert_eq!(ranges(&[0x10, 0x34], &[0xf0, 0xff]), [(2, 4), (5, 7)]);
+    assert_eq!(ranges(&[0x34, 0x12, 0x34], &[0xff, 0x00, 0xff]), [(1, 4)]);
+    assert_eq!(
+        ranges(&[0x00, 0x00], &[0x00, 0x00]),
+        [(1, 3), (3, 5), (5, 7)]
+    );
+    assert_eq!(ranges(&[0x12], &[0xff]), [(2, 3), (7, 8)]);
+    assert!(ranges(&[], &[]).is_empty());
+    assert!(ranges(&[0x12; 9], &[0xff; 9]).is_empty());
+    assert_eq!(bytes.pos(), 1);
+}
+
+#[test]
+fn grep_context() {
+    let data = [0u8; 80];
+    let bytes = ByteCode::new(&data);
+    let found = bytes.grep(&[0], &[0xff], 16).nth(40).unwrap();
+    assert_eq!(found.range, 40..41);
+    assert_eq!(found.context.to_string().lines().count(), 4);
+}
+
+#[test]
+#[should_panic]
+fn grep_mask_length() {
+    ByteCode::new(&[0]).grep(&[0], &[], 0);
+}
@@ -0,0 +1,489 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+#[cfg(feature = "std")]
+use tiny_ansi::TinyAnsi;
+
+use crate::ByteCode;
+
+/// An ANSI color. Colors can only be turned on with the `std` feature.
+#[derive(Clone, Copy)]
+pub(crate) enum Color {
+    Cyan,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Red,
+}
+
+impl Color {
+    pub(crate) fn paint(self, text: &str) -> String {
+        #[cfg(feature = "std")]
+        return match self {
+            Color::Cyan => text.cyan(),
+            Color::Green => text.green(),
+            Color::Yellow => text.yellow(),
+            Color::Blue => text.blue(),
+            Color::Magenta => text.magenta(),
+            Color::Red => text.red(),
+        };
+        #[cfg(not(feature = "std"))]
+        text.to_owned()
+    }
+}
+
+const LABEL_COLORS: [Color; 3] = [Color::Blue, Color::Magenta, Color::Red];
+
+const HEADER: &str = "         00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F";
+
+/// A configurable hex dump of a [`ByteCode`], created by [`hexdump`](ByteCode::hexdump).
+///
+/// Rows hold 16 bytes and are labelled with absolute positions.
+/// With color on, the byte at the pointer is green, highlighted bytes are yellow
+/// and labelled bytes are blue, magenta or red.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCode;
+///
+/// let mut bytes = ByteCode::new(b"\x7fELF\x02\x01\x01\x00");
+/// bytes.skip(4);
+/// let dump = bytes.hexdump().to_string();
+/// assert_eq!(
+///     dump.lines().nth(1).unwrap(),
+///     "00000000 7F 45 4C 46 02 01 01 00                          |.ELF....|"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    bounds: Option<Range<usize>>,
+    ascii: bool,
+    color: bool,
+    highlights: Vec<Range<usize>>,
+    labels: Vec<(Range<usize>, String)>,
+}
+
+impl<'a> HexDump<'a> {
+    /// Limits the dump to the rows within `num` bytes of the pointer on either side,
+    /// instead of the whole slice.
+    pub fn window(mut self, num: usize) -> Self {
+        self.bounds =
+            Some(self.pos.saturating_sub(num)..self.pos.saturating_add(num).saturating_add(1));
+        self
+    }
+
+    /// Limits the dump to the rows holding the bytes in `range`, given as absolute positions,
+    /// instead of the whole slice.
+    pub fn range(mut self, range: Range<usize>) -> Self {
+        self.bounds = Some(range);
+        self
+    }
+
+    /// Sets whether a column with the printable ASCII characters of each row is shown. On by default.
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Sets whether ANSI colors are used. Off by default, so the dump can be written to files.
+    #[cfg(feature = "std")]
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Highlights the bytes in `range`, given as absolute positions. Shown only with color on.
+    pub fn highlight(mut self, range: Range<usize>) -> Self {
+        self.highlights.push(range);
+        self
+    }
+
+    /// Labels the bytes in `range`, given as absolute positions.
+    /// The label is shown at the end of the row where the range starts, and with color on,
+    /// labelled ranges are colored in turn.
+    pub fn label(mut self, range: Range<usize>, label: &str) -> Self {
+        self.labels.push((range, label.to_owned()));
+        self
+    }
+
+    /// Writes the dump to `writer`.
+    ///
+    /// Use [`write!`] with the [`Display`] impl to write to a [`fmt::Write`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0; 64]);
+    /// let mut out = Vec::new();
+    /// bytes.hexdump().window(8).write_to(&mut out).unwrap();
+    /// assert_eq!(out.iter().filter(|byte| **byte == b'\n').count(), 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        write!(writer, "{}", self)
+    }
+
+    fn rows(&self) -> Range<usize> {
+        let (start, end) = match &self.bounds {
+            Some(bounds) => (bounds.start, bounds.end.min(self.bytes.len())),
+            None => (0, self.bytes.len()),
+        };
+        start / 16..end.div_ceil(16)
+    }
+
+    /// Returns the absolute positions of the bytes in the rows shown.
+    pub(crate) fn byte_range(&self) -> Range<usize> {
+        let rows = self.rows();
+        rows.start * 16..(rows.end * 16).min(self.bytes.len())
+    }
+
+    pub(crate) fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub(crate) fn shows_ascii(&self) -> bool {
+        self.ascii
+    }
+
+    /// Returns the labels whose ranges start within `range`, with their start positions.
+    pub(crate) fn labels_starting_in(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (usize, &str)> {
+        self.labels
+            .iter()
+            .filter(move |(label, _)| range.contains(&label.start))
+            .map(|(label, text)| (label.start, text.as_str()))
+    }
+
+    /// Returns the byte at `pos` in hex, colored if color is on.
+    pub(crate) fn paint_byte(&self, pos: usize) -> String {
+        let hex = format!("{:02X}", self.bytes[pos]);
+        if !self.color {
+            return hex;
+        }
+        match self.mark(pos) {
+            Some(Mark::Pointer) => Color::Green.paint(&hex),
+            Some(Mark::Highlight) => Color::Yellow.paint(&hex),
+            Some(Mark::Label(i)) => LABEL_COLORS[i % LABEL_COLORS.len()].paint(&hex),
+            None => hex,
+        }
+    }
+
+    /// Returns how the byte at `pos` stands out, with the pointer taking precedence over highlights
+    /// and highlights over labels.
+    fn mark(&self, pos: usize) -> Option<Mark> {
+        if pos == self.pos {
+            return Some(Mark::Pointer);
+        }
+        if self.highlights.iter().any(|range| range.contains(&pos)) {
+            return Some(Mark::Highlight);
+        }
+        self.labels
+            .iter()
+            .position(|(range, _)| range.contains(&pos))
+            .map(Mark::Label)
+    }
+
+    /// Renders the dump as an HTML `<pre class="hexdump">` element, for embedding in web pages.
+    ///
+    /// The layout is that of the [`Display`] output without color. Instead, the byte at the pointer,
+    /// highlighted bytes and labelled bytes are wrapped in `<span>`s with the classes `pointer`,
+    /// `highlight` and `label-0` to `label-2` in turn, to be styled with CSS.
+    /// The ASCII column and labels are escaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(b"<a&b>");
+    /// let html = bytes.hexdump().label(1..2, "<tag>").to_html();
+    /// assert!(html.starts_with("<pre class=\"hexdump\">\n"));
+    /// assert!(html.contains("00000000 <span class=\"pointer\">3C</span> <span class=\"label-0\">61</span> 26"));
+    /// assert!(html.contains("|&lt;a&amp;b&gt;|  &lt;tag&gt;\n"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = format!("<pre class=\"hexdump\">\n{}\n", HEADER);
+        for row in self.rows() {
+            let start = row * 16;
+            let line = &self.bytes[start..(start + 16).min(self.bytes.len())];
+            html.push_str(&format!("{:08X}", start));
+            for (pos, byte) in (start..).zip(line) {
+                let hex = format!("{:02X}", byte);
+                html.push(' ');
+                match self.mark(pos) {
+                    Some(mark) => {
+                        html.push_str(&format!("<span class=\"{}\">{}</span>", mark.class(), hex))
+                    }
+                    None => html.push_str(&hex),
+                }
+            }
+            html.push_str(&escape_html(&self.row_tail(row, line)));
+            html.push('\n');
+        }
+        html.push_str("</pre>\n");
+        html
+    }
+
+    /// Returns the rest of row `row` after its bytes `line`: the ASCII column and the labels
+    /// of the ranges that start in the row, padded to line up with full rows.
+    fn row_tail(&self, row: usize, line: &[u8]) -> String {
+        let mut tail = String::new();
+        let padding = "   ".repeat(16 - line.len());
+        if self.ascii {
+            tail.push_str(&format!("{}  |{}|", padding, printable(line)));
+        }
+        let labels: Vec<&str> = self
+            .labels
+            .iter()
+            .filter(|(range, _)| range.start / 16 == row)
+            .map(|(_, label)| label.as_str())
+            .collect();
+        if !labels.is_empty() {
+            if !self.ascii {
+                tail.push_str(&padding);
+            }
+            tail.push_str(&format!("  {}", labels.join(", ")));
+        }
+        tail
+    }
+}
+
+/// How a byte stands out in a [`HexDump`].
+#[derive(Clone, Copy)]
+enum Mark {
+    Pointer,
+    Highlight,
+    /// Labelled by the label with this index.
+    Label(usize),
+}
+
+impl Mark {
+    fn class(self) -> String {
+        match self {
+            Mark::Pointer => "pointer".to_owned(),
+            Mark::Highlight => "highlight".to_owned(),
+            Mark::Label(i) => format!("label-{}", i % LABEL_COLORS.len()),
+        }
+    }
+}
+
+/// Escapes the characters of `text` that are special in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the printable ASCII characters of `bytes`, with other bytes as dots.
+pub(crate) fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| match byte {
+            0x20..=0x7e => *byte as char,
+            _ => '.',
+        })
+        .collect()
+}
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.color {
+            true => writeln!(f, "{}", Color::Cyan.paint(HEADER))?,
+            false => writeln!(f, "{}", HEADER)?,
+        }
+        for row in self.rows() {
+            let start = row * 16;
+            let line = &self.bytes[start..(start + 16).min(self.bytes.len())];
+            write!(f, "{:08X}", start)?;
+            // Rows are written as they are formatted, so that dumping a large input,
+            // such as a memory-mapped file, does not allocate more than a row at a time.
+            for (pos, byte) in (start..).zip(line) {
+                match self.color {
+                    true => write!(f, " {}", self.paint_byte(pos))?,
+                    false => write!(f, " {:02X}", byte)?,
+                }
+            }
+            writeln!(f, "{}", self.row_tail(row, line))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns a hex dump of the whole slice, which can be limited to a window around the pointer,
+    /// and then displayed or written out.
+    ///
+    /// The [`Debug`](std::fmt::Debug) output is this dump in color, without the ASCII column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let data: Vec<u8> = (0..=255).collect();
+    /// let mut bytes = ByteCode::new(&data);
+    /// bytes.skip(0x80);
+    /// println!("{}", bytes.hexdump().window(32).highlight(0x80..0x84));
+    /// ```
+    pub fn hexdump(&self) -> HexDump<'a> {
+        HexDump {
+            bytes: self.original(),
+            pos: self.pos,
+            bounds: None,
+            ascii: true,
+            color: false,
+            highlights: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::string::ToString;
+
+#[test]
+fn hexdump() {
+    let data: Vec<u8> = (0x30..0x52).collect();
+    let mut bytes = ByteCode::new(&data);
+    bytes.skip(17);
+    assert_eq!(
+        bytes.hexdump().to_string(),
+        "         00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n\
+         00000000 30 31 32 33 34 35 36 37 38 39 3A 3B 3C 3D 3E 3F  |0123456789:;<=>?|\n\
+         00000010 40 41 42 43 44 45 46 47 48 49 4A 4B 4C 4D 4E 4F  |@ABCDEFGHIJKLMNO|\n\
+         00000020 50 51                                            |PQ|\n"
+    );
+    assert_eq!(
+        bytes.hexdump().ascii(false).window(1).to_string(),
+        "         00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n\
+         00000010 40 41 42 43 44 45 46 47 48 49 4A 4B 4C 4D 4E 4F\n"
+    );
+    assert_eq!(bytes.hexdump().window(2).to_string().lines().count(), 3);
+}
+
+#[test]
+fn hexdump_window_at_end() {
+    let mut bytes = ByteCode::new(&[0; 40]);
+    bytes.skip(40);
+    assert_eq!(bytes.hexdump().window(0).to_string().lines().count(), 2);
+    assert_eq!(bytes.hexdump().window(10).to_string().lines().count(), 3);
+    assert_eq!(
+        bytes
+            .hexdump()
+            .window(usize::MAX)
+            .to_string()
+            .lines()
+            .count(),
+        4
+    );
+    assert_eq!(ByteCode::new(&[]).hexdump().to_string().lines().count(), 1);
+}
+
+#[test]
+fn hexdump_range() {
+    let bytes = ByteCode::new(&[0; 64]);
+    let rows = |dump: HexDump| {
+        dump.to_string()
+            .lines()
+            .skip(1)
+            .map(|line| line[..8].to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        rows(bytes.hexdump().range(15..33)),
+        ["00000000", "00000010", "00000020"]
+    );
+    assert_eq!(rows(bytes.hexdump().range(16..17)), ["00000010"]);
+    assert_eq!(rows(bytes.hexdump().range(60..100)), ["00000030"]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn hexdump_color() {
+    let mut bytes = ByteCode::new(&[0xaa, 0xbb, 0xcc, 0xdd]);
+    bytes.skip(1);
+    let plain = bytes.hexdump().highlight(2..4).to_string();
+    assert!(!plain.contains('\x1b'));
+    let colored = bytes.hexdump().highlight(2..4).color(true).to_string();
+    assert!(colored.contains(&"BB".green()));
+    assert!(colored.contains(&"CC".yellow()));
+    assert!(colored.contains(&"DD".yellow()));
+    assert!(!colored.contains(&"AA".yellow()));
+}
+
+#[test]
+fn hexdump_labels() {
+    let bytes = ByteCode::new(&[0; 20]);
+    let dump = bytes
+        .hexdump()
+        .label(0..4, "magic")
+        .label(2..18, "body")
+        .label(18..20, "crc");
+    assert_eq!(
+        dump.to_string().lines().skip(1).collect::<Vec<_>>(),
+        [
+            "00000000 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  |................|  magic, body",
+            "00000010 00 00 00 00                                      |....|  crc",
+        ]
+    );
+    #[cfg(feature = "std")]
+    {
+        let colored = dump.color(true).to_string();
+        assert!(colored.contains(&format!("{} {}", "00".green(), "00".blue())));
+        assert!(colored.contains(&"00".magenta()));
+        assert!(colored.contains(&"00".red()));
+    }
+}
+
+#[test]
+fn hexdump_html() {
+    let data: Vec<u8> = (0x20..0x32).collect();
+    let mut bytes = ByteCode::new(&data);
+    bytes.skip(0x11);
+    let html = bytes
+        .hexdump()
+        .highlight(0..2)
+        .label(1..3, "a")
+        .label(2..4, "b & c")
+        .to_html();
+    assert_eq!(
+        html,
+        "<pre class=\"hexdump\">\n         00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n\
+         00000000 <span class=\"highlight\">20</span> <span class=\"highlight\">21</span> \
+         <span class=\"label-0\">22</span> <span class=\"label-1\">23</span> \
+         24 25 26 27 28 29 2A 2B 2C 2D 2E 2F  | !&quot;#$%&amp;&#39;()*+,-./|  a, b &amp; c\n\
+         00000010 30 <span class=\"pointer\">31</span>                                            |01|\n\
+         </pre>\n"
+    );
+    assert_eq!(ByteCode::new(&[]).hexdump().to_html().lines().count(), 3);
+
+    let html = bytes
+        .hexdump()
+        .ascii(false)
+        .label(0x10..0x11, "x")
+        .to_html();
+    assert!(html.contains(&format!(
+        "00000010 <span class=\"label-0\">30</span> <span class=\"pointer\">31</span>{}  x\n",
+        " ".repeat(42)
+    )));
+}
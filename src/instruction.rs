@@ -0,0 +1,223 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::ByteCode;
+
+/// Decoded instructions in order of position, with the byte span each was decoded from.
+///
+/// The map resolves positions to instruction indices, so that branch targets given as offsets
+/// can be turned into indices without a second pass over the code.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCode;
+///
+/// // 0x01 nn: push nn, 0x02 dd: jump by signed dd from the next instruction, 0x00: return
+/// let mut code = ByteCode::new(&[0x01, 0x07, 0x02, 0xfc, 0x00]);
+/// let map = code.decode_instructions(|code| {
+///     let opcode = code.take_into_u8();
+///     let operand = match opcode {
+///         0x01 | 0x02 => code.take_into_u8() as i8,
+///         _ => 0,
+///     };
+///     Some((opcode, operand))
+/// });
+/// assert_eq!(map.len(), 3);
+/// let (_, displacement) = map[1];
+/// assert_eq!(map.branch_target(1, displacement as i64), Some(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionMap<T> {
+    instructions: Vec<(Range<usize>, T)>,
+}
+
+impl<T> Default for InstructionMap<T> {
+    fn default() -> Self {
+        InstructionMap {
+            instructions: Vec::new(),
+        }
+    }
+}
+
+impl<T> InstructionMap<T> {
+    /// Creates an empty `InstructionMap`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an instruction decoded from `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `span` is empty or starts before the end of the last instruction.
+    pub fn push(&mut self, span: Range<usize>, instruction: T) {
+        let end = self.instructions.last().map_or(0, |(last, _)| last.end);
+        if span.start >= span.end || span.start < end {
+            panic!(
+                "instruction span {:#x}..{:#x} is empty or overlaps the previous one ending at {:#x}",
+                span.start, span.end, end
+            );
+        }
+        self.instructions.push((span, instruction));
+    }
+
+    /// Returns the number of instructions.
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Returns `true` if there are no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /// Returns the instruction at `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.instructions
+            .get(index)
+            .map(|(_, instruction)| instruction)
+    }
+
+    /// Returns the byte span of the instruction at `index`.
+    pub fn span(&self, index: usize) -> Option<Range<usize>> {
+        self.instructions.get(index).map(|(span, _)| span.clone())
+    }
+
+    /// Returns an iterator over the instructions with their spans, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<usize>, &T)> {
+        self.instructions
+            .iter()
+            .map(|(span, instruction)| (span.clone(), instruction))
+    }
+
+    /// Returns the index of the instruction starting at `offset`.
+    ///
+    /// `None` means no instruction starts there, which for a branch target usually means a decoding
+    /// error or a jump into the middle of an instruction.
+    pub fn index_at(&self, offset: usize) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&offset, |(span, _)| span.start)
+            .ok()
+    }
+
+    /// Returns the index of the instruction whose span contains `offset`.
+    pub fn index_containing(&self, offset: usize) -> Option<usize> {
+        let index = self
+            .instructions
+            .partition_point(|(span, _)| span.start <= offset)
+            .checked_sub(1)?;
+        self.instructions[index]
+            .0
+            .contains(&offset)
+            .then_some(index)
+    }
+
+    /// Returns the index of the instruction targeted by a branch at `index`
+    /// whose `displacement` is relative to the end of the branch, that is, the next instruction.
+    ///
+    /// For instruction sets whose displacements are relative to the start of the branch,
+    /// use [`index_at`](InstructionMap::index_at) with the start of its [`span`](InstructionMap::span).
+    pub fn branch_target(&self, index: usize, displacement: i64) -> Option<usize> {
+        let end = self.instructions.get(index)?.0.end;
+        let target = usize::try_from(i64::try_from(end).ok()?.checked_add(displacement)?).ok()?;
+        self.index_at(target)
+    }
+}
+
+impl<T> core::ops::Index<usize> for InstructionMap<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.instructions[index].1
+    }
+}
+
+impl<T> FromIterator<(Range<usize>, T)> for InstructionMap<T> {
+    /// Collects instructions with their spans.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a span is empty or starts before the end of the previous one.
+    fn from_iter<I: IntoIterator<Item = (Range<usize>, T)>>(iter: I) -> Self {
+        let mut map = InstructionMap::new();
+        for (span, instruction) in iter {
+            map.push(span, instruction);
+        }
+        map
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Decodes instructions with `decode` until the end of the slice, recording the span of each.
+    ///
+    /// Decoding also stops when `decode` returns `None` or consumes nothing,
+    /// leaving the pointer at the start of the instruction that could not be decoded.
+    /// Spans are absolute positions, as with [`original`](ByteCode::original).
+    pub fn decode_instructions<T>(
+        &mut self,
+        mut decode: impl FnMut(&mut ByteCode<'a>) -> Option<T>,
+    ) -> InstructionMap<T> {
+        let mut map = InstructionMap::new();
+        while !self.is_end() {
+            let start = self.checkpoint();
+            match decode(self) {
+                Some(instruction) if self.pos > start.pos() => {
+                    map.push(start.pos()..self.pos, instruction)
+                }
+                _ => {
+                    self.restore(start);
+                    break;
+                }
+            }
+        }
+        map
+    }
+}
+
+#[test]
+fn decode_instructions() {
+    let mut code = ByteCode::new(&[0x01, 0xaa, 0x00, 0x03, 0xff, 0xee]);
+    code.skip(1);
+    let map = code.decode_instructions(|code| match code.take_into_u8() {
+        0x03 => None,
+        opcode => Some(opcode),
+    });
+    assert_eq!(
+        map.iter().collect::<Vec<_>>(),
+        [(1..2, &0xaa), (2..3, &0x00)]
+    );
+    assert_eq!(code.pos(), 3);
+
+    let map = code.decode_instructions(|_| Some(()));
+    assert!(map.is_empty());
+    assert_eq!(code.pos(), 3);
+}
+
+#[test]
+fn lookup() {
+    let map: InstructionMap<&str> = [(0..2, "a"), (2..5, "b"), (8..9, "c")]
+        .into_iter()
+        .collect();
+    assert_eq!(map.index_at(2), Some(1));
+    assert_eq!(map.index_at(3), None);
+    assert_eq!(map.index_containing(4), Some(1));
+    assert_eq!(map.index_containing(6), None);
+    assert_eq!(map.index_containing(9), None);
+    assert_eq!(map.span(2), Some(8..9));
+    assert_eq!(map.get(3), None);
+
+    assert_eq!(map.branch_target(0, 6), Some(2));
+    assert_eq!(map.branch_target(1, -5), Some(0));
+    assert_eq!(map.branch_target(1, -6), None);
+    assert_eq!(map.branch_target(1, 1), None);
+    assert_eq!(map.branch_target(2, i64::MAX), None);
+}
+
+#[test]
+#[should_panic]
+fn push_overlapping() {
+    let mut map = InstructionMap::new();
+    map.push(0..4, ());
+    map.push(3..5, ());
+}
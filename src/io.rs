@@ -0,0 +1,180 @@
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+
+use crate::ByteCode;
+
+// These are implemented for `&mut ByteCode` rather than `ByteCode`, so that `Read::take`
+// and friends never shadow the inherent methods of the same name.
+
+/// Reads from the pointer and moves it forward by the number of bytes read.
+///
+/// Pass `&mut bytes` to APIs expecting [`io::Read`]; the pointer is left after the consumed bytes.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+///
+/// use bytecode::ByteCode;
+///
+/// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+/// bytes.skip(2);
+/// let mut buf = [0; 4];
+/// (&mut bytes).read_exact(&mut buf).unwrap();
+/// assert_eq!(buf, [2, 3, 4, 5]);
+/// assert_eq!(bytes.pos(), 6);
+/// ```
+impl Read for &mut ByteCode<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.inner.len());
+        buf[..len].copy_from_slice(&self.inner[..len]);
+        **self += len;
+        Ok(len)
+    }
+}
+
+/// Exposes the remaining slice as the buffer, so no bytes are held back from the pointer.
+impl BufRead for &mut ByteCode<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.inner)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.inner.len());
+        **self += amt;
+    }
+}
+
+/// Moves the pointer, with positions measured from the beginning of the slice.
+///
+/// Unlike [`io::Cursor`], seeking before the beginning or past the end is an error,
+/// since the pointer cannot leave the slice.
+///
+/// Call it as `Seek::seek`, since the inherent [`ByteCode::seek`] takes precedence in method syntax.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Seek, SeekFrom};
+///
+/// use bytecode::ByteCode;
+///
+/// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+/// let mut reader = &mut bytes;
+/// assert_eq!(Seek::seek(&mut reader, SeekFrom::End(-2)).unwrap(), 6);
+/// assert_eq!(Seek::seek(&mut reader, SeekFrom::Current(-4)).unwrap(), 2);
+/// assert!(Seek::seek(&mut reader, SeekFrom::Start(9)).is_err());
+/// assert_eq!(bytes.pos(), 2);
+/// ```
+impl Seek for &mut ByteCode<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => usize::try_from(offset).ok(),
+            SeekFrom::End(offset) => offset_from(self.len(), offset),
+            SeekFrom::Current(offset) => offset_from(self.pos, offset),
+        };
+        match target {
+            Some(target) if target <= self.len() => {
+                ByteCode::seek(self, target);
+                Ok(target as u64)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a position outside the slice",
+            )),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos as u64)
+    }
+}
+
+fn offset_from(base: usize, offset: i64) -> Option<usize> {
+    if offset >= 0 {
+        base.checked_add(usize::try_from(offset).ok()?)
+    } else {
+        base.checked_sub(usize::try_from(offset.unsigned_abs()).ok()?)
+    }
+}
+
+#[test]
+fn read() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    let mut buf = [0; 3];
+    assert_eq!((&mut bytes).read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [0, 1, 2]);
+    assert_eq!(bytes.pos, 3);
+
+    let mut rest = Vec::new();
+    assert_eq!((&mut bytes).read_to_end(&mut rest).unwrap(), 5);
+    assert_eq!(rest, [3, 4, 5, 6, 7]);
+    assert!(bytes.is_end());
+    assert_eq!((&mut bytes).read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn read_exact_short() {
+    let mut bytes = ByteCode::new(&[0, 1, 2]);
+    let mut buf = [0; 4];
+    let err = (&mut bytes).read_exact(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn buf_read() {
+    let mut bytes = ByteCode::new(b"foo\nbar\nbaz");
+    bytes.skip(1);
+    let mut reader = &mut bytes;
+    assert_eq!(reader.fill_buf().unwrap(), b"oo\nbar\nbaz");
+    reader.consume(3);
+    assert_eq!(reader.pos, 4);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "bar\n");
+    assert_eq!(reader.pos, 8);
+
+    reader.consume(100);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn seek() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    let mut reader = &mut bytes;
+    assert_eq!(Seek::seek(&mut reader, SeekFrom::Start(5)).unwrap(), 5);
+    assert_eq!(reader.inner, [5, 6, 7]);
+    assert_eq!(Seek::seek(&mut reader, SeekFrom::Current(-3)).unwrap(), 2);
+    assert_eq!(Seek::seek(&mut reader, SeekFrom::End(0)).unwrap(), 8);
+    assert_eq!(reader.stream_position().unwrap(), 8);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn seek_out_of_range() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    bytes.skip(1);
+    let mut reader = &mut bytes;
+    assert!(Seek::seek(&mut reader, SeekFrom::Start(5)).is_err());
+    assert!(Seek::seek(&mut reader, SeekFrom::End(1)).is_err());
+    assert!(Seek::seek(&mut reader, SeekFrom::Current(-2)).is_err());
+    assert!(Seek::seek(&mut reader, SeekFrom::End(i64::MIN)).is_err());
+    assert_eq!(bytes.pos, 1);
+}
+
+#[test]
+fn handoff_and_resume() {
+    let mut bytes = ByteCode::new(&[0xaa, 1, 2, 3, 0xbb]);
+    assert_eq!(bytes.take_into_u8(), 0xaa);
+    let mut payload = [0; 3];
+    Read::take(&mut bytes, 3).read_exact(&mut payload).unwrap();
+    assert_eq!(payload, [1, 2, 3]);
+    assert_eq!(bytes.take_into_u8(), 0xbb);
+}
+
+#[test]
+fn inherent_take_not_shadowed() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3]);
+    assert_eq!(bytes.take(2), [0, 1]);
+    assert_eq!(bytes.pos, 2);
+}
@@ -0,0 +1,147 @@
+use core::marker::PhantomData;
+
+use crate::{ByteCode, FixedSize, FromByteCode};
+
+/// An iterator over the remaining bytes, moving the pointer forward past each one.
+///
+/// This struct is created by [`ByteCode::iter`] and by iterating over a `&mut ByteCode`.
+pub struct Iter<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+}
+
+impl Iterator for Iter<'_, '_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = *self.bytes.inner.first()?;
+        self.bytes.skip(1);
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bytes.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_, '_> {}
+
+impl<'b, 'a> IntoIterator for &'b mut ByteCode<'a> {
+    type Item = u8;
+    type IntoIter = Iter<'b, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter { bytes: self }
+    }
+}
+
+/// An iterator over fixed-size values read from the remaining bytes.
+///
+/// This struct is created by [`ByteCode::chunks_exact_into`].
+pub struct ChunksExactInto<'b, 'a, T> {
+    bytes: &'b mut ByteCode<'a>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: FromByteCode<'a> + FixedSize> Iterator for ChunksExactInto<'_, 'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if T::SIZE == 0 || self.bytes.inner.len() < T::SIZE {
+            return None;
+        }
+        self.bytes.try_take_into().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match T::SIZE {
+            0 => (0, Some(0)),
+            size => (0, Some(self.bytes.inner.len() / size)),
+        }
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns an iterator over the remaining bytes, moving the pointer forward past each one
+    /// as it is yielded. Iterating over a `&mut ByteCode` does the same.
+    ///
+    /// Stopping the iteration early leaves the pointer after the last byte yielded,
+    /// so the rest can still be read with the other methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x02, 0x00, 0x03]);
+    /// let head: Vec<u8> = bytes.iter().take_while(|byte| *byte != 0).collect();
+    /// assert_eq!(head, [0x01, 0x02]);
+    /// assert_eq!(bytes.pos(), 3);
+    ///
+    /// for byte in &mut bytes {
+    ///     assert_eq!(byte, 0x03);
+    /// }
+    /// assert!(bytes.is_end());
+    /// ```
+    pub fn iter<'b>(&'b mut self) -> Iter<'b, 'a> {
+        Iter { bytes: self }
+    }
+
+    /// Returns an iterator over values of a fixed-size type read from the remaining bytes,
+    /// honouring the current endian setting. Moves the pointer forward past each value as it is yielded.
+    ///
+    /// The iteration stops when fewer than [`T::SIZE`](FixedSize::SIZE) bytes remain,
+    /// leaving the trailing bytes unread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0xff]);
+    /// bytes.set_endian(Endian::Little);
+    /// let words: Vec<u32> = bytes.chunks_exact_into::<u32>().collect();
+    /// assert_eq!(words, [1, 2]);
+    /// assert_eq!(bytes.as_slice(), [0xff]);
+    /// ```
+    pub fn chunks_exact_into<'b, T: FromByteCode<'a> + FixedSize>(
+        &'b mut self,
+    ) -> ChunksExactInto<'b, 'a, T> {
+        ChunksExactInto {
+            bytes: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn iter() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03, 0x04]);
+    bytes.skip(1);
+    let mut iter = bytes.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(0x02));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(bytes.pos(), 2);
+    assert_eq!((&mut bytes).into_iter().sum::<u8>(), 0x07);
+    assert!(bytes.is_end());
+    assert_eq!(bytes.iter().next(), None);
+}
+
+#[test]
+fn chunks_exact_into() {
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00, 0x02, 0x00]);
+    let mut chunks = bytes.chunks_exact_into::<u16>();
+    assert_eq!(chunks.size_hint(), (0, Some(2)));
+    assert_eq!(chunks.next(), Some(1));
+    assert_eq!(chunks.next(), Some(2));
+    assert_eq!(chunks.next(), None);
+    assert_eq!(bytes.pos(), 4);
+
+    let mut bytes = ByteCode::new(&[0xff, 0x00, 0x01, 0x7f]);
+    let pairs: Vec<(i8, u8)> = bytes.chunks_exact_into().collect();
+    assert_eq!(pairs, [(-1, 0), (1, 0x7f)]);
+}
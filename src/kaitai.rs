@@ -0,0 +1,1292 @@
+//! A runtime interpreter for [Kaitai Struct](https://kaitai.io) (`.ksy`) definitions.
+//!
+//! A definition is loaded with [`Schema::from_yaml`] and drives a [`ByteCode`] to produce a dynamic [`Value`] tree,
+//! so existing format definitions can be used without code generation.
+//!
+//! The supported subset covers `meta/endian`, `seq` with fixed-size integers, floats, byte arrays and strings,
+//! `size`, `size-eos`, `contents`, `terminator`, `repeat` (`eos`, `expr` and `until`), `if`, `enum`,
+//! `switch-on` types, and nested `types` and `enums`. Expressions support integer, string and boolean literals,
+//! arithmetic, bitwise, comparison and logical operators, field access, indexing, `enum::label` literals,
+//! `_`, `_parent`, `_root`, `_io.pos`, `_io.size` and `_io.eof`, as well as `.size` and `.length`.
+//! Instances, bit-sized integers and process routines are not supported.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytecode::{kaitai::{Schema, Value}, ByteCode};
+//!
+//! let schema = Schema::from_yaml(r#"
+//! meta:
+//!   id: record
+//!   endian: be
+//! seq:
+//!   - id: len
+//!     type: u2
+//!   - id: name
+//!     type: str
+//!     size: len
+//!     encoding: ASCII
+//! "#).unwrap();
+//!
+//! let value = schema.parse(&mut ByteCode::new(&[0x00, 0x03, 0x66, 0x6f, 0x6f])).unwrap();
+//! assert_eq!(value.get("name"), Some(&Value::Str("foo".to_owned())));
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
+
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::{ByteCode, Endian};
+
+/// The deepest nesting of user types [`Schema::parse`] accepts.
+pub const MAX_DEPTH: usize = 100;
+
+/// A value produced by interpreting a definition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    Str(String),
+    Array(Vec<Value>),
+    /// The fields of a user type, in `seq` order.
+    Struct(Vec<(String, Value)>),
+    /// An integer mapped through an `enum`, with its label if the value is known.
+    Enum {
+        label: Option<String>,
+        value: i128,
+    },
+}
+
+impl Value {
+    /// Returns the field with the given id, if this is a `Struct`.
+    pub fn get(&self, id: &str) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields
+                .iter()
+                .find(|(name, _)| name == id)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// An error loading a definition or interpreting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The definition is not valid YAML.
+    Yaml(String),
+    /// The definition uses an unsupported or invalid construct.
+    Schema(String),
+    /// The data does not match the definition.
+    /// `pos` is the offset from the position the parse started at.
+    Parse { pos: usize, message: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Error::Yaml(message) => write!(f, "invalid YAML: {}", message),
+            Error::Schema(message) => write!(f, "invalid definition: {}", message),
+            Error::Parse { pos, message } => write!(f, "{} at {:#x}", message, pos),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn schema_error<T>(message: impl Into<String>) -> Result<T, Error> {
+    Err(Error::Schema(message.into()))
+}
+
+/// A loaded Kaitai Struct definition.
+#[derive(Debug)]
+pub struct Schema {
+    root: TypeDef,
+}
+
+impl Schema {
+    /// Loads a definition from the contents of a `.ksy` file.
+    pub fn from_yaml(source: &str) -> Result<Schema, Error> {
+        let documents =
+            YamlLoader::load_from_str(source).map_err(|error| Error::Yaml(error.to_string()))?;
+        let Some(document) = documents.first() else {
+            return Err(Error::Yaml("empty document".to_owned()));
+        };
+        Ok(Schema {
+            root: TypeDef::from_yaml(document)?,
+        })
+    }
+
+    /// Parses the data at the current position according to the top-level type.
+    /// Moves the pointer forward past the consumed data.
+    ///
+    /// On error, the pointer is left where the failing field started.
+    /// User types nested more than [`MAX_DEPTH`] levels deep are rejected.
+    pub fn parse(&self, bytes: &mut ByteCode) -> Result<Value, Error> {
+        let base = bytes.pos();
+        let parser = Interpreter { base };
+        parser.parse_type(&[&self.root], self.root.endian, bytes, None)
+    }
+}
+
+#[derive(Debug)]
+struct TypeDef {
+    endian: Option<Endian>,
+    seq: Vec<Attribute>,
+    types: HashMap<String, TypeDef>,
+    enums: HashMap<String, HashMap<i128, String>>,
+}
+
+impl TypeDef {
+    fn from_yaml(yaml: &Yaml) -> Result<TypeDef, Error> {
+        let endian = match yaml["meta"]["endian"].as_str() {
+            Some("le") => Some(Endian::Little),
+            Some("be") => Some(Endian::Big),
+            Some(endian) => return schema_error(format!("unsupported endian `{}`", endian)),
+            None => None,
+        };
+        let seq = match &yaml["seq"] {
+            Yaml::Array(seq) => seq
+                .iter()
+                .map(Attribute::from_yaml)
+                .collect::<Result<_, _>>()?,
+            Yaml::BadValue | Yaml::Null => Vec::new(),
+            _ => return schema_error("`seq` must be a list"),
+        };
+        let mut types = HashMap::new();
+        if let Some(hash) = yaml["types"].as_hash() {
+            for (name, definition) in hash {
+                let name = name.as_str().ok_or(Error::Schema("type name".to_owned()))?;
+                types.insert(name.to_owned(), TypeDef::from_yaml(definition)?);
+            }
+        }
+        let mut enums = HashMap::new();
+        if let Some(hash) = yaml["enums"].as_hash() {
+            for (name, entries) in hash {
+                let name = name.as_str().ok_or(Error::Schema("enum name".to_owned()))?;
+                let mut labels = HashMap::new();
+                for (value, label) in entries.as_hash().into_iter().flatten() {
+                    let value = value
+                        .as_i64()
+                        .ok_or(Error::Schema(format!("enum `{}` key", name)))?;
+                    let label = match label {
+                        Yaml::String(label) => label,
+                        _ => label["id"]
+                            .as_str()
+                            .ok_or(Error::Schema(format!("enum `{}` label", name)))?,
+                    };
+                    labels.insert(value as i128, label.to_owned());
+                }
+                enums.insert(name.to_owned(), labels);
+            }
+        }
+        Ok(TypeDef {
+            endian,
+            seq,
+            types,
+            enums,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum TypeRef {
+    Bytes,
+    Named(String),
+    Switch {
+        on: Expr,
+        cases: Vec<(Expr, String)>,
+    },
+}
+
+#[derive(Debug)]
+enum Repeat {
+    Once,
+    Eos,
+    Expr(Expr),
+    Until(Expr),
+}
+
+#[derive(Debug)]
+struct Attribute {
+    id: Option<String>,
+    type_ref: TypeRef,
+    size: Option<Expr>,
+    size_eos: bool,
+    contents: Option<Vec<u8>>,
+    repeat: Repeat,
+    encoding: Option<String>,
+    terminator: Option<u8>,
+    include: bool,
+    consume: bool,
+    enum_name: Option<String>,
+    condition: Option<Expr>,
+}
+
+impl Attribute {
+    fn from_yaml(yaml: &Yaml) -> Result<Attribute, Error> {
+        let id = yaml["id"].as_str().map(str::to_owned);
+        let type_ref = match &yaml["type"] {
+            Yaml::String(name) => TypeRef::Named(name.clone()),
+            Yaml::Hash(_) => {
+                let on = Expr::from_yaml(&yaml["type"]["switch-on"])?;
+                let mut cases = Vec::new();
+                for (case, name) in yaml["type"]["cases"].as_hash().into_iter().flatten() {
+                    let name = name.as_str().ok_or(Error::Schema("case type".to_owned()))?;
+                    cases.push((Expr::from_yaml(case)?, name.to_owned()));
+                }
+                TypeRef::Switch { on, cases }
+            }
+            Yaml::BadValue | Yaml::Null => TypeRef::Bytes,
+            _ => return schema_error("`type` must be a string or a switch"),
+        };
+        let contents = match &yaml["contents"] {
+            Yaml::String(string) => Some(string.as_bytes().to_vec()),
+            Yaml::Array(items) => {
+                let mut contents = Vec::new();
+                for item in items {
+                    match item {
+                        Yaml::Integer(byte) => contents.push(*byte as u8),
+                        Yaml::String(string) => contents.extend(string.as_bytes()),
+                        _ => return schema_error("`contents` items must be bytes or strings"),
+                    }
+                }
+                Some(contents)
+            }
+            _ => None,
+        };
+        let repeat = match yaml["repeat"].as_str() {
+            None => Repeat::Once,
+            Some("eos") => Repeat::Eos,
+            Some("expr") => Repeat::Expr(Expr::from_yaml(&yaml["repeat-expr"])?),
+            Some("until") => Repeat::Until(Expr::from_yaml(&yaml["repeat-until"])?),
+            Some(repeat) => return schema_error(format!("unsupported repeat `{}`", repeat)),
+        };
+        let optional_expr = |key: &str| match &yaml[key] {
+            Yaml::BadValue => Ok(None),
+            value => Expr::from_yaml(value).map(Some),
+        };
+        Ok(Attribute {
+            id,
+            type_ref,
+            size: optional_expr("size")?,
+            size_eos: yaml["size-eos"].as_bool().unwrap_or(false),
+            contents,
+            repeat,
+            encoding: yaml["encoding"].as_str().map(str::to_owned),
+            terminator: yaml["terminator"].as_i64().map(|byte| byte as u8),
+            include: yaml["include"].as_bool().unwrap_or(false),
+            consume: yaml["consume"].as_bool().unwrap_or(true),
+            enum_name: yaml["enum"].as_str().map(str::to_owned),
+            condition: optional_expr("if")?,
+        })
+    }
+}
+
+/// The evaluation context of expressions: the fields parsed so far in the current type and its parents.
+struct Context<'c> {
+    fields: &'c [(String, Value)],
+    parent: Option<&'c Context<'c>>,
+    item: Option<&'c Value>,
+    io_pos: usize,
+    io_size: usize,
+}
+
+struct Interpreter {
+    base: usize,
+}
+
+impl Interpreter {
+    fn error<T>(&self, bytes: &ByteCode, message: impl Into<String>) -> Result<T, Error> {
+        Err(Error::Parse {
+            pos: bytes.pos() - self.base,
+            message: message.into(),
+        })
+    }
+
+    fn parse_type(
+        &self,
+        scopes: &[&TypeDef],
+        endian: Option<Endian>,
+        bytes: &mut ByteCode,
+        parent: Option<&Context>,
+    ) -> Result<Value, Error> {
+        let current = scopes[scopes.len() - 1];
+        let endian = current.endian.or(endian);
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        for (i, attribute) in current.seq.iter().enumerate() {
+            if let Some(condition) = &attribute.condition {
+                let context = Context {
+                    fields: &fields,
+                    parent,
+                    item: None,
+                    io_pos: bytes.pos(),
+                    io_size: bytes.len(),
+                };
+                if !condition.eval(&context, scopes)?.truthy()? {
+                    continue;
+                }
+            }
+            let value =
+                match &attribute.repeat {
+                    Repeat::Once => {
+                        self.parse_attribute(attribute, scopes, endian, bytes, &fields, parent)?
+                    }
+                    Repeat::Eos => {
+                        let mut items = Vec::new();
+                        while !bytes.is_end() {
+                            let start = bytes.pos();
+                            items.push(self.parse_attribute(
+                                attribute, scopes, endian, bytes, &fields, parent,
+                            )?);
+                            if bytes.pos() == start {
+                                return self
+                                    .error(bytes, "repeat until eos without consuming bytes");
+                            }
+                        }
+                        Value::Array(items)
+                    }
+                    Repeat::Expr(count) => {
+                        let context = Context {
+                            fields: &fields,
+                            parent,
+                            item: None,
+                            io_pos: bytes.pos(),
+                            io_size: bytes.len(),
+                        };
+                        let count = count.eval(&context, scopes)?.as_usize()?;
+                        let remaining = bytes.len() - bytes.pos();
+                        let mut items = Vec::new();
+                        for _ in 0..count {
+                            let start = bytes.pos();
+                            items.push(self.parse_attribute(
+                                attribute, scopes, endian, bytes, &fields, parent,
+                            )?);
+                            if bytes.pos() == start && count > remaining {
+                                return self.error(bytes, "repeat expr without consuming bytes");
+                            }
+                        }
+                        Value::Array(items)
+                    }
+                    Repeat::Until(condition) => {
+                        let mut items = Vec::new();
+                        loop {
+                            let start = bytes.pos();
+                            let item = self.parse_attribute(
+                                attribute, scopes, endian, bytes, &fields, parent,
+                            )?;
+                            let context = Context {
+                                fields: &fields,
+                                parent,
+                                item: Some(&item),
+                                io_pos: bytes.pos(),
+                                io_size: bytes.len(),
+                            };
+                            let done = condition.eval(&context, scopes)?.truthy()?;
+                            items.push(item);
+                            if done {
+                                break;
+                            }
+                            if bytes.pos() == start {
+                                return self.error(bytes, "repeat until without consuming bytes");
+                            }
+                        }
+                        Value::Array(items)
+                    }
+                };
+            let id = match &attribute.id {
+                Some(id) => id.clone(),
+                None => format!("_unnamed{}", i),
+            };
+            fields.push((id, value));
+        }
+        Ok(Value::Struct(fields))
+    }
+
+    fn parse_attribute(
+        &self,
+        attribute: &Attribute,
+        scopes: &[&TypeDef],
+        endian: Option<Endian>,
+        bytes: &mut ByteCode,
+        fields: &[(String, Value)],
+        parent: Option<&Context>,
+    ) -> Result<Value, Error> {
+        let context = Context {
+            fields,
+            parent,
+            item: None,
+            io_pos: bytes.pos(),
+            io_size: bytes.len(),
+        };
+
+        if let Some(contents) = &attribute.contents {
+            if !bytes.as_slice().starts_with(contents) {
+                return self.error(bytes, format!("expected contents {:02X?}", contents));
+            }
+            bytes.skip(contents.len());
+            return Ok(Value::Bytes(contents.clone()));
+        }
+
+        let size = match &attribute.size {
+            Some(size) => Some(size.eval(&context, scopes)?.as_usize()?),
+            None if attribute.size_eos => Some(bytes.as_slice().len()),
+            None => None,
+        };
+        let type_name = match &attribute.type_ref {
+            TypeRef::Bytes => None,
+            TypeRef::Named(name) => Some(name.as_str()),
+            TypeRef::Switch { on, cases } => {
+                let on = on.eval(&context, scopes)?;
+                let mut selected = None;
+                for (case, name) in cases {
+                    if matches!(case, Expr::Name(name) if name == "_")
+                        || binary(BinaryOp::Eq, case.eval(&context, scopes)?, on.clone())?
+                            .truthy()?
+                    {
+                        selected = Some(name.as_str());
+                        break;
+                    }
+                }
+                selected
+            }
+        };
+
+        let value = match size {
+            Some(size) => {
+                if bytes.as_slice().len() < size {
+                    return self.error(bytes, format!("expected {} bytes", size));
+                }
+                let start = bytes.pos();
+                let inner = &bytes.as_slice()[..size];
+                let mut sub = ByteCode::new(inner);
+                let sub_interpreter = Interpreter { base: 0 };
+                let value = sub_interpreter
+                    .parse_value(
+                        attribute, type_name, scopes, endian, &mut sub, &context, true,
+                    )
+                    .map_err(|error| match error {
+                        Error::Parse { pos, message } => Error::Parse {
+                            pos: start - self.base + pos,
+                            message,
+                        },
+                        error => error,
+                    })?;
+                bytes.skip(size);
+                value
+            }
+            None => {
+                self.parse_value(attribute, type_name, scopes, endian, bytes, &context, false)?
+            }
+        };
+
+        match (&attribute.enum_name, value) {
+            (Some(enum_name), Value::Int(value)) => {
+                let labels = lookup(scopes, |scope| scope.enums.get(enum_name))
+                    .ok_or_else(|| Error::Schema(format!("unknown enum `{}`", enum_name)))?
+                    .1;
+                Ok(Value::Enum {
+                    label: labels.get(&value).cloned(),
+                    value,
+                })
+            }
+            (_, value) => Ok(value),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn parse_value(
+        &self,
+        attribute: &Attribute,
+        type_name: Option<&str>,
+        scopes: &[&TypeDef],
+        endian: Option<Endian>,
+        bytes: &mut ByteCode,
+        context: &Context,
+        sized: bool,
+    ) -> Result<Value, Error> {
+        match type_name {
+            None | Some("str") => {
+                let terminator = attribute.terminator;
+                let data = self.take_terminated(bytes, terminator, attribute, sized)?;
+                match type_name {
+                    None => Ok(Value::Bytes(data)),
+                    _ => self.decode_string(bytes, &data, attribute.encoding.as_deref()),
+                }
+            }
+            Some("strz") => {
+                let data = self.take_terminated(
+                    bytes,
+                    Some(attribute.terminator.unwrap_or(0)),
+                    attribute,
+                    sized,
+                )?;
+                self.decode_string(bytes, &data, attribute.encoding.as_deref())
+            }
+            Some(name) => {
+                if let Some(value) = self.parse_primitive(name, endian, bytes)? {
+                    return Ok(value);
+                }
+                let Some((depth, definition)) = lookup(scopes, |scope| scope.types.get(name))
+                else {
+                    return schema_error(format!("unknown type `{}`", name));
+                };
+                if std::iter::successors(Some(context), |context| context.parent).count()
+                    > MAX_DEPTH
+                {
+                    return self.error(
+                        bytes,
+                        format!("user types nested more than {} levels deep", MAX_DEPTH),
+                    );
+                }
+                let mut child_scopes = scopes[..=depth].to_vec();
+                child_scopes.push(definition);
+                let value = self.parse_type(&child_scopes, endian, bytes, Some(context))?;
+                if sized {
+                    bytes.skip(bytes.as_slice().len());
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    fn take_terminated(
+        &self,
+        bytes: &mut ByteCode,
+        terminator: Option<u8>,
+        attribute: &Attribute,
+        sized: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let inner = bytes.as_slice();
+        let Some(terminator) = terminator else {
+            if sized {
+                bytes.skip(inner.len());
+                return Ok(inner.to_vec());
+            }
+            return schema_error("byte array or string without `size`, `size-eos` or `terminator`");
+        };
+        match inner.iter().position(|byte| *byte == terminator) {
+            Some(len) => {
+                let data = inner[..len + attribute.include as usize].to_vec();
+                bytes.skip(len + attribute.consume as usize);
+                if sized {
+                    bytes.skip(bytes.as_slice().len());
+                }
+                Ok(data)
+            }
+            None if sized => {
+                bytes.skip(inner.len());
+                Ok(inner.to_vec())
+            }
+            None => self.error(bytes, format!("terminator {:#04x} not found", terminator)),
+        }
+    }
+
+    fn decode_string(
+        &self,
+        bytes: &ByteCode,
+        data: &[u8],
+        encoding: Option<&str>,
+    ) -> Result<Value, Error> {
+        let utf16 = |to_unit: fn([u8; 2]) -> u16| {
+            let units: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|unit| to_unit([unit[0], unit[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        };
+        let string = match encoding.map(str::to_ascii_uppercase).as_deref() {
+            Some("UTF-8") | Some("UTF8") | Some("ASCII") => {
+                String::from_utf8_lossy(data).into_owned()
+            }
+            Some("ISO-8859-1") | Some("LATIN1") => data.iter().map(|byte| *byte as char).collect(),
+            Some("UTF-16LE") => utf16(u16::from_le_bytes),
+            Some("UTF-16BE") => utf16(u16::from_be_bytes),
+            Some(encoding) => return schema_error(format!("unsupported encoding `{}`", encoding)),
+            None => return self.error(bytes, "string without `encoding`"),
+        };
+        Ok(Value::Str(string))
+    }
+
+    fn parse_primitive(
+        &self,
+        name: &str,
+        endian: Option<Endian>,
+        bytes: &mut ByteCode,
+    ) -> Result<Option<Value>, Error> {
+        let (base, endian) = match name.strip_suffix("le") {
+            Some(base) => (base, Some(Endian::Little)),
+            None => match name.strip_suffix("be") {
+                Some(base) => (base, Some(Endian::Big)),
+                None => (name, endian),
+            },
+        };
+        let (kind, size) = match base {
+            "u1" | "s1" | "u2" | "s2" | "u4" | "s4" | "u8" | "s8" | "f4" | "f8" => {
+                (&base[..1], base[1..].parse::<usize>().unwrap())
+            }
+            _ => return Ok(None),
+        };
+        let endian = match (endian, size) {
+            (_, 1) => Endian::Big,
+            (Some(endian), _) => endian,
+            (None, _) => return schema_error(format!("no endianness for type `{}`", name)),
+        };
+        if bytes.as_slice().len() < size {
+            return self.error(bytes, format!("expected {} bytes for `{}`", size, name));
+        }
+        let raw = endian.read_uint(&bytes.as_slice()[..size]);
+        bytes.skip(size);
+        let value = match kind {
+            "u" => Value::Int(raw as i128),
+            "s" => {
+                let shift = 64 - size * 8;
+                Value::Int((((raw << shift) as i64) >> shift) as i128)
+            }
+            _ if size == 4 => Value::Float(f32::from_bits(raw as u32) as f64),
+            _ => Value::Float(f64::from_bits(raw)),
+        };
+        Ok(Some(value))
+    }
+}
+
+/// Looks up a name from the innermost scope outwards, returning the depth of the defining scope.
+fn lookup<'s, T>(
+    scopes: &[&'s TypeDef],
+    find: impl Fn(&'s TypeDef) -> Option<&'s T>,
+) -> Option<(usize, &'s T)> {
+    scopes
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(depth, scope)| find(scope).map(|found| (depth, found)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shl,
+    Shr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnaryOp {
+    Neg,
+    Not,
+    BitNot,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Int(i128),
+    Str(String),
+    Bool(bool),
+    Name(String),
+    EnumLiteral(String, String),
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i128),
+    Str(String),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 22] = [
+    "::", "==", "!=", "<=", ">=", "<<", ">>", "<", ">", "+", "-", "*", "/", "%", "&", "|", "^",
+    "~", "(", ")", "[", "]",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            let value = if let Some(hex) = literal.strip_prefix("0x") {
+                i128::from_str_radix(hex, 16)
+            } else if let Some(binary) = literal.strip_prefix("0b") {
+                i128::from_str_radix(binary, 2)
+            } else {
+                literal.parse()
+            };
+            tokens.push(Token::Int(value.map_err(|_| {
+                Error::Schema(format!("invalid number `{}` in `{}`", literal, source))
+            })?));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c == '"' || c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|quote| *quote == c)
+                .ok_or(Error::Schema(format!(
+                    "unterminated string in `{}`",
+                    source
+                )))?;
+            tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+            i += end + 2;
+        } else if c == '.' {
+            tokens.push(Token::Symbol("."));
+            i += 1;
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) else {
+                return schema_error(format!("unexpected `{}` in `{}`", c, source));
+            };
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.pos += 1;
+                true
+            }
+            Some(Token::Ident(s)) if s == symbol => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn binary_op(&self, level: usize) -> Option<BinaryOp> {
+        let op = match self.peek()? {
+            Token::Symbol(symbol) => *symbol,
+            Token::Ident(ident) => ident.as_str(),
+            _ => return None,
+        };
+        let op = match (level, op) {
+            (0, "or") => BinaryOp::Or,
+            (1, "and") => BinaryOp::And,
+            (2, "==") => BinaryOp::Eq,
+            (2, "!=") => BinaryOp::Ne,
+            (2, "<") => BinaryOp::Lt,
+            (2, "<=") => BinaryOp::Le,
+            (2, ">") => BinaryOp::Gt,
+            (2, ">=") => BinaryOp::Ge,
+            (3, "|") => BinaryOp::BitOr,
+            (4, "^") => BinaryOp::BitXor,
+            (5, "&") => BinaryOp::BitAnd,
+            (6, "<<") => BinaryOp::Shl,
+            (6, ">>") => BinaryOp::Shr,
+            (7, "+") => BinaryOp::Add,
+            (7, "-") => BinaryOp::Sub,
+            (8, "*") => BinaryOp::Mul,
+            (8, "/") => BinaryOp::Div,
+            (8, "%") => BinaryOp::Rem,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    fn parse_binary(&mut self, level: usize) -> Result<Expr, Error> {
+        if level > 8 {
+            return self.parse_unary();
+        }
+        let mut lhs = self.parse_binary(level + 1)?;
+        while let Some(op) = self.binary_op(level) {
+            self.pos += 1;
+            let rhs = self.parse_binary(level + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, Error> {
+        let op = if self.eat("-") {
+            UnaryOp::Neg
+        } else if self.eat("not") {
+            UnaryOp::Not
+        } else if self.eat("~") {
+            UnaryOp::BitNot
+        } else {
+            return self.parse_postfix();
+        };
+        Ok(Expr::Unary(op, Box::new(self.parse_unary()?)))
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, Error> {
+        let mut expr = match self.tokens.get(self.pos).cloned() {
+            Some(Token::Int(value)) => Expr::Int(value),
+            Some(Token::Str(value)) => Expr::Str(value),
+            Some(Token::Ident(ident)) if ident == "true" => Expr::Bool(true),
+            Some(Token::Ident(ident)) if ident == "false" => Expr::Bool(false),
+            Some(Token::Ident(ident)) => {
+                if let (Some(Token::Symbol("::")), Some(Token::Ident(label))) =
+                    (self.tokens.get(self.pos + 1), self.tokens.get(self.pos + 2))
+                {
+                    let label = label.clone();
+                    self.pos += 2;
+                    Expr::EnumLiteral(ident, label)
+                } else {
+                    Expr::Name(ident)
+                }
+            }
+            Some(Token::Symbol("(")) => {
+                self.pos += 1;
+                let expr = self.parse_binary(0)?;
+                if !self.eat(")") {
+                    return schema_error("expected `)`");
+                }
+                self.pos -= 1;
+                expr
+            }
+            token => return schema_error(format!("unexpected token {:?}", token)),
+        };
+        self.pos += 1;
+        loop {
+            if self.eat(".") {
+                match self.tokens.get(self.pos).cloned() {
+                    Some(Token::Ident(field)) => {
+                        self.pos += 1;
+                        expr = Expr::Field(Box::new(expr), field);
+                    }
+                    _ => return schema_error("expected a field name after `.`"),
+                }
+            } else if self.eat("[") {
+                let index = self.parse_binary(0)?;
+                if !self.eat("]") {
+                    return schema_error("expected `]`");
+                }
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+}
+
+impl Expr {
+    fn from_yaml(yaml: &Yaml) -> Result<Expr, Error> {
+        match yaml {
+            Yaml::Integer(value) => Ok(Expr::Int(*value as i128)),
+            Yaml::Boolean(value) => Ok(Expr::Bool(*value)),
+            Yaml::String(source) => Expr::parse(source),
+            _ => schema_error(format!("invalid expression {:?}", yaml)),
+        }
+    }
+
+    fn parse(source: &str) -> Result<Expr, Error> {
+        let mut parser = ExprParser {
+            tokens: tokenize(source)?,
+            pos: 0,
+        };
+        let expr = parser.parse_binary(0)?;
+        if parser.pos != parser.tokens.len() {
+            return schema_error(format!("unexpected trailing tokens in `{}`", source));
+        }
+        Ok(expr)
+    }
+
+    fn eval(&self, context: &Context, scopes: &[&TypeDef]) -> Result<Value, Error> {
+        let value = match self {
+            Expr::Int(value) => Value::Int(*value),
+            Expr::Str(value) => Value::Str(value.clone()),
+            Expr::Bool(value) => Value::Bool(*value),
+            Expr::Name(name) => match name.as_str() {
+                "_" => context
+                    .item
+                    .cloned()
+                    .ok_or(Error::Schema("`_` outside `repeat-until`".to_owned()))?,
+                "_parent" => match context.parent {
+                    Some(parent) => Value::Struct(parent.fields.to_vec()),
+                    None => return schema_error("`_parent` of the top-level type"),
+                },
+                "_root" => {
+                    let mut root = context;
+                    while let Some(parent) = root.parent {
+                        root = parent;
+                    }
+                    Value::Struct(root.fields.to_vec())
+                }
+                _ => match context.fields.iter().find(|(id, _)| id == name) {
+                    Some((_, value)) => value.clone(),
+                    None => return schema_error(format!("unknown field `{}`", name)),
+                },
+            },
+            Expr::EnumLiteral(enum_name, label) => {
+                let labels = lookup(scopes, |scope| scope.enums.get(enum_name))
+                    .ok_or_else(|| Error::Schema(format!("unknown enum `{}`", enum_name)))?
+                    .1;
+                match labels.iter().find(|(_, l)| *l == label) {
+                    Some((value, _)) => Value::Int(*value),
+                    None => {
+                        return schema_error(format!("unknown label `{}::{}`", enum_name, label))
+                    }
+                }
+            }
+            Expr::Field(base, field) => {
+                if matches!(&**base, Expr::Name(name) if name == "_io") {
+                    return match field.as_str() {
+                        "pos" => Ok(Value::Int(context.io_pos as i128)),
+                        "size" => Ok(Value::Int(context.io_size as i128)),
+                        "eof" => Ok(Value::Bool(context.io_pos >= context.io_size)),
+                        _ => schema_error(format!("unsupported `_io.{}`", field)),
+                    };
+                }
+                match (base.eval(context, scopes)?, field.as_str()) {
+                    (Value::Array(items), "size" | "length") => Value::Int(items.len() as i128),
+                    (Value::Bytes(bytes), "size" | "length") => Value::Int(bytes.len() as i128),
+                    (Value::Str(string), "length") => Value::Int(string.chars().count() as i128),
+                    (Value::Enum { value, .. }, "to_i") => Value::Int(value),
+                    (value @ Value::Struct(_), _) => match value.get(field) {
+                        Some(value) => value.clone(),
+                        None => return schema_error(format!("unknown field `{}`", field)),
+                    },
+                    (value, _) => {
+                        return schema_error(format!("no field `{}` on {:?}", field, value))
+                    }
+                }
+            }
+            Expr::Index(base, index) => {
+                let index = index.eval(context, scopes)?.as_usize()?;
+                match base.eval(context, scopes)? {
+                    Value::Array(items) => items.get(index).cloned(),
+                    Value::Bytes(bytes) => bytes.get(index).map(|byte| Value::Int(*byte as i128)),
+                    _ => return schema_error("indexing a non-array value"),
+                }
+                .ok_or(Error::Schema(format!("index {} out of range", index)))?
+            }
+            Expr::Unary(op, operand) => match (op, operand.eval(context, scopes)?) {
+                (UnaryOp::Neg, Value::Int(value)) => Value::Int(value.wrapping_neg()),
+                (UnaryOp::Neg, Value::Float(value)) => Value::Float(-value),
+                (UnaryOp::BitNot, Value::Int(value)) => Value::Int(!value),
+                (UnaryOp::Not, value) => Value::Bool(!value.truthy()?),
+                (op, value) => {
+                    return schema_error(format!("cannot apply {:?} to {:?}", op, value))
+                }
+            },
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = lhs.eval(context, scopes)?;
+                match op {
+                    BinaryOp::And if !lhs.truthy()? => return Ok(Value::Bool(false)),
+                    BinaryOp::Or if lhs.truthy()? => return Ok(Value::Bool(true)),
+                    BinaryOp::And | BinaryOp::Or => {
+                        return Ok(Value::Bool(rhs.eval(context, scopes)?.truthy()?))
+                    }
+                    _ => {}
+                }
+                binary(*op, lhs, rhs.eval(context, scopes)?)?
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value, Error> {
+    let as_int = |value: &Value| match value {
+        Value::Int(value) | Value::Enum { value, .. } => Some(*value),
+        _ => None,
+    };
+    if let (Some(a), Some(b)) = (as_int(&lhs), as_int(&rhs)) {
+        let value = match op {
+            BinaryOp::Eq => return Ok(Value::Bool(a == b)),
+            BinaryOp::Ne => return Ok(Value::Bool(a != b)),
+            BinaryOp::Lt => return Ok(Value::Bool(a < b)),
+            BinaryOp::Le => return Ok(Value::Bool(a <= b)),
+            BinaryOp::Gt => return Ok(Value::Bool(a > b)),
+            BinaryOp::Ge => return Ok(Value::Bool(a >= b)),
+            BinaryOp::BitOr => a | b,
+            BinaryOp::BitXor => a ^ b,
+            BinaryOp::BitAnd => a & b,
+            BinaryOp::Shl => a.checked_shl(b as u32).unwrap_or(0),
+            BinaryOp::Shr => a.checked_shr(b as u32).unwrap_or(0),
+            BinaryOp::Add => a.wrapping_add(b),
+            BinaryOp::Sub => a.wrapping_sub(b),
+            BinaryOp::Mul => a.wrapping_mul(b),
+            BinaryOp::Div | BinaryOp::Rem if b == 0 => return schema_error("division by zero"),
+            BinaryOp::Div => a.wrapping_div_euclid(b),
+            BinaryOp::Rem => a.wrapping_rem_euclid(b),
+            BinaryOp::And | BinaryOp::Or => unreachable!(),
+        };
+        return Ok(Value::Int(value));
+    }
+    match (op, lhs, rhs) {
+        (BinaryOp::Eq, lhs, rhs) => Ok(Value::Bool(lhs == rhs)),
+        (BinaryOp::Ne, lhs, rhs) => Ok(Value::Bool(lhs != rhs)),
+        (BinaryOp::Add, Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+        (op, lhs, rhs) => schema_error(format!("cannot apply {:?} to {:?} and {:?}", op, lhs, rhs)),
+    }
+}
+
+impl Value {
+    fn truthy(&self) -> Result<bool, Error> {
+        match self {
+            Value::Bool(value) => Ok(*value),
+            value => schema_error(format!("expected a boolean, found {:?}", value)),
+        }
+    }
+
+    fn as_usize(&self) -> Result<usize, Error> {
+        match self {
+            Value::Int(value) | Value::Enum { value, .. } => usize::try_from(*value)
+                .or(schema_error(format!("expected a size, found {}", value))),
+            value => schema_error(format!("expected an integer, found {:?}", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+const SAMPLE: &str = r#"
+meta:
+  id: sample
+  endian: le
+seq:
+  - id: magic
+    contents: [0x53, "MP"]
+  - id: version
+    type: u1
+  - id: count
+    type: u2
+  - id: entries
+    type: entry
+    repeat: expr
+    repeat-expr: count
+  - id: trailer
+    type: strz
+    encoding: ASCII
+    if: version >= 2
+  - id: rest
+    size-eos: true
+types:
+  entry:
+    seq:
+      - id: kind
+        type: u1
+        enum: kind
+      - id: len
+        type: u1
+      - id: body
+        size: len
+        type:
+          switch-on: kind
+          cases:
+            'kind::number': number
+            _: raw
+    types:
+      number:
+        seq:
+          - id: value
+            type: s4be
+      raw:
+        seq:
+          - id: data
+            size-eos: true
+enums:
+  kind:
+    1: number
+    2: raw
+"#;
+
+#[test]
+fn schema_parse() {
+    let schema = Schema::from_yaml(SAMPLE).unwrap();
+    let data = [
+        0x53, 0x4d, 0x50, 0x02, 0x02, 0x00, // header
+        0x01, 0x04, 0xff, 0xff, 0xff, 0xfe, // number -2
+        0x02, 0x02, 0xaa, 0xbb, // raw
+        0x68, 0x69, 0x00, // "hi"
+        0x01, 0x02,
+    ];
+    let mut bytes = ByteCode::new(&data);
+    let value = schema.parse(&mut bytes).unwrap();
+    assert!(bytes.is_end());
+
+    assert_eq!(value.get("version"), Some(&Value::Int(2)));
+    let Some(Value::Array(entries)) = value.get("entries") else {
+        panic!("entries: {:?}", value);
+    };
+    assert_eq!(
+        entries[0].get("kind"),
+        Some(&Value::Enum {
+            label: Some("number".to_owned()),
+            value: 1
+        })
+    );
+    assert_eq!(
+        entries[0].get("body").unwrap().get("value"),
+        Some(&Value::Int(-2))
+    );
+    assert_eq!(
+        entries[1].get("body").unwrap().get("data"),
+        Some(&Value::Bytes(vec![0xaa, 0xbb]))
+    );
+    assert_eq!(value.get("trailer"), Some(&Value::Str("hi".to_owned())));
+    assert_eq!(value.get("rest"), Some(&Value::Bytes(vec![0x01, 0x02])));
+}
+
+#[test]
+fn schema_parse_condition_and_repeat() {
+    let schema = Schema::from_yaml(
+        r#"
+meta:
+  endian: be
+seq:
+  - id: flags
+    type: u1
+  - id: extra
+    type: u2
+    if: (flags & 0x80) != 0
+  - id: items
+    type: u1
+    repeat: until
+    repeat-until: _ == 0 or _io.eof
+"#,
+    )
+    .unwrap();
+
+    let value = schema
+        .parse(&mut ByteCode::new(&[0x00, 0x05, 0x00, 0x07]))
+        .unwrap();
+    assert_eq!(value.get("extra"), None);
+    assert_eq!(
+        value.get("items"),
+        Some(&Value::Array(vec![Value::Int(5), Value::Int(0)]))
+    );
+
+    let value = schema
+        .parse(&mut ByteCode::new(&[0x80, 0x01, 0x02, 0x03]))
+        .unwrap();
+    assert_eq!(value.get("extra"), Some(&Value::Int(0x0102)));
+    assert_eq!(value.get("items"), Some(&Value::Array(vec![Value::Int(3)])));
+}
+
+#[test]
+fn schema_parse_errors() {
+    let schema = Schema::from_yaml(SAMPLE).unwrap();
+    let mut bytes = ByteCode::new(&[0x53, 0x4d, 0x51]);
+    match schema.parse(&mut bytes) {
+        Err(Error::Parse { pos, .. }) => assert_eq!(pos, 0),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let mut bytes = ByteCode::new(&[0x53, 0x4d, 0x50, 0x01, 0x01, 0x00, 0x01, 0x04, 0xff]);
+    match schema.parse(&mut bytes) {
+        Err(Error::Parse { pos, .. }) => assert_eq!(pos, 8),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    assert!(matches!(Schema::from_yaml("seq: ["), Err(Error::Yaml(_))));
+    assert!(matches!(
+        Schema::from_yaml("seq:\n  - id: a\n    repeat: sometimes"),
+        Err(Error::Schema(_))
+    ));
+    let schema = Schema::from_yaml("seq:\n  - id: a\n    size: 0\n    repeat: eos").unwrap();
+    match schema.parse(&mut ByteCode::new(&[0, 0])) {
+        Err(Error::Parse { pos, .. }) => assert_eq!(pos, 0),
+        result => panic!("unexpected result: {:?}", result),
+    }
+    let schema = Schema::from_yaml(
+        "seq:\n  - id: a\n    size: 0\n    repeat: until\n    repeat-until: false",
+    )
+    .unwrap();
+    match schema.parse(&mut ByteCode::new(&[0, 0])) {
+        Err(Error::Parse { pos, .. }) => assert_eq!(pos, 0),
+        result => panic!("unexpected result: {:?}", result),
+    }
+    let schema = Schema::from_yaml(
+        "seq:\n  - id: n\n    type: u1\n  - id: a\n    size: 0\n    repeat: expr\n    repeat-expr: n",
+    )
+    .unwrap();
+    match schema.parse(&mut ByteCode::new(&[0xff, 0])) {
+        Err(Error::Parse { pos, .. }) => assert_eq!(pos, 1),
+        result => panic!("unexpected result: {:?}", result),
+    }
+    assert!(schema.parse(&mut ByteCode::new(&[1, 0])).is_ok());
+    let schema = Schema::from_yaml(
+        "seq:\n  - id: a\n    type: node\ntypes:\n  node:\n    seq:\n      - id: next\n        type: node",
+    )
+    .unwrap();
+    assert!(matches!(
+        schema.parse(&mut ByteCode::new(&[0, 0])),
+        Err(Error::Parse { .. })
+    ));
+    let schema = Schema::from_yaml("seq:\n  - id: a\n    type: u2").unwrap();
+    assert!(matches!(
+        schema.parse(&mut ByteCode::new(&[0, 0])),
+        Err(Error::Schema(_))
+    ));
+}
+
+#[test]
+fn expressions() {
+    let context = Context {
+        fields: &[
+            ("a".to_owned(), Value::Int(6)),
+            ("s".to_owned(), Value::Bytes(vec![1, 2, 3])),
+        ],
+        parent: None,
+        item: None,
+        io_pos: 2,
+        io_size: 4,
+    };
+    let eval = |source: &str| Expr::parse(source).unwrap().eval(&context, &[]).unwrap();
+    assert_eq!(eval("a * 2 + 1"), Value::Int(13));
+    assert_eq!(eval("(a + 2) * 2"), Value::Int(16));
+    assert_eq!(eval("-a % 4"), Value::Int(2));
+    assert_eq!(
+        eval("-(-0x7fffffffffffffffffffffffffffffff - 1)"),
+        Value::Int(i128::MIN)
+    );
+    assert_eq!(eval("(1 << 127) / -1"), Value::Int(i128::MIN));
+    assert_eq!(eval("(1 << 127) % -1"), Value::Int(0));
+    assert_eq!(eval("a << 2 | 1"), Value::Int(25));
+    assert_eq!(eval("s.size + s[2]"), Value::Int(6));
+    assert_eq!(eval("a > 5 and not _io.eof"), Value::Bool(true));
+    assert_eq!(eval("0x10 == 16 and 0b11 == 3"), Value::Bool(true));
+    assert_eq!(eval("'ab' + \"c\" == 'abc'"), Value::Bool(true));
+    assert!(Expr::parse("a +").is_err());
+    assert!(Expr::parse("(a").is_err());
+}
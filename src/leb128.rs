@@ -0,0 +1,343 @@
+use crate::{ByteCode, Error};
+
+/// The longest encoding of a 64-bit value.
+const MAX_LEN: usize = 10;
+
+impl<'a> ByteCode<'a> {
+    /// Decodes an unsigned LEB128 value, as used by DWARF and WebAssembly.
+    /// Moves the pointer forward past the encoded bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding is truncated or does not fit in `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xe5, 0x8e, 0x26, 0x00]);
+    /// assert_eq!(bytes.take_uleb128(), 624485);
+    /// assert_eq!(bytes.pos(), 3);
+    /// ```
+    pub fn take_uleb128(&mut self) -> u64 {
+        self.try_take_uleb128()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Decodes a signed LEB128 value, as used by DWARF and WebAssembly.
+    /// Moves the pointer forward past the encoded bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding is truncated or does not fit in `i64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xc0, 0xbb, 0x78]);
+    /// assert_eq!(bytes.take_sleb128(), -123456);
+    /// ```
+    pub fn take_sleb128(&mut self) -> i64 {
+        self.try_take_sleb128()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Decodes a Protocol Buffers base-128 varint.
+    /// Moves the pointer forward past the encoded bytes.
+    ///
+    /// The encoding is the same as unsigned LEB128.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding is truncated or does not fit in `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x96, 0x01]);
+    /// assert_eq!(bytes.take_varint_u64(), 150);
+    /// ```
+    pub fn take_varint_u64(&mut self) -> u64 {
+        self.take_uleb128()
+    }
+
+    /// Decodes an unsigned LEB128 value. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_uleb128`](ByteCode::take_uleb128).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0xe5, 0x8e]);
+    /// assert!(matches!(bytes.try_take_uleb128(), Err(Error::OutOfBounds { .. })));
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn try_take_uleb128(&mut self) -> Result<u64, Error> {
+        let (value, len) = self.leb128(false)?;
+        self.skip(len);
+        Ok(value)
+    }
+
+    /// Decodes a signed LEB128 value. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_sleb128`](ByteCode::take_sleb128).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x7f]);
+    /// assert_eq!(bytes.try_take_sleb128().unwrap(), -1);
+    /// assert!(bytes.try_take_sleb128().is_err());
+    /// ```
+    pub fn try_take_sleb128(&mut self) -> Result<i64, Error> {
+        let (value, len) = self.leb128(true)?;
+        self.skip(len);
+        Ok(value as i64)
+    }
+
+    /// Decodes a Protocol Buffers base-128 varint. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_varint_u64`](ByteCode::take_varint_u64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x96, 0x01, 0x80]);
+    /// assert_eq!(bytes.try_take_varint_u64().unwrap(), 150);
+    /// assert!(bytes.try_take_varint_u64().is_err());
+    /// ```
+    pub fn try_take_varint_u64(&mut self) -> Result<u64, Error> {
+        self.try_take_uleb128()
+    }
+
+    /// Decodes an `i32` in the 7-bit encoded format of .NET's `BinaryReader.Read7BitEncodedInt`.
+    /// Moves the pointer forward past the encoded bytes.
+    ///
+    /// The bytes are unsigned LEB128 of the value's bits, so negative values always take 5 bytes.
+    /// Unlike [`take_uleb128`](ByteCode::take_uleb128), the encoding is at most 5 bytes long
+    /// and the 5th byte may only hold the top 4 bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding is truncated or does not fit in 32 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x80, 0x01, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+    /// assert_eq!(bytes.take_into_7bit_encoded_int(), 128);
+    /// assert_eq!(bytes.take_into_7bit_encoded_int(), -1);
+    /// ```
+    pub fn take_into_7bit_encoded_int(&mut self) -> i32 {
+        self.try_take_into_7bit_encoded_int()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Decodes an `i64` in the 7-bit encoded format of .NET's `BinaryReader.Read7BitEncodedInt64`.
+    /// Moves the pointer forward past the encoded bytes.
+    ///
+    /// The encoding is at most 10 bytes long and the 10th byte may only hold the top bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding is truncated or does not fit in 64 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut data = vec![0xff; 9];
+    /// data.push(0x01);
+    /// let mut bytes = ByteCode::new(&data);
+    /// assert_eq!(bytes.take_into_7bit_encoded_int64(), -1);
+    /// ```
+    pub fn take_into_7bit_encoded_int64(&mut self) -> i64 {
+        self.try_take_into_7bit_encoded_int64()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Decodes an `i32` in .NET's 7-bit encoded format. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_7bit_encoded_int`](ByteCode::take_into_7bit_encoded_int).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0xff, 0xff, 0xff, 0xff, 0x10]);
+    /// assert_eq!(bytes.try_take_into_7bit_encoded_int(), Err(Error::Overflow { pos: 0 }));
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn try_take_into_7bit_encoded_int(&mut self) -> Result<i32, Error> {
+        let mut value = 0u32;
+        for (i, byte) in self.inner.iter().enumerate() {
+            // The 5th byte holds bits 28..32 and must end the encoding.
+            if i == 4 && *byte > 0x0f {
+                return Err(Error::Overflow { pos: self.pos });
+            }
+            value |= ((byte & 0x7f) as u32) << (i * 7);
+            if byte & 0x80 == 0 {
+                self.skip(i + 1);
+                return Ok(value as i32);
+            }
+        }
+        Err(Error::OutOfBounds {
+            pos: self.pos,
+            requested: self.inner.len() + 1,
+            available: self.inner.len(),
+        })
+    }
+
+    /// Decodes an `i64` in .NET's 7-bit encoded format. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_7bit_encoded_int64`](ByteCode::take_into_7bit_encoded_int64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x7f, 0x80]);
+    /// assert_eq!(bytes.try_take_into_7bit_encoded_int64().unwrap(), 127);
+    /// assert!(bytes.try_take_into_7bit_encoded_int64().is_err());
+    /// ```
+    pub fn try_take_into_7bit_encoded_int64(&mut self) -> Result<i64, Error> {
+        // The 64-bit format has the same limits as unsigned LEB128.
+        self.try_take_uleb128().map(|value| value as i64)
+    }
+
+    /// Returns the decoded value, sign-extended if `signed`, and the length of its encoding.
+    fn leb128(&self, signed: bool) -> Result<(u64, usize), Error> {
+        let mut value = 0u64;
+        for (i, byte) in self.inner.iter().enumerate() {
+            let payload = byte & 0x7f;
+            if i == MAX_LEN - 1 {
+                // Only bit 63 is left; the other bits must be its sign extension.
+                let valid = match signed {
+                    true => payload == 0x00 || payload == 0x7f,
+                    false => payload <= 0x01,
+                };
+                if !valid || byte & 0x80 != 0 {
+                    return Err(Error::Overflow { pos: self.pos });
+                }
+            }
+            value |= (payload as u64) << (i * 7);
+            if byte & 0x80 == 0 {
+                let shift = (i + 1) * 7;
+                if signed && shift < 64 && byte & 0x40 != 0 {
+                    value |= !0 << shift;
+                }
+                return Ok((value, i + 1));
+            }
+        }
+        Err(Error::OutOfBounds {
+            pos: self.pos,
+            requested: self.inner.len() + 1,
+            available: self.inner.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn take_uleb128() {
+    let mut bytes = ByteCode::new(&[0x00, 0x7f, 0x80, 0x01, 0x80, 0x80, 0x00]);
+    assert_eq!(bytes.take_uleb128(), 0);
+    assert_eq!(bytes.take_uleb128(), 127);
+    assert_eq!(bytes.take_uleb128(), 128);
+    assert_eq!(bytes.take_uleb128(), 0); // padded
+    assert!(bytes.is_end());
+
+    let mut data = vec![0xff; 9];
+    data.push(0x01);
+    assert_eq!(ByteCode::new(&data).take_uleb128(), u64::MAX);
+}
+
+#[test]
+fn take_sleb128() {
+    let mut bytes = ByteCode::new(&[0x02, 0x7e, 0xff, 0x00, 0x81, 0x7f, 0x80, 0x7f]);
+    assert_eq!(bytes.take_sleb128(), 2);
+    assert_eq!(bytes.take_sleb128(), -2);
+    assert_eq!(bytes.take_sleb128(), 127);
+    assert_eq!(bytes.take_sleb128(), -127);
+    assert_eq!(bytes.take_sleb128(), -128);
+    assert!(bytes.is_end());
+
+    let mut data = vec![0x80; 9];
+    data.push(0x7f);
+    assert_eq!(ByteCode::new(&data).take_sleb128(), i64::MIN);
+    let mut data = vec![0xff; 9];
+    data.push(0x00);
+    assert_eq!(ByteCode::new(&data).take_sleb128(), i64::MAX);
+}
+
+#[test]
+fn try_take_leb128() {
+    let mut bytes = ByteCode::new(&[0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78, 0x80]);
+    assert_eq!(bytes.try_take_uleb128(), Ok(624485));
+    assert_eq!(bytes.try_take_sleb128(), Ok(-123456));
+    assert!(bytes.try_take_uleb128().is_err());
+    assert_eq!(bytes.pos(), 6);
+}
+
+#[test]
+fn try_take_leb128_overflow() {
+    let mut data = vec![0xff; 9];
+    data.push(0x02);
+    let mut bytes = ByteCode::new(&data);
+    assert_eq!(bytes.try_take_uleb128(), Err(Error::Overflow { pos: 0 }));
+
+    let mut data = vec![0x80; 10];
+    data.push(0x00);
+    assert!(ByteCode::new(&data).try_take_varint_u64().is_err());
+
+    let mut data = vec![0x80; 9];
+    data.push(0x01);
+    assert!(ByteCode::new(&data).try_take_sleb128().is_err());
+}
+
+#[test]
+fn take_into_7bit_encoded_int() {
+    let mut bytes = ByteCode::new(&[
+        0x00, 0x7f, 0xac, 0x02, 0xff, 0xff, 0xff, 0xff, 0x07, 0x80, 0x80, 0x80, 0x80, 0x08,
+    ]);
+    assert_eq!(bytes.take_into_7bit_encoded_int(), 0);
+    assert_eq!(bytes.take_into_7bit_encoded_int(), 127);
+    assert_eq!(bytes.take_into_7bit_encoded_int(), 300);
+    assert_eq!(bytes.take_into_7bit_encoded_int(), i32::MAX);
+    assert_eq!(bytes.take_into_7bit_encoded_int(), i32::MIN);
+    assert!(bytes.is_end());
+
+    let mut bytes = ByteCode::new(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+    assert_eq!(
+        bytes.try_take_into_7bit_encoded_int(),
+        Err(Error::Overflow { pos: 0 })
+    );
+    assert_eq!(bytes.try_take_into_7bit_encoded_int64(), Ok(0));
+    let mut bytes = ByteCode::new(&[0x80, 0x80]);
+    assert!(bytes.try_take_into_7bit_encoded_int().is_err());
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+#[should_panic]
+fn take_uleb128_truncated() {
+    ByteCode::new(&[0x80, 0x80]).take_uleb128();
+}
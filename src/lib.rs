@@ -50,7 +50,108 @@
 //! let _string = bytes.take_into_string(3); // "foo".to_owned()
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod align;
+mod annotate;
+mod bitrev;
+mod bits;
+mod block;
+mod bookmark;
+#[cfg(feature = "bytes")]
+mod buf;
+mod checkpoint;
+mod checksum;
+mod convert;
 mod core;
+mod coverage;
+mod diagnostic;
+mod diff;
+mod disasm;
+mod endian;
+mod error;
+mod expect;
+mod fallible;
+mod flow;
+mod fourcc;
+mod grep;
+mod hexdump;
+mod instruction;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+mod leb128;
+#[cfg(feature = "memmap2")]
+mod mmap;
+mod mutf8;
+mod narrow;
+mod nibble;
+mod overlay;
+mod owned;
+mod panic_style;
+mod pool;
+mod prefixed;
+mod primitive;
+mod scan;
+mod snapshot;
+mod sparse;
+mod stats;
+#[cfg(feature = "std")]
+mod stream;
+mod strictness;
+mod string;
+mod strtab;
+mod tlv;
 mod util;
+mod window;
+mod writer;
 
+pub mod formats;
+#[cfg(feature = "kaitai")]
+pub mod kaitai;
+
+pub use crate::annotate::{FieldType, Recorder, Span, SpanKind};
+pub use crate::bitrev::{reverse_bits_per_byte, reverse_bits_per_word};
+pub use crate::bits::BitReader;
+pub use crate::block::{Block, BlockIter};
+pub use crate::checkpoint::{Checkpoint, FrozenPosition};
+pub use crate::convert::{FixedSize, FromByteCode};
 pub use crate::core::ByteCode;
+pub use crate::coverage::{Claim, Coverage, Overlap};
+pub use crate::diagnostic::Diagnostic;
+pub use crate::diff::Compared;
+pub use crate::disasm::{
+    Disassembler, Instruction, InstructionSet, Listing, Opcode, Operand, OperandKind,
+};
+pub use crate::endian::{BigEndian, ByteOrder, Endian, LittleEndian};
+pub use crate::error::Error;
+pub use crate::flow::{BasicBlock, ControlFlowGraph, Flow};
+pub use crate::fourcc::FourCc;
+pub use crate::grep::{Grep, GrepMatch};
+pub use crate::hexdump::HexDump;
+pub use crate::instruction::InstructionMap;
+pub use crate::iter::{ChunksExactInto, Iter};
+pub use crate::nibble::Nibbles;
+pub use crate::overlay::Overlay;
+pub use crate::owned::ByteCodeBuf;
+pub use crate::panic_style::PanicStyle;
+pub use crate::pool::StringPool;
+pub use crate::scan::{Strings, TextEncoding};
+pub use crate::sparse::SparseBuffer;
+pub use crate::stats::DecodeStats;
+#[cfg(feature = "std")]
+pub use crate::stream::ByteCodeStream;
+pub use crate::strictness::Strictness;
+pub use crate::strtab::StringTable;
+pub use crate::tlv::{Tlv, TlvIter};
+pub use crate::window::Window;
+pub use crate::writer::ByteCodeWriter;
+#[cfg(feature = "derive")]
+pub use bytecode_derive::{FixedSize, FromByteCode};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::convert::take_vec;
+}
@@ -0,0 +1,48 @@
+use std::{fs::File, io, path::Path};
+
+use memmap2::Mmap;
+
+use crate::{ByteCode, ByteCodeBuf};
+
+impl ByteCode<'_> {
+    /// Maps the file at `path` into memory and returns an owning reader over the mapping,
+    /// so that multi-gigabyte inputs are paged in as they are read instead of loaded up front.
+    ///
+    /// [`ByteCodeBuf::parse`] gives access to the whole `ByteCode` API over the mapping.
+    ///
+    /// The file must not be modified or truncated while it is mapped, as the mapping
+    /// would then change under the reader; other processes are not prevented from doing so.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of opening or mapping the file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut dump = ByteCode::from_file("firmware.bin").unwrap();
+    /// let magic = dump.parse(|bytes| bytes.take_into_u32());
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<ByteCodeBuf<Mmap>> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only, and modifying the file while it is mapped
+        // is ruled out in the documentation above, as memmap2 requires.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(ByteCodeBuf::from_backing(map))
+    }
+}
+
+#[test]
+fn from_file() {
+    let path = std::env::temp_dir().join(format!("bytecode-mmap-{}", std::process::id()));
+    std::fs::write(&path, [0x00, 0x00, 0x01, 0x00, 0xff]).unwrap();
+    let mut buf = ByteCode::from_file(&path).unwrap();
+    assert_eq!(buf.parse(|bytes| bytes.take_into_u32()), 256);
+    assert_eq!(buf.take(1), [0xff]);
+    assert!(buf.is_end());
+    drop(buf);
+    std::fs::remove_file(&path).unwrap();
+    assert!(ByteCode::from_file(&path).is_err());
+}
@@ -0,0 +1,126 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{ByteCode, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Returns the string consisting of the given number of bytes from the beginning of the slice,
+    /// decoded as modified UTF-8 (MUTF-8), the string encoding used by Java class files and DEX.
+    /// Moves the pointer forward by given number.
+    ///
+    /// The overlong encoding of NUL (`0xC0 0x80`) is decoded into `'\0'`,
+    /// and surrogate pairs encoded as two separate 3-byte sequences are combined into one character.
+    /// A surrogate without its pair cannot be represented in a `String` and is replaced with `U+FFFD`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x66, 0xc0, 0x80, 0x6f, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_into_mutf8_string(4), "f\0o".to_owned());
+    /// ```
+    pub fn take_into_mutf8_string(&mut self, num: usize) -> String {
+        self.try_take_into_mutf8_string(num)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the string consisting of the given number of bytes from the beginning of the slice,
+    /// decoded as modified UTF-8 (MUTF-8).
+    /// Moves the pointer forward by given number. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_mutf8_string`](ByteCode::take_into_mutf8_string),
+    /// which also reports malformed sequences as [`Error::InvalidUtf8`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x66, 0xc0, 0x80, 0xc0]);
+    /// assert_eq!(bytes.try_take_into_mutf8_string(3).unwrap(), "f\0");
+    /// assert_eq!(
+    ///     bytes.try_take_into_mutf8_string(1),
+    ///     Err(Error::InvalidUtf8 { pos: 3, len: 1 })
+    /// );
+    /// ```
+    pub fn try_take_into_mutf8_string(&mut self, num: usize) -> Result<String, Error> {
+        let string = decode(self.try_peek(num)?).ok_or(Error::InvalidUtf8 {
+            pos: self.pos,
+            len: num,
+        })?;
+        self.skip(num);
+        Ok(string)
+    }
+}
+
+/// Decodes modified UTF-8, returning `None` if the bytes are malformed.
+pub(crate) fn decode(bytes: &[u8]) -> Option<String> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let first = bytes[i] as u16;
+        let (unit, width) = match first {
+            0x00..=0x7f => (first, 1),
+            0xc0..=0xdf => {
+                let second = continuation(bytes.get(i + 1))?;
+                ((first & 0x1f) << 6 | second, 2)
+            }
+            0xe0..=0xef => {
+                let second = continuation(bytes.get(i + 1))?;
+                let third = continuation(bytes.get(i + 2))?;
+                ((first & 0x0f) << 12 | second << 6 | third, 3)
+            }
+            _ => return None,
+        };
+        units.push(unit);
+        i += width;
+    }
+    Some(
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect(),
+    )
+}
+
+fn continuation(byte: Option<&u8>) -> Option<u16> {
+    match byte {
+        Some(&byte) if byte & 0xc0 == 0x80 => Some((byte & 0x3f) as u16),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+use alloc::borrow::ToOwned;
+
+#[test]
+fn take_into_mutf8_string() {
+    let mut bytes = ByteCode::new(&[0x66, 0x6f, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(bytes.take_into_mutf8_string(3), "foo".to_owned());
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+fn take_into_mutf8_string_nul() {
+    let mut bytes = ByteCode::new(&[0xc0, 0x80, 0x61]);
+    assert_eq!(bytes.take_into_mutf8_string(3), "\0a".to_owned());
+}
+
+#[test]
+fn take_into_mutf8_string_surrogate_pair() {
+    // U+1F600 is encoded as the surrogate pair D83D DE00.
+    let mut bytes = ByteCode::new(&[0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]);
+    assert_eq!(bytes.take_into_mutf8_string(6), "\u{1f600}".to_owned());
+}
+
+#[test]
+fn take_into_mutf8_string_lone_surrogate() {
+    let mut bytes = ByteCode::new(&[0xed, 0xa0, 0xbd, 0x61]);
+    assert_eq!(bytes.take_into_mutf8_string(4), "\u{fffd}a".to_owned());
+}
+
+#[test]
+#[should_panic]
+fn take_into_mutf8_string_truncated() {
+    let mut bytes = ByteCode::new(&[0x61, 0xe3, 0x81]);
+    bytes.take_into_mutf8_string(3);
+}
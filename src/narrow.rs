@@ -0,0 +1,103 @@
+use crate::{ByteCode, ByteCodeWriter, Error};
+
+macro_rules! checked_positions {
+    ($($ty:ident, $pos:ident, $len_since:ident;)*) => {
+        impl<'a> ByteCode<'a> {
+            $(
+                #[doc = concat!("Returns the pointer position as `", stringify!($ty), "`, for storing it in a ", stringify!($ty), " offset field.")]
+                ///
+                /// # Errors
+                ///
+                #[doc = concat!("Returns [`Error::Overflow`] if the position does not fit in `", stringify!($ty), "`.")]
+                pub fn $pos(&self) -> Result<$ty, Error> {
+                    $ty::try_from(self.pos).map_err(|_| Error::Overflow { pos: self.pos })
+                }
+            )*
+        }
+
+        impl ByteCodeWriter {
+            $(
+                #[doc = concat!("Returns the position at which the next value will be written as `", stringify!($ty), "`,")]
+                #[doc = concat!("for emitting it into a ", stringify!($ty), " offset table.")]
+                ///
+                /// # Errors
+                ///
+                #[doc = concat!("Returns [`Error::Overflow`] if the position does not fit in `", stringify!($ty), "`.")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCodeWriter;
+                ///
+                /// let mut writer = ByteCodeWriter::new();
+                /// writer.put_slice(&[0; 3]);
+                #[doc = concat!("assert_eq!(writer.", stringify!($pos), "(), Ok(3));")]
+                /// ```
+                pub fn $pos(&self) -> Result<$ty, Error> {
+                    $ty::try_from(self.pos()).map_err(|_| Error::Overflow { pos: self.pos() })
+                }
+
+                #[doc = concat!("Returns the number of bytes written since `start` as `", stringify!($ty), "`,")]
+                /// for patching it into a length field written before them.
+                ///
+                /// # Errors
+                ///
+                #[doc = concat!("Returns [`Error::Overflow`] with `start` if the length does not fit in `", stringify!($ty), "`.")]
+                ///
+                /// # Panics
+                ///
+                /// Panics if `start` is past the bytes written so far.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCodeWriter;
+                ///
+                /// let mut writer = ByteCodeWriter::new();
+                #[doc = concat!("writer.put_", stringify!($ty), "(0);")]
+                /// let start = writer.pos();
+                /// writer.put_string("body");
+                #[doc = concat!("let len = writer.", stringify!($len_since), "(start).unwrap();")]
+                #[doc = concat!("writer.patch_", stringify!($ty), "(0, len);")]
+                #[doc = concat!("assert_eq!(len, 4);")]
+                /// ```
+                pub fn $len_since(&self, start: usize) -> Result<$ty, Error> {
+                    let pos = self.pos();
+                    if start > pos {
+                        panic!("start {} is past the {} bytes written", start, pos);
+                    }
+                    $ty::try_from(pos - start).map_err(|_| Error::Overflow { pos: start })
+                }
+            )*
+        }
+    };
+}
+
+checked_positions! {
+    u16, pos_u16, len_since_u16;
+    u32, pos_u32, len_since_u32;
+}
+
+#[test]
+fn checked_positions() {
+    let data = alloc::vec![0; 0x10001];
+    let mut bytes = ByteCode::new(&data);
+    bytes.skip(0xffff);
+    assert_eq!(bytes.pos_u16(), Ok(0xffff));
+    bytes.skip(1);
+    assert_eq!(bytes.pos_u16(), Err(Error::Overflow { pos: 0x10000 }));
+    assert_eq!(bytes.pos_u32(), Ok(0x10000));
+
+    let mut writer = ByteCodeWriter::from(data);
+    assert_eq!(writer.pos_u16(), Err(Error::Overflow { pos: 0x10001 }));
+    assert_eq!(writer.len_since_u16(2), Ok(0xffff));
+    assert_eq!(writer.len_since_u16(1), Err(Error::Overflow { pos: 1 }));
+    writer.put_u8(0);
+    assert_eq!(writer.len_since_u32(0), Ok(0x10002));
+}
+
+#[test]
+#[should_panic]
+fn len_since_past_end() {
+    let _ = ByteCodeWriter::new().len_since_u32(1);
+}
@@ -0,0 +1,266 @@
+use crate::{ByteCode, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Returns the high 4 bits of the first byte.
+    /// Moves the pointer forward 1, discarding the low 4 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12, 0x34]);
+    /// assert_eq!(bytes.take_nibble_high(), 0x1);
+    /// assert_eq!(bytes.pos(), 1);
+    /// ```
+    pub fn take_nibble_high(&mut self) -> u8 {
+        self.try_take_nibble_high()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the high 4 bits of the first byte.
+    /// Moves the pointer forward 1, discarding the low 4 bits. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_nibble_high`](ByteCode::take_nibble_high).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12]);
+    /// assert_eq!(bytes.try_take_nibble_high().unwrap(), 0x1);
+    /// assert!(bytes.try_take_nibble_high().is_err());
+    /// ```
+    pub fn try_take_nibble_high(&mut self) -> Result<u8, Error> {
+        Ok(self.try_take_into_u8()? >> 4)
+    }
+
+    /// Returns the low 4 bits of the first byte.
+    /// Moves the pointer forward 1, discarding the high 4 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12, 0x34]);
+    /// assert_eq!(bytes.take_nibble_low(), 0x2);
+    /// assert_eq!(bytes.pos(), 1);
+    /// ```
+    pub fn take_nibble_low(&mut self) -> u8 {
+        self.try_take_nibble_low()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the low 4 bits of the first byte.
+    /// Moves the pointer forward 1, discarding the high 4 bits. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_nibble_low`](ByteCode::take_nibble_low).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12]);
+    /// assert_eq!(bytes.try_take_nibble_low().unwrap(), 0x2);
+    /// assert!(bytes.try_take_nibble_low().is_err());
+    /// ```
+    pub fn try_take_nibble_low(&mut self) -> Result<u8, Error> {
+        Ok(self.try_take_into_u8()? & 0x0f)
+    }
+
+    /// Returns a view reading 4 bits at a time, high nibble first.
+    ///
+    /// The view shares the position of this `ByteCode`: the pointer moves forward
+    /// once both nibbles of a byte are taken. Dropping the view halfway through a byte
+    /// consumes the rest of that byte, so byte reads always resume on a byte boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12, 0x34, 0x56]);
+    /// let mut nibbles = bytes.nibbles();
+    /// assert_eq!(nibbles.take_nibble(), 0x1);
+    /// assert_eq!(nibbles.take_nibble(), 0x2);
+    /// assert_eq!(nibbles.take_nibble(), 0x3);
+    /// drop(nibbles);
+    /// assert_eq!(bytes.take_into_u8(), 0x56);
+    /// ```
+    pub fn nibbles<'b>(&'b mut self) -> Nibbles<'b, 'a> {
+        Nibbles {
+            bytes: self,
+            low_first: false,
+            half: false,
+        }
+    }
+}
+
+/// A view over a [`ByteCode`] reading 4 bits at a time, created by [`ByteCode::nibbles`].
+#[derive(Debug)]
+pub struct Nibbles<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    low_first: bool,
+    /// `true` if the first nibble of the current byte has been taken.
+    half: bool,
+}
+
+impl Nibbles<'_, '_> {
+    /// Reads the low nibble of each byte before the high one, as in little-endian 4-bit pixel formats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12]);
+    /// let mut nibbles = bytes.nibbles().low_first();
+    /// assert_eq!(nibbles.take_nibble(), 0x2);
+    /// assert_eq!(nibbles.take_nibble(), 0x1);
+    /// ```
+    pub fn low_first(mut self) -> Self {
+        self.low_first = true;
+        self
+    }
+
+    /// Returns the next nibble without moving forward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no bytes left.
+    pub fn peek_nibble(&self) -> u8 {
+        self.try_peek_nibble()
+            .unwrap_or_else(|error| self.bytes.fail(error))
+    }
+
+    /// Returns the next nibble.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no bytes left.
+    pub fn take_nibble(&mut self) -> u8 {
+        self.try_take_nibble()
+            .unwrap_or_else(|error| self.bytes.fail(error))
+    }
+
+    /// Returns the next nibble without moving forward.
+    ///
+    /// Fallible version of [`peek_nibble`](Nibbles::peek_nibble).
+    pub fn try_peek_nibble(&self) -> Result<u8, Error> {
+        let byte = self.bytes.try_peek_into_u8()?;
+        Ok(if self.half != self.low_first {
+            byte & 0x0f
+        } else {
+            byte >> 4
+        })
+    }
+
+    /// Returns the next nibble. Nothing is consumed on error.
+    ///
+    /// Fallible version of [`take_nibble`](Nibbles::take_nibble).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x12]);
+    /// let mut nibbles = bytes.nibbles();
+    /// assert_eq!(nibbles.try_take_nibble().unwrap(), 0x1);
+    /// assert_eq!(nibbles.try_take_nibble().unwrap(), 0x2);
+    /// assert!(nibbles.try_take_nibble().is_err());
+    /// ```
+    pub fn try_take_nibble(&mut self) -> Result<u8, Error> {
+        let nibble = self.try_peek_nibble()?;
+        if self.half {
+            self.bytes.next();
+        }
+        self.half = !self.half;
+        Ok(nibble)
+    }
+
+    /// Returns `true` if the next nibble starts a byte.
+    pub fn is_aligned(&self) -> bool {
+        !self.half
+    }
+
+    /// Skips the rest of the current byte, if a nibble of it has been taken.
+    pub fn align_to_byte(&mut self) {
+        if self.half {
+            self.bytes.next();
+            self.half = false;
+        }
+    }
+
+    /// Returns the number of nibbles left.
+    pub fn remaining(&self) -> usize {
+        self.bytes.as_slice().len() * 2 - self.half as usize
+    }
+}
+
+impl Drop for Nibbles<'_, '_> {
+    fn drop(&mut self) {
+        self.align_to_byte();
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn take_nibble_high_low() {
+    let mut bytes = ByteCode::new(&[0xab, 0xcd]);
+    assert_eq!(bytes.take_nibble_high(), 0xa);
+    assert_eq!(bytes.take_nibble_low(), 0xd);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn nibbles() {
+    let mut bytes = ByteCode::new(&[0x12, 0x34, 0x56]);
+    bytes.next();
+    let mut nibbles = bytes.nibbles();
+    assert_eq!(nibbles.remaining(), 4);
+    assert_eq!(nibbles.take_nibble(), 0x3);
+    assert!(!nibbles.is_aligned());
+    assert_eq!(nibbles.remaining(), 3);
+    assert_eq!(nibbles.peek_nibble(), 0x4);
+    nibbles.align_to_byte();
+    assert!(nibbles.is_aligned());
+    assert_eq!(nibbles.take_nibble(), 0x5);
+    assert_eq!(nibbles.take_nibble(), 0x6);
+    assert_eq!(nibbles.remaining(), 0);
+    drop(nibbles);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn nibbles_low_first() {
+    let mut bytes = ByteCode::new(&[0x21, 0x43]);
+    let mut nibbles = bytes.nibbles().low_first();
+    let values: Vec<u8> = (0..3).map(|_| nibbles.take_nibble()).collect();
+    assert_eq!(values, [1, 2, 3]);
+    drop(nibbles);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn try_take_nibble_high_low() {
+    let mut bytes = ByteCode::new(&[0xab]);
+    assert_eq!(bytes.try_take_nibble_low().unwrap(), 0xb);
+    assert!(bytes.try_take_nibble_high().is_err());
+    assert!(bytes.is_end());
+}
+
+#[test]
+#[should_panic]
+fn nibbles_out_of_range() {
+    let mut bytes = ByteCode::new(&[0x12]);
+    let mut nibbles = bytes.nibbles();
+    nibbles.take_nibble();
+    nibbles.take_nibble();
+    nibbles.take_nibble();
+}
@@ -0,0 +1,240 @@
+use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
+use core::ops::Range;
+
+use crate::{ByteCode, Endian};
+
+/// A copy-on-write editor over borrowed data, for patching large inputs without copying them up front.
+///
+/// Edits are kept in a side table of runs and take precedence over the base data when reading.
+/// The edited data is only built by [`materialize`](Overlay::materialize).
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCode;
+///
+/// let data = [0x00; 8];
+/// let mut overlay = ByteCode::new(&data).overlay();
+/// overlay.patch_u16(2, 0xcafe);
+/// overlay.patch_slice(4, &[0xba, 0xbe]);
+/// assert_eq!(overlay.edits().count(), 1);
+/// assert_eq!(*overlay.read(1..5), [0x00, 0xca, 0xfe, 0xba]);
+/// assert_eq!(overlay.materialize(), [0x00, 0x00, 0xca, 0xfe, 0xba, 0xbe, 0x00, 0x00]);
+/// assert_eq!(data, [0x00; 8]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlay<'a> {
+    base: &'a [u8],
+    edits: BTreeMap<usize, Vec<u8>>,
+    endian: Endian,
+}
+
+impl<'a> Overlay<'a> {
+    /// Creates a new `Overlay` over `base` with no edits.
+    ///
+    /// Multi-byte values are written in big-endian order until changed with [`set_endian`](Overlay::set_endian).
+    pub fn new(base: &'a [u8]) -> Self {
+        Overlay {
+            base,
+            edits: BTreeMap::new(),
+            endian: Endian::Big,
+        }
+    }
+
+    /// Returns the byte order used by `patch_*` writes.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Sets the byte order used by `patch_*` writes.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns the number of elements, which edits do not change.
+    pub fn len(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Returns `true` if the base data is empty.
+    pub fn is_empty(&self) -> bool {
+        self.base.is_empty()
+    }
+
+    /// Returns the unedited data.
+    pub fn base(&self) -> &'a [u8] {
+        self.base
+    }
+
+    /// Returns `true` if any edit has been made.
+    ///
+    /// Edits writing the bytes already there still count.
+    pub fn is_modified(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    /// Returns an iterator over the edited runs with their positions, in order.
+    /// Overlapping and adjacent edits are merged into one run.
+    pub fn edits(&self) -> impl Iterator<Item = (usize, &[u8])> {
+        self.edits.iter().map(|(pos, run)| (*pos, run.as_slice()))
+    }
+
+    /// Overwrites the bytes at `pos` with a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos..pos + slice.len()` is out of range.
+    pub fn patch_slice(&mut self, pos: usize, slice: &[u8]) {
+        let end = pos.saturating_add(slice.len());
+        if end > self.base.len() {
+            panic!(
+                "patch range {}..{} out of range for length {}",
+                pos,
+                end,
+                self.base.len()
+            );
+        }
+        if slice.is_empty() {
+            return;
+        }
+        // Merge the runs touching the new one, so that runs never overlap or adjoin.
+        let touching: Vec<usize> = self
+            .edits
+            .range(..=end)
+            .rev()
+            .take_while(|(start, run)| *start + run.len() >= pos)
+            .map(|(start, _)| *start)
+            .collect();
+        let start = touching.last().map_or(pos, |first| pos.min(*first));
+        let mut merged = self.base[start..end].to_vec();
+        for run_start in touching.into_iter().rev() {
+            let run = self.edits.remove(&run_start).unwrap();
+            let run_end = run_start + run.len();
+            if run_end > start + merged.len() {
+                merged.extend_from_slice(&self.base[start + merged.len()..run_end]);
+            }
+            merged[run_start - start..run_end - start].copy_from_slice(&run);
+        }
+        merged[pos - start..end - start].copy_from_slice(slice);
+        self.edits.insert(start, merged);
+    }
+
+    /// Overwrites the `u8` at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of range.
+    pub fn patch_u8(&mut self, pos: usize, value: u8) {
+        self.patch_slice(pos, &[value]);
+    }
+
+    /// Overwrites the `u16` at `pos` in the byte order set by [`set_endian`](Overlay::set_endian).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the 2 bytes at `pos` are out of range.
+    pub fn patch_u16(&mut self, pos: usize, value: u16) {
+        let bytes = match self.endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.patch_slice(pos, &bytes);
+    }
+
+    /// Overwrites the `u32` at `pos` in the byte order set by [`set_endian`](Overlay::set_endian).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the 4 bytes at `pos` are out of range.
+    pub fn patch_u32(&mut self, pos: usize, value: u32) {
+        let bytes = match self.endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.patch_slice(pos, &bytes);
+    }
+
+    /// Returns the edited bytes in `range`, borrowed from the base data if no edit touches them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of range or its start is greater than its end.
+    pub fn read(&self, range: Range<usize>) -> Cow<'a, [u8]> {
+        let base = &self.base[range.clone()];
+        let mut runs = self
+            .edits
+            .range(..range.end)
+            .rev()
+            .take_while(|(start, run)| *start + run.len() > range.start)
+            .peekable();
+        if runs.peek().is_none() {
+            return Cow::Borrowed(base);
+        }
+        let mut result = base.to_vec();
+        for (start, run) in runs {
+            let from = range.start.max(*start);
+            let to = range.end.min(start + run.len());
+            result[from - range.start..to - range.start]
+                .copy_from_slice(&run[from - start..to - start]);
+        }
+        Cow::Owned(result)
+    }
+
+    /// Returns the edited data.
+    pub fn materialize(&self) -> Vec<u8> {
+        self.read(0..self.base.len()).into_owned()
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns a copy-on-write editor over the whole slice, including consumed elements.
+    pub fn overlay(&self) -> Overlay<'a> {
+        Overlay::new(self.original())
+    }
+}
+
+#[test]
+fn patch_merges_runs() {
+    let base: Vec<u8> = (0..16).collect();
+    let mut overlay = Overlay::new(&base);
+    overlay.patch_slice(2, &[0xa2, 0xa3]);
+    overlay.patch_slice(8, &[0xa8]);
+    overlay.patch_slice(5, &[0xa5]);
+    assert_eq!(overlay.edits().count(), 3);
+
+    overlay.patch_slice(4, &[0xb4, 0xb5, 0xb6, 0xb7]);
+    assert_eq!(
+        overlay.edits().collect::<Vec<_>>(),
+        [(2, &[0xa2, 0xa3, 0xb4, 0xb5, 0xb6, 0xb7, 0xa8][..])]
+    );
+    overlay.patch_slice(0, &[]);
+    overlay.patch_u8(15, 0xff);
+    assert_eq!(overlay.edits().count(), 2);
+    assert!(matches!(overlay.read(9..15), Cow::Borrowed(_)));
+    assert_eq!(*overlay.read(7..10), [0xb7, 0xa8, 9]);
+    assert_eq!(
+        overlay.materialize(),
+        [0, 1, 0xa2, 0xa3, 0xb4, 0xb5, 0xb6, 0xb7, 0xa8, 9, 10, 11, 12, 13, 14, 0xff]
+    );
+}
+
+#[test]
+fn patch_inside_run() {
+    let base = [0; 6];
+    let mut overlay = Overlay::new(&base);
+    overlay.set_endian(Endian::Little);
+    overlay.patch_u32(1, 0x04030201);
+    overlay.patch_u8(2, 0xff);
+    assert_eq!(
+        overlay.edits().collect::<Vec<_>>(),
+        [(1, &[1, 0xff, 3, 4][..])]
+    );
+    assert!(overlay.read(0..0).is_empty());
+    assert!(overlay.is_modified());
+}
+
+#[test]
+#[should_panic]
+fn patch_out_of_range() {
+    Overlay::new(&[0; 4]).patch_u32(1, 0);
+}
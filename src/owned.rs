@@ -0,0 +1,283 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use crate::{ByteCode, Endian, Error, PanicStyle};
+
+/// An owning counterpart of [`ByteCode`], for when the reader must outlive the code that loaded the data.
+///
+/// By default the data is shared, so cloning a `ByteCodeBuf` is cheap and gives an independent pointer.
+/// Other backings, such as a memory-mapped file, can be used with [`from_backing`](ByteCodeBuf::from_backing).
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCodeBuf;
+///
+/// fn load() -> ByteCodeBuf {
+///     ByteCodeBuf::new(vec![0x00, 0x02, 0x66, 0x6f, 0x6f])
+/// }
+///
+/// let mut buf = load();
+/// let len = buf.parse(|bytes| bytes.take_into_u16());
+/// assert_eq!(buf.take(len as usize), b"fo");
+/// assert_eq!(buf.pos(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ByteCodeBuf<D = Arc<[u8]>> {
+    data: D,
+    pos: usize,
+    endian: Endian,
+    panic_style: PanicStyle,
+}
+
+impl ByteCodeBuf {
+    /// Creates a new `ByteCodeBuf` owning the given data.
+    ///
+    /// Multi-byte values are read in big-endian order until changed with [`set_endian`](ByteCodeBuf::set_endian).
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Self {
+        ByteCodeBuf::from_backing(data.into())
+    }
+}
+
+impl<D: AsRef<[u8]>> ByteCodeBuf<D> {
+    /// Creates a new `ByteCodeBuf` over any owned backing of the data.
+    ///
+    /// Multi-byte values are read in big-endian order until changed with [`set_endian`](ByteCodeBuf::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeBuf;
+    ///
+    /// let mut buf = ByteCodeBuf::from_backing([0x00, 0x2a]);
+    /// assert_eq!(buf.parse(|bytes| bytes.take_into_u16()), 42);
+    /// ```
+    pub fn from_backing(data: D) -> Self {
+        ByteCodeBuf {
+            data,
+            pos: 0,
+            endian: Endian::Big,
+            panic_style: PanicStyle::default(),
+        }
+    }
+
+    /// Returns a `ByteCode` borrowing the data, with the same pointer position, byte order and panic style.
+    ///
+    /// Moving its pointer does not affect this `ByteCodeBuf`; use [`parse`](ByteCodeBuf::parse) for that.
+    pub fn as_bytecode(&self) -> ByteCode<'_> {
+        let mut bytes = ByteCode::new(self.data.as_ref());
+        bytes += self.pos;
+        bytes.endian = self.endian;
+        bytes.panic_style = self.panic_style;
+        bytes
+    }
+
+    /// Runs `f` on a `ByteCode` over the data, keeping the pointer position, byte order and panic style it leaves behind.
+    ///
+    /// This gives access to the whole `ByteCode` API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCodeBuf, Endian};
+    ///
+    /// let mut buf = ByteCodeBuf::new(vec![0x01, 0x00, 0x02, 0x00]);
+    /// let first = buf.parse(|bytes| {
+    ///     bytes.set_endian(Endian::Little);
+    ///     bytes.take_into_u16()
+    /// });
+    /// assert_eq!(first, 1);
+    /// assert_eq!(buf.endian(), Endian::Little);
+    /// assert_eq!(buf.parse(|bytes| bytes.take_into_u16()), 2);
+    /// ```
+    pub fn parse<T>(&mut self, f: impl FnOnce(&mut ByteCode<'_>) -> T) -> T {
+        let mut bytes = self.as_bytecode();
+        let value = f(&mut bytes);
+        let (pos, endian, panic_style) = (bytes.pos, bytes.endian, bytes.panic_style);
+        self.pos = pos;
+        self.endian = endian;
+        self.panic_style = panic_style;
+        value
+    }
+
+    /// Returns the whole data, including consumed elements.
+    pub fn original(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// Extracts the backing of the data.
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    /// Returns the number of elements.
+    ///
+    /// Note that consumed elements are also counted.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.data.as_ref().len()
+    }
+
+    /// Returns the pointer position.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns `true` if all elements have been consumed.
+    pub fn is_end(&self) -> bool {
+        self.pos == self.data.as_ref().len()
+    }
+
+    /// Returns the byte order used by `take_into_*` reads.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Sets the byte order used by `take_into_*` reads.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns how panicking methods describe their errors.
+    pub fn panic_style(&self) -> PanicStyle {
+        self.panic_style
+    }
+
+    /// Sets how panicking methods describe their errors, as [`ByteCode::set_panic_style`] does.
+    pub fn set_panic_style(&mut self, panic_style: PanicStyle) {
+        self.panic_style = panic_style;
+    }
+
+    /// Returns a reference to subslice corresponding to the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `num` elements remain.
+    pub fn peek(&self, num: usize) -> &[u8] {
+        let bytes = self.as_bytecode();
+        bytes
+            .try_peek(num)
+            .unwrap_or_else(|error| bytes.fail(error))
+    }
+
+    /// Returns a reference to subslice corresponding to the given size.
+    ///
+    /// Fallible version of [`peek`](ByteCodeBuf::peek).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeBuf;
+    ///
+    /// let buf = ByteCodeBuf::new(vec![0, 1, 2]);
+    /// assert_eq!(buf.try_peek(2).unwrap(), [0, 1]);
+    /// assert!(buf.try_peek(4).is_err());
+    /// ```
+    pub fn try_peek(&self, num: usize) -> Result<&[u8], Error> {
+        self.as_bytecode().try_peek(num)
+    }
+
+    /// Move the pointer forward by the given number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `num` elements remain.
+    pub fn skip(&mut self, num: usize) {
+        self.parse(|bytes| bytes.skip(num));
+    }
+
+    /// Move the pointer forward by the given number.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`skip`](ByteCodeBuf::skip).
+    pub fn try_skip(&mut self, num: usize) -> Result<(), Error> {
+        self.parse(|bytes| bytes.try_skip(num))
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size.
+    /// Moves the pointer forward by the length of subslice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `num` elements remain.
+    pub fn take(&mut self, num: usize) -> Vec<u8> {
+        self.parse(|bytes| bytes.take(num))
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size.
+    /// Moves the pointer forward by the length of subslice. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take`](ByteCodeBuf::take).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCodeBuf, Error};
+    ///
+    /// let mut buf = ByteCodeBuf::new(vec![0, 1, 2]);
+    /// assert_eq!(buf.try_take(2).unwrap(), [0, 1]);
+    /// assert_eq!(
+    ///     buf.try_take(2),
+    ///     Err(Error::OutOfBounds { pos: 2, requested: 2, available: 1 })
+    /// );
+    /// ```
+    pub fn try_take(&mut self, num: usize) -> Result<Vec<u8>, Error> {
+        self.parse(|bytes| bytes.try_take(num))
+    }
+}
+
+impl From<Vec<u8>> for ByteCodeBuf {
+    fn from(data: Vec<u8>) -> Self {
+        ByteCodeBuf::new(data)
+    }
+}
+
+impl From<Arc<[u8]>> for ByteCodeBuf {
+    fn from(data: Arc<[u8]>) -> Self {
+        ByteCodeBuf::new(data)
+    }
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn parse() {
+    let mut buf = ByteCodeBuf::from(vec![0, 1, 2, 3, 4, 5, 6, 7]);
+    buf.skip(2);
+    assert_eq!(buf.parse(|bytes| bytes.take(3)), [2, 3, 4]);
+    assert_eq!(buf.pos(), 5);
+    assert_eq!(buf.peek(2), [5, 6]);
+    assert_eq!(buf.as_bytecode().pos(), 5);
+    assert_eq!(buf.take(3), [5, 6, 7]);
+    assert!(buf.is_end());
+    assert_eq!(buf.len(), 8);
+}
+
+#[test]
+fn clone_is_independent() {
+    let data: Arc<[u8]> = Arc::from(&[0x00, 0x01, 0x00, 0x02][..]);
+    let mut first = ByteCodeBuf::from(Arc::clone(&data));
+    first.set_endian(Endian::Little);
+    first.skip(2);
+    let mut second = first.clone();
+    assert_eq!(second.parse(|bytes| bytes.take_into_u16()), 0x0200);
+    assert_eq!(first.pos(), 2);
+    assert!(Arc::ptr_eq(&first.into_inner(), &data));
+}
+
+#[test]
+#[should_panic(expected = "pointer at 0x2")]
+fn peek_out_of_range_context() {
+    let mut buf = ByteCodeBuf::new(vec![0, 1, 2]);
+    buf.set_panic_style(PanicStyle::Context);
+    buf.skip(2);
+    buf.peek(2);
+}
+
+#[test]
+#[should_panic]
+fn peek_out_of_range() {
+    let mut buf = ByteCodeBuf::new(vec![0, 1, 2]);
+    buf.skip(2);
+    buf.peek(2);
+}
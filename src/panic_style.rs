@@ -0,0 +1,132 @@
+use crate::{ByteCode, Error};
+
+/// How the panicking methods of [`ByteCode`] describe the error they panic with.
+///
+/// Every panicking read and pointer move of a `ByteCode`, [`BitReader`](crate::BitReader), [`Nibbles`](crate::Nibbles),
+/// [`ByteCodeBuf`](crate::ByteCodeBuf) or `ByteCodeStream` panics with the same [`Error`] as its `try_*` version, including invalid arguments,
+/// so code can switch to the fallible methods, or to [`FromByteCode`](crate::FromByteCode) impls, one call at a time.
+/// Builders and writers such as [`ByteCodeWriter`](crate::ByteCodeWriter) panic with their own messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicStyle {
+    /// Panic with the error message only.
+    #[default]
+    Plain,
+    /// Panic with the error message, the pointer position and a hex dump of the rows around the error.
+    Context,
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns how panicking methods describe their errors.
+    pub fn panic_style(&self) -> PanicStyle {
+        self.panic_style
+    }
+
+    /// Sets how panicking methods describe their errors, such as to find where a legacy parser
+    /// fails on untrusted input before moving it to the `try_*` methods.
+    ///
+    /// Child readers created from this one inherit this setting.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use bytecode::{ByteCode, PanicStyle};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0x02]);
+    /// bytes.set_panic_style(PanicStyle::Context);
+    /// bytes.skip(2);
+    /// // Panics with the error followed by:
+    /// // pointer at 0x2
+    /// //          00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F
+    /// // 00000000 00 01 02                                         |...|
+    /// bytes.take_into_u16();
+    /// ```
+    pub fn set_panic_style(&mut self, panic_style: PanicStyle) {
+        self.panic_style = panic_style;
+    }
+
+    /// Panics with `error`, described as set by [`set_panic_style`](ByteCode::set_panic_style).
+    pub(crate) fn fail(&self, error: Error) -> ! {
+        match self.panic_style {
+            PanicStyle::Plain => panic!("{}", error),
+            PanicStyle::Context => {
+                let pos = error.pos();
+                let dump = self
+                    .hexdump()
+                    .range(pos.saturating_sub(16)..pos.saturating_add(16));
+                panic!("{}\npointer at {:#x}\n{}", error, self.pos, dump)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+fn panic_message(read: impl FnOnce() + std::panic::UnwindSafe) -> std::string::String {
+    let payload = std::panic::catch_unwind(read).unwrap_err();
+    match payload.downcast::<std::string::String>() {
+        Ok(message) => *message,
+        Err(payload) => (*payload.downcast::<&str>().unwrap()).into(),
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn panic_style() {
+    let data: alloc::vec::Vec<u8> = (0..40).collect();
+    let message = panic_message(|| {
+        let mut bytes = ByteCode::new(&data);
+        bytes.skip(38);
+        bytes.take_into_u32();
+    });
+    assert_eq!(
+        message,
+        "out of bounds at 0x26: requested 4 bytes, but only 2 remain"
+    );
+
+    let message = panic_message(|| {
+        let mut bytes = ByteCode::new(&data);
+        bytes.set_panic_style(PanicStyle::Context);
+        bytes.skip(38);
+        bytes.take_bytecode(1).take_ref(4);
+    });
+    let lines: alloc::vec::Vec<&str> = message.lines().collect();
+    assert_eq!(
+        lines[..2],
+        [
+            "out of bounds at 0x0: requested 4 bytes, but only 1 remain",
+            "pointer at 0x0"
+        ]
+    );
+    assert_eq!(lines.len(), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn panic_style_pointer_moves_and_arguments() {
+    let data = [0u8; 8];
+    let message = panic_message(|| {
+        let mut bytes = ByteCode::new(&data);
+        bytes.set_panic_style(PanicStyle::Context);
+        bytes.skip(6);
+        bytes.seek(9);
+    });
+    assert!(message.starts_with(
+        "out of bounds at 0x6: requested 3 bytes, but only 2 remain\npointer at 0x6\n"
+    ));
+
+    let message = panic_message(|| {
+        let mut bytes = ByteCode::new(&data);
+        bytes.set_panic_style(PanicStyle::Context);
+        bytes.skip(2);
+        bytes -= 3;
+    });
+    assert!(message.contains("pointer at 0x2"));
+
+    let message = panic_message(|| {
+        let mut bytes = ByteCode::new(&data);
+        bytes.set_panic_style(PanicStyle::Context);
+        bytes.align_to(0);
+    });
+    assert!(
+        message.starts_with("invalid argument at 0x0: alignment must not be 0\npointer at 0x0\n")
+    );
+}
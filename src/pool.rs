@@ -0,0 +1,189 @@
+use alloc::vec::Vec;
+
+use crate::{ByteCode, Error};
+
+/// The length marking a missing entry, as mruby RITE binaries do for null symbols.
+const NULL_LEN: usize = 0xffff;
+
+/// A pool of length-prefixed, NUL-terminated strings referenced by index,
+/// as in the symbol tables of mruby RITE binaries.
+///
+/// Created by [`ByteCode::take_string_pool`]. Entries borrow from the data and are not required
+/// to be valid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCode;
+///
+/// let mut bytes = ByteCode::new(b"\x00\x04puts\x00\xff\xff\x00\x00\x00");
+/// let pool = bytes.take_string_pool(3);
+/// assert_eq!(pool.get_str(0), Some("puts"));
+/// assert_eq!(pool.get(1), None);
+/// assert_eq!(pool.get(2), Some(&b""[..]));
+/// assert!(bytes.is_end());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringPool<'a> {
+    entries: Vec<Option<&'a [u8]>>,
+}
+
+impl<'a> StringPool<'a> {
+    /// Returns the number of entries, including missing ones.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the pool has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the bytes of the entry at `index`, without the terminating NUL.
+    ///
+    /// Returns `None` if `index` is out of range or the entry is missing.
+    pub fn get(&self, index: usize) -> Option<&'a [u8]> {
+        self.entries.get(index).copied().flatten()
+    }
+
+    /// Returns the entry at `index` as a string slice.
+    ///
+    /// Returns `None` if `index` is out of range, the entry is missing, or it is not valid UTF-8.
+    pub fn get_str(&self, index: usize) -> Option<&'a str> {
+        core::str::from_utf8(self.get(index)?).ok()
+    }
+
+    /// Returns an iterator over the entries in index order, with `None` for missing entries.
+    pub fn iter(&self) -> impl Iterator<Item = Option<&'a [u8]>> + '_ {
+        self.entries.iter().copied()
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Reads `count` entries, each a `u16` length in the byte order set by
+    /// [`set_endian`](ByteCode::set_endian), that many bytes and a NUL terminator.
+    /// Moves the pointer forward past the entries.
+    ///
+    /// A length of `0xffff` marks a missing entry with no bytes or terminator that follow it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry is out of range or not terminated by a NUL right after its bytes.
+    pub fn take_string_pool(&mut self, count: usize) -> StringPool<'a> {
+        self.try_take_string_pool(count)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Reads `count` length-prefixed, NUL-terminated entries. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_string_pool`](ByteCode::take_string_pool).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if an entry is out of range, or [`Error::Unexpected`]
+    /// if the byte after an entry is not NUL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(b"\x00\x02hi!");
+    /// assert_eq!(
+    ///     bytes.try_take_string_pool(1),
+    ///     Err(Error::Unexpected { pos: 4, expected: vec![0x00], found: vec![b'!'] })
+    /// );
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn try_take_string_pool(&mut self, count: usize) -> Result<StringPool<'a>, Error> {
+        let inner: &'a [u8] = self.inner;
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        for _ in 0..count {
+            let len = match inner.get(offset..offset + 2) {
+                Some(bytes) => self.endian.read_uint(bytes) as usize,
+                None => {
+                    return Err(Error::OutOfBounds {
+                        pos: self.pos + offset,
+                        requested: 2,
+                        available: inner.len() - offset,
+                    })
+                }
+            };
+            offset += 2;
+            if len == NULL_LEN {
+                entries.push(None);
+                continue;
+            }
+            let available = inner.len() - offset;
+            if len >= available {
+                return Err(Error::OutOfBounds {
+                    pos: self.pos + offset,
+                    requested: len + 1,
+                    available,
+                });
+            }
+            let terminator = inner[offset + len];
+            if terminator != 0 {
+                return Err(Error::Unexpected {
+                    pos: self.pos + offset + len,
+                    expected: alloc::vec![0],
+                    found: alloc::vec![terminator],
+                });
+            }
+            entries.push(Some(&inner[offset..offset + len]));
+            offset += len + 1;
+        }
+        self.skip(offset);
+        Ok(StringPool { entries })
+    }
+}
+
+#[test]
+fn take_string_pool() {
+    let data = b"\x03\x00abc\x00\xff\xff\x02\x00\xc3\xa9\x00\x01\x00\xff\x00";
+    let mut bytes = ByteCode::new(data);
+    bytes.set_endian(crate::Endian::Little);
+    let pool = bytes.take_string_pool(4);
+    assert_eq!(pool.len(), 4);
+    assert_eq!(pool.get_str(0), Some("abc"));
+    assert_eq!(pool.get_str(1), None);
+    assert_eq!(pool.get_str(2), Some("é"));
+    assert_eq!(pool.get(3), Some(&[0xff][..]));
+    assert_eq!(pool.get_str(3), None);
+    assert_eq!(pool.get(4), None);
+    assert_eq!(pool.iter().filter(Option::is_none).count(), 1);
+    assert!(bytes.is_end());
+    assert!(bytes.take_string_pool(0).is_empty());
+}
+
+#[test]
+fn try_take_string_pool_out_of_bounds() {
+    let mut bytes = ByteCode::new(b"\x00\x01a\x00\x00\x02ab");
+    assert_eq!(
+        bytes.try_take_string_pool(2),
+        Err(Error::OutOfBounds {
+            pos: 6,
+            requested: 3,
+            available: 2
+        })
+    );
+    assert_eq!(
+        bytes.try_take_string_pool(3),
+        Err(Error::OutOfBounds {
+            pos: 6,
+            requested: 3,
+            available: 2
+        })
+    );
+    let mut bytes = ByteCode::new(b"\x00\x01a\x00\x00");
+    assert_eq!(
+        bytes.try_take_string_pool(2),
+        Err(Error::OutOfBounds {
+            pos: 4,
+            requested: 2,
+            available: 1
+        })
+    );
+    assert_eq!(bytes.pos(), 0);
+}
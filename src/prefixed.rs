@@ -0,0 +1,132 @@
+use crate::{ByteCode, Error};
+
+macro_rules! length_prefixed_reads {
+    ($($width:literal, $take:ident, $try_take:ident, $take_string:ident, $try_take_string:ident, $example:literal;)*) => {
+        impl<'a> ByteCode<'a> {
+            $(
+                #[doc = concat!("Reads a ", $width, "-byte length and returns a reference to the following bytes of that length, without copying.")]
+                /// The length is read in the byte order set by [`set_endian`](ByteCode::set_endian).
+                /// Moves the pointer forward past the length and the bytes.
+                ///
+                /// # Panics
+                ///
+                /// Panics if the length or the bytes are out of range.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($take), "(), b\"hi\");")]
+                /// assert!(bytes.is_end());
+                /// ```
+                pub fn $take(&mut self) -> &'a [u8] {
+                    self.$try_take()
+                        .unwrap_or_else(|error| self.fail(error))
+                }
+
+                #[doc = concat!("Reads a ", $width, "-byte length and returns a reference to the following bytes of that length, without copying.")]
+                /// The length is read in the byte order set by [`set_endian`](ByteCode::set_endian).
+                /// Moves the pointer forward past the length and the bytes. The pointer is not moved on error.
+                ///
+                #[doc = concat!("Fallible version of [`", stringify!($take), "`](ByteCode::", stringify!($take), ").")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, "[..", $width, " + 1]);")]
+                #[doc = concat!("assert!(bytes.", stringify!($try_take), "().is_err());")]
+                /// assert_eq!(bytes.pos(), 0);
+                /// ```
+                pub fn $try_take(&mut self) -> Result<&'a [u8], Error> {
+                    let bytes = self.peek_length_prefixed($width, usize::MAX)?;
+                    self.skip($width + bytes.len());
+                    Ok(bytes)
+                }
+
+                #[doc = concat!("Reads a ", $width, "-byte length and returns the string slice of the following bytes of that length, without copying.")]
+                /// The length is read in the byte order set by [`set_endian`](ByteCode::set_endian).
+                /// Moves the pointer forward past the length and the string.
+                ///
+                /// # Panics
+                ///
+                /// Panics if the length or the string is out of range, or if the string is not valid UTF-8.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($take_string), "(), \"hi\");")]
+                /// ```
+                pub fn $take_string(&mut self) -> &'a str {
+                    self.$try_take_string()
+                        .unwrap_or_else(|error| self.fail(error))
+                }
+
+                #[doc = concat!("Reads a ", $width, "-byte length and returns the string slice of the following bytes of that length, without copying.")]
+                /// The length is read in the byte order set by [`set_endian`](ByteCode::set_endian).
+                /// Moves the pointer forward past the length and the string. The pointer is not moved on error.
+                ///
+                #[doc = concat!("Fallible version of [`", stringify!($take_string), "`](ByteCode::", stringify!($take_string), ").")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::{ByteCode, Error};
+                ///
+                #[doc = concat!("let mut data = ", $example, ";")]
+                /// data[data.len() - 1] = 0xff;
+                /// let mut bytes = ByteCode::new(&data);
+                /// assert_eq!(
+                #[doc = concat!("    bytes.", stringify!($try_take_string), "(),")]
+                #[doc = concat!("    Err(Error::InvalidUtf8 { pos: ", $width, ", len: 2 })")]
+                /// );
+                /// ```
+                pub fn $try_take_string(&mut self) -> Result<&'a str, Error> {
+                    let bytes = self.peek_length_prefixed($width, usize::MAX)?;
+                    let string = core::str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8 {
+                        pos: self.pos + $width,
+                        len: bytes.len(),
+                    })?;
+                    self.skip($width + bytes.len());
+                    Ok(string)
+                }
+            )*
+        }
+    };
+}
+
+length_prefixed_reads! {
+    2, take_length_prefixed_slice_u16, try_take_length_prefixed_slice_u16, take_length_prefixed_str_u16, try_take_length_prefixed_str_u16, "[0x00, 0x02, 0x68, 0x69]";
+    4, take_length_prefixed_slice_u32, try_take_length_prefixed_slice_u32, take_length_prefixed_str_u32, try_take_length_prefixed_str_u32, "[0x00, 0x00, 0x00, 0x02, 0x68, 0x69]";
+}
+
+#[test]
+fn length_prefixed() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x03, 0x00, 0x66, 0x6f, 0x6f, 0x01, 0x00]);
+    assert_eq!(bytes.take_length_prefixed_slice_u16(), []);
+    bytes.set_endian(crate::Endian::Little);
+    assert_eq!(bytes.try_take_length_prefixed_str_u16(), Ok("foo"));
+    assert_eq!(
+        bytes.try_take_length_prefixed_slice_u16(),
+        Err(Error::OutOfBounds {
+            pos: 9,
+            requested: 1,
+            available: 0
+        })
+    );
+    assert_eq!(bytes.pos(), 7);
+    assert!(bytes.try_take_length_prefixed_slice_u32().is_err());
+    assert_eq!(bytes.pos(), 7);
+}
+
+#[test]
+#[should_panic]
+fn length_prefixed_out_of_bounds() {
+    ByteCode::new(&[0x00, 0x00, 0x00, 0x01]).take_length_prefixed_slice_u32();
+}
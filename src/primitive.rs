@@ -0,0 +1,479 @@
+use alloc::format;
+
+use crate::{ByteCode, ByteOrder, Endian, Error};
+
+macro_rules! primitive_reads {
+    ($($ty:ident, $size:literal, $take:ident, $peek:ident, $try_take:ident, $try_peek:ident, $example:literal, $expected:literal;)*) => {
+        impl<'a> ByteCode<'a> {
+            $(
+                #[doc = concat!("Returns the first ", $size, " elements of the slice converted into `", stringify!($ty), "`")]
+                /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+                #[doc = concat!("Moves the pointer forward ", $size, ".")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($take), "(), ", $expected, ");")]
+                /// ```
+                pub fn $take(&mut self) -> $ty {
+                    self.$try_take().unwrap_or_else(|error| self.fail(error))
+                }
+
+                #[doc = concat!("Returns the first ", $size, " elements of the slice converted into `", stringify!($ty), "`")]
+                /// in the byte order set by [`set_endian`](ByteCode::set_endian), without moving the pointer.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($peek), "(), ", $expected, ");")]
+                /// assert_eq!(bytes.pos(), 0);
+                /// ```
+                pub fn $peek(&self) -> $ty {
+                    self.$try_peek().unwrap_or_else(|error| self.fail(error))
+                }
+
+                #[doc = concat!("Returns the first ", $size, " elements of the slice converted into `", stringify!($ty), "`")]
+                /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+                #[doc = concat!("Moves the pointer forward ", $size, ". The pointer is not moved on error.")]
+                ///
+                #[doc = concat!("Fallible version of [`", stringify!($take), "`](ByteCode::", stringify!($take), ").")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($try_take), "().unwrap(), ", $expected, ");")]
+                #[doc = concat!("assert!(bytes.", stringify!($try_take), "().is_err());")]
+                /// ```
+                pub fn $try_take(&mut self) -> Result<$ty, Error> {
+                    let value = self.$try_peek()?;
+                    self.skip($size);
+                    Ok(value)
+                }
+
+                #[doc = concat!("Returns the first ", $size, " elements of the slice converted into `", stringify!($ty), "`")]
+                /// in the byte order set by [`set_endian`](ByteCode::set_endian), without moving the pointer.
+                ///
+                #[doc = concat!("Fallible version of [`", stringify!($peek), "`](ByteCode::", stringify!($peek), ").")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::ByteCode;
+                ///
+                #[doc = concat!("let bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($try_peek), "().unwrap(), ", $expected, ");")]
+                #[doc = concat!("assert!(ByteCode::new(&", $example, "[1..]).", stringify!($try_peek), "().is_err());")]
+                /// ```
+                pub fn $try_peek(&self) -> Result<$ty, Error> {
+                    let bytes: [u8; $size] = self.try_peek_array()?;
+                    Ok(match self.endian {
+                        Endian::Big => $ty::from_be_bytes(bytes),
+                        Endian::Little => $ty::from_le_bytes(bytes),
+                    })
+                }
+            )*
+        }
+    };
+}
+
+primitive_reads! {
+    i8, 1, take_into_i8, peek_into_i8, try_take_into_i8, try_peek_into_i8, "[0xff]", "-1";
+    i16, 2, take_into_i16, peek_into_i16, try_take_into_i16, try_peek_into_i16, "[0xff, 0xfe]", "-2";
+    i32, 4, take_into_i32, peek_into_i32, try_take_into_i32, try_peek_into_i32, "[0xff, 0xff, 0xff, 0xfd]", "-3";
+    i64, 8, take_into_i64, peek_into_i64, try_take_into_i64, try_peek_into_i64, "[0xff; 8]", "-1";
+    u64, 8, take_into_u64, peek_into_u64, try_take_into_u64, try_peek_into_u64, "[0xff; 8]", "u64::MAX";
+    u128, 16, take_into_u128, peek_into_u128, try_take_into_u128, try_peek_into_u128, "[0xff; 16]", "u128::MAX";
+    f32, 4, take_into_f32, peek_into_f32, try_take_into_f32, try_peek_into_f32, "[0x3f, 0xc0, 0x00, 0x00]", "1.5";
+    f64, 8, take_into_f64, peek_into_f64, try_take_into_f64, try_peek_into_f64, "[0xc0, 0x04, 0, 0, 0, 0, 0, 0]", "-2.5";
+}
+
+macro_rules! endian_generic_reads {
+    ($($ty:ident, $size:literal, $take:ident, $try_take:ident, $example:literal, $expected:literal;)*) => {
+        impl<'a> ByteCode<'a> {
+            $(
+                #[doc = concat!("Returns the first ", $size, " elements of the slice converted into `", stringify!($ty), "`")]
+                /// in the byte order `E`, chosen at compile time.
+                #[doc = concat!("Moves the pointer forward ", $size, ".")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::{ByteCode, LittleEndian};
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, ");")]
+                #[doc = concat!("assert_eq!(bytes.", stringify!($take), "::<LittleEndian>(), ", $expected, ");")]
+                /// ```
+                pub fn $take<E: ByteOrder>(&mut self) -> $ty {
+                    self.$try_take::<E>()
+                        .unwrap_or_else(|error| self.fail(error))
+                }
+
+                #[doc = concat!("Returns the first ", $size, " elements of the slice converted into `", stringify!($ty), "`")]
+                /// in the byte order `E`, chosen at compile time.
+                #[doc = concat!("Moves the pointer forward ", $size, ". The pointer is not moved on error.")]
+                ///
+                #[doc = concat!("Fallible version of [`", stringify!($take), "`](ByteCode::", stringify!($take), ").")]
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use bytecode::{BigEndian, ByteCode};
+                ///
+                #[doc = concat!("let mut bytes = ByteCode::new(&", $example, "[1..]);")]
+                #[doc = concat!("assert!(bytes.", stringify!($try_take), "::<BigEndian>().is_err());")]
+                /// ```
+                pub fn $try_take<E: ByteOrder>(&mut self) -> Result<$ty, Error> {
+                    let bytes: [u8; $size] = self.try_take_array()?;
+                    Ok(match E::ENDIAN {
+                        Endian::Big => $ty::from_be_bytes(bytes),
+                        Endian::Little => $ty::from_le_bytes(bytes),
+                    })
+                }
+            )*
+        }
+    };
+}
+
+endian_generic_reads! {
+    u16, 2, take_into_u16_endian, try_take_into_u16_endian, "[0x01, 0x00]", "1";
+    u32, 4, take_into_u32_endian, try_take_into_u32_endian, "[0x01, 0x00, 0x00, 0x00]", "1";
+    u64, 8, take_into_u64_endian, try_take_into_u64_endian, "[0x01, 0, 0, 0, 0, 0, 0, 0]", "1";
+    i16, 2, take_into_i16_endian, try_take_into_i16_endian, "[0xfe, 0xff]", "-2";
+    i32, 4, take_into_i32_endian, try_take_into_i32_endian, "[0xfe, 0xff, 0xff, 0xff]", "-2";
+    i64, 8, take_into_i64_endian, try_take_into_i64_endian, "[0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]", "-2";
+    f32, 4, take_into_f32_endian, try_take_into_f32_endian, "[0x00, 0x00, 0xc0, 0x3f]", "1.5";
+    f64, 8, take_into_f64_endian, try_take_into_f64_endian, "[0, 0, 0, 0, 0, 0, 0x04, 0xc0]", "-2.5";
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns the first byte without moving the pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0xff, 0x00]);
+    /// assert_eq!(bytes.peek_into_u8(), u8::MAX);
+    /// ```
+    pub fn peek_into_u8(&self) -> u8 {
+        self.try_peek_into_u8()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian), without moving the pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0x01, 0x00]);
+    /// assert_eq!(bytes.peek_into_u16(), 256);
+    /// ```
+    pub fn peek_into_u16(&self) -> u16 {
+        self.try_peek_into_u16()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian), without moving the pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00]);
+    /// assert_eq!(bytes.peek_into_u32(), 256);
+    /// ```
+    pub fn peek_into_u32(&self) -> u32 {
+        self.try_peek_into_u32()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 3 elements of the slice converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian), as in MP4 and FLAC headers.
+    /// Moves the pointer forward 3.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_into_u24(), 0x010000);
+    /// ```
+    pub fn take_into_u24(&mut self) -> u32 {
+        self.take_uint(3, self.endian) as u32
+    }
+
+    /// Returns the first 3 elements of the slice converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+    /// Moves the pointer forward 3. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u24`](ByteCode::take_into_u24).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xff, 0xff, 0xff, 0xff]);
+    /// assert_eq!(bytes.try_take_into_u24().unwrap(), 0xffffff);
+    /// assert!(bytes.try_take_into_u24().is_err());
+    /// ```
+    pub fn try_take_into_u24(&mut self) -> Result<u32, Error> {
+        Ok(self.try_take_uint(3, self.endian)? as u32)
+    }
+
+    /// Returns the first 6 elements of the slice converted into `u64`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+    /// Moves the pointer forward 6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_into_u48(), 0x0100_0000_0000);
+    /// ```
+    pub fn take_into_u48(&mut self) -> u64 {
+        self.take_uint(6, self.endian)
+    }
+
+    /// Returns the first 6 elements of the slice converted into `u64`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
+    /// Moves the pointer forward 6. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u48`](ByteCode::take_into_u48).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00; 8]);
+    /// assert_eq!(bytes.try_take_into_u48().unwrap(), 0);
+    /// assert!(bytes.try_take_into_u48().is_err());
+    /// ```
+    pub fn try_take_into_u48(&mut self) -> Result<u64, Error> {
+        self.try_take_uint(6, self.endian)
+    }
+
+    /// Returns the first `num` elements of the slice converted into `u64` in the given byte order,
+    /// for integers of any width up to 8 bytes. Moves the pointer forward `num`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` is greater than 8 or exceeds the remaining elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03, 0x04, 0x05]);
+    /// assert_eq!(bytes.take_uint(5, Endian::Little), 0x05_0403_0201);
+    /// ```
+    pub fn take_uint(&mut self, num: usize, endian: Endian) -> u64 {
+        self.try_take_uint(num, endian)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first `num` elements of the slice converted into `u64` in the given byte order.
+    /// Moves the pointer forward `num`. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_uint`](ByteCode::take_uint),
+    /// which also reports a `num` greater than 8 as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x02]);
+    /// assert!(bytes.try_take_uint(3, Endian::Big).is_err());
+    /// assert_eq!(bytes.try_take_uint(2, Endian::Big).unwrap(), 0x0102);
+    /// ```
+    pub fn try_take_uint(&mut self, num: usize, endian: Endian) -> Result<u64, Error> {
+        if num > 8 {
+            return Err(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!("integer width {} is greater than 8 bytes", num),
+            });
+        }
+        let value = endian.read_uint(self.try_peek(num)?);
+        self.skip(num);
+        Ok(value)
+    }
+
+    /// Returns the first `num` elements of the slice converted into `i64` in the given byte order,
+    /// sign-extended from the highest bit of the `num` bytes. Moves the pointer forward `num`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` is greater than 8 or exceeds the remaining elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0xff, 0xff, 0xfe, 0x7f, 0xff, 0xff]);
+    /// assert_eq!(bytes.take_int(3, Endian::Big), -2);
+    /// assert_eq!(bytes.take_int(3, Endian::Big), 0x7fffff);
+    /// ```
+    pub fn take_int(&mut self, num: usize, endian: Endian) -> i64 {
+        self.try_take_int(num, endian)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first `num` elements of the slice converted into `i64` in the given byte order,
+    /// sign-extended from the highest bit of the `num` bytes.
+    /// Moves the pointer forward `num`. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_int`](ByteCode::take_int),
+    /// which also reports a `num` greater than 8 as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x80, 0x00]);
+    /// assert_eq!(bytes.try_take_int(2, Endian::Little).unwrap(), 0x80);
+    /// assert!(bytes.try_take_int(1, Endian::Little).is_err());
+    /// ```
+    pub fn try_take_int(&mut self, num: usize, endian: Endian) -> Result<i64, Error> {
+        let value = self.try_take_uint(num, endian)?;
+        Ok(match num {
+            0 => 0,
+            _ => {
+                let shift = 64 - 8 * num as u32;
+                ((value << shift) as i64) >> shift
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+use alloc::{vec, vec::Vec};
+
+#[test]
+fn take_into_signed() {
+    let mut bytes = ByteCode::new(&[0x80, 0xff, 0x7f, 0xff, 0xff, 0xff, 0xfe]);
+    assert_eq!(bytes.take_into_i8(), i8::MIN);
+    assert_eq!(bytes.take_into_i16(), -129);
+    assert_eq!(bytes.take_into_i32(), -2);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn take_into_wide() {
+    let mut data = vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+    data.extend(1u128.to_le_bytes());
+    data.extend((-5i64).to_le_bytes());
+    let mut bytes = ByteCode::new(&data);
+    assert_eq!(bytes.take_into_u64(), 0x0102_0304_0506_0708);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.peek_into_u128(), 1);
+    assert_eq!(bytes.take_into_u128(), 1);
+    assert_eq!(bytes.take_into_i64(), -5);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn take_into_odd_width() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02, 0x03, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x80]);
+    assert_eq!(bytes.take_into_u24(), 0x010203);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.take_into_u48(), 0x0605_0403_0201);
+    assert_eq!(bytes.try_take_into_u24().unwrap_err().pos(), 9);
+    assert_eq!(bytes.take_int(1, Endian::Big), -128);
+    assert_eq!(bytes.take_uint(0, Endian::Big), 0);
+    assert_eq!(bytes.take_int(0, Endian::Big), 0);
+
+    let mut bytes = ByteCode::new(&[0xff; 8]);
+    assert_eq!(bytes.take_int(8, Endian::Little), -1);
+}
+
+#[test]
+#[should_panic]
+fn take_uint_too_wide() {
+    ByteCode::new(&[0; 9]).take_uint(9, Endian::Big);
+}
+
+#[test]
+fn take_into_endian_generic() {
+    use crate::{BigEndian, LittleEndian};
+
+    fn read_header<E: ByteOrder>(bytes: &mut ByteCode) -> (u16, u32) {
+        (
+            bytes.take_into_u16_endian::<E>(),
+            bytes.take_into_u32_endian::<E>(),
+        )
+    }
+
+    let data = [0x00, 0x01, 0x00, 0x00, 0x00, 0x02];
+    assert_eq!(read_header::<BigEndian>(&mut ByteCode::new(&data)), (1, 2));
+    assert_eq!(
+        read_header::<LittleEndian>(&mut ByteCode::new(&data)),
+        (0x0100, 0x0200_0000)
+    );
+
+    let mut bytes = ByteCode::new(&[0x00; 7]);
+    bytes.set_endian(Endian::Little);
+    assert!(bytes.try_take_into_u64_endian::<BigEndian>().is_err());
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+fn take_into_float() {
+    let mut data = Vec::new();
+    data.extend(0.1f32.to_le_bytes());
+    data.extend(f64::INFINITY.to_le_bytes());
+    let mut bytes = ByteCode::new(&data);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.take_into_f32(), 0.1);
+    assert_eq!(bytes.take_into_f64(), f64::INFINITY);
+}
+
+#[test]
+fn peek_into() {
+    let bytes = ByteCode::new(&[0x12, 0x34, 0x56, 0x78]);
+    assert_eq!(bytes.peek_into_u8(), 0x12);
+    assert_eq!(bytes.peek_into_u16(), 0x1234);
+    assert_eq!(bytes.peek_into_u32(), 0x1234_5678);
+    assert_eq!(bytes.peek_into_i32(), 0x1234_5678);
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+fn try_take_into_primitive() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    assert!(bytes.try_take_into_u64().is_err());
+    assert_eq!(bytes.pos(), 0);
+    assert_eq!(bytes.try_take_into_i16().unwrap(), 0);
+    assert!(bytes.try_take_into_f64().is_err());
+    assert_eq!(bytes.try_take_into_f32().unwrap(), 0.0);
+    assert_eq!(bytes.try_take_into_i8().unwrap(), 1);
+}
+
+#[test]
+#[should_panic]
+fn take_into_u128_out_of_range() {
+    let mut bytes = ByteCode::new(&[0; 15]);
+    bytes.take_into_u128();
+}
@@ -0,0 +1,619 @@
+use alloc::{borrow::Cow, format, vec::Vec};
+use core::ops::Range;
+
+use crate::{ByteCode, Endian, Error};
+
+/// The number of bytes compared at a time.
+const WORD: usize = core::mem::size_of::<u64>();
+
+/// Returns the index of the first occurrence of `byte` in `haystack`, a word at a time.
+pub(crate) fn find_byte(haystack: &[u8], byte: u8) -> Option<usize> {
+    const LO: u64 = u64::from_ne_bytes([0x01; WORD]);
+    const HI: u64 = u64::from_ne_bytes([0x80; WORD]);
+    let pattern = u64::from_ne_bytes([byte; WORD]);
+    let mut offset = 0;
+    for chunk in haystack.chunks_exact(WORD) {
+        // Zero bytes in `x` are the matches; this sets their high bits (and possibly later ones).
+        let x = u64::from_ne_bytes(chunk.try_into().unwrap()) ^ pattern;
+        if x.wrapping_sub(LO) & !x & HI != 0 {
+            break;
+        }
+        offset += WORD;
+    }
+    haystack[offset..]
+        .iter()
+        .position(|&b| b == byte)
+        .map(|i| offset + i)
+}
+
+/// How text is encoded, for [`strings`](ByteCode::strings).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Printable ASCII and tab.
+    Ascii,
+    /// Printable UTF-8, that is, any character other than controls, and tab.
+    Utf8,
+    /// Printable UTF-16 in the given byte order, read in code units aligned to the pointer.
+    Utf16(Endian),
+}
+
+impl TextEncoding {
+    /// Decodes the printable character at the beginning of `bytes`, returning it with its length in bytes.
+    fn printable(self, bytes: &[u8]) -> Option<(char, usize)> {
+        let (c, len) = match self {
+            TextEncoding::Ascii => (*bytes.first()? as char, 1),
+            TextEncoding::Utf8 => {
+                let len = match bytes.first()? {
+                    0x00..=0x7f => 1,
+                    0xc2..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    0xf0..=0xf4 => 4,
+                    _ => return None,
+                };
+                let c = core::str::from_utf8(bytes.get(..len)?)
+                    .ok()?
+                    .chars()
+                    .next()?;
+                (c, len)
+            }
+            TextEncoding::Utf16(endian) => {
+                let unit = |i: usize| Some(endian.read_uint(bytes.get(i..i + 2)?) as u16);
+                match char::decode_utf16([unit(0)?]).next()? {
+                    Ok(c) => (c, 2),
+                    Err(_) => {
+                        let c = char::decode_utf16([unit(0)?, unit(2)?]).next()?.ok()?;
+                        (c, 4)
+                    }
+                }
+            }
+        };
+        let printable = match self {
+            TextEncoding::Ascii => c == '\t' || (' '..='~').contains(&c),
+            _ => c == '\t' || !c.is_control(),
+        };
+        printable.then_some((c, len))
+    }
+}
+
+/// An iterator over the runs of printable text in a slice, as `(position, text)` pairs.
+///
+/// This struct is created by [`ByteCode::strings`].
+#[derive(Debug, Clone)]
+pub struct Strings<'a> {
+    bytes: &'a [u8],
+    base: usize,
+    offset: usize,
+    min_len: usize,
+    encoding: TextEncoding,
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = (usize, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let step = match self.encoding {
+            TextEncoding::Utf16(_) => 2,
+            _ => 1,
+        };
+        while self.offset < self.bytes.len() {
+            let start = self.offset;
+            let mut chars = 0;
+            while let Some((_, len)) = self.encoding.printable(&self.bytes[self.offset..]) {
+                self.offset += len;
+                chars += 1;
+            }
+            if chars == 0 {
+                self.offset += step;
+                continue;
+            }
+            if chars < self.min_len {
+                continue;
+            }
+            let run = &self.bytes[start..self.offset];
+            let text = match self.encoding {
+                TextEncoding::Ascii | TextEncoding::Utf8 => {
+                    Cow::Borrowed(core::str::from_utf8(run).unwrap())
+                }
+                TextEncoding::Utf16(endian) => Cow::Owned(
+                    char::decode_utf16(
+                        run.chunks_exact(2)
+                            .map(|unit| endian.read_uint(unit) as u16),
+                    )
+                    .map(|c| c.unwrap())
+                    .collect(),
+                ),
+            };
+            return Some((self.base + start, text));
+        }
+        None
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns the index of the first element of the slice that is not equal to `byte`,
+    /// or `None` if all remaining elements are equal to it.
+    ///
+    /// The slice is scanned a word at a time, which makes this fast over long runs
+    /// such as zero-filled gaps in firmware images.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0xff, 0xff, 0xff, 0x12, 0xff]);
+    /// assert_eq!(bytes.find_non(0xff), Some(3));
+    /// assert_eq!(bytes.find_non(0x00), Some(0));
+    /// ```
+    pub fn find_non(&self, byte: u8) -> Option<usize> {
+        let pattern = u64::from_ne_bytes([byte; WORD]);
+        let mut offset = 0;
+        for chunk in self.inner.chunks_exact(WORD) {
+            if u64::from_ne_bytes(chunk.try_into().unwrap()) != pattern {
+                break;
+            }
+            offset += WORD;
+        }
+        self.inner[offset..]
+            .iter()
+            .position(|&b| b != byte)
+            .map(|i| offset + i)
+    }
+
+    /// Returns the number of consecutive elements equal to `byte` at the beginning of the slice,
+    /// without moving the pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0xff, 0xff, 0x00, 0xff]);
+    /// assert_eq!(bytes.count_leading(0xff), 2);
+    /// assert_eq!(bytes.count_leading(0x00), 0);
+    /// ```
+    pub fn count_leading(&self, byte: u8) -> usize {
+        self.find_non(byte).unwrap_or(self.inner.len())
+    }
+
+    /// Moves the pointer forward past a run of zeros and returns its length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x7f, 0x00]);
+    /// assert_eq!(bytes.skip_zeros(), 3);
+    /// assert_eq!(bytes.pos(), 3);
+    /// assert_eq!(bytes.skip_zeros(), 0);
+    /// ```
+    pub fn skip_zeros(&mut self) -> usize {
+        let num = self.count_leading(0x00);
+        self.skip(num);
+        num
+    }
+
+    /// Returns `true` if the elements at `offset` from the pointer match `pattern`
+    /// in the bits set in `mask`, without moving the pointer.
+    ///
+    /// Returns `false` if fewer than `offset + pattern.len()` elements remain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` and `mask` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// // A JPEG starts with SOI followed by any marker.
+    /// let bytes = ByteCode::new(&[0xff, 0xd8, 0xff, 0xe0, 0x00, 0x10]);
+    /// assert!(bytes.matches_at(0, &[0xff, 0xd8, 0xff, 0x00], &[0xff, 0xff, 0xff, 0x00]));
+    /// assert!(bytes.matches_at(4, &[0x00, 0x10], &[0xff, 0xf0]));
+    /// assert!(!bytes.matches_at(5, &[0x10, 0x00], &[0xff, 0xff]));
+    /// ```
+    pub fn matches_at(&self, offset: usize, pattern: &[u8], mask: &[u8]) -> bool {
+        if pattern.len() != mask.len() {
+            self.fail(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!(
+                    "pattern length {} does not match mask length {}",
+                    pattern.len(),
+                    mask.len()
+                ),
+            });
+        }
+        let Some(bytes) = offset
+            .checked_add(pattern.len())
+            .and_then(|end| self.inner.get(offset..end))
+        else {
+            return false;
+        };
+        bytes
+            .iter()
+            .zip(pattern)
+            .zip(mask)
+            .all(|((byte, pattern), mask)| byte & mask == pattern & mask)
+    }
+
+    /// Returns the index of the first occurrence of `needle` in the remaining slice,
+    /// without moving the pointer.
+    ///
+    /// An empty `needle` is found at index 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(b"garbage\xca\xfe\xba\xbe");
+    /// assert_eq!(bytes.find(&[0xca, 0xfe]), Some(7));
+    /// assert_eq!(bytes.find(b"zz"), None);
+    /// ```
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        let Some((&first, rest)) = needle.split_first() else {
+            return Some(0);
+        };
+        let mut start = 0;
+        loop {
+            let index = start + find_byte(self.inner.get(start..)?, first)?;
+            if self.inner[index + 1..].starts_with(rest) {
+                return Some(index);
+            }
+            start = index + 1;
+        }
+    }
+
+    /// Moves the pointer forward to the first occurrence of `needle` and returns the number of skipped elements.
+    ///
+    /// Returns `None` and leaves the pointer unchanged if `needle` does not occur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"\x00\x01SYNC\x02");
+    /// assert_eq!(bytes.skip_until(b"SYNC"), Some(2));
+    /// assert!(bytes.starts_with(b"SYNC"));
+    /// ```
+    pub fn skip_until(&mut self, needle: &[u8]) -> Option<usize> {
+        let num = self.find(needle)?;
+        self.skip(num);
+        Some(num)
+    }
+
+    /// Returns a reference to the elements before the first occurrence of `delim`, without copying.
+    /// Moves the pointer forward to the delimiter, which is not consumed.
+    ///
+    /// Returns `None` and leaves the pointer unchanged if `delim` does not occur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"key=value");
+    /// assert_eq!(bytes.take_until(b"="), Some(&b"key"[..]));
+    /// assert_eq!(bytes.as_slice(), b"=value");
+    /// ```
+    pub fn take_until(&mut self, delim: &[u8]) -> Option<&'a [u8]> {
+        let num = self.find(delim)?;
+        Some(self.take_ref(num))
+    }
+
+    /// Returns a reference to the elements up to and including the first occurrence of `delim`, without copying.
+    /// Moves the pointer forward past the delimiter.
+    ///
+    /// Returns `None` and leaves the pointer unchanged if `delim` does not occur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"line 1\r\nline 2");
+    /// assert_eq!(bytes.take_until_inclusive(b"\r\n"), Some(&b"line 1\r\n"[..]));
+    /// assert_eq!(bytes.as_slice(), b"line 2");
+    /// ```
+    pub fn take_until_inclusive(&mut self, delim: &[u8]) -> Option<&'a [u8]> {
+        let num = self.find(delim)?;
+        Some(self.take_ref(num + delim.len()))
+    }
+
+    /// Returns a reference to the leading elements that satisfy `predicate`, without copying.
+    /// Moves the pointer forward past them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"1234abc");
+    /// assert_eq!(bytes.take_while(|byte| byte.is_ascii_digit()), b"1234");
+    /// assert_eq!(bytes.take_while(|byte| byte.is_ascii_digit()), b"");
+    /// ```
+    pub fn take_while(&mut self, mut predicate: impl FnMut(u8) -> bool) -> &'a [u8] {
+        let num = self
+            .inner
+            .iter()
+            .position(|&byte| !predicate(byte))
+            .unwrap_or(self.inner.len());
+        self.take_ref(num)
+    }
+
+    /// Returns the words in `range` whose values, read as offsets from the beginning of the slice,
+    /// point inside it, as `(position, value)` pairs.
+    ///
+    /// Words are read every `word_size` bytes from `range.start` in the given byte order.
+    /// Positions are absolute, as with [`original`](ByteCode::original), and the pointer does not move.
+    /// Zero is never reported, since it is far more often padding than a pointer.
+    ///
+    /// Runs of plausible offsets are a common sign of a table in undocumented data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `word_size` is not in `1..=8`, or `range` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let bytes = ByteCode::new(&[0x0c, 0x00, 0x10, 0x00, 0xff, 0xff, 0x00, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// let offsets = bytes.scan_plausible_offsets(2, Endian::Little, 0..8);
+    /// assert_eq!(offsets, [(0, 12)]);
+    /// ```
+    pub fn scan_plausible_offsets(
+        &self,
+        word_size: usize,
+        endian: Endian,
+        range: Range<usize>,
+    ) -> Vec<(usize, usize)> {
+        if !(1..=8).contains(&word_size) {
+            self.fail(Error::InvalidArgument {
+                pos: self.pos,
+                message: format!("word size must be in 1..=8, but was {}", word_size),
+            });
+        }
+        let original = self.original();
+        let region = self.original_range(range.clone());
+        region
+            .chunks_exact(word_size)
+            .enumerate()
+            .filter_map(|(i, word)| {
+                let value = endian.read_uint(word);
+                match usize::try_from(value) {
+                    Ok(value) if value != 0 && value < original.len() => {
+                        Some((range.start + i * word_size, value))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the runs of at least `min_len` printable characters in the rest of the slice,
+    /// like the Unix `strings` tool.
+    ///
+    /// Positions are absolute, as with [`original`](ByteCode::original), and the pointer does not move.
+    /// ASCII and UTF-8 runs borrow from the slice, while UTF-16 runs are decoded into owned strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian, TextEncoding};
+    ///
+    /// let bytes = ByteCode::new(b"\x00\x01main\x00ab\x00printf\x00");
+    /// let strings: Vec<_> = bytes.strings(4, TextEncoding::Ascii).collect();
+    /// assert_eq!(strings, [(2, "main".into()), (10, "printf".into())]);
+    ///
+    /// let bytes = ByteCode::new(b"E\x00F\x00I\x00\x00\x00");
+    /// let strings: Vec<_> = bytes.strings(3, TextEncoding::Utf16(Endian::Little)).collect();
+    /// assert_eq!(strings, [(0, "EFI".into())]);
+    /// ```
+    pub fn strings(&self, min_len: usize, encoding: TextEncoding) -> Strings<'a> {
+        Strings {
+            bytes: self.inner,
+            base: self.pos,
+            offset: 0,
+            min_len: min_len.max(1),
+            encoding,
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn find_non() {
+    let mut data = vec![0x00; 37];
+    data.push(0x01);
+    data.extend([0x00; 20]);
+    let mut bytes = ByteCode::new(&data);
+    assert_eq!(bytes.find_non(0x00), Some(37));
+    bytes += 38;
+    assert_eq!(bytes.find_non(0x00), None);
+    assert_eq!(bytes.find_non(0x01), Some(0));
+}
+
+#[test]
+fn count_leading() {
+    let bytes = ByteCode::new(&[0xff; 19]);
+    assert_eq!(bytes.count_leading(0xff), 19);
+    assert_eq!(ByteCode::new(&[]).count_leading(0xff), 0);
+    for i in 0..16 {
+        let mut data = vec![0xaa; 16];
+        data[i] = 0x55;
+        assert_eq!(ByteCode::new(&data).count_leading(0xaa), i);
+    }
+}
+
+#[test]
+fn skip_zeros() {
+    let mut data = vec![0x00; 4096];
+    data.extend([0xde, 0xad, 0x00, 0x00]);
+    let mut bytes = ByteCode::new(&data);
+    bytes += 1;
+    assert_eq!(bytes.skip_zeros(), 4095);
+    assert_eq!(bytes.pos(), 4096);
+    bytes += 2;
+    assert_eq!(bytes.skip_zeros(), 2);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn matches_at() {
+    // ARM `bx lr` in little-endian, with the condition field masked out.
+    let mut bytes = ByteCode::new(&[0x00, 0x1e, 0xff, 0x2f, 0x01]);
+    bytes += 1;
+    assert!(bytes.matches_at(0, &[0x1e, 0xff, 0x2f, 0x01], &[0xff, 0xff, 0xff, 0x0f]));
+    assert!(!bytes.matches_at(0, &[0x1e, 0xff, 0x2f, 0x02], &[0xff, 0xff, 0xff, 0x0f]));
+    assert!(bytes.matches_at(4, &[], &[]));
+    assert!(!bytes.matches_at(4, &[0x00], &[0x00]));
+    assert!(!bytes.matches_at(usize::MAX, &[0x00], &[0x00]));
+    assert_eq!(bytes.pos(), 1);
+}
+
+#[test]
+#[should_panic]
+fn matches_at_mask_length() {
+    ByteCode::new(&[0x00; 4]).matches_at(0, &[0x00; 2], &[0xff]);
+}
+
+#[test]
+fn scan_plausible_offsets() {
+    let mut data = vec![0x00; 64];
+    data[8..12].copy_from_slice(&[0x00, 0x00, 0x00, 0x30]);
+    data[12..16].copy_from_slice(&[0x00, 0x00, 0x00, 0x3f]);
+    data[16..20].copy_from_slice(&[0x00, 0x00, 0x00, 0x40]);
+    data[20..24].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    let mut bytes = ByteCode::new(&data);
+    bytes += 32;
+    assert_eq!(
+        bytes.scan_plausible_offsets(4, Endian::Big, 0..32),
+        [(8, 0x30), (12, 0x3f)]
+    );
+    assert_eq!(
+        bytes.scan_plausible_offsets(4, Endian::Big, 12..22),
+        [(12, 0x3f)]
+    );
+    assert_eq!(bytes.scan_plausible_offsets(4, Endian::Little, 8..16), []);
+    assert_eq!(
+        bytes.scan_plausible_offsets(1, Endian::Big, 11..13),
+        [(11, 0x30)]
+    );
+    assert_eq!(bytes.pos(), 32);
+}
+
+#[test]
+#[should_panic]
+fn scan_plausible_offsets_word_size() {
+    ByteCode::new(&[0x00; 16]).scan_plausible_offsets(9, Endian::Big, 0..16);
+}
+
+#[test]
+#[should_panic(
+    expected = "out of bounds at 0x8: requested 12 bytes, but only 8 remain\npointer at 0x0"
+)]
+fn scan_plausible_offsets_range() {
+    let mut bytes = ByteCode::new(&[0x00; 16]);
+    bytes.set_panic_style(crate::PanicStyle::Context);
+    bytes.scan_plausible_offsets(4, Endian::Big, 8..20);
+}
+
+#[test]
+fn find_byte_word_boundaries() {
+    for len in 0..20 {
+        for i in 0..len {
+            let mut data = vec![0x81; len];
+            data[i] = 0x80;
+            assert_eq!(find_byte(&data, 0x80), Some(i));
+            data[i] = 0x01;
+            assert_eq!(find_byte(&data, 0x01), Some(i));
+        }
+        assert_eq!(find_byte(&vec![0x00; len], 0xff), None);
+    }
+}
+
+#[test]
+fn find() {
+    let mut data = vec![0x00; 40];
+    data.extend(b"MAGMAGIC\x01");
+    let mut bytes = ByteCode::new(&data);
+    assert_eq!(bytes.find(b"MAGIC"), Some(43));
+    assert_eq!(bytes.find(b"MAGIX"), None);
+    assert_eq!(bytes.find(b""), Some(0));
+    bytes += 44;
+    assert_eq!(bytes.find(b"MAGIC"), None);
+    assert_eq!(bytes.find(b"\x01"), Some(4));
+    assert_eq!(bytes.find(b"\x01\x02"), None);
+}
+
+#[test]
+fn skip_until() {
+    let mut bytes = ByteCode::new(b"..\xff\xd8..\xff\xd8");
+    bytes.next();
+    assert_eq!(bytes.skip_until(b"\xff\xd8"), Some(1));
+    assert_eq!(bytes.pos(), 2);
+    assert_eq!(bytes.skip_until(b"\xff\xd8"), Some(0));
+    bytes.skip(2);
+    assert_eq!(bytes.skip_until(b"\xff\xd9"), None);
+    assert_eq!(bytes.pos(), 4);
+}
+
+#[test]
+fn take_until() {
+    let mut bytes = ByteCode::new(b"a,bc,");
+    assert_eq!(bytes.take_until(b","), Some(&b"a"[..]));
+    assert_eq!(bytes.pos(), 1);
+    assert_eq!(bytes.take_until_inclusive(b","), Some(&b","[..]));
+    assert_eq!(bytes.take_until_inclusive(b","), Some(&b"bc,"[..]));
+    assert!(bytes.is_end());
+    assert_eq!(bytes.take_until(b","), None);
+    assert_eq!(bytes.take_until(b""), Some(&b""[..]));
+}
+
+#[test]
+fn take_while() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00]);
+    assert_eq!(bytes.take_while(|byte| byte == 0x00), [0x00, 0x00]);
+    assert_eq!(bytes.take_while(|byte| byte != 0xff), [0x01, 0x00]);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn strings() {
+    let mut bytes = ByteCode::new(b"xx\x00abc\x00caf\xc3\xa9\tok\x00\xff\x80long enough");
+    bytes.skip(1);
+    let ascii: Vec<_> = bytes.strings(3, TextEncoding::Ascii).collect();
+    assert_eq!(
+        ascii,
+        [
+            (3, "abc".into()),
+            (7, "caf".into()),
+            (12, "\tok".into()),
+            (18, "long enough".into())
+        ]
+    );
+    let utf8: Vec<_> = bytes.strings(4, TextEncoding::Utf8).collect();
+    assert_eq!(utf8, [(7, "café\tok".into()), (18, "long enough".into())]);
+    assert_eq!(
+        bytes.strings(0, TextEncoding::Ascii).next(),
+        Some((1, "x".into()))
+    );
+    assert_eq!(bytes.pos(), 1);
+}
+
+#[test]
+fn strings_utf16() {
+    let bytes = ByteCode::new(&[
+        0x00, 0x68, 0x00, 0x69, 0xd8, 0x3d, 0xde, 0x00, 0x00, 0x07, 0xdc, 0x00, 0x00, 0x61, 0x00,
+    ]);
+    let strings: Vec<_> = bytes.strings(1, TextEncoding::Utf16(Endian::Big)).collect();
+    assert_eq!(strings, [(0, "hi\u{1f600}".into()), (12, "a".into())]);
+    assert!(matches!(strings[0].1, Cow::Owned(_)));
+}
@@ -0,0 +1,138 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{hexdump::printable, ByteCode, Span, SpanKind};
+
+impl<'a> ByteCode<'a> {
+    /// Returns a plain-text description of the reader for snapshot tests of parsers built on it.
+    ///
+    /// The output lists the pointer position, a hex dump of the whole slice, and then the recorded
+    /// [spans](ByteCode::spans), [bookmarks](ByteCode::bookmark) and [diagnostics](ByteCode::diagnostics),
+    /// each section left out if empty. It never contains color, does not depend on the terminal,
+    /// and its layout is kept unchanged across minor releases, unlike [`hexdump`](ByteCode::hexdump) output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"RITE\x00\x03");
+    /// bytes.record_spans();
+    /// bytes.labeled("magic", |bytes| bytes.take(4));
+    /// bytes.take_into_u16_labeled("version");
+    /// bytes.warn(4, "old version");
+    /// assert_eq!(
+    ///     bytes.snapshot_format(),
+    ///     "pos: 00000006 of 00000006\n\
+    ///      00000000  52 49 54 45 00 03                                |RITE..|\n\
+    ///      spans:\n\
+    ///      \x20 00000000..00000004  magic: bytes\n\
+    ///      \x20 00000004..00000006  version: u16\n\
+    ///      diagnostics:\n\
+    ///      \x20 00000004  old version\n"
+    /// );
+    /// ```
+    pub fn snapshot_format(&self) -> String {
+        let data = self.original();
+        let mut output = String::new();
+        writeln!(output, "pos: {:08x} of {:08x}", self.pos, data.len()).unwrap();
+        for (row, line) in data.chunks(16).enumerate() {
+            write!(output, "{:08x} ", row * 16).unwrap();
+            for byte in line {
+                write!(output, " {:02x}", byte).unwrap();
+            }
+            let padding = "   ".repeat(16 - line.len());
+            writeln!(output, "{}  |{}|", padding, printable(line)).unwrap();
+        }
+        if !self.spans().is_empty() {
+            output.push_str("spans:\n");
+            write_spans(&mut output, self.spans(), 1);
+        }
+        if !self.bookmarks.is_empty() {
+            output.push_str("bookmarks:\n");
+            for (name, pos) in &self.bookmarks {
+                writeln!(output, "  {:08x}  {}", pos, name).unwrap();
+            }
+        }
+        if !self.diagnostics.is_empty() {
+            output.push_str("diagnostics:\n");
+            for diagnostic in &self.diagnostics {
+                writeln!(output, "  {:08x}  {}", diagnostic.pos, diagnostic.message).unwrap();
+            }
+        }
+        output
+    }
+}
+
+fn write_spans(output: &mut String, spans: &[Span], depth: usize) {
+    for span in spans {
+        let indent = "  ".repeat(depth);
+        let (start, end) = (span.range.start, span.range.end);
+        match &span.kind {
+            SpanKind::Field(field_type) => writeln!(
+                output,
+                "{}{:08x}..{:08x}  {}: {}",
+                indent,
+                start,
+                end,
+                span.name,
+                field_type.snapshot_name()
+            )
+            .unwrap(),
+            SpanKind::Struct {
+                type_name,
+                children,
+            } => {
+                writeln!(
+                    output,
+                    "{}{:08x}..{:08x}  {}: {}",
+                    indent, start, end, span.name, type_name
+                )
+                .unwrap();
+                write_spans(output, children, depth + 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn snapshot_format() {
+    let data: alloc::vec::Vec<u8> = (0x40..0x52).collect();
+    let mut bytes = ByteCode::new(&data);
+    assert_eq!(
+        bytes.snapshot_format(),
+        "pos: 00000000 of 00000012\n\
+         00000000  40 41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f  |@ABCDEFGHIJKLMNO|\n\
+         00000010  50 51                                            |PQ|\n"
+    );
+
+    bytes.mark("z");
+    bytes.skip(2);
+    bytes.mark("a");
+    bytes.spans = Some(alloc::vec![Span {
+        name: "header".into(),
+        range: 0..2,
+        kind: SpanKind::Struct {
+            type_name: "Header".into(),
+            children: alloc::vec![Span {
+                name: "tag".into(),
+                range: 0..1,
+                kind: SpanKind::Field(crate::FieldType::Char),
+            }],
+        },
+    }]);
+    let snapshot = bytes.snapshot_format();
+    assert!(snapshot.starts_with("pos: 00000002 of 00000012\n"));
+    assert!(snapshot.ends_with(
+        "spans:\n\
+         \x20 00000000..00000002  header: Header\n\
+         \x20   00000000..00000001  tag: char\n\
+         bookmarks:\n\
+         \x20 00000002  a\n\
+         \x20 00000000  z\n"
+    ));
+    assert_eq!(
+        ByteCode::new(&[]).snapshot_format(),
+        "pos: 00000000 of 00000000\n"
+    );
+}
@@ -0,0 +1,311 @@
+use alloc::{borrow::Cow, vec::Vec};
+use core::ops::Range;
+
+use crate::{ByteCode, Endian, Error, FixedSize, FromByteCode};
+
+/// An address space made of `(offset, bytes)` segments with gaps between them,
+/// as reconstructed from Intel HEX images or core dumps with omitted pages.
+///
+/// Reads inside gaps fail with [`Error::Gap`], or return a fill byte if one is set with
+/// [`fill`](SparseBuffer::fill). Segments can be borrowed or owned.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{Error, SparseBuffer};
+///
+/// let mut image = SparseBuffer::new();
+/// image.insert(0x1000, &[0x01, 0x02][..]);
+/// image.insert(0x1004, vec![0x05]);
+/// assert_eq!(*image.read(0x1000, 2).unwrap(), [0x01, 0x02]);
+/// assert_eq!(image.read(0x1001, 4), Err(Error::Gap { pos: 0x1002, len: 2 }));
+///
+/// let image = image.fill(0xff);
+/// assert_eq!(*image.read(0x1001, 4).unwrap(), [0x02, 0xff, 0xff, 0x05]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SparseBuffer<'a> {
+    segments: Vec<(usize, Cow<'a, [u8]>)>,
+    fill: Option<u8>,
+    endian: Endian,
+}
+
+impl<'a> SparseBuffer<'a> {
+    /// Creates an empty `SparseBuffer` whose gaps are errors.
+    ///
+    /// Multi-byte values are read in big-endian order until changed with [`endian`](SparseBuffer::endian).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes reads inside gaps return `byte` instead of failing.
+    pub fn fill(mut self, byte: u8) -> Self {
+        self.fill = Some(byte);
+        self
+    }
+
+    /// Sets the byte order used by [`read_into`](SparseBuffer::read_into).
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Adds a segment of `bytes` at `offset`. Empty segments are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the segment overlaps another one or ends past `usize::MAX`.
+    pub fn insert(&mut self, offset: usize, bytes: impl Into<Cow<'a, [u8]>>) {
+        let bytes = bytes.into();
+        if bytes.is_empty() {
+            return;
+        }
+        let end = offset
+            .checked_add(bytes.len())
+            .unwrap_or_else(|| panic!("segment at {:#x} ends past usize::MAX", offset));
+        let index = self.segments.partition_point(|(start, _)| *start < offset);
+        let overlaps_previous = index
+            .checked_sub(1)
+            .is_some_and(|previous| self.segment_range(previous).end > offset);
+        let overlaps_next = self
+            .segments
+            .get(index)
+            .is_some_and(|(start, _)| *start < end);
+        if overlaps_previous || overlaps_next {
+            panic!(
+                "segment {:#x}..{:#x} overlaps an existing segment",
+                offset, end
+            );
+        }
+        self.segments.insert(index, (offset, bytes));
+    }
+
+    /// Returns the end of the last segment, which is the length of the [`materialize`](SparseBuffer::materialize)d data.
+    pub fn len(&self) -> usize {
+        self.segments
+            .len()
+            .checked_sub(1)
+            .map_or(0, |last| self.segment_range(last).end)
+    }
+
+    /// Returns `true` if there are no segments.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns an iterator over the segments with their offsets, in order.
+    pub fn segments(&self) -> impl Iterator<Item = (usize, &[u8])> {
+        self.segments
+            .iter()
+            .map(|(offset, bytes)| (*offset, bytes.as_ref()))
+    }
+
+    /// Returns the gaps between the start of the address space and the end of the last segment.
+    pub fn gaps(&self) -> Vec<Range<usize>> {
+        let mut gaps = Vec::new();
+        let mut end = 0;
+        for index in 0..self.segments.len() {
+            let range = self.segment_range(index);
+            if range.start > end {
+                gaps.push(end..range.start);
+            }
+            end = range.end;
+        }
+        gaps
+    }
+
+    /// Returns the `len` bytes at `offset`, borrowed if they lie within one segment.
+    ///
+    /// Everything outside the segments, including past the last one, is a gap.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Gap`] with the first gap in the range if no fill byte is set,
+    /// or [`Error::Overflow`] if the range ends past `usize::MAX`.
+    /// Gaps are found before anything is allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a fill byte is set and the range has to be copied but `len` bytes cannot be allocated.
+    pub fn read(&self, offset: usize, len: usize) -> Result<Cow<'_, [u8]>, Error> {
+        let end = offset
+            .checked_add(len)
+            .ok_or(Error::Overflow { pos: offset })?;
+        let mut index = self.segments.partition_point(|(start, _)| *start <= offset);
+        if let Some(previous) = index.checked_sub(1) {
+            let range = self.segment_range(previous);
+            if range.end >= end {
+                let bytes = &self.segments[previous].1;
+                return Ok(Cow::Borrowed(
+                    &bytes[offset - range.start..end - range.start],
+                ));
+            }
+            if range.end > offset {
+                index = previous;
+            }
+        }
+        if self.fill.is_none() {
+            let mut pos = offset;
+            let mut next = index;
+            while pos < end {
+                match self.segments.get(next).map(|_| self.segment_range(next)) {
+                    Some(range) if range.start <= pos => {
+                        pos = range.end;
+                        next += 1;
+                    }
+                    range => {
+                        let stop = range.map_or(end, |range| range.start.min(end));
+                        return Err(Error::Gap {
+                            pos,
+                            len: stop - pos,
+                        });
+                    }
+                }
+            }
+        }
+        let mut result = Vec::with_capacity(len);
+        let mut pos = offset;
+        while pos < end {
+            let next = self.segments.get(index).map(|_| self.segment_range(index));
+            match next {
+                Some(range) if range.start <= pos => {
+                    let bytes = &self.segments[index].1;
+                    let stop = range.end.min(end);
+                    result.extend_from_slice(&bytes[pos - range.start..stop - range.start]);
+                    pos = stop;
+                    index += 1;
+                }
+                _ => {
+                    // Without a fill byte, gaps were rejected above.
+                    let stop = next.map_or(end, |range| range.start.min(end));
+                    result.resize(result.len() + stop - pos, self.fill.unwrap_or_default());
+                    pos = stop;
+                }
+            }
+        }
+        Ok(Cow::Owned(result))
+    }
+
+    /// Reads a value of a fixed-size type at `offset`, in the byte order set by [`endian`](SparseBuffer::endian).
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`read`](SparseBuffer::read) and of the value's [`FromByteCode`] impl,
+    /// with positions as offsets into the address space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{Endian, SparseBuffer};
+    ///
+    /// let mut image = SparseBuffer::new().endian(Endian::Little);
+    /// image.insert(0x8000, &[0x34, 0x12][..]);
+    /// image.insert(0x8002, &[0x00, 0x00][..]);
+    /// assert_eq!(image.read_into::<u32>(0x8000).unwrap(), 0x1234);
+    /// assert!(image.read_into::<u16>(0x8003).is_err());
+    /// ```
+    pub fn read_into<T: for<'b> FromByteCode<'b> + FixedSize>(
+        &self,
+        offset: usize,
+    ) -> Result<T, Error> {
+        let bytes = self.read(offset, T::SIZE)?;
+        let mut reader = ByteCode::new(&bytes);
+        reader.set_endian(self.endian);
+        T::from_bytecode(&mut reader).map_err(|error| error.offset_by(offset))
+    }
+
+    /// Returns the data with the gaps before the end of the last segment filled in.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Gap`] with the first gap if no fill byte is set.
+    pub fn materialize(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.read(0, self.len())?.into_owned())
+    }
+
+    fn segment_range(&self, index: usize) -> Range<usize> {
+        let (offset, bytes) = &self.segments[index];
+        *offset..offset + bytes.len()
+    }
+}
+
+#[cfg(test)]
+use alloc::{string::ToString, vec};
+
+#[test]
+fn read() {
+    let mut image = SparseBuffer::new();
+    image.insert(4, vec![0x04, 0x05]);
+    image.insert(0, &[0x00, 0x01][..]);
+    image.insert(6, &[][..]);
+    image.insert(8, &[0x08][..]);
+    assert_eq!(image.len(), 9);
+    assert_eq!(image.gaps(), [2..4, 6..8]);
+    assert!(matches!(image.read(4, 2), Ok(Cow::Borrowed([0x04, 0x05]))));
+    assert!(image.read(5, 0).unwrap().is_empty());
+    assert_eq!(image.read(0, 3), Err(Error::Gap { pos: 2, len: 1 }));
+    assert_eq!(image.read(9, 2), Err(Error::Gap { pos: 9, len: 2 }));
+    assert_eq!(
+        image.read(usize::MAX, 2),
+        Err(Error::Overflow { pos: usize::MAX })
+    );
+    assert!(image.materialize().is_err());
+
+    let image = image.fill(0xee);
+    assert_eq!(
+        image.materialize().unwrap(),
+        [0x00, 0x01, 0xee, 0xee, 0x04, 0x05, 0xee, 0xee, 0x08]
+    );
+    assert_eq!(*image.read(7, 3).unwrap(), [0xee, 0x08, 0xee]);
+    assert_eq!(image.read_into::<u16>(5).unwrap(), 0x05ee);
+    assert_eq!(
+        image.read_into::<crate::FourCc>(0).unwrap().to_string(),
+        "\\x00\\x01\\xee\\xee"
+    );
+}
+
+#[test]
+fn read_huge_gap() {
+    let mut image = SparseBuffer::new();
+    image.insert(0, &[0x00, 0x01][..]);
+    assert_eq!(
+        image.read(0, 1 << 40),
+        Err(Error::Gap {
+            pos: 2,
+            len: (1 << 40) - 2
+        })
+    );
+    assert_eq!(
+        image.read(0, usize::MAX - 1),
+        Err(Error::Gap {
+            pos: 2,
+            len: usize::MAX - 3
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn read_huge_fill() {
+    let mut image = SparseBuffer::new().fill(0xff);
+    image.insert(0, &[0x00, 0x01][..]);
+    image.read(0, usize::MAX - 1).unwrap();
+}
+
+#[test]
+fn read_into_gap() {
+    let mut image = SparseBuffer::new();
+    image.insert(0x100, &b"ab\xff"[..]);
+    assert_eq!(
+        image.read_into::<[u8; 4]>(0x100),
+        Err(Error::Gap { pos: 0x103, len: 1 })
+    );
+}
+
+#[test]
+#[should_panic]
+fn insert_overlapping() {
+    let mut image = SparseBuffer::new();
+    image.insert(4, &[0; 4][..]);
+    image.insert(0, &[0; 5][..]);
+}
@@ -0,0 +1,252 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::{ByteCode, Instruction, InstructionSet};
+
+/// Opcode frequency and coverage of a decoded instruction stream,
+/// for checking an [`InstructionSet`] against real binaries.
+///
+/// Created by [`ByteCode::decode_stats`], or by [`from_instructions`](DecodeStats::from_instructions)
+/// for instructions decoded in another way.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, InstructionSet, Opcode};
+///
+/// struct Vm;
+///
+/// impl InstructionSet for Vm {
+///     fn opcode(&self, byte: u8) -> Option<Opcode> {
+///         match byte {
+///             0x00 => Some(Opcode { mnemonic: "nop", operands: &[] }),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let code = ByteCode::new(&[0x00, 0x00, 0xff, 0x00]);
+/// let stats = code.decode_stats(Vm);
+/// assert_eq!(stats.count(0x00), 3);
+/// assert_eq!(stats.undecodable(), [2..3]);
+/// assert_eq!(stats.coverage(), 75.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeStats {
+    region: Range<usize>,
+    opcodes: BTreeMap<u8, (&'static str, usize)>,
+    instructions: usize,
+    decoded: usize,
+    undecodable: Vec<Range<usize>>,
+}
+
+impl DecodeStats {
+    /// Collects statistics for `instructions` decoded from the absolute positions in `region`.
+    ///
+    /// Bytes of the region outside every instruction span count as undecodable.
+    /// Spans are expected in order and not to overlap; the parts of them outside the region are ignored.
+    pub fn from_instructions<'i>(
+        region: Range<usize>,
+        instructions: impl IntoIterator<Item = &'i Instruction>,
+    ) -> Self {
+        let mut stats = DecodeStats {
+            region: region.clone(),
+            opcodes: BTreeMap::new(),
+            instructions: 0,
+            decoded: 0,
+            undecodable: Vec::new(),
+        };
+        let mut pos = region.start;
+        for instruction in instructions {
+            let start = instruction.span.start.clamp(pos, region.end);
+            let end = instruction.span.end.clamp(start, region.end);
+            stats.add_undecodable(pos..start);
+            stats.add_instruction(instruction, end - start);
+            pos = end;
+        }
+        stats.add_undecodable(pos..region.end);
+        stats
+    }
+
+    /// Returns the number of instructions decoded with `opcode`.
+    pub fn count(&self, opcode: u8) -> usize {
+        self.opcodes.get(&opcode).map_or(0, |(_, count)| *count)
+    }
+
+    /// Returns the decoded opcodes with their mnemonics and counts, most frequent first,
+    /// and in opcode order among equal counts.
+    pub fn frequency(&self) -> Vec<(u8, &'static str, usize)> {
+        let mut frequency: Vec<_> = self
+            .opcodes
+            .iter()
+            .map(|(opcode, (mnemonic, count))| (*opcode, *mnemonic, *count))
+            .collect();
+        frequency.sort_by_key(|(_, _, count)| core::cmp::Reverse(*count));
+        frequency
+    }
+
+    /// Returns the number of decoded instructions.
+    pub fn instructions(&self) -> usize {
+        self.instructions
+    }
+
+    /// Returns the absolute ranges that could not be decoded, in order.
+    /// Adjacent undecodable bytes are merged into one range.
+    pub fn undecodable(&self) -> &[Range<usize>] {
+        &self.undecodable
+    }
+
+    /// Returns the number of bytes in the region covered by decoded instructions.
+    pub fn decoded_len(&self) -> usize {
+        self.decoded
+    }
+
+    /// Returns the percentage of the region covered by decoded instructions, 100 for an empty region.
+    pub fn coverage(&self) -> f64 {
+        match self.region.len() {
+            0 => 100.0,
+            len => self.decoded as f64 * 100.0 / len as f64,
+        }
+    }
+
+    fn add_instruction(&mut self, instruction: &Instruction, len: usize) {
+        self.opcodes
+            .entry(instruction.opcode)
+            .or_insert((instruction.mnemonic, 0))
+            .1 += 1;
+        self.instructions += 1;
+        self.decoded += len;
+    }
+
+    fn add_undecodable(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        match self.undecodable.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => self.undecodable.push(range),
+        }
+    }
+}
+
+impl Display for DecodeStats {
+    /// Formats the coverage, the undecodable ranges and the opcode frequency table, one item per line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "coverage: {:.2}% ({} of {} bytes, {} instructions)",
+            self.coverage(),
+            self.decoded,
+            self.region.len(),
+            self.instructions
+        )?;
+        for range in &self.undecodable {
+            writeln!(f, "undecodable: {:08x}..{:08x}", range.start, range.end)?;
+        }
+        let width = self
+            .opcodes
+            .values()
+            .map(|(mnemonic, _)| mnemonic.len())
+            .max()
+            .unwrap_or(0);
+        for (opcode, mnemonic, count) in self.frequency() {
+            writeln!(
+                f,
+                "{:02x}  {:width$}  {}",
+                opcode,
+                mnemonic,
+                count,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Decodes the instructions of `set` from the pointer to the end of the slice and collects
+    /// their statistics, without moving the pointer.
+    ///
+    /// Unlike [`disassemble`](ByteCode::disassemble), decoding resumes at the next byte after an error,
+    /// so that every undecodable range is reported.
+    pub fn decode_stats<I: InstructionSet>(&self, set: I) -> DecodeStats {
+        let mut reader = ByteCode::new(self.original());
+        reader.skip(self.pos);
+        let mut instructions = Vec::new();
+        while !reader.is_end() {
+            match set.decode(&mut reader) {
+                Ok(instruction) => instructions.push(instruction),
+                Err(_) => reader.skip(1),
+            }
+        }
+        DecodeStats::from_instructions(self.pos..self.original().len(), &instructions)
+    }
+}
+
+#[cfg(test)]
+use alloc::{string::ToString, vec};
+
+#[cfg(test)]
+use crate::{Opcode, OperandKind};
+
+#[cfg(test)]
+struct TestSet;
+
+#[cfg(test)]
+impl InstructionSet for TestSet {
+    fn opcode(&self, byte: u8) -> Option<Opcode> {
+        let (mnemonic, operands): (_, &'static [_]) = match byte {
+            0x00 => ("ret", &[]),
+            0x01 => ("push", &[(OperandKind::Unsigned, 2)]),
+            _ => return None,
+        };
+        Some(Opcode { mnemonic, operands })
+    }
+}
+
+#[test]
+fn decode_stats() {
+    let mut code = ByteCode::new(&[
+        0xff, 0x01, 0x00, 0x02, 0xee, 0xdd, 0x00, 0x01, 0x00, 0x03, 0x00, 0x01, 0x05,
+    ]);
+    code.skip(1);
+    let stats = code.decode_stats(TestSet);
+    assert_eq!(code.pos(), 1);
+    assert_eq!(stats.instructions(), 4);
+    assert_eq!(stats.frequency(), [(0x00, "ret", 2), (0x01, "push", 2)]);
+    assert_eq!(stats.count(0x02), 0);
+    // The truncated push at the end is undecodable, and decoding resumes inside it.
+    assert_eq!(stats.undecodable(), [4..6, 11..13]);
+    assert_eq!(stats.decoded_len(), 8);
+    assert_eq!(stats.coverage(), 100.0 * 8.0 / 12.0);
+    assert_eq!(
+        stats.to_string(),
+        "coverage: 66.67% (8 of 12 bytes, 4 instructions)\n\
+         undecodable: 00000004..00000006\n\
+         undecodable: 0000000b..0000000d\n\
+         00  ret   2\n\
+         01  push  2\n"
+    );
+}
+
+#[test]
+fn from_instructions() {
+    let instruction = |span: Range<usize>, opcode| Instruction {
+        span,
+        opcode,
+        mnemonic: "op",
+        operands: Vec::new(),
+    };
+    let instructions = [instruction(0..2, 0x01), instruction(4..8, 0x02)];
+    let stats = DecodeStats::from_instructions(1..6, &instructions);
+    assert_eq!(stats.undecodable(), vec![2..4]);
+    assert_eq!(stats.decoded_len(), 3);
+    assert_eq!(stats.coverage(), 60.0);
+
+    let stats = DecodeStats::from_instructions(3..3, []);
+    assert_eq!(stats.coverage(), 100.0);
+    assert!(stats.undecodable().is_empty());
+}
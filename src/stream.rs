@@ -0,0 +1,451 @@
+use std::io::{self, Read};
+
+use crate::{ByteCode, Endian, Error, PanicStyle};
+
+/// Maximum number of bytes the buffer grows by per read, so that a large request
+/// only allocates as much as the source actually provides.
+const CHUNK: usize = 8192;
+
+/// A reader over an [`io::Read`] source that buffers only what is being parsed,
+/// so that large files can be read without loading them whole.
+///
+/// The reads mirror those of [`ByteCode`]: `take`, `peek` and `skip` panic if the source ends early,
+/// and their `try_*` versions return [`Error::OutOfBounds`] instead.
+/// Other failures of the source are reported as [`Error::Io`].
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCodeStream;
+///
+/// let source: &[u8] = &[0x00, 0x03, 0x66, 0x6f, 0x6f, 0xff];
+/// let mut stream = ByteCodeStream::new(source);
+/// let len = stream.take_into_u16();
+/// let name = stream.parse(len as usize, |bytes| bytes.take_into_string(len as usize));
+/// assert_eq!(name, "foo");
+/// assert_eq!(stream.pos(), 5);
+/// assert!(stream.try_take_into_u16().is_err());
+/// ```
+#[derive(Debug)]
+pub struct ByteCodeStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// The number of consumed bytes at the front of `buf`.
+    start: usize,
+    pos: usize,
+    endian: Endian,
+    panic_style: PanicStyle,
+}
+
+impl<R: Read> ByteCodeStream<R> {
+    /// Creates a new `ByteCodeStream` reading from `reader`.
+    ///
+    /// Multi-byte values are read in big-endian order until changed with [`set_endian`](ByteCodeStream::set_endian).
+    pub fn new(reader: R) -> Self {
+        ByteCodeStream {
+            reader,
+            buf: Vec::new(),
+            start: 0,
+            pos: 0,
+            endian: Endian::Big,
+            panic_style: PanicStyle::default(),
+        }
+    }
+
+    /// Returns the pointer position, counted from the start of the stream.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the byte order used by `take_into_*` reads.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Sets the byte order used by `take_into_*` reads.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns how panicking methods describe their errors.
+    pub fn panic_style(&self) -> PanicStyle {
+        self.panic_style
+    }
+
+    /// Sets how panicking methods describe their errors, as [`ByteCode::set_panic_style`] does.
+    ///
+    /// [`PanicStyle::Context`] adds the pointer position but no hex dump, since consumed bytes are not kept.
+    /// The `ByteCode` given to [`parse`](ByteCodeStream::parse) inherits this setting.
+    pub fn set_panic_style(&mut self, panic_style: PanicStyle) {
+        self.panic_style = panic_style;
+    }
+
+    /// Returns `true` if the source has no more bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source fails.
+    pub fn is_end(&mut self) -> bool {
+        self.try_is_end().unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns `true` if the source has no more bytes.
+    ///
+    /// Fallible version of [`is_end`](ByteCodeStream::is_end).
+    pub fn try_is_end(&mut self) -> Result<bool, Error> {
+        Ok(self.fill(1)? == 0)
+    }
+
+    /// Returns the underlying source.
+    ///
+    /// Bytes already buffered but not consumed are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Panics with `error`, described as set by [`set_panic_style`](ByteCodeStream::set_panic_style).
+    fn fail(&self, error: Error) -> ! {
+        match self.panic_style {
+            PanicStyle::Plain => panic!("{}", error),
+            PanicStyle::Context => panic!("{}\npointer at {:#x}", error, self.pos),
+        }
+    }
+
+    /// Buffers up to `num` unconsumed bytes, stopping early only at the end of the source,
+    /// and returns the number available.
+    fn fill(&mut self, num: usize) -> Result<usize, Error> {
+        if self.buf.len() - self.start >= num {
+            return Ok(num);
+        }
+        self.buf.drain(..self.start);
+        self.start = 0;
+        while self.buf.len() < num {
+            let len = self.buf.len();
+            self.buf.resize(len + CHUNK, 0);
+            let result = self.reader.read(&mut self.buf[len..]);
+            let read = *result.as_ref().unwrap_or(&0);
+            self.buf.truncate(len + read);
+            match result {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    return Err(Error::Io {
+                        pos: self.pos,
+                        kind: error.kind(),
+                    })
+                }
+            }
+        }
+        Ok(self.buf.len().min(num))
+    }
+
+    fn fill_exact(&mut self, num: usize) -> Result<(), Error> {
+        let available = self.fill(num)?;
+        match available == num {
+            true => Ok(()),
+            false => Err(Error::OutOfBounds {
+                pos: self.pos,
+                requested: num,
+                available,
+            }),
+        }
+    }
+
+    /// Returns a reference to subslice corresponding to the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source ends before `num` bytes or fails.
+    pub fn peek(&mut self, num: usize) -> &[u8] {
+        if let Err(error) = self.fill_exact(num) {
+            self.fail(error);
+        }
+        &self.buf[self.start..self.start + num]
+    }
+
+    /// Returns a reference to subslice corresponding to the given size.
+    ///
+    /// Fallible version of [`peek`](ByteCodeStream::peek).
+    pub fn try_peek(&mut self, num: usize) -> Result<&[u8], Error> {
+        self.fill_exact(num)?;
+        Ok(&self.buf[self.start..self.start + num])
+    }
+
+    /// Move the pointer forward by the given number, without buffering the skipped bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source ends before `num` bytes or fails.
+    pub fn skip(&mut self, num: usize) {
+        self.try_skip(num).unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Move the pointer forward by the given number, without buffering the skipped bytes.
+    ///
+    /// Fallible version of [`skip`](ByteCodeStream::skip).
+    /// Unlike the other reads, the pointer is moved to the end of the source if it ends early,
+    /// since the skipped bytes are not kept.
+    pub fn try_skip(&mut self, num: usize) -> Result<(), Error> {
+        let start = self.pos;
+        let buffered = num.min(self.buf.len() - self.start);
+        self.start += buffered;
+        self.pos += buffered;
+        let rest = (num - buffered) as u64;
+        let skipped =
+            io::copy(&mut (&mut self.reader).take(rest), &mut io::sink()).map_err(|error| {
+                Error::Io {
+                    pos: self.pos,
+                    kind: error.kind(),
+                }
+            })?;
+        self.pos += skipped as usize;
+        match skipped == rest {
+            true => Ok(()),
+            false => Err(Error::OutOfBounds {
+                pos: start,
+                requested: num,
+                available: self.pos - start,
+            }),
+        }
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size.
+    /// Moves the pointer forward by the length of subslice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source ends before `num` bytes or fails.
+    pub fn take(&mut self, num: usize) -> Vec<u8> {
+        self.try_take(num).unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns a vector containing a copy of subslice corresponding to the given size.
+    /// Moves the pointer forward by the length of subslice. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take`](ByteCodeStream::take).
+    pub fn try_take(&mut self, num: usize) -> Result<Vec<u8>, Error> {
+        self.try_parse(num, |bytes| bytes.take(num))
+    }
+
+    /// Returns the first byte.
+    /// Moves the pointer forward 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source has ended or fails.
+    pub fn take_into_u8(&mut self) -> u8 {
+        self.try_take_into_u8()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first byte.
+    /// Moves the pointer forward 1. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u8`](ByteCodeStream::take_into_u8).
+    pub fn try_take_into_u8(&mut self) -> Result<u8, Error> {
+        self.try_parse(1, |bytes| bytes.take_into_u8())
+    }
+
+    /// Returns the first 2 bytes converted into `u16`
+    /// in the byte order set by [`set_endian`](ByteCodeStream::set_endian).
+    /// Moves the pointer forward 2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source ends before 2 bytes or fails.
+    pub fn take_into_u16(&mut self) -> u16 {
+        self.try_take_into_u16()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 2 bytes converted into `u16`
+    /// in the byte order set by [`set_endian`](ByteCodeStream::set_endian).
+    /// Moves the pointer forward 2. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u16`](ByteCodeStream::take_into_u16).
+    pub fn try_take_into_u16(&mut self) -> Result<u16, Error> {
+        self.try_parse(2, |bytes| bytes.take_into_u16())
+    }
+
+    /// Returns the first 4 bytes converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCodeStream::set_endian).
+    /// Moves the pointer forward 4.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source ends before 4 bytes or fails.
+    pub fn take_into_u32(&mut self) -> u32 {
+        self.try_take_into_u32()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 4 bytes converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCodeStream::set_endian).
+    /// Moves the pointer forward 4. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_into_u32`](ByteCodeStream::take_into_u32).
+    pub fn try_take_into_u32(&mut self) -> Result<u32, Error> {
+        self.try_parse(4, |bytes| bytes.take_into_u32())
+    }
+
+    /// Buffers the next `num` bytes and runs `f` on a `ByteCode` over them,
+    /// keeping the pointer position and byte order it leaves behind.
+    ///
+    /// This gives access to the whole `ByteCode` API, one record at a time.
+    /// The `ByteCode` positions are relative to the start of the record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source ends before `num` bytes or fails.
+    pub fn parse<T>(&mut self, num: usize, f: impl FnOnce(&mut ByteCode<'_>) -> T) -> T {
+        self.try_parse(num, f)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Buffers the next `num` bytes and runs `f` on a `ByteCode` over them,
+    /// keeping the pointer position and byte order it leaves behind.
+    ///
+    /// Fallible version of [`parse`](ByteCodeStream::parse).
+    /// Use the `try_*` methods inside `f` and return their `Result` to report errors in the record as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeStream;
+    ///
+    /// let mut stream = ByteCodeStream::new(&[0x00, 0x01, 0x02][..]);
+    /// let value = stream.try_parse(2, |bytes| bytes.try_take_into_u16())??;
+    /// assert_eq!(value, 1);
+    /// assert!(stream.try_parse(2, |bytes| bytes.take(2)).is_err());
+    /// # Ok::<(), bytecode::Error>(())
+    /// ```
+    pub fn try_parse<T>(
+        &mut self,
+        num: usize,
+        f: impl FnOnce(&mut ByteCode<'_>) -> T,
+    ) -> Result<T, Error> {
+        self.fill_exact(num)?;
+        let mut bytes = ByteCode::new(&self.buf[self.start..self.start + num]);
+        bytes.endian = self.endian;
+        bytes.panic_style = self.panic_style;
+        let value = f(&mut bytes);
+        let (consumed, endian) = (bytes.pos, bytes.endian);
+        self.start += consumed;
+        self.pos += consumed;
+        self.endian = endian;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+/// A source returning at most 3 bytes per read, to exercise refilling.
+struct Trickle<'a>(&'a [u8]);
+
+#[cfg(test)]
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(3);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn refill() {
+    let data: Vec<u8> = (0..32).collect();
+    let mut stream = ByteCodeStream::new(Trickle(&data));
+    assert_eq!(stream.peek(5), [0, 1, 2, 3, 4]);
+    assert_eq!(stream.take(10), (0..10).collect::<Vec<_>>());
+    assert_eq!(stream.take_into_u16(), 0x0a0b);
+    stream.set_endian(Endian::Little);
+    assert_eq!(stream.take_into_u32(), 0x0f0e0d0c);
+    stream.skip(14);
+    assert_eq!(stream.pos(), 30);
+    assert_eq!(stream.take_into_u8(), 30);
+    assert!(!stream.is_end());
+    assert_eq!(stream.take_into_u8(), 31);
+    assert!(stream.is_end());
+}
+
+#[test]
+fn parse_partial() {
+    let mut stream = ByteCodeStream::new(&[0x01, 0x02, 0x03, 0x04][..]);
+    let first = stream.parse(4, |bytes| {
+        bytes.set_endian(Endian::Little);
+        bytes.take_into_u16()
+    });
+    assert_eq!(first, 0x0201);
+    assert_eq!(stream.pos(), 2);
+    assert_eq!(stream.endian(), Endian::Little);
+    assert_eq!(stream.take_into_u16(), 0x0403);
+}
+
+#[test]
+fn unexpected_eof() {
+    let mut stream = ByteCodeStream::new(Trickle(&[0, 1, 2, 3, 4]));
+    stream.skip(1);
+    assert_eq!(
+        stream.try_take(5),
+        Err(Error::OutOfBounds {
+            pos: 1,
+            requested: 5,
+            available: 4
+        })
+    );
+    assert_eq!(stream.pos(), 1);
+    assert_eq!(stream.try_take(4).unwrap(), [1, 2, 3, 4]);
+
+    let mut stream = ByteCodeStream::new(Trickle(&[0, 1, 2]));
+    assert_eq!(
+        stream.try_skip(4),
+        Err(Error::OutOfBounds {
+            pos: 0,
+            requested: 4,
+            available: 3
+        })
+    );
+    assert_eq!(stream.pos(), 3);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds at 0x1: requested 2 bytes, but only 1 remain")]
+fn take_out_of_range() {
+    let mut stream = ByteCodeStream::new(&[0, 1][..]);
+    stream.skip(1);
+    stream.take_into_u16();
+}
+
+#[test]
+fn source_error() {
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+    }
+
+    let mut stream = ByteCodeStream::new(Failing);
+    assert_eq!(
+        stream.try_peek(1),
+        Err(Error::Io {
+            pos: 0,
+            kind: io::ErrorKind::PermissionDenied
+        })
+    );
+}
+
+#[test]
+fn take_huge() {
+    let mut stream = ByteCodeStream::new(&[0, 1, 2][..]);
+    assert_eq!(
+        stream.try_peek(usize::MAX / 2),
+        Err(Error::OutOfBounds {
+            pos: 0,
+            requested: usize::MAX / 2,
+            available: 3
+        })
+    );
+}
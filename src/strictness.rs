@@ -0,0 +1,181 @@
+use crate::{ByteCode, Diagnostic, Error};
+
+/// How format violations that do not prevent decoding, such as set reserved bits or non-zero padding, are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Violations are errors.
+    Strict,
+    /// Violations are recorded as [diagnostics](ByteCode::diagnostics) and decoding continues.
+    #[default]
+    Permissive,
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns how format violations are handled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Strictness};
+    ///
+    /// let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// assert_eq!(bytes.strictness(), Strictness::Permissive);
+    /// ```
+    pub fn strictness(&self) -> Strictness {
+        self.strictness
+    }
+
+    /// Sets how format violations are handled, by the validation helpers and the parsers in [`formats`](crate::formats).
+    ///
+    /// Child readers created from this one inherit this setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Strictness};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01]);
+    /// bytes.set_strictness(Strictness::Strict);
+    /// assert!(bytes.skip_padding(2).is_err());
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn set_strictness(&mut self, strictness: Strictness) {
+        self.strictness = strictness;
+    }
+
+    /// Reports a format violation: returns it in strict mode, or records it as a diagnostic in permissive mode.
+    ///
+    /// This is the hook parsers use for checks of their own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x02]);
+    /// let version = bytes.take_into_u8();
+    /// if version != 1 {
+    ///     bytes.violation(Error::ReservedBits { pos: 0, bits: version as u64 }).unwrap();
+    /// }
+    /// assert_eq!(bytes.diagnostics().len(), 1);
+    /// ```
+    pub fn violation(&mut self, error: Error) -> Result<(), Error> {
+        match self.strictness {
+            Strictness::Strict => Err(error),
+            Strictness::Permissive => {
+                self.diagnostics.push(Diagnostic::from(error));
+                Ok(())
+            }
+        }
+    }
+
+    /// Reports a violation if any bit of `value` in `mask` is set.
+    /// `pos` is where `value` was read from, for the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error, Strictness};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x81]);
+    /// bytes.set_strictness(Strictness::Strict);
+    /// let pos = bytes.pos();
+    /// let flags = bytes.take_into_u8();
+    /// assert_eq!(
+    ///     bytes.check_reserved_bits(pos, flags as u64, 0xf0),
+    ///     Err(Error::ReservedBits { pos: 0, bits: 0x80 })
+    /// );
+    /// ```
+    pub fn check_reserved_bits(&mut self, pos: usize, value: u64, mask: u64) -> Result<(), Error> {
+        match value & mask {
+            0 => Ok(()),
+            bits => self.violation(Error::ReservedBits { pos, bits }),
+        }
+    }
+
+    /// Moves the pointer forward past `num` bytes of padding, reporting a violation if any of them is not zero.
+    ///
+    /// The pointer does not move if an error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if fewer than `num` elements remain,
+    /// or [`Error::NonZeroPadding`] in strict mode.
+    pub fn skip_padding(&mut self, num: usize) -> Result<(), Error> {
+        if num > self.inner.len() {
+            return Err(Error::OutOfBounds {
+                pos: self.pos,
+                requested: num,
+                available: self.inner.len(),
+            });
+        }
+        if self.inner[..num].iter().any(|byte| *byte != 0) {
+            self.violation(Error::NonZeroPadding {
+                pos: self.pos,
+                len: num,
+            })?;
+        }
+        self.skip(num);
+        Ok(())
+    }
+}
+
+#[test]
+fn permissive_records_diagnostics() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00, 0xf0]);
+    bytes.skip_padding(2).unwrap();
+    assert!(bytes.diagnostics().is_empty());
+    bytes.skip_padding(2).unwrap();
+    assert_eq!(bytes.pos, 4);
+    bytes.check_reserved_bits(4, 0xf0, 0x0f).unwrap();
+    bytes.check_reserved_bits(4, 0xf0, 0x30).unwrap();
+    assert_eq!(
+        bytes.diagnostics(),
+        [
+            Diagnostic::from(Error::NonZeroPadding { pos: 2, len: 2 }),
+            Diagnostic::from(Error::ReservedBits { pos: 4, bits: 0x30 }),
+        ]
+    );
+}
+
+#[test]
+fn strict_returns_errors() {
+    let mut bytes = ByteCode::new(&[0x00, 0x02, 0x00]);
+    bytes.set_strictness(Strictness::Strict);
+    assert_eq!(
+        bytes.skip_padding(2),
+        Err(Error::NonZeroPadding { pos: 0, len: 2 })
+    );
+    assert_eq!(bytes.pos, 0);
+    assert!(bytes.check_reserved_bits(0, 0x02, 0x02).is_err());
+    assert!(bytes.diagnostics().is_empty());
+    assert!(matches!(
+        bytes.skip_padding(4),
+        Err(Error::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn child_inherits_strictness() {
+    let mut bytes = ByteCode::new(&[0x01, 0x00]);
+    bytes.set_strictness(Strictness::Strict);
+    bytes.violation(Error::Overflow { pos: 0 }).unwrap_err();
+    let mut child = bytes.take_bytecode(2);
+    assert_eq!(child.strictness(), Strictness::Strict);
+    assert!(child.skip_padding(1).is_err());
+}
+
+#[test]
+fn restore_discards_diagnostics() {
+    let mut bytes = ByteCode::new(&[0x01, 0x02]);
+    bytes.skip_padding(1).unwrap();
+    let checkpoint = bytes.checkpoint();
+    bytes.skip_padding(1).unwrap();
+    bytes.warn(1, "unexpected");
+    assert_eq!(bytes.diagnostics().len(), 3);
+    bytes.restore(checkpoint);
+    assert_eq!(
+        bytes.diagnostics()[..],
+        [Diagnostic::from(Error::NonZeroPadding { pos: 0, len: 1 })]
+    );
+}
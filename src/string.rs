@@ -0,0 +1,247 @@
+use alloc::{borrow::ToOwned, string::String};
+
+use crate::{ByteCode, Endian, Error};
+
+impl<'a> ByteCode<'a> {
+    /// Returns the string up to the next NUL byte, as in C strings and most string tables.
+    /// Moves the pointer forward past the NUL.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no NUL byte or the string is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"main\0printf\0");
+    /// assert_eq!(bytes.take_cstring(), "main");
+    /// assert_eq!(bytes.take_cstring(), "printf");
+    /// assert!(bytes.is_end());
+    /// ```
+    pub fn take_cstring(&mut self) -> String {
+        self.try_take_cstring()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the string consisting of `len` UTF-16 code units (`2 * len` bytes) in the given byte order,
+    /// as used by Windows and Java. Moves the pointer forward `2 * len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `2 * len` elements remain or the string contains an unpaired surrogate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x45, 0x00, 0x46, 0x00, 0x49, 0x00]);
+    /// assert_eq!(bytes.take_utf16_string(3, Endian::Little), "EFI");
+    /// ```
+    pub fn take_utf16_string(&mut self, len: usize, endian: Endian) -> String {
+        self.try_take_utf16_string(len, endian)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the string consisting of the given number of bytes from the beginning of the slice,
+    /// replacing invalid UTF-8 sequences with `U+FFFD`. Moves the pointer forward by given number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `num` elements remain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x66, 0x6f, 0xff, 0x6f]);
+    /// assert_eq!(bytes.take_string_lossy(4), "fo\u{fffd}o");
+    /// ```
+    pub fn take_string_lossy(&mut self, num: usize) -> String {
+        String::from_utf8_lossy(self.take_ref(num)).into_owned()
+    }
+
+    /// Returns the longest run of printable characters from the beginning of the slice, at most `max_len` bytes long.
+    /// Moves the pointer forward by the length of the run in bytes.
+    ///
+    /// Printable characters are valid UTF-8 other than control characters, except for tab.
+    /// This suits picking up names and messages of unknown length, as the `strings` tool does,
+    /// and returns an empty string if the first byte does not start one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(b"caf\xc3\xa9\x00\x01long name");
+    /// assert_eq!(bytes.take_printable_string(16), "café");
+    /// assert_eq!(bytes.take_printable_string(16), "");
+    /// bytes.skip(2);
+    /// assert_eq!(bytes.take_printable_string(4), "long");
+    /// ```
+    pub fn take_printable_string(&mut self, max_len: usize) -> String {
+        let bytes = &self.inner[..max_len.min(self.inner.len())];
+        let valid = match core::str::from_utf8(bytes) {
+            Ok(valid) => valid,
+            Err(error) => core::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap(),
+        };
+        let len = valid
+            .char_indices()
+            .find(|(_, c)| c.is_control() && *c != '\t')
+            .map_or(valid.len(), |(index, _)| index);
+        let string = valid[..len].to_owned();
+        self.skip(len);
+        string
+    }
+
+    /// Returns the string up to the next NUL byte. The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_cstring`](ByteCode::take_cstring).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Error};
+    ///
+    /// let mut bytes = ByteCode::new(b"ok\0\xff\0bad");
+    /// assert_eq!(bytes.try_take_cstring().unwrap(), "ok");
+    /// assert_eq!(bytes.try_take_cstring(), Err(Error::InvalidUtf8 { pos: 3, len: 1 }));
+    /// bytes.skip(2);
+    /// assert_eq!(bytes.try_take_cstring(), Err(Error::Unterminated { pos: 5 }));
+    /// ```
+    pub fn try_take_cstring(&mut self) -> Result<String, Error> {
+        let len = self
+            .inner
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or(Error::Unterminated { pos: self.pos })?;
+        let string = self.try_take_into_string(len)?;
+        self.skip(1);
+        Ok(string)
+    }
+
+    /// Returns the string consisting of `len` UTF-16 code units in the given byte order.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_utf16_string`](ByteCode::take_utf16_string).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian, Error};
+    ///
+    /// let mut bytes = ByteCode::new(&[0xd8, 0x3d, 0xde, 0x00, 0xd8, 0x3d, 0x00, 0x41]);
+    /// assert_eq!(bytes.try_take_utf16_string(2, Endian::Big).unwrap(), "\u{1f600}");
+    /// assert_eq!(
+    ///     bytes.try_take_utf16_string(2, Endian::Big),
+    ///     Err(Error::InvalidUtf16 { pos: 4, len: 4 })
+    /// );
+    /// ```
+    pub fn try_take_utf16_string(&mut self, len: usize, endian: Endian) -> Result<String, Error> {
+        let num = len.checked_mul(2).ok_or(Error::OutOfBounds {
+            pos: self.pos,
+            requested: usize::MAX,
+            available: self.inner.len(),
+        })?;
+        let units = self
+            .try_peek(num)?
+            .chunks_exact(2)
+            .map(|unit| endian.read_uint(unit) as u16);
+        let string = char::decode_utf16(units)
+            .collect::<Result<String, _>>()
+            .map_err(|_| Error::InvalidUtf16 {
+                pos: self.pos,
+                len: num,
+            })?;
+        self.skip(num);
+        Ok(string)
+    }
+
+    /// Returns the string consisting of the given number of bytes, replacing invalid UTF-8 sequences with `U+FFFD`.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_string_lossy`](ByteCode::take_string_lossy).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xc3, 0x28]);
+    /// assert!(bytes.try_take_string_lossy(3).is_err());
+    /// assert_eq!(bytes.try_take_string_lossy(2).unwrap(), "\u{fffd}(");
+    /// ```
+    pub fn try_take_string_lossy(&mut self, num: usize) -> Result<String, Error> {
+        Ok(String::from_utf8_lossy(self.try_take_ref(num)?).into_owned())
+    }
+}
+
+#[test]
+fn take_cstring() {
+    let mut bytes = ByteCode::new(b"\0caf\xc3\xa9\0rest");
+    assert_eq!(bytes.take_cstring(), "");
+    assert_eq!(bytes.take_cstring(), "café");
+    assert_eq!(bytes.pos(), 7);
+    assert_eq!(
+        bytes.try_take_cstring(),
+        Err(Error::Unterminated { pos: 7 })
+    );
+    assert_eq!(bytes.pos(), 7);
+}
+
+#[test]
+#[should_panic]
+fn take_cstring_unterminated() {
+    ByteCode::new(b"abc").take_cstring();
+}
+
+#[test]
+fn take_utf16_string() {
+    let mut bytes = ByteCode::new(&[0x00, 0x68, 0x00, 0x69, 0x3d, 0xd8, 0x00, 0xde, 0x00]);
+    assert_eq!(bytes.take_utf16_string(2, Endian::Big), "hi");
+    assert_eq!(bytes.take_utf16_string(0, Endian::Big), "");
+    assert_eq!(bytes.take_utf16_string(2, Endian::Little), "\u{1f600}");
+    assert!(matches!(
+        bytes.try_take_utf16_string(1, Endian::Little),
+        Err(Error::OutOfBounds { .. })
+    ));
+    assert!(bytes
+        .try_take_utf16_string(usize::MAX, Endian::Little)
+        .is_err());
+    assert_eq!(bytes.pos(), 8);
+}
+
+#[test]
+fn take_utf16_string_unpaired_surrogate() {
+    let mut bytes = ByteCode::new(&[0x00, 0xdc, 0x41, 0x00]);
+    assert_eq!(
+        bytes.try_take_utf16_string(2, Endian::Little),
+        Err(Error::InvalidUtf16 { pos: 0, len: 4 })
+    );
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+fn take_string_lossy() {
+    let mut bytes = ByteCode::new(&[0x66, 0xc3, 0x28, 0x6f]);
+    assert_eq!(bytes.take_string_lossy(3), "f\u{fffd}(");
+    assert_eq!(bytes.try_take_string_lossy(1).unwrap(), "o");
+    assert!(bytes.try_take_string_lossy(1).is_err());
+}
+
+#[test]
+fn take_printable_string() {
+    let mut bytes = ByteCode::new(b"a\tb\xe2\x82\xac\xff\x7fend");
+    assert_eq!(bytes.take_printable_string(5), "a\tb");
+    assert_eq!(bytes.take_printable_string(usize::MAX), "\u{20ac}");
+    assert_eq!(bytes.pos(), 6);
+    assert_eq!(bytes.take_printable_string(8), "");
+    bytes.skip(2);
+    assert_eq!(bytes.take_printable_string(0), "");
+    assert_eq!(bytes.take_printable_string(8), "end");
+    assert!(bytes.is_end());
+    assert_eq!(bytes.take_printable_string(8), "");
+}
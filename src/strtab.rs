@@ -0,0 +1,105 @@
+use alloc::collections::BTreeMap;
+use core::cell::RefCell;
+
+/// A table of NUL-terminated strings referenced by byte offset,
+/// as in ELF `.strtab`, Mach-O symbol tables and similar formats.
+///
+/// Offsets may point into the middle of an entry, as linkers do when sharing suffixes.
+/// Each decoded string is cached, so repeated lookups of the same offset are cheap.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, StringTable};
+///
+/// let mut bytes = ByteCode::new(b"\0main\0printf\0\xff");
+/// let table = StringTable::new(bytes.take_ref(13));
+/// assert_eq!(table.get(0), Some(""));
+/// assert_eq!(table.get(1), Some("main"));
+/// assert_eq!(table.get(9), Some("ntf"));
+/// assert_eq!(table.get(13), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StringTable<'a> {
+    data: &'a [u8],
+    cache: RefCell<BTreeMap<usize, &'a str>>,
+}
+
+impl<'a> StringTable<'a> {
+    /// Creates a new `StringTable` over the given region.
+    pub fn new(data: &'a [u8]) -> Self {
+        StringTable {
+            data,
+            cache: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the string starting at `offset`, up to the next NUL.
+    ///
+    /// Returns `None` if `offset` is out of range, the string is not NUL-terminated
+    /// within the region, or it is not valid UTF-8.
+    pub fn get(&self, offset: usize) -> Option<&'a str> {
+        if let Some(string) = self.cache.borrow().get(&offset) {
+            return Some(string);
+        }
+        let data: &'a [u8] = self.data;
+        let rest = data.get(offset..)?;
+        let len = rest.iter().position(|byte| *byte == 0)?;
+        let string = core::str::from_utf8(&rest[..len]).ok()?;
+        self.cache.borrow_mut().insert(offset, string);
+        Some(string)
+    }
+
+    /// Returns an iterator over the entries and their offsets, in order.
+    ///
+    /// Iteration stops at the first entry that is not NUL-terminated or not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::StringTable;
+    ///
+    /// let table = StringTable::new(b"\0foo\0bar\0");
+    /// let entries: Vec<_> = table.entries().collect();
+    /// assert_eq!(entries, [(0, ""), (1, "foo"), (5, "bar")]);
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            let string = self.get(offset)?;
+            let entry = (offset, string);
+            offset += string.len() + 1;
+            Some(entry)
+        })
+    }
+
+    /// Extracts the underlying region.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn get() {
+    let table = StringTable::new(b"\0.text\0.data\0caf\xc3\xa9\0\xff\0unterminated");
+    assert_eq!(table.get(0), Some(""));
+    assert_eq!(table.get(1), Some(".text"));
+    assert_eq!(table.get(8), Some("data"));
+    assert_eq!(table.get(13), Some("café"));
+    assert_eq!(table.get(13), Some("café"));
+    assert_eq!(table.get(15), Some("fé"));
+    assert_eq!(table.get(19), None);
+    assert_eq!(table.get(21), None);
+    assert_eq!(table.get(usize::MAX), None);
+    assert_eq!(table.cache.borrow().len(), 5);
+}
+
+#[test]
+fn entries() {
+    let table = StringTable::new(b"\0.text\0.data\0caf\xc3\xa9\0\xff\0unterminated");
+    let entries: Vec<_> = table.entries().map(|(_, string)| string).collect();
+    assert_eq!(entries, ["", ".text", ".data", "café"]);
+}
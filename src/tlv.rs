@@ -0,0 +1,229 @@
+use alloc::{borrow::ToOwned, format};
+
+use crate::{ByteCode, Endian, Error};
+
+/// Layout of a tag-length-value container.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{Endian, Tlv};
+///
+/// let layout = Tlv::new(2, 2).endian(Endian::Little).alignment(4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tlv {
+    tag_width: usize,
+    length_width: usize,
+    endian: Endian,
+    alignment: usize,
+}
+
+impl Tlv {
+    /// Creates a new big-endian, unaligned `Tlv` layout with the given tag and length widths in bytes.
+    ///
+    /// The tag width must be in `0..=8` and the length width in `1..=8`;
+    /// they are checked by [`ByteCode::tlv`].
+    pub fn new(tag_width: usize, length_width: usize) -> Self {
+        Tlv {
+            tag_width,
+            length_width,
+            endian: Endian::Big,
+            alignment: 1,
+        }
+    }
+
+    /// Sets the byte order of the tag and length fields.
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Sets the boundary each record is padded to, relative to the start of the container.
+    ///
+    /// The alignment must not be 0; it is checked by [`ByteCode::tlv`].
+    pub fn alignment(mut self, alignment: usize) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    fn check(&self, pos: usize) -> Result<(), Error> {
+        let message = if self.tag_width > 8 || self.length_width == 0 || self.length_width > 8 {
+            format!(
+                "TLV tag width {} and length width {} must be in 0..=8 and 1..=8",
+                self.tag_width, self.length_width
+            )
+        } else if self.alignment == 0 {
+            "TLV alignment must not be 0".to_owned()
+        } else {
+            return Ok(());
+        };
+        Err(Error::InvalidArgument { pos, message })
+    }
+}
+
+/// An iterator over the records of a tag-length-value container.
+///
+/// This struct is created by [`ByteCode::tlv`].
+pub struct TlvIter<'b, 'a> {
+    bytes: &'b mut ByteCode<'a>,
+    layout: Tlv,
+    start: usize,
+}
+
+impl<'a> Iterator for TlvIter<'_, 'a> {
+    type Item = (u64, ByteCode<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let layout = self.layout;
+        let header = layout.tag_width + layout.length_width;
+        let inner: &'a [u8] = self.bytes.inner;
+        if inner.len() < header {
+            return None;
+        }
+        let tag = layout.endian.read_uint(&inner[..layout.tag_width]);
+        let len = layout.endian.read_uint(&inner[layout.tag_width..header]);
+        let len = usize::try_from(len).ok()?;
+        if len > inner.len() - header {
+            return None;
+        }
+        let value = self.bytes.child(&inner[header..header + len], 0);
+
+        let record = (self.bytes.pos() - self.start) + header + len;
+        let padding = (layout.alignment - record % layout.alignment) % layout.alignment;
+        self.bytes.skip((header + len + padding).min(inner.len()));
+        Some((tag, value))
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns an iterator over tag-length-value records with the given layout.
+    /// Each record is yielded as its tag and a new `ByteCode` over its value.
+    /// Moves the pointer forward past each record as it is yielded.
+    ///
+    /// Each value reader inherits the byte order, strictness and panic style of this one.
+    ///
+    /// The iteration stops at the end of the slice, or at a record whose header or value is truncated,
+    /// in which case the pointer is left at the beginning of that record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a width of the layout is out of range or its alignment is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Tlv};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x02, 0x66, 0x6f, 0x02, 0x01, 0x6f]);
+    /// let records: Vec<_> = bytes
+    ///     .tlv(Tlv::new(1, 1))
+    ///     .map(|(tag, value)| (tag, value.as_slice()))
+    ///     .collect();
+    /// assert_eq!(records, [(1, &[0x66, 0x6f][..]), (2, &[0x6f][..])]);
+    /// assert!(bytes.is_end());
+    /// ```
+    pub fn tlv<'b>(&'b mut self, layout: Tlv) -> TlvIter<'b, 'a> {
+        if let Err(error) = layout.check(self.pos) {
+            self.fail(error);
+        }
+        let start = self.pos();
+        TlvIter {
+            bytes: self,
+            layout,
+            start,
+        }
+    }
+
+    /// Returns an iterator over tag-length-value records with the given layout.
+    ///
+    /// Fallible version of [`tlv`](ByteCode::tlv),
+    /// which reports an invalid layout as [`Error::InvalidArgument`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Tlv};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0x01, 0x66]);
+    /// assert!(bytes.try_tlv(Tlv::new(1, 0)).is_err());
+    /// assert_eq!(bytes.try_tlv(Tlv::new(1, 1)).unwrap().count(), 1);
+    /// ```
+    pub fn try_tlv<'b>(&'b mut self, layout: Tlv) -> Result<TlvIter<'b, 'a>, Error> {
+        layout.check(self.pos)?;
+        let start = self.pos();
+        Ok(TlvIter {
+            bytes: self,
+            layout,
+            start,
+        })
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn tlv() {
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00, 0x02, 0x66, 0x6f, 0x00, 0x02, 0x00, 0x00]);
+    let mut records = bytes.tlv(Tlv::new(2, 2));
+
+    let (tag, value) = records.next().unwrap();
+    assert_eq!(tag, 1);
+    assert_eq!(value.as_slice(), [0x66, 0x6f]);
+
+    let (tag, value) = records.next().unwrap();
+    assert_eq!(tag, 2);
+    assert!(value.is_end());
+
+    assert!(records.next().is_none());
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn tlv_little_endian_aligned() {
+    let mut bytes = ByteCode::new(&[
+        0x01, 0x00, 0x01, 0x00, 0xaa, 0x00, 0x00, 0x00, //
+        0x02, 0x00, 0x02, 0x00, 0xbb, 0xcc, 0x00, 0x00,
+    ]);
+    let records: Vec<_> = bytes
+        .tlv(Tlv::new(2, 2).endian(Endian::Little).alignment(4))
+        .map(|(tag, value)| (tag, value.as_slice()))
+        .collect();
+    assert_eq!(records, [(1, &[0xaa][..]), (2, &[0xbb, 0xcc][..])]);
+    assert!(bytes.is_end());
+}
+
+#[test]
+fn tlv_inherit_settings() {
+    let mut bytes = ByteCode::new(&[0x07, 0x02, 0x01, 0x00]);
+    bytes.set_endian(Endian::Little);
+    bytes.set_strictness(crate::Strictness::Strict);
+    bytes.set_panic_style(crate::PanicStyle::Context);
+    let (tag, mut value) = bytes.tlv(Tlv::new(1, 1)).next().unwrap();
+    assert_eq!(tag, 7);
+    assert_eq!(value.endian(), Endian::Little);
+    assert_eq!(value.strictness(), crate::Strictness::Strict);
+    assert_eq!(value.panic_style(), crate::PanicStyle::Context);
+    assert_eq!(value.take_into_u16(), 1);
+}
+
+#[test]
+fn tlv_truncated() {
+    let mut bytes = ByteCode::new(&[0x01, 0x01, 0xaa, 0x02, 0x05, 0xbb]);
+    assert_eq!(bytes.tlv(Tlv::new(1, 1)).count(), 1);
+    assert_eq!(bytes.pos(), 3);
+}
+
+#[test]
+#[should_panic]
+fn tlv_invalid_layout() {
+    ByteCode::new(&[]).tlv(Tlv::new(1, 0));
+}
+
+#[test]
+fn try_tlv_invalid_alignment() {
+    let mut bytes = ByteCode::new(&[]);
+    let error = bytes.try_tlv(Tlv::new(1, 1).alignment(0)).err().unwrap();
+    assert!(matches!(error, Error::InvalidArgument { pos: 0, .. }));
+}
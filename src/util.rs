@@ -1,4 +1,6 @@
-use crate::ByteCode;
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+use crate::{ByteCode, Endian, Error};
 
 impl<'a> ByteCode<'a> {
     /// Returns a reference to subslice corresponding to the given size.
@@ -12,14 +14,7 @@ impl<'a> ByteCode<'a> {
     /// assert_eq!(bytes.peek(3), [0, 1, 2]);
     /// ```
     pub fn peek(&'a self, num: usize) -> &'a [u8] {
-        if num > self.inner.len() {
-            panic!(
-                "range end index {} out of range for slice of length {}",
-                num,
-                self.inner.len()
-            );
-        }
-        &self[0..num]
+        self.try_peek(num).unwrap_or_else(|error| self.fail(error))
     }
 
     /// Returns `true` if given subslice is a prefix of the slice.
@@ -99,9 +94,72 @@ impl<'a> ByteCode<'a> {
     /// assert_eq!(bytes.take(3), [0, 1, 2]);
     /// ```
     pub fn take(&mut self, num: usize) -> Vec<u8> {
-        let result = self.peek(num).to_owned();
-        self.skip(num);
-        result
+        self.take_ref(num).to_owned()
+    }
+
+    /// Returns a reference to subslice corresponding to the given size, without copying.
+    /// Moves the pointer forward by the length of subslice.
+    ///
+    /// The subslice borrows the underlying data rather than this `ByteCode`,
+    /// so it stays valid as the pointer moves on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// let first = bytes.take_ref(3);
+    /// let second = bytes.take_ref(2);
+    /// assert_eq!(first, [0, 1, 2]);
+    /// assert_eq!(second, [3, 4]);
+    /// ```
+    pub fn take_ref(&mut self, num: usize) -> &'a [u8] {
+        self.try_take_ref(num)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first `N` elements of the slice as an array, for signatures, UUIDs and hashes.
+    /// Moves the pointer forward `N`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `N` elements remain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00]);
+    /// let signature: [u8; 8] = bytes.take_array();
+    /// assert_eq!(&signature, b"\x89PNG\r\n\x1a\n");
+    /// assert_eq!(bytes.pos(), 8);
+    /// ```
+    pub fn take_array<const N: usize>(&mut self) -> [u8; N] {
+        let array = self.peek_array();
+        self.skip(N);
+        array
+    }
+
+    /// Returns the first `N` elements of the slice as an array without moving the pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `N` elements remain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0xca, 0xfe, 0xba, 0xbe]);
+    /// assert_eq!(bytes.peek_array::<2>(), [0xca, 0xfe]);
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn peek_array<const N: usize>(&self) -> [u8; N] {
+        self.try_peek_array()
+            .unwrap_or_else(|error| self.fail(error))
     }
 
     /// Returns the first byte.
@@ -119,7 +177,8 @@ impl<'a> ByteCode<'a> {
         self.take(1)[0]
     }
 
-    /// Returns the first 2 elements of the slice converted into `u16`.
+    /// Returns the first 2 elements of the slice converted into `u16`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
     /// Moves the pointer forward 2.
     ///
     /// # Examples
@@ -131,11 +190,14 @@ impl<'a> ByteCode<'a> {
     /// assert_eq!(bytes.take_into_u16(), u16::MAX);
     /// ```
     pub fn take_into_u16(&mut self) -> u16 {
-        let bytes: [u8; 2] = self.take(2).try_into().unwrap();
-        u16::from_be_bytes(bytes)
+        match self.endian {
+            Endian::Big => self.take_be_u16(),
+            Endian::Little => self.take_le_u16(),
+        }
     }
 
-    /// Returns the first 4 elements of the slice converted into `u32`.
+    /// Returns the first 4 elements of the slice converted into `u32`
+    /// in the byte order set by [`set_endian`](ByteCode::set_endian).
     /// Moves the pointer forward 4.
     ///
     /// # Examples
@@ -147,8 +209,258 @@ impl<'a> ByteCode<'a> {
     /// assert_eq!(bytes.take_into_u32(), u32::MAX);
     /// ```
     pub fn take_into_u32(&mut self) -> u32 {
-        let bytes: [u8; 4] = self.take(4).try_into().unwrap();
-        u32::from_be_bytes(bytes)
+        match self.endian {
+            Endian::Big => self.take_be_u32(),
+            Endian::Little => self.take_le_u32(),
+        }
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16` in network (big-endian) order.
+    /// Moves the pointer forward 2.
+    ///
+    /// Unlike `take_into_u16`, ignores the byte order set by [`set_endian`](ByteCode::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x01, 0xbb, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_be_u16(), 443);
+    /// ```
+    pub fn take_be_u16(&mut self) -> u16 {
+        self.try_take_be_u16()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32` in network (big-endian) order.
+    /// Moves the pointer forward 4.
+    ///
+    /// Unlike `take_into_u32`, ignores the byte order set by [`set_endian`](ByteCode::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_be_u32(), 256);
+    /// ```
+    pub fn take_be_u32(&mut self) -> u32 {
+        self.try_take_be_u32()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 2 elements of the slice converted into `u16` in little-endian order.
+    /// Moves the pointer forward 2.
+    ///
+    /// Unlike `take_into_u16`, ignores the byte order set by [`set_endian`](ByteCode::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0xbb, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_le_u16(), 443);
+    /// ```
+    pub fn take_le_u16(&mut self) -> u16 {
+        self.try_take_le_u16()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns the first 4 elements of the slice converted into `u32` in little-endian order.
+    /// Moves the pointer forward 4.
+    ///
+    /// Unlike `take_into_u32`, ignores the byte order set by [`set_endian`](ByteCode::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_le_u32(), 256);
+    /// ```
+    pub fn take_le_u32(&mut self) -> u32 {
+        self.try_take_le_u32()
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Reads a `u16` length in the byte order set by [`set_endian`](ByteCode::set_endian) and returns
+    /// a child `ByteCode` over the following bytes of that length, as [`take_bytecode`](ByteCode::take_bytecode) does.
+    /// Moves the pointer forward past the length and the body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length exceeds `max` or the remaining elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x02, 0x66, 0x6f, 0x00]);
+    /// let mut record = bytes.take_length_prefixed_u16(16);
+    /// assert_eq!(record.take(2), [0x66, 0x6f]);
+    /// assert_eq!(bytes.pos(), 4);
+    /// ```
+    pub fn take_length_prefixed_u16(&mut self, max: usize) -> ByteCode<'a> {
+        self.try_take_length_prefixed_u16(max)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Reads a `u32` length in the byte order set by [`set_endian`](ByteCode::set_endian) and returns
+    /// a child `ByteCode` over the following bytes of that length, as [`take_bytecode`](ByteCode::take_bytecode) does.
+    /// Moves the pointer forward past the length and the body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length exceeds `max` or the remaining elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// let mut bytes = ByteCode::new(&[0x02, 0x00, 0x00, 0x00, 0x66, 0x6f, 0x00]);
+    /// bytes.set_endian(Endian::Little);
+    /// let mut record = bytes.take_length_prefixed_u32(16);
+    /// assert_eq!(record.take_into_u16(), 0x6f66);
+    /// assert_eq!(bytes.pos(), 6);
+    /// ```
+    pub fn take_length_prefixed_u32(&mut self, max: usize) -> ByteCode<'a> {
+        self.try_take_length_prefixed_u32(max)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Reads a `u16` length and returns a child `ByteCode` over the following bytes of that length.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_length_prefixed_u16`](ByteCode::take_length_prefixed_u16).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if the length field is truncated, or if the length exceeds
+    /// `max` or the remaining elements, with `available` being the smaller of the two.
+    pub fn try_take_length_prefixed_u16(&mut self, max: usize) -> Result<ByteCode<'a>, Error> {
+        self.try_take_length_prefixed(2, max)
+    }
+
+    /// Reads a `u32` length and returns a child `ByteCode` over the following bytes of that length.
+    /// The pointer is not moved on error.
+    ///
+    /// Fallible version of [`take_length_prefixed_u32`](ByteCode::take_length_prefixed_u32).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if the length field is truncated, or if the length exceeds
+    /// `max` or the remaining elements, with `available` being the smaller of the two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x03, 0x66, 0x6f, 0x6f]);
+    /// assert!(bytes.try_take_length_prefixed_u32(2).is_err());
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn try_take_length_prefixed_u32(&mut self, max: usize) -> Result<ByteCode<'a>, Error> {
+        self.try_take_length_prefixed(4, max)
+    }
+
+    fn try_take_length_prefixed(
+        &mut self,
+        width: usize,
+        max: usize,
+    ) -> Result<ByteCode<'a>, Error> {
+        let len = self.peek_length_prefixed(width, max)?.len();
+        self.skip(width);
+        Ok(self.take_bytecode(len))
+    }
+
+    /// Returns the bytes following a `width`-byte length of at most `max`, without moving the pointer.
+    pub(crate) fn peek_length_prefixed(&self, width: usize, max: usize) -> Result<&'a [u8], Error> {
+        let len = self.endian.read_uint(self.try_peek(width)?);
+        let inner: &'a [u8] = self.inner;
+        let available = (inner.len() - width).min(max);
+        let len = usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= available)
+            .ok_or(Error::OutOfBounds {
+                pos: self.pos + width,
+                requested: usize::try_from(len).unwrap_or(usize::MAX),
+                available,
+            })?;
+        Ok(&inner[width..width + len])
+    }
+
+    /// Returns a new `ByteCode` over the first `num` elements of the slice without moving the pointer.
+    ///
+    /// The child reader inherits the byte order of this one and cannot read past its window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// let child = bytes.peek_bytecode(3);
+    /// assert_eq!(child.as_slice(), [0, 1, 2]);
+    /// assert_eq!(bytes.pos(), 0);
+    /// ```
+    pub fn peek_bytecode(&self, num: usize) -> ByteCode<'a> {
+        let inner = self.try_peek(num).unwrap_or_else(|error| self.fail(error));
+        self.child(inner, 0)
+    }
+
+    /// Returns a new `ByteCode` over the first `num` elements of the slice,
+    /// as for a chunk that should be parsed with its own bounded cursor.
+    /// Moves the pointer forward by given number.
+    ///
+    /// The child reader inherits the byte order of this one and cannot read past its window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCode, Endian};
+    ///
+    /// // A RIFF chunk: 4-byte id, little-endian length, body.
+    /// let mut bytes = ByteCode::new(b"fmt \x02\x00\x00\x00\x01\x00data");
+    /// bytes.set_endian(Endian::Little);
+    /// bytes.skip(4);
+    /// let len = bytes.take_into_u32() as usize;
+    /// let mut chunk = bytes.take_bytecode(len);
+    /// assert_eq!(chunk.take_into_u16(), 1);
+    /// assert!(chunk.is_end());
+    /// assert!(bytes.starts_with(b"data"));
+    /// ```
+    pub fn take_bytecode(&mut self, num: usize) -> ByteCode<'a> {
+        let child = self.peek_bytecode(num);
+        self.skip(num);
+        child
+    }
+
+    /// Returns the first 4 elements of the slice decoded as a 28-bit syncsafe integer,
+    /// as used by ID3v2 tags, where only the lower 7 bits of each byte carry the value.
+    /// Moves the pointer forward 4.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 4 elements, or if the most significant bit of any of the 4 bytes is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x00, 0x00, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_into_syncsafe_u32(), 257);
+    /// ```
+    pub fn take_into_syncsafe_u32(&mut self) -> u32 {
+        self.try_take_into_syncsafe_u32()
+            .unwrap_or_else(|error| self.fail(error))
     }
 
     /// Returns the string consisting of the given number of bytes from the beginning of the slice.
@@ -163,11 +475,97 @@ impl<'a> ByteCode<'a> {
     /// assert_eq!(bytes.take_into_string(3), "foo".to_owned());
     /// ```
     pub fn take_into_string(&mut self, num: usize) -> String {
-        let bytes = self.take(num);
-        String::from_utf8(bytes).unwrap()
+        self.take_str(num).to_owned()
+    }
+
+    /// Returns the string slice consisting of the given number of bytes from the beginning of the slice,
+    /// without copying. Moves the pointer forward by given number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytes are not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0x66, 0x6f, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(bytes.take_str(3), "foo");
+    /// ```
+    pub fn take_str(&mut self, num: usize) -> &'a str {
+        self.try_take_str(num)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns `len`-byte subslices located at the given absolute offsets from the beginning of the slice,
+    /// as in index tables. All offsets are validated before any subslice is collected.
+    /// The pointer is not moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any subslice is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    /// bytes.skip(6);
+    /// assert_eq!(bytes.take_at_offsets(&[4, 0], 2), [&[4, 5], &[0, 1]]);
+    /// ```
+    pub fn take_at_offsets(&self, offsets: &[usize], len: usize) -> Vec<&'a [u8]> {
+        self.try_take_at_offsets(offsets, len)
+            .unwrap_or_else(|error| self.fail(error))
+    }
+
+    /// Returns `count` elements of `element_size` bytes each, taken every `stride` bytes, concatenated into a vector.
+    /// Moves the pointer forward to the end of the last element.
+    ///
+    /// Useful for extracting one channel from interleaved data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than `element_size`, or if the last element is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCode;
+    ///
+    /// // 16-bit stereo frames: take the left channel
+    /// let mut bytes = ByteCode::new(&[0x01, 0x02, 0xaa, 0xaa, 0x03, 0x04, 0xbb, 0xbb]);
+    /// assert_eq!(bytes.take_strided(2, 4, 2), [0x01, 0x02, 0x03, 0x04]);
+    /// assert_eq!(bytes.pos(), 6);
+    /// ```
+    pub fn take_strided(&mut self, element_size: usize, stride: usize, count: usize) -> Vec<u8> {
+        self.try_take_strided(element_size, stride, count)
+            .unwrap_or_else(|error| self.fail(error))
     }
 }
 
+/// Returns the number of bytes from the start of the first strided element to the end of the last.
+pub(crate) fn strided_span(element_size: usize, stride: usize, count: usize) -> usize {
+    match count {
+        0 => 0,
+        _ => (count - 1)
+            .checked_mul(stride)
+            .and_then(|offset| offset.checked_add(element_size))
+            .unwrap_or(usize::MAX),
+    }
+}
+
+pub(crate) fn gather_strided(span: &[u8], element_size: usize, stride: usize) -> Vec<u8> {
+    let mut result = Vec::with_capacity(span.len() / stride.max(1) * element_size + element_size);
+    let mut offset = 0;
+    while offset < span.len() {
+        result.extend_from_slice(&span[offset..offset + element_size]);
+        offset += stride;
+    }
+    result
+}
+
 #[test]
 fn peek() {
     let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
@@ -181,6 +579,23 @@ fn peek_out_of_range() {
     bytes.peek(9);
 }
 
+#[test]
+fn take_array() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4]);
+    assert_eq!(bytes.take_array::<0>(), []);
+    assert_eq!(bytes.take_array(), [0, 1, 2]);
+    assert_eq!(bytes.peek_array(), [3, 4]);
+    assert_eq!(bytes.try_take_array::<3>().unwrap_err().pos(), 3);
+    assert_eq!(bytes.try_take_array(), Ok([3, 4]));
+    assert!(bytes.is_end());
+}
+
+#[test]
+#[should_panic]
+fn take_array_out_of_range() {
+    ByteCode::new(&[0; 15]).take_array::<16>();
+}
+
 #[test]
 fn starts_with() {
     let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
@@ -243,8 +658,165 @@ fn take_into_u32() {
     assert_eq!(bytes.peek(3), [0, 0, 0]);
 }
 
+#[test]
+fn take_be_u16() {
+    let mut bytes = ByteCode::new(&[0x01, 0xbb, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(bytes.take_be_u16(), 443);
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+fn take_be_u32() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(bytes.take_be_u32(), 256);
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+fn take_le_u16() {
+    let mut bytes = ByteCode::new(&[0xbb, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(bytes.take_le_u16(), 443);
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+fn take_le_u32() {
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(bytes.take_le_u32(), 256);
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+fn take_length_prefixed() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x03, 0x66, 0x6f, 0x6f, 0x00]);
+    bytes.set_strictness(crate::Strictness::Strict);
+    let mut record = bytes.take_length_prefixed_u32(3);
+    assert_eq!(record.pos(), 0);
+    assert_eq!(record.len(), 3);
+    assert_eq!(record.strictness(), crate::Strictness::Strict);
+    assert_eq!(record.take_into_string(3), "foo".to_owned());
+    assert_eq!(bytes.pos(), 7);
+
+    let mut bytes = ByteCode::new(&[0x01, 0x00, 0xff, 0x00]);
+    bytes.set_endian(Endian::Little);
+    assert_eq!(bytes.take_length_prefixed_u16(1).as_slice(), [0xff]);
+}
+
+#[test]
+fn take_length_prefixed_out_of_range() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x03, 0x66, 0x6f, 0x6f, 0x00]);
+    assert_eq!(
+        bytes.try_take_length_prefixed_u32(2).unwrap_err(),
+        Error::OutOfBounds {
+            pos: 4,
+            requested: 3,
+            available: 2
+        }
+    );
+    assert_eq!(bytes.pos(), 0);
+
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00, 0x05, 0x66, 0x6f, 0x6f, 0x00]);
+    assert!(bytes.try_take_length_prefixed_u32(16).is_err());
+    assert_eq!(bytes.pos(), 0);
+
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x00]);
+    assert!(bytes.try_take_length_prefixed_u32(16).is_err());
+    assert!(bytes.try_take_length_prefixed_u16(16).is_ok());
+}
+
+#[test]
+fn take_into_syncsafe_u32() {
+    let mut bytes = ByteCode::new(&[0x7f, 0x7f, 0x7f, 0x7f, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(bytes.take_into_syncsafe_u32(), 0x0fff_ffff);
+    assert_eq!(bytes.peek(3), [0, 0, 0]);
+}
+
+#[test]
+#[should_panic]
+fn take_into_syncsafe_u32_reserved_bit() {
+    let mut bytes = ByteCode::new(&[0x00, 0x00, 0x80, 0x00]);
+    bytes.take_into_syncsafe_u32();
+}
+
 #[test]
 fn take_into_string() {
     let mut bytes = ByteCode::new(&[0x66, 0x6f, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00]);
     assert_eq!(bytes.take_into_string(3), "foo".to_owned());
 }
+
+#[test]
+fn take_strided() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(bytes.take_strided(1, 3, 3), [0, 3, 6]);
+    assert_eq!(bytes.pos(), 7);
+    assert_eq!(bytes.take_strided(1, 1, 0), []);
+    assert_eq!(bytes.take_strided(1, 1, 1), [7]);
+}
+
+#[test]
+#[should_panic]
+fn take_strided_out_of_range() {
+    let mut bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes.take_strided(2, 4, 3);
+}
+
+#[test]
+fn take_at_offsets() {
+    let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(
+        bytes.take_at_offsets(&[6, 2, 2], 2),
+        [&[6, 7], &[2, 3], &[2, 3]]
+    );
+    assert!(bytes.take_at_offsets(&[], 2).is_empty());
+    assert_eq!(bytes.pos(), 0);
+}
+
+#[test]
+#[should_panic]
+fn take_at_offsets_out_of_range() {
+    let bytes = ByteCode::new(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    bytes.take_at_offsets(&[0, 7], 2);
+}
+
+#[test]
+fn take_bytecode() {
+    let mut bytes = ByteCode::new(&[0x00, 0x01, 0x02, 0x03, 0x04, 0x05]);
+    bytes.set_endian(Endian::Little);
+    bytes.next();
+    let mut child = bytes.take_bytecode(4);
+    assert_eq!(bytes.pos(), 5);
+    assert_eq!(child.len(), 4);
+    assert_eq!(child.endian(), Endian::Little);
+    assert_eq!(child.take_into_u16(), 0x0201);
+    assert!(child.try_take_into_u32().is_err());
+    assert_eq!(bytes.peek_bytecode(1).as_slice(), [0x05]);
+    assert_eq!(bytes.take_bytecode(1).take_bytecode(1).as_slice(), [0x05]);
+    assert!(bytes.is_end());
+}
+
+#[test]
+#[should_panic]
+fn take_bytecode_out_of_range() {
+    let mut bytes = ByteCode::new(&[0, 1, 2]);
+    bytes.take_bytecode(4);
+}
+
+#[test]
+fn take_ref() {
+    let data = [0x66, 0x6f, 0x6f, 0x62, 0x61, 0x72];
+    let (first, second) = {
+        let mut bytes = ByteCode::new(&data);
+        let first = bytes.take_ref(2);
+        bytes.next();
+        (first, bytes.take_str(3))
+    };
+    assert_eq!(first, b"fo");
+    assert_eq!(second, "bar");
+}
+
+#[test]
+#[should_panic]
+fn take_str_invalid_utf8() {
+    let mut bytes = ByteCode::new(&[0x66, 0xff]);
+    bytes.take_str(2);
+}
@@ -0,0 +1,210 @@
+use core::ops::Range;
+
+use crate::ByteCode;
+
+/// The number of bytes in a row, as in [`HexDump`](crate::HexDump).
+const ROW_LEN: usize = 16;
+
+/// A scrollable view of a fixed number of 16-byte rows, as the backend of a hex viewer.
+///
+/// Scrolling returns the rows that became visible, so that a viewer redraws only those
+/// and shifts the rest. In follow mode, moving the cursor scrolls just enough to keep it visible.
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::ByteCode;
+///
+/// let data = [0; 256];
+/// let mut window = ByteCode::new(&data).view(4);
+/// assert_eq!(window.range(), 0..64);
+///
+/// // The cursor moves one row past the bottom, so only that row needs drawing.
+/// assert_eq!(window.set_cursor(0x40), 4..5);
+/// assert_eq!(window.range(), 16..80);
+///
+/// let mut lines = Vec::new();
+/// window.render_rows(4..5, |offset, row| lines.push((offset, row.len())));
+/// assert_eq!(lines, [(0x40, 16)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window<'a> {
+    bytes: &'a [u8],
+    top: usize,
+    rows: usize,
+    cursor: usize,
+    follow: bool,
+}
+
+impl<'a> Window<'a> {
+    /// Creates a new `Window` of `rows` rows at the start of `bytes`, with the cursor at 0 and follow mode on.
+    pub fn new(bytes: &'a [u8], rows: usize) -> Self {
+        Window {
+            bytes,
+            top: 0,
+            rows,
+            cursor: 0,
+            follow: true,
+        }
+    }
+
+    /// Sets whether moving the cursor scrolls the window to keep it visible. On by default.
+    pub fn follow(mut self, follow: bool) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    /// Returns the index of the first visible row.
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Returns the number of rows the window holds, which may exceed the rows left to show.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of rows in the whole data.
+    pub fn total_rows(&self) -> usize {
+        self.bytes.len().div_ceil(ROW_LEN)
+    }
+
+    /// Returns the indices of the visible rows.
+    pub fn visible_rows(&self) -> Range<usize> {
+        self.top..(self.top + self.rows).min(self.total_rows())
+    }
+
+    /// Returns the absolute positions of the visible bytes.
+    pub fn range(&self) -> Range<usize> {
+        let rows = self.visible_rows();
+        rows.start * ROW_LEN..(rows.end * ROW_LEN).min(self.bytes.len())
+    }
+
+    /// Returns the cursor position.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the cursor to `pos`, clamped to the last byte, and in follow mode scrolls the fewest rows
+    /// that bring it into view. Returns the rows that became visible.
+    pub fn set_cursor(&mut self, pos: usize) -> Range<usize> {
+        self.cursor = pos.min(self.bytes.len().saturating_sub(1));
+        if !self.follow {
+            return self.top..self.top;
+        }
+        let row = self.cursor / ROW_LEN;
+        if row < self.top {
+            self.scroll_to(row)
+        } else if row >= self.top + self.rows {
+            self.scroll_to(row + 1 - self.rows.max(1))
+        } else {
+            self.top..self.top
+        }
+    }
+
+    /// Scrolls so that `row` is the first visible row, as far as the data allows.
+    /// Returns the rows that became visible.
+    pub fn scroll_to(&mut self, row: usize) -> Range<usize> {
+        let old = self.visible_rows();
+        self.top = row.min(self.total_rows().saturating_sub(self.rows));
+        exposed(old, self.visible_rows())
+    }
+
+    /// Scrolls by `delta` rows, up if negative. Returns the rows that became visible.
+    pub fn scroll_by(&mut self, delta: isize) -> Range<usize> {
+        self.scroll_to(self.top.saturating_add_signed(delta))
+    }
+
+    /// Changes the number of rows the window holds, keeping the top row where possible.
+    /// Returns the rows that became visible, or all visible rows if the window grew at both ends.
+    pub fn resize(&mut self, rows: usize) -> Range<usize> {
+        let old = self.visible_rows();
+        self.rows = rows;
+        self.top = self.top.min(self.total_rows().saturating_sub(rows));
+        exposed(old, self.visible_rows())
+    }
+
+    /// Calls `render` with the position and bytes of each visible row within `rows`, in order.
+    ///
+    /// The last row of the data may be shorter than 16 bytes.
+    pub fn render_rows(&self, rows: Range<usize>, mut render: impl FnMut(usize, &'a [u8])) {
+        let visible = self.visible_rows();
+        for row in rows.start.max(visible.start)..rows.end.min(visible.end) {
+            let start = row * ROW_LEN;
+            let end = (start + ROW_LEN).min(self.bytes.len());
+            render(start, &self.bytes[start..end]);
+        }
+    }
+}
+
+/// Returns the rows of `new` that are not in `old`, or all of `new` if they lie on both sides of `old`.
+fn exposed(old: Range<usize>, new: Range<usize>) -> Range<usize> {
+    if new.start < old.start && new.end > old.end {
+        new
+    } else if new.start < old.start {
+        new.start..new.end.min(old.start)
+    } else if new.end > old.end {
+        new.start.max(old.end)..new.end
+    } else {
+        new.start..new.start
+    }
+}
+
+impl<'a> ByteCode<'a> {
+    /// Returns a [`Window`] of `rows` rows over the whole slice, with the cursor at the pointer and in view.
+    pub fn view(&self, rows: usize) -> Window<'a> {
+        let mut window = Window::new(self.original(), rows);
+        window.set_cursor(self.pos);
+        window
+    }
+}
+
+#[test]
+fn scroll() {
+    let data: alloc::vec::Vec<u8> = (0..100).collect();
+    let mut window = Window::new(&data, 3);
+    assert_eq!(window.total_rows(), 7);
+    assert_eq!(window.scroll_by(1), 3..4);
+    assert_eq!(window.scroll_by(-2), 0..1);
+    assert_eq!(window.scroll_to(100), 4..7);
+    assert_eq!(window.range(), 64..100);
+    assert_eq!(window.scroll_by(-1), 3..4);
+    assert!(window.scroll_by(0).is_empty());
+
+    assert_eq!(window.resize(5), 2..7);
+    assert_eq!(window.visible_rows(), 2..7);
+    assert_eq!(window.resize(10), 0..2);
+    assert!(window.resize(2).is_empty());
+    assert_eq!(window.visible_rows(), 0..2);
+}
+
+#[test]
+fn follow_cursor() {
+    let data = [0; 100];
+    let mut window = Window::new(&data, 2);
+    assert!(window.set_cursor(31).is_empty());
+    assert_eq!(window.set_cursor(50), 2..4);
+    assert_eq!(window.visible_rows(), 2..4);
+    assert_eq!(window.set_cursor(20), 1..2);
+    assert_eq!(window.set_cursor(usize::MAX), 5..7);
+    assert_eq!(window.cursor(), 99);
+
+    let mut window = Window::new(&data, 2).follow(false);
+    assert!(window.set_cursor(99).is_empty());
+    assert_eq!(window.top(), 0);
+
+    let mut bytes = ByteCode::new(&data);
+    bytes.skip(60);
+    assert_eq!(bytes.view(1).visible_rows(), 3..4);
+    assert!(ByteCode::new(&[]).view(4).range().is_empty());
+}
+
+#[test]
+fn render_rows() {
+    let data: alloc::vec::Vec<u8> = (0..40).collect();
+    let mut window = Window::new(&data, 2);
+    window.scroll_to(1);
+    let mut rows = alloc::vec::Vec::new();
+    window.render_rows(0..10, |offset, row| rows.push((offset, row)));
+    assert_eq!(rows, [(16, &data[16..32]), (32, &data[32..40])]);
+}
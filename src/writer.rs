@@ -0,0 +1,519 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::Endian;
+
+/// A growable buffer for emitting bytecode, the writing counterpart of [`ByteCode`](crate::ByteCode).
+///
+/// # Examples
+///
+/// ```
+/// use bytecode::{ByteCode, ByteCodeWriter};
+///
+/// let mut writer = ByteCodeWriter::new();
+/// writer.put_u8(0x01);
+/// let len_pos = writer.pos();
+/// writer.put_u16(0);
+/// writer.put_string("foo");
+/// writer.patch_u16(len_pos, 3);
+///
+/// let mut bytes = ByteCode::new(writer.as_slice());
+/// assert_eq!(bytes.take_into_u8(), 0x01);
+/// let len = bytes.take_into_u16() as usize;
+/// assert_eq!(bytes.take_into_string(len), "foo");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteCodeWriter {
+    inner: Vec<u8>,
+    endian: Endian,
+}
+
+impl ByteCodeWriter {
+    /// Creates a new empty `ByteCodeWriter`.
+    ///
+    /// Multi-byte values are written in big-endian order until changed with [`set_endian`](ByteCodeWriter::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let writer = ByteCodeWriter::new();
+    /// assert!(writer.as_slice().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the byte order used by `put_*` and `patch_*` writes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCodeWriter, Endian};
+    ///
+    /// let writer = ByteCodeWriter::new();
+    /// assert_eq!(writer.endian(), Endian::Big);
+    /// ```
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Sets the byte order used by `put_*` and `patch_*` writes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::{ByteCodeWriter, Endian};
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.set_endian(Endian::Little);
+    /// writer.put_u16(1);
+    /// assert_eq!(writer.as_slice(), [0x01, 0x00]);
+    /// ```
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns the position at which the next value will be written,
+    /// which is also the number of bytes written so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u32(0);
+    /// assert_eq!(writer.pos(), 4);
+    /// ```
+    pub fn pos(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Extracts a slice of the bytes written so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_slice(&[0, 1, 2]);
+    /// assert_eq!(writer.as_slice(), [0, 1, 2]);
+    /// ```
+    pub fn as_slice(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Consumes the writer, returning the bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u8(0xff);
+    /// assert_eq!(writer.into_inner(), vec![0xff]);
+    /// ```
+    pub fn into_inner(self) -> Vec<u8> {
+        self.inner
+    }
+
+    /// Appends a `u8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u8(u8::MAX);
+    /// assert_eq!(writer.as_slice(), [0xff]);
+    /// ```
+    pub fn put_u8(&mut self, value: u8) {
+        self.inner.push(value);
+    }
+
+    /// Appends a `u16` in the byte order set by [`set_endian`](ByteCodeWriter::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u16(256);
+    /// assert_eq!(writer.as_slice(), [0x01, 0x00]);
+    /// ```
+    pub fn put_u16(&mut self, value: u16) {
+        let bytes = match self.endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.put_slice(&bytes);
+    }
+
+    /// Appends a `u32` in the byte order set by [`set_endian`](ByteCodeWriter::set_endian).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u32(256);
+    /// assert_eq!(writer.as_slice(), [0x00, 0x00, 0x01, 0x00]);
+    /// ```
+    pub fn put_u32(&mut self, value: u32) {
+        let bytes = match self.endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.put_slice(&bytes);
+    }
+
+    /// Appends a slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_slice(&[0xca, 0xfe]);
+    /// assert_eq!(writer.as_slice(), [0xca, 0xfe]);
+    /// ```
+    pub fn put_slice(&mut self, slice: &[u8]) {
+        self.inner.extend_from_slice(slice);
+    }
+
+    /// Appends the UTF-8 bytes of a string, without a length or terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_string("foo");
+    /// assert_eq!(writer.as_slice(), [0x66, 0x6f, 0x6f]);
+    /// ```
+    pub fn put_string(&mut self, s: &str) {
+        self.put_slice(s.as_bytes());
+    }
+
+    /// Overwrites a `u8` previously written at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is not before the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_slice(&[0x00, 0x00]);
+    /// writer.patch_u8(1, 0xff);
+    /// assert_eq!(writer.as_slice(), [0x00, 0xff]);
+    /// ```
+    pub fn patch_u8(&mut self, pos: usize, value: u8) {
+        self.patch_slice(pos, &[value]);
+    }
+
+    /// Overwrites a `u16` previously written at `pos`
+    /// in the byte order set by [`set_endian`](ByteCodeWriter::set_endian).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the 2 bytes at `pos` have not been written yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u16(0);
+    /// writer.put_u8(0xff);
+    /// writer.patch_u16(0, 1);
+    /// assert_eq!(writer.as_slice(), [0x00, 0x01, 0xff]);
+    /// ```
+    pub fn patch_u16(&mut self, pos: usize, value: u16) {
+        let bytes = match self.endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.patch_slice(pos, &bytes);
+    }
+
+    /// Overwrites a `u32` previously written at `pos`
+    /// in the byte order set by [`set_endian`](ByteCodeWriter::set_endian).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the 4 bytes at `pos` have not been written yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_u32(0);
+    /// writer.patch_u32(0, 0xcafe_babe);
+    /// assert_eq!(writer.as_slice(), [0xca, 0xfe, 0xba, 0xbe]);
+    /// ```
+    pub fn patch_u32(&mut self, pos: usize, value: u32) {
+        let bytes = match self.endian {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.patch_slice(pos, &bytes);
+    }
+
+    /// Overwrites bytes previously written at `pos` with a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytes at `pos..pos + slice.len()` have not been written yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_string("foo");
+    /// writer.patch_slice(1, b"ee");
+    /// assert_eq!(writer.as_slice(), b"fee");
+    /// ```
+    pub fn patch_slice(&mut self, pos: usize, slice: &[u8]) {
+        let end = pos.saturating_add(slice.len());
+        if end > self.inner.len() {
+            panic!(
+                "patch range {}..{} out of range for written length {}",
+                pos,
+                end,
+                self.inner.len()
+            );
+        }
+        self.inner[pos..end].copy_from_slice(slice);
+    }
+
+    /// Copies previously written bytes in `src` to `dest`, as when relocating a section.
+    ///
+    /// The ranges may overlap; the result is as if `src` were first copied to a temporary buffer.
+    /// The destination may extend past the current position, in which case the buffer grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` has not been written yet, its start is greater than its end,
+    /// or `dest` is after the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_slice(&[0, 1, 2, 3, 4]);
+    /// writer.copy_within(0..3, 1);
+    /// assert_eq!(writer.as_slice(), [0, 0, 1, 2, 4]);
+    /// writer.copy_within(3..5, 5);
+    /// assert_eq!(writer.as_slice(), [0, 0, 1, 2, 4, 2, 4]);
+    /// ```
+    pub fn copy_within(&mut self, src: Range<usize>, dest: usize) {
+        if src.start > src.end || src.end > self.inner.len() || dest > self.inner.len() {
+            panic!(
+                "copy from {}..{} to {} out of range for written length {}",
+                src.start,
+                src.end,
+                dest,
+                self.inner.len()
+            );
+        }
+        let end = dest + src.len();
+        if end > self.inner.len() {
+            self.inner.resize(end, 0);
+        }
+        self.inner.copy_within(src, dest);
+    }
+
+    /// Appends `len` bytes copied from `distance` bytes before the current position,
+    /// as an LZ77-style back-reference.
+    ///
+    /// The copy proceeds one byte at a time, so a `len` greater than `distance`
+    /// repeats the referenced bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `distance` is 0 or greater than the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::new();
+    /// writer.put_string("abc");
+    /// writer.put_back_reference(2, 5);
+    /// assert_eq!(writer.as_slice(), b"abcbcbcb");
+    /// ```
+    pub fn put_back_reference(&mut self, distance: usize, len: usize) {
+        if distance == 0 || distance > self.inner.len() {
+            panic!(
+                "back-reference distance {} out of range for written length {}",
+                distance,
+                self.inner.len()
+            );
+        }
+        let start = self.inner.len() - distance;
+        self.inner.reserve(len);
+        if len <= distance {
+            self.inner.extend_from_within(start..start + len);
+        } else {
+            for i in start..start + len {
+                self.inner.push(self.inner[i]);
+            }
+        }
+    }
+}
+
+impl From<Vec<u8>> for ByteCodeWriter {
+    /// Creates a writer holding `bytes`, positioned at their end, so that existing data can be patched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecode::ByteCodeWriter;
+    ///
+    /// let mut writer = ByteCodeWriter::from(vec![0x00, 0x00, 0xff]);
+    /// writer.patch_u16(0, 0x1234);
+    /// writer.put_u8(0xee);
+    /// assert_eq!(writer.as_slice(), [0x12, 0x34, 0xff, 0xee]);
+    /// ```
+    fn from(bytes: Vec<u8>) -> Self {
+        ByteCodeWriter {
+            inner: bytes,
+            endian: Endian::Big,
+        }
+    }
+}
+
+#[cfg(test)]
+use alloc::vec;
+
+#[test]
+fn put() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_u8(0x01);
+    writer.put_u16(0x0203);
+    writer.put_u32(0x0405_0607);
+    writer.set_endian(Endian::Little);
+    writer.put_u16(0x0203);
+    writer.put_u32(0x0405_0607);
+    writer.put_string("ok");
+    assert_eq!(
+        writer.as_slice(),
+        [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x03, 0x02, 0x07, 0x06, 0x05, 0x04, 0x6f,
+            0x6b
+        ]
+    );
+    assert_eq!(writer.pos(), 15);
+}
+
+#[test]
+fn patch() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_u32(0);
+    writer.put_slice(&[0xaa; 4]);
+    writer.set_endian(Endian::Little);
+    writer.patch_u32(0, 8);
+    writer.patch_u16(6, 0x1234);
+    assert_eq!(writer.pos(), 8);
+    assert_eq!(
+        writer.into_inner(),
+        [0x08, 0x00, 0x00, 0x00, 0xaa, 0xaa, 0x34, 0x12]
+    );
+}
+
+#[test]
+fn round_trip() {
+    use crate::ByteCode;
+
+    let mut writer = ByteCodeWriter::new();
+    writer.put_u16(0xcafe);
+    writer.put_u32(u32::MAX);
+    writer.put_string("bar");
+
+    let mut bytes = ByteCode::new(writer.as_slice());
+    assert_eq!(bytes.take_into_u16(), 0xcafe);
+    assert_eq!(bytes.take_into_u32(), u32::MAX);
+    assert_eq!(bytes.take_into_string(3), "bar");
+    assert!(bytes.is_end());
+}
+
+#[test]
+#[should_panic]
+fn patch_out_of_range() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_u16(0);
+    writer.patch_u16(1, 0);
+}
+
+#[test]
+fn copy_within() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_slice(&[1, 2, 3, 4, 5, 6]);
+    writer.copy_within(2..6, 0);
+    assert_eq!(writer.as_slice(), [3, 4, 5, 6, 5, 6]);
+    writer.copy_within(0..4, 4);
+    assert_eq!(writer.as_slice(), [3, 4, 5, 6, 3, 4, 5, 6]);
+    writer.copy_within(3..3, 8);
+    assert_eq!(writer.pos(), 8);
+}
+
+#[test]
+#[should_panic]
+fn copy_within_out_of_range() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_slice(&[1, 2, 3]);
+    writer.copy_within(1..4, 0);
+}
+
+#[test]
+fn put_back_reference() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_u8(0xaa);
+    writer.put_back_reference(1, 3);
+    assert_eq!(writer.as_slice(), [0xaa; 4]);
+    writer.put_u8(0xbb);
+    writer.put_back_reference(3, 2);
+    assert_eq!(
+        writer.as_slice(),
+        [0xaa, 0xaa, 0xaa, 0xaa, 0xbb, 0xaa, 0xaa]
+    );
+    writer.put_back_reference(7, 0);
+    assert_eq!(writer.pos(), 7);
+}
+
+#[test]
+#[should_panic]
+fn put_back_reference_out_of_range() {
+    let mut writer = ByteCodeWriter::new();
+    writer.put_u8(0xaa);
+    writer.put_back_reference(2, 1);
+}
+
+#[test]
+fn from_vec() {
+    let mut writer = ByteCodeWriter::from(vec![1, 2, 3]);
+    assert_eq!(writer.pos(), 3);
+    writer.patch_u8(2, 0);
+    assert_eq!(writer.into_inner(), [1, 2, 0]);
+}